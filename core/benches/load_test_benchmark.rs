@@ -0,0 +1,215 @@
+//! Fixed-rate load-test harness for `RenderService::render_batch`.
+//!
+//! Unlike `render_benchmark.rs`'s criterion benchmarks (which report a mean time per
+//! operation over a fixed number of iterations), this drives renders at a configured
+//! target rate for a fixed wall-clock duration - an open-loop load generator, not a
+//! closed one: a render that overruns its slot doesn't get to "catch up" against the
+//! next one's schedule, so sustained overload shows up as growing per-operation
+//! latency instead of being hidden by the harness waiting for each render to finish
+//! before issuing the next. That's what surfaces tail-latency regressions (e.g. in the
+//! isolate borrow/cleanup path) a mean-time benchmark can't.
+//!
+//! This is a plain `fn main()`, not a criterion harness, so it can take its own CLI
+//! flags - requires a Cargo.toml `[[bench]] name = "load_test_benchmark" harness =
+//! false` entry to run standalone rather than under the default libtest harness (this
+//! tree has no Cargo.toml to carry that yet, same as `render_benchmark.rs` below).
+//!
+//! Run with: `cargo bench -p dinja-core --bench load_test_benchmark -- \
+//!     --operations-per-second 50 --bench-length-seconds 30`
+//!
+//! Prints one JSON object to stdout summarizing achieved throughput and latency
+//! percentiles/histogram, so two runs (e.g. from two commits) can be diffed.
+
+use dinja_core::models::{ComponentDefinition, NamedMdxBatchInput, OutputFormat, RenderSettings};
+use dinja_core::service::{RenderService, RenderServiceConfig};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+/// Parsed `--operations-per-second`/`--bench-length-seconds` CLI flags.
+struct LoadTestArgs {
+    operations_per_second: f64,
+    bench_length_seconds: f64,
+}
+
+impl Default for LoadTestArgs {
+    fn default() -> Self {
+        Self {
+            operations_per_second: 20.0,
+            bench_length_seconds: 10.0,
+        }
+    }
+}
+
+/// Parses `--operations-per-second <n>`/`--bench-length-seconds <n>` out of the
+/// process's own arguments, ignoring anything else (e.g. `cargo bench`'s own
+/// `--bench` flag, when invoked via `cargo bench -- <these flags>`).
+fn parse_args() -> LoadTestArgs {
+    let mut args = LoadTestArgs::default();
+    let mut raw = std::env::args().skip(1);
+
+    while let Some(flag) = raw.next() {
+        match flag.as_str() {
+            "--operations-per-second" => {
+                if let Some(value) = raw.next() {
+                    if let Ok(parsed) = value.parse() {
+                        args.operations_per_second = parsed;
+                    }
+                }
+            }
+            "--bench-length-seconds" => {
+                if let Some(value) = raw.next() {
+                    if let Ok(parsed) = value.parse() {
+                        args.bench_length_seconds = parsed;
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    args
+}
+
+/// Upper bounds (in milliseconds) of the latency histogram's buckets. The last bucket
+/// is implicitly `+Inf`. Exponential spacing, so both a healthy sub-millisecond render
+/// and a badly regressed multi-second one land in a meaningful bucket.
+const HISTOGRAM_BUCKETS_MS: &[f64] = &[
+    0.5, 1.0, 2.0, 5.0, 10.0, 25.0, 50.0, 100.0, 250.0, 500.0, 1000.0, 2500.0,
+];
+
+fn simple_markdown_with_component() -> NamedMdxBatchInput {
+    let mdx = r#"---
+title: Load Test
+---
+
+# Welcome
+
+<Card title={context('title')}>
+  This is the card content with **bold** text.
+</Card>
+"#
+    .to_string();
+
+    let mut components = HashMap::new();
+    components.insert(
+        "Card".to_string(),
+        ComponentDefinition {
+            name: Some("Card".to_string()),
+            docs: None,
+            args: None,
+            code: r#"export default function Component({ title, children }) {
+    return (
+        <div class="card">
+            <h2>{title}</h2>
+            <div class="content">{children}</div>
+        </div>
+    );
+}"#
+            .to_string(),
+        },
+    );
+
+    let mut mdx_files = HashMap::new();
+    mdx_files.insert("test.mdx".to_string(), mdx);
+
+    NamedMdxBatchInput {
+        settings: RenderSettings {
+            output: OutputFormat::Html,
+            ..Default::default()
+        },
+        mdx: mdx_files,
+        components: Some(components),
+        partials: None,
+    }
+}
+
+/// The value at the given percentile (0.0-100.0) of an already-sorted slice, using
+/// nearest-rank interpolation - simple and stable enough for a load-test summary,
+/// where the input is typically thousands of samples.
+fn percentile(sorted_ms: &[f64], p: f64) -> f64 {
+    if sorted_ms.is_empty() {
+        return 0.0;
+    }
+    let rank = ((p / 100.0) * (sorted_ms.len() - 1) as f64).round() as usize;
+    sorted_ms[rank.min(sorted_ms.len() - 1)]
+}
+
+fn main() {
+    let args = parse_args();
+
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+    let static_dir = PathBuf::from(manifest_dir).join("static");
+    let config = RenderServiceConfig {
+        static_dir,
+        ..RenderServiceConfig::default()
+    };
+    let service = RenderService::new(config).expect("Failed to create RenderService");
+
+    // Pre-generate the fixture once, outside the timed loop.
+    let input = simple_markdown_with_component();
+
+    let interval =
+        Duration::from_secs_f64(1.0 / args.operations_per_second.max(f64::MIN_POSITIVE));
+    let bench_length = Duration::from_secs_f64(args.bench_length_seconds);
+
+    let start = Instant::now();
+    let deadline = start + bench_length;
+    let mut next_scheduled_at = start;
+    let mut latencies_ms = Vec::new();
+
+    while next_scheduled_at < deadline {
+        let now = Instant::now();
+        if now < next_scheduled_at {
+            std::thread::sleep(next_scheduled_at - now);
+        }
+
+        // Measured from the scheduled start, not the actual one, so time spent queued
+        // behind an overrunning render counts toward this operation's latency.
+        service.render_batch(&input).expect("render_batch failed during load test");
+        latencies_ms.push(next_scheduled_at.elapsed().as_secs_f64() * 1000.0);
+
+        next_scheduled_at += interval;
+    }
+
+    let elapsed = start.elapsed();
+    let operations = latencies_ms.len();
+    let achieved_ops_per_second = operations as f64 / elapsed.as_secs_f64();
+
+    let mut sorted_ms = latencies_ms.clone();
+    sorted_ms.sort_by(|a, b| a.total_cmp(b));
+
+    let mean_ms = if operations == 0 {
+        0.0
+    } else {
+        sorted_ms.iter().sum::<f64>() / operations as f64
+    };
+
+    let histogram: Vec<serde_json::Value> = HISTOGRAM_BUCKETS_MS
+        .iter()
+        .map(|&bound_ms| {
+            let cumulative_count = sorted_ms.iter().filter(|&&ms| ms <= bound_ms).count();
+            serde_json::json!({ "le_ms": bound_ms, "cumulative_count": cumulative_count })
+        })
+        .collect();
+
+    let summary = serde_json::json!({
+        "target_operations_per_second": args.operations_per_second,
+        "bench_length_seconds": args.bench_length_seconds,
+        "elapsed_seconds": elapsed.as_secs_f64(),
+        "operations": operations,
+        "achieved_operations_per_second": achieved_ops_per_second,
+        "latency_ms": {
+            "min": sorted_ms.first().copied().unwrap_or(0.0),
+            "mean": mean_ms,
+            "p50": percentile(&sorted_ms, 50.0),
+            "p90": percentile(&sorted_ms, 90.0),
+            "p99": percentile(&sorted_ms, 99.0),
+            "p99_9": percentile(&sorted_ms, 99.9),
+            "max": sorted_ms.last().copied().unwrap_or(0.0),
+        },
+        "latency_histogram_ms": histogram,
+    });
+
+    println!("{}", serde_json::to_string_pretty(&summary).expect("summary is valid JSON"));
+}
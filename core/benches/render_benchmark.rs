@@ -6,10 +6,12 @@
 
 use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
 use dinja_core::models::{ComponentDefinition, NamedMdxBatchInput, OutputFormat, RenderSettings};
+use dinja_core::renderer::pool::{RendererPool, RendererProfile};
 use dinja_core::service::{RenderService, RenderServiceConfig};
 use std::collections::HashMap;
 use std::path::PathBuf;
 use std::sync::OnceLock;
+use std::time::Duration;
 
 static SERVICE: OnceLock<RenderService> = OnceLock::new();
 
@@ -21,7 +23,11 @@ fn get_service() -> &'static RenderService {
         let config = RenderServiceConfig {
             static_dir,
             max_cached_renderers: 4,
+            max_batch_concurrency: 1,
             resource_limits: Default::default(),
+            compression: Default::default(),
+            upload: Default::default(),
+            ..RenderServiceConfig::default()
         };
 
         RenderService::new(config).expect("Failed to create RenderService")
@@ -244,5 +250,92 @@ fn render_benchmarks(c: &mut Criterion) {
     group.finish();
 }
 
-criterion_group!(benches, render_benchmarks);
+/// Compares renderer startup cost with and without a V8 startup snapshot - see
+/// [`dinja_core::renderer::pool::RendererPool::set_snapshot_enabled`]. Each iteration
+/// reaps the calling thread's cache first (without touching the snapshot cache) so
+/// `checkout` always has to build or deserialize a fresh renderer instead of just
+/// handing back one it already had cached.
+fn renderer_startup_benchmarks(c: &mut Criterion) {
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+    let static_dir = PathBuf::from(manifest_dir).join("static");
+    let profile = RendererProfile::engine(static_dir);
+
+    let mut group = c.benchmark_group("renderer_startup");
+
+    let cold_pool = RendererPool::new(1).with_snapshot_enabled(false);
+    group.bench_function("cold", |b| {
+        b.iter(|| {
+            cold_pool.reap(Duration::ZERO);
+            let lease = cold_pool.checkout(&profile).unwrap();
+            black_box(lease.renderer().unwrap());
+        })
+    });
+
+    let snapshot_pool = RendererPool::new(1).with_snapshot_enabled(true);
+    snapshot_pool.warm(&[profile.clone()], 1);
+    group.bench_function("snapshot", |b| {
+        b.iter(|| {
+            snapshot_pool.reap(Duration::ZERO);
+            let lease = snapshot_pool.checkout(&profile).unwrap();
+            black_box(lease.renderer().unwrap());
+        })
+    });
+
+    group.finish();
+}
+
+/// Throughput scaling across persistent worker-pool sizes - see
+/// [`dinja_core::service::RenderServiceConfig::worker_threads`]. Each worker count
+/// gets its own `RenderService` (and its own `max_cached_renderers`/
+/// `max_batch_concurrency`, sized to match) so a larger pool isn't bottlenecked on a
+/// renderer cache sized for a smaller one.
+fn batch_worker_scaling_benchmarks(c: &mut Criterion) {
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+    let static_dir = PathBuf::from(manifest_dir).join("static");
+    let content = simple_markdown();
+    const FILE_COUNT: usize = 64;
+
+    let mut group = c.benchmark_group("batch_worker_scaling");
+    group.throughput(Throughput::Elements(FILE_COUNT as u64));
+
+    for &workers in &[1usize, 2, 4, 8] {
+        let config = RenderServiceConfig {
+            static_dir: static_dir.clone(),
+            max_cached_renderers: workers,
+            max_batch_concurrency: workers,
+            worker_threads: Some(workers),
+            ..RenderServiceConfig::default()
+        };
+        let service = RenderService::new(config).expect("Failed to create RenderService");
+
+        group.bench_with_input(BenchmarkId::from_parameter(workers), &workers, |b, _| {
+            b.iter(|| {
+                let mut mdx_files = HashMap::new();
+                for i in 0..FILE_COUNT {
+                    mdx_files.insert(format!("file{}.mdx", i), content.clone());
+                }
+
+                let input = NamedMdxBatchInput {
+                    settings: RenderSettings {
+                        output: OutputFormat::Html,
+                        ..Default::default()
+                    },
+                    mdx: mdx_files,
+                    components: None,
+                    partials: None,
+                };
+
+                black_box(service.render_batch(&input).unwrap())
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    render_benchmarks,
+    renderer_startup_benchmarks,
+    batch_worker_scaling_benchmarks
+);
 criterion_main!(benches);
@@ -0,0 +1,141 @@
+//! Content-addressed cache of whole-file render results.
+//!
+//! Unlike [`crate::transform_cache`]'s process-wide, always-on cache of TSX-to-JS
+//! transform output, this cache is opt-in per [`crate::service::RenderService`] (via
+//! [`crate::service::RenderService::with_cache`]) and sits one layer higher: it caches
+//! an entire file's [`RenderedMdx`] (HTML/JS output plus TOC), keyed on a SHA-512
+//! digest of everything that can change that result - the MDX source bytes, the
+//! serialized [`ComponentDefinition`]s it references, and the active
+//! [`RenderSettings`]. A repeated [`crate::service::RenderService::render_batch`] call
+//! over a mostly-unchanged document set (e.g. a watch loop re-rendering on every save)
+//! then skips straight to a cached result for every file whose digest hasn't changed.
+//!
+//! ## Eviction
+//!
+//! Bounded at the capacity passed to [`crate::service::RenderService::with_cache`].
+//! Once full, the oldest entry is evicted to make room for the new one (FIFO), the
+//! same tradeoff [`crate::transform_cache`] makes for the same reason: it keeps
+//! bookkeeping to a single insertion-order queue rather than tracking per-entry
+//! last-access time.
+
+use crate::models::{ComponentDefinition, RenderSettings, RenderedMdx};
+use sha2::{Digest as _, Sha512};
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+
+/// A SHA-512 digest identifying a cached render result's exact inputs.
+pub type CacheDigest = [u8; 64];
+
+/// Computes the cache digest for one file: its MDX bytes, the component definitions it
+/// references (name-sorted so reference order can't change the digest), the
+/// registered partials (also name-sorted - a caller can't tell in advance which ones
+/// `mdx_source` transitively includes, so the whole registry is hashed rather than
+/// trying to narrow it like `referenced_components`), and the active render settings -
+/// serialized to JSON, the same representation used on the wire, so a settings change
+/// that doesn't round-trip through JSON can't silently produce a stale hit.
+pub(crate) fn digest_for(
+    mdx_source: &str,
+    referenced_components: &[(&str, &ComponentDefinition)],
+    partials: Option<&HashMap<String, String>>,
+    settings: &RenderSettings,
+) -> CacheDigest {
+    let mut hasher = Sha512::new();
+    hasher.update(mdx_source.as_bytes());
+
+    let mut sorted = referenced_components.to_vec();
+    sorted.sort_by_key(|(name, _)| *name);
+    for (name, component) in sorted {
+        hasher.update(name.as_bytes());
+        if let Ok(json) = serde_json::to_vec(component) {
+            hasher.update(&json);
+        }
+    }
+
+    if let Some(partials) = partials {
+        let mut sorted: Vec<(&String, &String)> = partials.iter().collect();
+        sorted.sort_by_key(|(name, _)| *name);
+        for (name, source) in sorted {
+            hasher.update(name.as_bytes());
+            hasher.update(source.as_bytes());
+        }
+    }
+
+    if let Ok(json) = serde_json::to_vec(settings) {
+        hasher.update(&json);
+    }
+
+    hasher.finalize().into()
+}
+
+struct CacheState {
+    map: HashMap<CacheDigest, RenderedMdx>,
+    /// Insertion order, oldest first, used for FIFO eviction.
+    order: VecDeque<CacheDigest>,
+}
+
+/// Bounded, FIFO-evicted cache of whole-file render results, keyed by [`CacheDigest`].
+pub(crate) struct BatchCache {
+    state: Mutex<CacheState>,
+    capacity: usize,
+}
+
+impl BatchCache {
+    /// Creates an empty cache holding at most `capacity` entries (clamped to at least
+    /// one, so a caller passing `0` still gets a working, if pointless, cache rather
+    /// than one that can never store anything).
+    pub(crate) fn new(capacity: usize) -> Self {
+        Self {
+            state: Mutex::new(CacheState {
+                map: HashMap::new(),
+                order: VecDeque::new(),
+            }),
+            capacity: capacity.max(1),
+        }
+    }
+
+    /// Returns a clone of the cached result for `digest`, if present.
+    pub(crate) fn get(&self, digest: &CacheDigest) -> Option<RenderedMdx> {
+        let state = self
+            .state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        state.map.get(digest).cloned()
+    }
+
+    /// Stores `value` under `digest`, evicting the oldest entry first if the cache is
+    /// already at capacity.
+    pub(crate) fn insert(&self, digest: CacheDigest, value: RenderedMdx) {
+        let mut state = self
+            .state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        if !state.map.contains_key(&digest) {
+            if state.order.len() >= self.capacity {
+                if let Some(oldest) = state.order.pop_front() {
+                    state.map.remove(&oldest);
+                }
+            }
+            state.order.push_back(digest);
+        }
+        state.map.insert(digest, value);
+    }
+
+    /// Number of entries currently cached.
+    pub(crate) fn len(&self) -> usize {
+        let state = self
+            .state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        state.map.len()
+    }
+
+    /// Discards every cached entry.
+    pub(crate) fn clear(&self) {
+        let mut state = self
+            .state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        state.map.clear();
+        state.order.clear();
+    }
+}
@@ -0,0 +1,309 @@
+//! A persistent pool of batch-render worker threads backing
+//! [`crate::service::RenderService::render_batch_streaming`]'s concurrent path - see
+//! [`crate::service::RenderServiceConfig::worker_threads`].
+//!
+//! `render_batch_streaming`'s original concurrent path spawns a fresh batch of
+//! [`std::thread::scope`] worker threads on every call, each checking out its own
+//! renderer from the thread-local [`crate::renderer::pool::RendererPool`] (a V8
+//! isolate isn't [`Send`], so it can only ever be driven from the thread that created
+//! it). That's cheap relative to a render, but still pays OS thread setup/teardown on
+//! every batch. This module instead spawns `worker_threads` threads once, for the
+//! lifetime of the owning [`crate::service::RenderService`], and feeds them one
+//! file-rendering [`Job`] at a time over a shared [`std::sync::mpsc`] queue. Each
+//! worker still checks out its own renderer from the thread-local pool per job (so a
+//! renderer warmed on one job stays warm, and thread-pinned, for the next job that
+//! lands on the same worker) - a persistent pool of threads reusing that mechanism,
+//! not a replacement for it.
+//!
+//! Every [`Job`] carries its own component definitions, partials, and
+//! [`RenderSettings`], so it's independent of which worker happens to dequeue it. A
+//! per-job panic is already caught and converted to a [`BatchError`] by
+//! [`crate::service::render_one_file_catching_panics`] before it would ever unwind
+//! into a worker thread, so a misbehaving component can't poison the pool the way a
+//! panic across a [`std::sync::Mutex`] lock would.
+
+use crate::batch_cache::BatchCache;
+use crate::error::MdxError;
+use crate::models::{ComponentDefinition, RenderSettings};
+use crate::renderer::pool::{RendererPool, RendererProfile};
+use crate::service::{render_one_file_catching_panics, BatchError, FileRenderOutcome, RenderEvent};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+
+/// One file's worth of work dispatched to the pool - see the module doc for why each
+/// job carries its own copy of everything it needs rather than borrowing from the
+/// dispatching call.
+struct Job {
+    name: String,
+    mdx_source: String,
+    pool: RendererPool,
+    profile: RendererProfile,
+    components: Arc<Option<HashMap<String, ComponentDefinition>>>,
+    partials: Arc<Option<HashMap<String, String>>>,
+    settings: Arc<RenderSettings>,
+    cache: Option<Arc<BatchCache>>,
+    sink: Sender<RenderEvent>,
+    /// Set by the dispatcher once any job in the same batch reports a
+    /// `FailureCategory::Forbidden` failure, so a worker that hasn't started this job
+    /// yet can skip it instead of doing wasted work - mirrors the early return the
+    /// sequential path takes on the same condition.
+    abort: Arc<AtomicBool>,
+    reply: Sender<JobOutcome>,
+}
+
+/// What a worker reports back for one [`Job`], over its `reply` channel.
+pub(crate) enum JobOutcome {
+    /// Rendered (successfully or not) before `abort` was observed.
+    Rendered {
+        name: String,
+        file_outcome: FileRenderOutcome,
+        batch_error: Option<BatchError>,
+        coverage: Option<HashMap<String, u32>>,
+    },
+    /// Never rendered because `abort` was already set by the time a worker dequeued
+    /// this job.
+    Skipped,
+    /// The worker couldn't check out a renderer, apply permissions, start coverage,
+    /// or read coverage back for this job - a dispatcher treats this the same way the
+    /// sequential path treats a failed renderer checkout: abort the whole
+    /// batch rather than record it as one file's failure.
+    Failed(MdxError),
+}
+
+/// A fixed-size pool of long-lived worker threads - see the module doc.
+pub(crate) struct BatchWorkerPool {
+    /// Taken by [`Drop::drop`] to close the channel, which is what makes every
+    /// worker's blocking `recv` return `Err` and its loop exit.
+    sender: Mutex<Option<Sender<Job>>>,
+    handles: Mutex<Vec<JoinHandle<()>>>,
+    worker_count: usize,
+}
+
+impl BatchWorkerPool {
+    /// Spawns `worker_count` (clamped to at least one) persistent worker threads,
+    /// each parked on the shared job queue until this pool is dropped.
+    pub(crate) fn new(worker_count: usize, stack_size: Option<usize>) -> Self {
+        let worker_count = worker_count.max(1);
+        let (sender, receiver) = mpsc::channel::<Job>();
+        let receiver = Arc::new(Mutex::new(receiver));
+
+        let handles = (0..worker_count)
+            .map(|id| {
+                let receiver = Arc::clone(&receiver);
+                let mut builder = thread::Builder::new().name(format!("dinja-batch-worker-{id}"));
+                if let Some(stack_size) = stack_size {
+                    builder = builder.stack_size(stack_size);
+                }
+                builder
+                    .spawn(move || worker_loop(&receiver))
+                    .expect("failed to spawn persistent batch worker thread")
+            })
+            .collect();
+
+        Self {
+            sender: Mutex::new(Some(sender)),
+            handles: Mutex::new(handles),
+            worker_count,
+        }
+    }
+
+    /// Number of persistent worker threads in this pool.
+    pub(crate) fn worker_count(&self) -> usize {
+        self.worker_count
+    }
+
+    /// Opens a [`BatchDispatch`] for one [`crate::service::RenderService::render_batch_streaming`]
+    /// call, sharing `components`/`partials`/`settings` (wrapped once, cheaply cloned
+    /// per job) across however many files get enqueued on it.
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn begin_batch(
+        &self,
+        pool: RendererPool,
+        profile: RendererProfile,
+        components: Arc<Option<HashMap<String, ComponentDefinition>>>,
+        partials: Arc<Option<HashMap<String, String>>>,
+        settings: Arc<RenderSettings>,
+        cache: Option<Arc<BatchCache>>,
+        sink: Sender<RenderEvent>,
+    ) -> BatchDispatch<'_> {
+        let (reply_tx, reply_rx) = mpsc::channel();
+        BatchDispatch {
+            worker_pool: self,
+            pool,
+            profile,
+            components,
+            partials,
+            settings,
+            cache,
+            sink,
+            abort: Arc::new(AtomicBool::new(false)),
+            reply_tx,
+            reply_rx,
+        }
+    }
+}
+
+/// A handle onto [`BatchWorkerPool`] scoped to a single
+/// [`crate::service::RenderService::render_batch_streaming`] call - see
+/// [`BatchWorkerPool::begin_batch`]. Enqueues feed the pool's shared job queue;
+/// [`Self::recv`] drains this batch's own reply channel, so this batch's results
+/// never get mixed up with a concurrent call's even though they may share workers.
+pub(crate) struct BatchDispatch<'a> {
+    worker_pool: &'a BatchWorkerPool,
+    pool: RendererPool,
+    profile: RendererProfile,
+    components: Arc<Option<HashMap<String, ComponentDefinition>>>,
+    partials: Arc<Option<HashMap<String, String>>>,
+    settings: Arc<RenderSettings>,
+    cache: Option<Arc<BatchCache>>,
+    sink: Sender<RenderEvent>,
+    abort: Arc<AtomicBool>,
+    reply_tx: Sender<JobOutcome>,
+    reply_rx: Receiver<JobOutcome>,
+}
+
+impl<'a> BatchDispatch<'a> {
+    /// Enqueues one file as a [`Job`] onto the pool's shared queue. A caller wanting
+    /// to bound how many of this batch's files are in flight at once (mirroring
+    /// [`crate::models::RenderSettings::parallelism`]/
+    /// [`crate::service::RenderServiceConfig::max_batch_concurrency`], which bound
+    /// concurrency per call rather than across the whole pool) enqueues a window of
+    /// these, then enqueues one more each time [`Self::recv`] completes one.
+    pub(crate) fn enqueue(&self, name: &str, mdx_source: &str) {
+        let sender = self.worker_pool.sender.lock().unwrap();
+        let sender = sender
+            .as_ref()
+            .expect("batch worker pool dispatched to after shutdown");
+        sender
+            .send(Job {
+                name: name.to_string(),
+                mdx_source: mdx_source.to_string(),
+                pool: self.pool.clone(),
+                profile: self.profile.clone(),
+                components: Arc::clone(&self.components),
+                partials: Arc::clone(&self.partials),
+                settings: Arc::clone(&self.settings),
+                cache: self.cache.clone(),
+                sink: self.sink.clone(),
+                abort: Arc::clone(&self.abort),
+                reply: self.reply_tx.clone(),
+            })
+            .expect("batch worker pool has no live workers");
+    }
+
+    /// Blocks for the next enqueued job's [`JobOutcome`], in completion order (not
+    /// enqueue order).
+    pub(crate) fn recv(&self) -> JobOutcome {
+        self.reply_rx
+            .recv()
+            .expect("batch worker pool dropped a job without replying")
+    }
+
+    /// Marks every not-yet-started job on this batch as skippable - see
+    /// [`JobOutcome::Skipped`]. Already-enqueued-but-unclaimed jobs are still
+    /// delivered (so [`Self::recv`] sees exactly as many replies as
+    /// [`Self::enqueue`] calls), just without doing the render.
+    pub(crate) fn abort(&self) {
+        self.abort.store(true, Ordering::Relaxed);
+    }
+}
+
+impl Drop for BatchWorkerPool {
+    fn drop(&mut self) {
+        self.sender.lock().unwrap().take();
+        for handle in self.handles.lock().unwrap().drain(..) {
+            let _ = handle.join();
+        }
+    }
+}
+
+fn worker_loop(receiver: &Mutex<Receiver<Job>>) {
+    loop {
+        let job = {
+            let receiver = receiver.lock().unwrap();
+            receiver.recv()
+        };
+        let Ok(job) = job else {
+            break;
+        };
+        let reply = job.reply.clone();
+        // The dispatcher has already returned by the time this arrives if nothing is
+        // listening anymore (e.g. a prior job in the same batch already failed it
+        // outright); the send is simply dropped in that case.
+        let _ = reply.send(run_job(&job));
+    }
+}
+
+fn run_job(job: &Job) -> JobOutcome {
+    if job.abort.load(Ordering::Relaxed) {
+        return JobOutcome::Skipped;
+    }
+
+    let renderer = match job.pool.checkout(&job.profile) {
+        Ok(renderer) => renderer,
+        Err(e) => {
+            return JobOutcome::Failed(MdxError::tsx_transform(format!(
+                "Failed to check out renderer: {e}"
+            )))
+        }
+    };
+    let renderer_ref = match renderer.renderer() {
+        Ok(renderer_ref) => renderer_ref,
+        Err(e) => return JobOutcome::Failed(e),
+    };
+    if let Err(e) = renderer_ref.apply_permissions(&job.settings.permissions) {
+        return JobOutcome::Failed(MdxError::tsx_transform(format!(
+            "Failed to apply component permissions: {e}"
+        )));
+    }
+    if job.settings.coverage {
+        if let Err(e) = renderer_ref.start_coverage() {
+            return JobOutcome::Failed(MdxError::tsx_transform(format!(
+                "Failed to start component coverage: {e}"
+            )));
+        }
+    }
+
+    let _ = job.sink.send(RenderEvent::Wait {
+        name: job.name.clone(),
+    });
+    let (file_outcome, batch_error) = render_one_file_catching_panics(
+        &job.name,
+        &job.mdx_source,
+        renderer_ref,
+        job.components.as_ref().as_ref(),
+        job.partials.as_ref().as_ref(),
+        &job.settings,
+        job.cache.as_deref(),
+    );
+    let _ = job.sink.send(RenderEvent::Result {
+        name: job.name.clone(),
+        duration_ms: file_outcome.duration_ms,
+        status: file_outcome.status.clone(),
+        output: file_outcome.result.as_ref().and_then(|r| r.output.clone()),
+        error: file_outcome.error.clone(),
+    });
+
+    let coverage = if job.settings.coverage {
+        match renderer_ref.collect_coverage() {
+            Ok(counts) => Some(counts),
+            Err(e) => {
+                return JobOutcome::Failed(MdxError::tsx_transform(format!(
+                    "Failed to collect component coverage: {e}"
+                )))
+            }
+        }
+    } else {
+        None
+    };
+
+    JobOutcome::Rendered {
+        name: job.name.clone(),
+        file_outcome,
+        batch_error,
+        coverage,
+    }
+}
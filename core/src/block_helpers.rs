@@ -0,0 +1,525 @@
+//! `{{#each}}`/`{{#if}}`/`{{#with}}` block helpers, expanded against a document's
+//! frontmatter before it's rendered as markdown - see
+//! [`crate::models::RenderSettings::block_helpers`].
+//!
+//! This complements the JSX component path rather than replacing it: a document that
+//! wants a nav menu or a list of tag chips generated straight from a frontmatter array
+//! doesn't need a dedicated component for it, the way [`crate::partials`] and
+//! [`crate::scripting`] let simple structural needs skip a full JSX round-trip too.
+//!
+//! - `{{#each items}} ... {{/each}}` repeats its body once per element of the array at
+//!   `items` (a dotted path resolved against the current scope - see [`get_path`]),
+//!   with the element bound as the new scope and `{{@index}}`/`{{@first}}`/`{{@last}}`
+//!   available inside. A path that isn't an array (including one that resolves to
+//!   nothing) iterates zero times.
+//! - `{{#if cond}} ... {{else}} ... {{/if}}` emits the `{{else}}` branch (if any,
+//!   otherwise nothing) unless `cond` resolves to a JS-truthy value (see [`is_truthy`]).
+//! - `{{#with obj}} ... {{/with}}` narrows the scope to `obj` for its body - lookups
+//!   that miss on the narrowed scope still fall back to the enclosing one, so e.g. a
+//!   site-wide `title` stays reachable from inside an `{{#each}}` over `items`.
+//!
+//! Helpers nest freely (an `{{#each}}` body may contain `{{#if}}`, and so on). A plain
+//! `{{path.to.value}}` outside any helper interpolates that path as a string; `{{this}}`
+//! refers to the current scope's own value. Content with no `{{` passes through
+//! unchanged, even when `block_helpers` is enabled.
+
+use crate::error::MdxError;
+use regex::Regex;
+use serde_json::Value;
+use std::rc::Rc;
+use std::sync::LazyLock;
+
+/// Matches any `{{...}}` block-helper tag, capturing its trimmed inner text.
+static BLOCK_TAG: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\{\{\s*(.*?)\s*\}\}").expect("hardcoded regex pattern is valid"));
+
+/// A lexical piece of the template: either literal text or a recognized `{{...}}` tag.
+enum Token {
+    Text(String),
+    Var(String),
+    EachOpen(String),
+    IfOpen(String),
+    WithOpen(String),
+    Else,
+    EachClose,
+    IfClose,
+    WithClose,
+}
+
+/// A parsed node in a block helper's body.
+enum Node {
+    Text(String),
+    Var(String),
+    Each { path: String, body: Vec<Node> },
+    If {
+        cond: String,
+        then_branch: Vec<Node>,
+        else_branch: Vec<Node>,
+    },
+    With { path: String, body: Vec<Node> },
+}
+
+/// Expands every `{{#each}}`/`{{#if}}`/`{{#with}}` block helper in `content` against
+/// `data` (a document's parsed frontmatter), returning the expanded markdown source.
+///
+/// # Errors
+/// Returns [`MdxError::BlockHelper`] on malformed helper syntax - an unrecognized
+/// `{{#name}}`, an unclosed block, a mismatched closing tag, or a stray `{{else}}`
+/// outside an `{{#if}}`.
+pub(crate) fn expand_block_helpers(content: &str, data: &Value) -> Result<String, MdxError> {
+    if !content.contains("{{") {
+        return Ok(content.to_string());
+    }
+
+    let tokens = tokenize(content)?;
+    let mut parser = Parser::new(&tokens);
+    let (nodes, reason) = parser.parse_nodes(false)?;
+    match reason {
+        StopReason::Eof => {}
+        StopReason::Close(kind) => {
+            return Err(MdxError::BlockHelper(format!(
+                "Unexpected '{{{{/{kind}}}}}' with no matching '{{{{#{kind}}}}}'"
+            )));
+        }
+        StopReason::Else => {
+            return Err(MdxError::BlockHelper(
+                "Unexpected '{{else}}' with no enclosing '{{#if}}'".to_string(),
+            ));
+        }
+    }
+
+    let root = Scope::root(data.clone());
+    Ok(render_nodes(&nodes, &root))
+}
+
+fn tokenize(content: &str) -> Result<Vec<Token>, MdxError> {
+    let mut tokens = Vec::new();
+    let mut last = 0;
+    for caps in BLOCK_TAG.captures_iter(content) {
+        let whole = caps.get(0).expect("capture group 0 always matches");
+        if whole.start() > last {
+            tokens.push(Token::Text(content[last..whole.start()].to_string()));
+        }
+        tokens.push(classify_tag(&caps[1])?);
+        last = whole.end();
+    }
+    if last < content.len() {
+        tokens.push(Token::Text(content[last..].to_string()));
+    }
+    Ok(tokens)
+}
+
+fn classify_tag(text: &str) -> Result<Token, MdxError> {
+    let mut parts = text.splitn(2, char::is_whitespace);
+    let head = parts.next().unwrap_or("");
+    let rest = parts.next().unwrap_or("").trim();
+
+    let is_single_path = !rest.is_empty() && rest.split_whitespace().count() == 1;
+    match head {
+        "#each" if is_single_path => Ok(Token::EachOpen(rest.to_string())),
+        "#if" if is_single_path => Ok(Token::IfOpen(rest.to_string())),
+        "#with" if is_single_path => Ok(Token::WithOpen(rest.to_string())),
+        "#each" | "#if" | "#with" => Err(MdxError::BlockHelper(format!(
+            "'{{{{{text}}}}}' - '{{{{{head}}}}}' takes exactly one frontmatter path, e.g. '{{{{{head} items}}}}'"
+        ))),
+        "else" => Ok(Token::Else),
+        "/each" => Ok(Token::EachClose),
+        "/if" => Ok(Token::IfClose),
+        "/with" => Ok(Token::WithClose),
+        _ if head.starts_with('#') || head.starts_with('/') => Err(MdxError::BlockHelper(format!(
+            "Unrecognized block helper tag '{{{{{text}}}}}'"
+        ))),
+        _ => Ok(Token::Var(text.to_string())),
+    }
+}
+
+/// How a [`Parser::parse_nodes`] call consumed its tokens up to the current position.
+enum StopReason {
+    /// Ran out of tokens - valid only at the top level.
+    Eof,
+    /// Hit `{{/each}}`, `{{/if}}`, or `{{/with}}` (the matching keyword is named).
+    Close(&'static str),
+    /// Hit `{{else}}` (only returned when the caller opted in via `allow_else`).
+    Else,
+}
+
+struct Parser<'t> {
+    tokens: &'t [Token],
+    pos: usize,
+}
+
+impl<'t> Parser<'t> {
+    fn new(tokens: &'t [Token]) -> Self {
+        Self { tokens, pos: 0 }
+    }
+
+    /// Parses a run of nodes until end-of-input, a closing tag, or (if `allow_else`) an
+    /// `{{else}}` - the terminator is reported via [`StopReason`] rather than consumed
+    /// silently, so the caller can tell an unclosed block from a mismatched one.
+    fn parse_nodes(&mut self, allow_else: bool) -> Result<(Vec<Node>, StopReason), MdxError> {
+        let mut nodes = Vec::new();
+
+        loop {
+            let Some(token) = self.tokens.get(self.pos) else {
+                return Ok((nodes, StopReason::Eof));
+            };
+
+            match token {
+                Token::Text(text) => {
+                    nodes.push(Node::Text(text.clone()));
+                    self.pos += 1;
+                }
+                Token::Var(path) => {
+                    nodes.push(Node::Var(path.clone()));
+                    self.pos += 1;
+                }
+                Token::EachOpen(path) => {
+                    let path = path.clone();
+                    self.pos += 1;
+                    let (body, reason) = self.parse_nodes(false)?;
+                    if !matches!(reason, StopReason::Close("each")) {
+                        return Err(close_mismatch("each", reason));
+                    }
+                    nodes.push(Node::Each { path, body });
+                }
+                Token::WithOpen(path) => {
+                    let path = path.clone();
+                    self.pos += 1;
+                    let (body, reason) = self.parse_nodes(false)?;
+                    if !matches!(reason, StopReason::Close("with")) {
+                        return Err(close_mismatch("with", reason));
+                    }
+                    nodes.push(Node::With { path, body });
+                }
+                Token::IfOpen(cond) => {
+                    let cond = cond.clone();
+                    self.pos += 1;
+                    let (then_branch, reason) = self.parse_nodes(true)?;
+                    let else_branch = match reason {
+                        StopReason::Close("if") => Vec::new(),
+                        StopReason::Else => {
+                            let (else_nodes, reason) = self.parse_nodes(false)?;
+                            if !matches!(reason, StopReason::Close("if")) {
+                                return Err(close_mismatch("if", reason));
+                            }
+                            else_nodes
+                        }
+                        other => return Err(close_mismatch("if", other)),
+                    };
+                    nodes.push(Node::If {
+                        cond,
+                        then_branch,
+                        else_branch,
+                    });
+                }
+                Token::Else => {
+                    if allow_else {
+                        self.pos += 1;
+                        return Ok((nodes, StopReason::Else));
+                    }
+                    return Err(MdxError::BlockHelper(
+                        "Unexpected '{{else}}' - only '{{#if}}' supports '{{else}}'".to_string(),
+                    ));
+                }
+                Token::EachClose => {
+                    self.pos += 1;
+                    return Ok((nodes, StopReason::Close("each")));
+                }
+                Token::IfClose => {
+                    self.pos += 1;
+                    return Ok((nodes, StopReason::Close("if")));
+                }
+                Token::WithClose => {
+                    self.pos += 1;
+                    return Ok((nodes, StopReason::Close("with")));
+                }
+            }
+        }
+    }
+}
+
+fn close_mismatch(expected: &str, reason: StopReason) -> MdxError {
+    match reason {
+        StopReason::Eof => MdxError::BlockHelper(format!(
+            "Unclosed '{{{{#{expected} ...}}}}' - missing '{{{{/{expected}}}}}'"
+        )),
+        StopReason::Close(other) => MdxError::BlockHelper(format!(
+            "Mismatched block helper close: expected '{{{{/{expected}}}}}', found '{{{{/{other}}}}}'"
+        )),
+        StopReason::Else => MdxError::BlockHelper(format!(
+            "Unexpected '{{{{else}}}}' inside '{{{{#{expected}}}}}' - only '{{{{#if}}}}' supports '{{{{else}}}}'"
+        )),
+    }
+}
+
+/// A lookup scope: the value an `{{#each}}`/`{{#with}}` narrowed to, plus (for an
+/// `{{#each}}` element) its loop position, chained back to its enclosing scope so a
+/// path that misses on the narrowed value still resolves against the outer one.
+struct Scope {
+    value: Value,
+    index: Option<usize>,
+    first: Option<bool>,
+    last: Option<bool>,
+    parent: Option<Rc<Scope>>,
+}
+
+impl Scope {
+    fn root(value: Value) -> Rc<Scope> {
+        Rc::new(Scope {
+            value,
+            index: None,
+            first: None,
+            last: None,
+            parent: None,
+        })
+    }
+
+    fn child(parent: &Rc<Scope>, value: Value) -> Rc<Scope> {
+        Rc::new(Scope {
+            value,
+            index: None,
+            first: None,
+            last: None,
+            parent: Some(Rc::clone(parent)),
+        })
+    }
+
+    fn each_child(parent: &Rc<Scope>, value: Value, index: usize, first: bool, last: bool) -> Rc<Scope> {
+        Rc::new(Scope {
+            value,
+            index: Some(index),
+            first: Some(first),
+            last: Some(last),
+            parent: Some(Rc::clone(parent)),
+        })
+    }
+
+    /// Resolves `path` against this scope, falling back to the enclosing scope (and so
+    /// on) if it isn't found here - `this`/`.` return the scope's own value, and
+    /// `@index`/`@first`/`@last` return the nearest enclosing `{{#each}}`'s loop state.
+    fn resolve(&self, path: &str) -> Option<Value> {
+        match path {
+            "this" | "." => Some(self.value.clone()),
+            "@index" => self
+                .index
+                .map(|i| Value::from(i))
+                .or_else(|| self.parent.as_ref().and_then(|p| p.resolve("@index"))),
+            "@first" => self
+                .first
+                .map(Value::Bool)
+                .or_else(|| self.parent.as_ref().and_then(|p| p.resolve("@first"))),
+            "@last" => self
+                .last
+                .map(Value::Bool)
+                .or_else(|| self.parent.as_ref().and_then(|p| p.resolve("@last"))),
+            _ => get_path(&self.value, path)
+                .cloned()
+                .or_else(|| self.parent.as_ref().and_then(|p| p.resolve(path))),
+        }
+    }
+}
+
+/// Walks `value` by a dotted path (`"user.name"`, `"items.0.title"`), indexing objects
+/// by key and arrays by a numeric segment. Returns `None` as soon as a segment doesn't
+/// resolve, rather than erroring - a missing frontmatter field is routine, not malformed
+/// input.
+fn get_path<'a>(value: &'a Value, path: &str) -> Option<&'a Value> {
+    let mut current = value;
+    for segment in path.split('.') {
+        current = match current {
+            Value::Object(_) => current.get(segment)?,
+            Value::Array(items) => items.get(segment.parse::<usize>().ok()?)?,
+            _ => return None,
+        };
+    }
+    Some(current)
+}
+
+/// JS-style truthiness: `null`, `false`, `0`, `""`, and an empty array/object are
+/// falsy; everything else (including a non-empty array/object) is truthy.
+fn is_truthy(value: &Value) -> bool {
+    match value {
+        Value::Null => false,
+        Value::Bool(b) => *b,
+        Value::Number(n) => match n.as_f64() {
+            Some(f) => f != 0.0,
+            None => true,
+        },
+        Value::String(s) => !s.is_empty(),
+        Value::Array(items) => !items.is_empty(),
+        Value::Object(map) => !map.is_empty(),
+    }
+}
+
+/// Renders `value` for `{{...}}` interpolation: a bare string as itself (no quotes), a
+/// number/bool via its natural text form, `null` as empty, and an array/object as its
+/// compact JSON form (there's no other sensible flat text for a structured value).
+fn value_to_string(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(s) => s.clone(),
+        Value::Bool(b) => b.to_string(),
+        Value::Number(n) => n.to_string(),
+        Value::Array(_) | Value::Object(_) => value.to_string(),
+    }
+}
+
+fn render_nodes(nodes: &[Node], scope: &Rc<Scope>) -> String {
+    let mut out = String::new();
+    for node in nodes {
+        match node {
+            Node::Text(text) => out.push_str(text),
+            Node::Var(path) => {
+                if let Some(value) = scope.resolve(path) {
+                    out.push_str(&value_to_string(&value));
+                }
+            }
+            Node::Each { path, body } => {
+                if let Some(Value::Array(items)) = scope.resolve(path) {
+                    let len = items.len();
+                    for (index, item) in items.into_iter().enumerate() {
+                        let child = Scope::each_child(scope, item, index, index == 0, index + 1 == len);
+                        out.push_str(&render_nodes(body, &child));
+                    }
+                }
+            }
+            Node::If {
+                cond,
+                then_branch,
+                else_branch,
+            } => {
+                let truthy = scope.resolve(cond).map(|v| is_truthy(&v)).unwrap_or(false);
+                let branch = if truthy { then_branch } else { else_branch };
+                out.push_str(&render_nodes(branch, scope));
+            }
+            Node::With { path, body } => {
+                let value = scope.resolve(path).unwrap_or(Value::Null);
+                let child = Scope::child(scope, value);
+                out.push_str(&render_nodes(body, &child));
+            }
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_each_repeats_body_with_index_flags() {
+        let data = serde_json::json!({ "items": ["a", "b", "c"] });
+        let content = "{{#each items}}[{{@index}}:{{this}}:{{@first}}:{{@last}}]{{/each}}";
+        let result = expand_block_helpers(content, &data).unwrap();
+        assert_eq!(
+            result,
+            "[0:a:true:false][1:b:false:false][2:c:false:true]"
+        );
+    }
+
+    #[test]
+    fn test_each_over_missing_path_renders_nothing() {
+        let data = serde_json::json!({});
+        let result = expand_block_helpers("{{#each items}}{{this}}{{/each}}", &data).unwrap();
+        assert_eq!(result, "");
+    }
+
+    #[test]
+    fn test_each_binds_object_elements_for_nested_lookup() {
+        let data = serde_json::json!({ "items": [{ "name": "Card" }, { "name": "Hero" }] });
+        let result = expand_block_helpers("{{#each items}}{{name}} {{/each}}", &data).unwrap();
+        assert_eq!(result, "Card Hero ");
+    }
+
+    #[test]
+    fn test_if_emits_then_branch_when_truthy() {
+        let data = serde_json::json!({ "draft": true });
+        let result = expand_block_helpers("{{#if draft}}DRAFT{{else}}PUBLISHED{{/if}}", &data).unwrap();
+        assert_eq!(result, "DRAFT");
+    }
+
+    #[test]
+    fn test_if_emits_else_branch_when_falsy() {
+        let data = serde_json::json!({ "draft": false });
+        let result = expand_block_helpers("{{#if draft}}DRAFT{{else}}PUBLISHED{{/if}}", &data).unwrap();
+        assert_eq!(result, "PUBLISHED");
+    }
+
+    #[test]
+    fn test_if_without_else_emits_nothing_when_falsy() {
+        let data = serde_json::json!({ "tags": [] });
+        let result = expand_block_helpers("{{#if tags}}has tags{{/if}}", &data).unwrap();
+        assert_eq!(result, "");
+    }
+
+    #[test]
+    fn test_with_narrows_scope_for_nested_lookup() {
+        let data = serde_json::json!({ "author": { "name": "Ada" } });
+        let result = expand_block_helpers("{{#with author}}{{name}}{{/with}}", &data).unwrap();
+        assert_eq!(result, "Ada");
+    }
+
+    #[test]
+    fn test_with_falls_back_to_outer_scope_for_unknown_field() {
+        let data = serde_json::json!({ "site": "Docs", "author": { "name": "Ada" } });
+        let result = expand_block_helpers("{{#with author}}{{name}} from {{site}}{{/with}}", &data).unwrap();
+        assert_eq!(result, "Ada from Docs");
+    }
+
+    #[test]
+    fn test_nested_if_inside_each() {
+        let data = serde_json::json!({ "items": [{ "name": "Card", "hidden": false }, { "name": "Hero", "hidden": true }] });
+        let content = "{{#each items}}{{#if hidden}}-{{else}}{{name}}{{/if}} {{/each}}";
+        let result = expand_block_helpers(content, &data).unwrap();
+        assert_eq!(result, "Card - ");
+    }
+
+    #[test]
+    fn test_dotted_path_resolution() {
+        let data = serde_json::json!({ "author": { "name": "Ada" } });
+        let result = expand_block_helpers("{{author.name}}", &data).unwrap();
+        assert_eq!(result, "Ada");
+    }
+
+    #[test]
+    fn test_content_without_braces_passes_through_unchanged() {
+        let data = serde_json::json!({});
+        let result = expand_block_helpers("Plain markdown, no helpers here.", &data).unwrap();
+        assert_eq!(result, "Plain markdown, no helpers here.");
+    }
+
+    #[test]
+    fn test_unclosed_each_errors() {
+        let data = serde_json::json!({ "items": [] });
+        let err = expand_block_helpers("{{#each items}}x", &data).unwrap_err();
+        assert!(matches!(err, MdxError::BlockHelper(_)));
+    }
+
+    #[test]
+    fn test_mismatched_close_errors() {
+        let data = serde_json::json!({ "items": [] });
+        let err = expand_block_helpers("{{#each items}}x{{/if}}", &data).unwrap_err();
+        assert!(matches!(err, MdxError::BlockHelper(_)));
+    }
+
+    #[test]
+    fn test_stray_else_errors() {
+        let data = serde_json::json!({});
+        let err = expand_block_helpers("{{else}}", &data).unwrap_err();
+        assert!(matches!(err, MdxError::BlockHelper(_)));
+    }
+
+    #[test]
+    fn test_each_with_no_path_errors() {
+        let data = serde_json::json!({});
+        let err = expand_block_helpers("{{#each}}x{{/each}}", &data).unwrap_err();
+        assert!(matches!(err, MdxError::BlockHelper(_)));
+    }
+
+    #[test]
+    fn test_each_with_trailing_garbage_after_path_errors() {
+        let data = serde_json::json!({ "items": ["a"] });
+        let err = expand_block_helpers("{{#each items as item}}{{item}}{{/each}}", &data).unwrap_err();
+        assert!(matches!(err, MdxError::BlockHelper(_)));
+    }
+}
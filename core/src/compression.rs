@@ -0,0 +1,156 @@
+//! `Accept-Encoding` negotiation and response body compression.
+//!
+//! [`handle_render_result`][crate::handlers::handle_render_result] serializes every
+//! batch outcome to JSON uncompressed, which wastes bandwidth on a large batch. This
+//! module picks the best encoding a request's `Accept-Encoding` header and the
+//! service's [`CompressionConfig`] both allow, and compresses the serialized body
+//! against it - mirroring the negotiation approach [`crate::negotiation`] already uses
+//! for `Accept`.
+
+use serde::{Deserialize, Serialize};
+use std::io::Write as _;
+
+/// An HTTP content encoding [`select_encoding`] can choose between.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum ContentEncoding {
+    /// Brotli (`br`).
+    Brotli,
+    /// Gzip (`gzip`).
+    Gzip,
+    /// Raw DEFLATE (`deflate`).
+    Deflate,
+    /// No compression - left exactly as serialized.
+    Identity,
+}
+
+impl ContentEncoding {
+    /// The `Content-Encoding` header value for this encoding, or `None` for
+    /// [`ContentEncoding::Identity`] (which omits the header entirely).
+    pub(crate) fn header_value(self) -> Option<&'static str> {
+        match self {
+            ContentEncoding::Brotli => Some("br"),
+            ContentEncoding::Gzip => Some("gzip"),
+            ContentEncoding::Deflate => Some("deflate"),
+            ContentEncoding::Identity => None,
+        }
+    }
+}
+
+/// One `Accept-Encoding` coding together with its `q` weight (defaults to `1.0` when
+/// the `;q=` parameter is absent, per RFC 7231 §5.3.1).
+struct Coding<'a> {
+    name: &'a str,
+    q: f32,
+}
+
+/// Parses an `Accept-Encoding` header value into its codings, highest `q` first.
+fn parse_accept_encoding(header: &str) -> Vec<Coding<'_>> {
+    let mut codings: Vec<Coding<'_>> = header
+        .split(',')
+        .filter_map(|part| {
+            let part = part.trim();
+            if part.is_empty() {
+                return None;
+            }
+            let mut segments = part.split(';');
+            let name = segments.next().unwrap_or("").trim();
+            let q = segments
+                .filter_map(|param| {
+                    let param = param.trim();
+                    param.strip_prefix("q=").and_then(|v| v.trim().parse().ok())
+                })
+                .next()
+                .unwrap_or(1.0);
+            Some(Coding { name, q })
+        })
+        .collect();
+    codings.sort_by(|a, b| b.q.total_cmp(&a.q));
+    codings
+}
+
+/// Picks the best [`ContentEncoding`] that's both requested (via `accept_encoding`)
+/// and enabled in `allowed` (see [`CompressionConfig`]). Falls back to
+/// [`ContentEncoding::Identity`] when `accept_encoding` is absent, lists nothing
+/// acceptable, or explicitly excludes every allowed encoding with `q=0`.
+pub(crate) fn select_encoding(
+    accept_encoding: Option<&str>,
+    allowed: &CompressionConfig,
+) -> ContentEncoding {
+    let Some(header) = accept_encoding.filter(|value| !value.trim().is_empty()) else {
+        return ContentEncoding::Identity;
+    };
+
+    for coding in parse_accept_encoding(header) {
+        if coding.q <= 0.0 {
+            continue;
+        }
+        let encoding = match coding.name {
+            "br" if allowed.brotli => Some(ContentEncoding::Brotli),
+            "gzip" if allowed.gzip => Some(ContentEncoding::Gzip),
+            "deflate" if allowed.deflate => Some(ContentEncoding::Deflate),
+            "identity" | "*" => Some(ContentEncoding::Identity),
+            _ => None,
+        };
+        if let Some(encoding) = encoding {
+            return encoding;
+        }
+    }
+    ContentEncoding::Identity
+}
+
+/// Compresses `body` with `encoding`, returning it unchanged for
+/// [`ContentEncoding::Identity`].
+pub(crate) fn compress(body: &[u8], encoding: ContentEncoding) -> Vec<u8> {
+    match encoding {
+        ContentEncoding::Identity => body.to_vec(),
+        ContentEncoding::Gzip => {
+            let mut encoder =
+                flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+            encoder.write_all(body).expect("writing to an in-memory buffer cannot fail");
+            encoder.finish().expect("finishing an in-memory gzip stream cannot fail")
+        }
+        ContentEncoding::Deflate => {
+            let mut encoder =
+                flate2::write::DeflateEncoder::new(Vec::new(), flate2::Compression::default());
+            encoder.write_all(body).expect("writing to an in-memory buffer cannot fail");
+            encoder.finish().expect("finishing an in-memory deflate stream cannot fail")
+        }
+        ContentEncoding::Brotli => {
+            let mut out = Vec::new();
+            {
+                let mut writer = brotli::CompressorWriter::new(&mut out, 4096, 5, 22);
+                writer.write_all(body).expect("writing to an in-memory buffer cannot fail");
+            }
+            out
+        }
+    }
+}
+
+/// Which encodings [`select_encoding`] is allowed to pick, and the minimum body size
+/// worth compressing at all - part of
+/// [`crate::service::RenderServiceConfig`] so a deployment can disable brotli (it's
+/// the most CPU-expensive of the three) without touching request-handling code.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CompressionConfig {
+    /// Allow negotiating `gzip`.
+    pub gzip: bool,
+    /// Allow negotiating `br` (brotli).
+    pub brotli: bool,
+    /// Allow negotiating `deflate`.
+    pub deflate: bool,
+    /// Bodies smaller than this many bytes are sent as `identity` regardless of what
+    /// the client accepts - compressing a tiny payload costs more CPU than the bytes
+    /// it saves.
+    pub min_size_bytes: usize,
+}
+
+impl Default for CompressionConfig {
+    fn default() -> Self {
+        Self {
+            gzip: true,
+            brotli: true,
+            deflate: true,
+            min_size_bytes: 1024,
+        }
+    }
+}
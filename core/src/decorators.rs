@@ -0,0 +1,263 @@
+//! Extension point for user-registered template decorators and helpers.
+//!
+//! The crate ships no built-in decorators itself; [`DecoratorRegistry`] is how a host
+//! registers named ones - `@slugify`, `@truncate(80)`, `@currency("USD")` - as Rust
+//! closures via [`crate::service::RenderService::register_decorator`], in the spirit of
+//! Handlebars' helper registry, instead of the decorator set being a closed enum baked
+//! into the crate. A frontmatter string written as a decorator expression (`@name` or
+//! `@name(value, params...)`) resolves against the registry at render time; referencing
+//! an unregistered name fails the file with [`crate::error::MdxError::UnknownDecorator`]
+//! rather than passing the literal text through.
+
+use crate::error::MdxError;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+
+/// A registered decorator: given the value it's applied to (a decorator expression's
+/// first argument, or an empty string for a bare `@name` with none), its remaining
+/// parameters, and the render context (the file's full frontmatter, as parsed before
+/// any decorator in it was applied), returns the decorated string or a failure reason.
+pub trait Decorator: Fn(&str, &[String], &Value) -> Result<String, MdxError> + Send + Sync {}
+
+impl<F> Decorator for F where F: Fn(&str, &[String], &Value) -> Result<String, MdxError> + Send + Sync
+{}
+
+/// A registry of named decorators and helpers, turning the decorator system from a
+/// closed enum into an open extension point. Cheap to clone - registered decorators
+/// are held behind an [`Arc`], so cloning a [`crate::service::RenderService`] doesn't
+/// copy them.
+#[derive(Clone, Default)]
+pub struct DecoratorRegistry {
+    decorators: Arc<HashMap<String, Arc<dyn Decorator>>>,
+}
+
+impl fmt::Debug for DecoratorRegistry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut names: Vec<&str> = self.decorators.keys().map(String::as_str).collect();
+        names.sort_unstable();
+        f.debug_struct("DecoratorRegistry")
+            .field("registered", &names)
+            .finish()
+    }
+}
+
+impl DecoratorRegistry {
+    /// Creates an empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers `decorator` under `name` (referenced in a template as `@name` or
+    /// `@name(arg, ...)`, without the leading `@`), replacing any existing decorator
+    /// of that name.
+    pub fn register(
+        &mut self,
+        name: impl Into<String>,
+        decorator: impl Fn(&str, &[String], &Value) -> Result<String, MdxError> + Send + Sync + 'static,
+    ) -> &mut Self {
+        Arc::make_mut(&mut self.decorators).insert(name.into(), Arc::new(decorator));
+        self
+    }
+
+    /// Returns true if no decorators are registered.
+    pub fn is_empty(&self) -> bool {
+        self.decorators.is_empty()
+    }
+
+    /// Number of registered decorators.
+    pub fn len(&self) -> usize {
+        self.decorators.len()
+    }
+
+    /// Applies the decorator named `name` to `value`, or returns
+    /// [`MdxError::UnknownDecorator`] if no decorator of that name is registered.
+    pub fn apply(
+        &self,
+        name: &str,
+        value: &str,
+        params: &[String],
+        context: &Value,
+    ) -> Result<String, MdxError> {
+        match self.decorators.get(name) {
+            Some(decorator) => decorator(value, params, context),
+            None => Err(MdxError::UnknownDecorator(name.to_string())),
+        }
+    }
+}
+
+/// Parses a template decorator expression such as `@slugify` or
+/// `@truncate("a long title", 80)` into its bare name and argument list: the leading
+/// `@` is stripped, and a parenthesized argument list is split on commas, with each
+/// argument trimmed of surrounding whitespace and, if present, one layer of matching
+/// `"`/`'` quotes. Returns `None` if `expr` doesn't start with `@`, has unbalanced
+/// parentheses, or names an empty decorator.
+///
+/// # Examples
+/// ```
+/// use dinja_core::decorators::parse_decorator_expr;
+///
+/// assert_eq!(parse_decorator_expr("@uppercase"), Some(("uppercase".to_string(), vec![])));
+/// assert_eq!(
+///     parse_decorator_expr(r#"@currency("USD")"#),
+///     Some(("currency".to_string(), vec!["USD".to_string()]))
+/// );
+/// ```
+pub fn parse_decorator_expr(expr: &str) -> Option<(String, Vec<String>)> {
+    let rest = expr.strip_prefix('@')?.trim();
+
+    let (name, args) = match rest.find('(') {
+        Some(open) => {
+            let close = rest.rfind(')')?;
+            if close < open {
+                return None;
+            }
+            let args_str = &rest[open + 1..close];
+            let args = if args_str.trim().is_empty() {
+                Vec::new()
+            } else {
+                args_str.split(',').map(|arg| unquote(arg.trim())).collect()
+            };
+            (&rest[..open], args)
+        }
+        None => (rest, Vec::new()),
+    };
+
+    let name = name.trim();
+    if name.is_empty() {
+        return None;
+    }
+
+    Some((name.to_string(), args))
+}
+
+/// Strips one layer of matching `"`/`'` quotes from `arg`, if present.
+fn unquote(arg: &str) -> String {
+    let bytes = arg.as_bytes();
+    if bytes.len() >= 2 {
+        let first = bytes[0];
+        let last = bytes[bytes.len() - 1];
+        if (first == b'"' && last == b'"') || (first == b'\'' && last == b'\'') {
+            return arg[1..arg.len() - 1].to_string();
+        }
+    }
+    arg.to_string()
+}
+
+/// Recursively applies `registry`'s decorators to every string in `frontmatter`
+/// written as a decorator expression (see [`parse_decorator_expr`]) - the expression's
+/// first argument (or an empty string, for a bare `@name`) is passed as the decorated
+/// value and the rest as params; every decorator sees the same `context`, a snapshot
+/// of `frontmatter` taken before any decorator in it ran. A string that isn't a
+/// decorator expression passes through unchanged.
+///
+/// # Errors
+/// Returns [`MdxError::UnknownDecorator`] on the first decorator name referenced that
+/// isn't registered, or any error a decorator itself returns.
+pub fn apply_to_frontmatter(
+    frontmatter: &mut Value,
+    registry: &DecoratorRegistry,
+) -> Result<(), MdxError> {
+    let context = frontmatter.clone();
+    apply_recursive(frontmatter, registry, &context)
+}
+
+fn apply_recursive(value: &mut Value, registry: &DecoratorRegistry, context: &Value) -> Result<(), MdxError> {
+    match value {
+        Value::String(s) => {
+            if let Some((name, mut args)) = parse_decorator_expr(s) {
+                let target = if args.is_empty() {
+                    String::new()
+                } else {
+                    args.remove(0)
+                };
+                *s = registry.apply(&name, &target, &args, context)?;
+            }
+        }
+        Value::Array(items) => {
+            for item in items {
+                apply_recursive(item, registry, context)?;
+            }
+        }
+        Value::Object(map) => {
+            for v in map.values_mut() {
+                apply_recursive(v, registry, context)?;
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_decorator_expr_bare() {
+        assert_eq!(
+            parse_decorator_expr("@uppercase"),
+            Some(("uppercase".to_string(), vec![]))
+        );
+    }
+
+    #[test]
+    fn test_parse_decorator_expr_with_params() {
+        assert_eq!(
+            parse_decorator_expr("@truncate(80)"),
+            Some(("truncate".to_string(), vec!["80".to_string()]))
+        );
+    }
+
+    #[test]
+    fn test_parse_decorator_expr_with_quoted_string_param() {
+        assert_eq!(
+            parse_decorator_expr(r#"@currency("USD")"#),
+            Some(("currency".to_string(), vec!["USD".to_string()]))
+        );
+    }
+
+    #[test]
+    fn test_parse_decorator_expr_rejects_non_decorator() {
+        assert_eq!(parse_decorator_expr("uppercase"), None);
+    }
+
+    #[test]
+    fn test_registry_apply_unknown_decorator_errors() {
+        let registry = DecoratorRegistry::new();
+        let err = registry
+            .apply("slugify", "Hello World", &[], &Value::Null)
+            .unwrap_err();
+        assert!(matches!(err, MdxError::UnknownDecorator(name) if name == "slugify"));
+    }
+
+    #[test]
+    fn test_registry_apply_registered_decorator() {
+        let mut registry = DecoratorRegistry::new();
+        registry.register("uppercase", |value, _params, _context| Ok(value.to_uppercase()));
+
+        let result = registry.apply("uppercase", "hello", &[], &Value::Null).unwrap();
+        assert_eq!(result, "HELLO");
+    }
+
+    #[test]
+    fn test_apply_to_frontmatter_decorates_matching_strings() {
+        let mut registry = DecoratorRegistry::new();
+        registry.register("uppercase", |value, _params, _context| Ok(value.to_uppercase()));
+
+        let mut frontmatter = serde_json::json!({ "title": "@uppercase(hello)" });
+        apply_to_frontmatter(&mut frontmatter, &registry).unwrap();
+
+        assert_eq!(frontmatter["title"], serde_json::json!("HELLO"));
+    }
+
+    #[test]
+    fn test_apply_to_frontmatter_fails_on_unknown_decorator() {
+        let registry = DecoratorRegistry::new();
+        let mut frontmatter = serde_json::json!({ "title": "@slugify(Hello World)" });
+
+        let err = apply_to_frontmatter(&mut frontmatter, &registry).unwrap_err();
+        assert!(matches!(err, MdxError::UnknownDecorator(name) if name == "slugify"));
+    }
+}
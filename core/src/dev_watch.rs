@@ -0,0 +1,102 @@
+//! Dev-mode hot reload: polls `static_dir` for changes, invalidates cached renderers,
+//! and notifies connected browsers over server-sent events.
+//!
+//! Engines and components are loaded once into each cached [`JsRenderer`](crate::renderer::JsRenderer)
+//! (see [`crate::renderer::pool`]), so editing `engine.min.js`/`core.js` during local
+//! development previously required restarting the whole server before the change took
+//! effect. [`watch_static_dir`] spawns a background thread that polls file
+//! modification times and, on a change, calls [`RendererPool::invalidate`] so the next
+//! request on every thread rebuilds its renderers from the edited files, and fires
+//! [`ReloadBroadcaster::notify`] so a browser subscribed to the SSE endpoint can
+//! refresh itself. This is opt-in (`--watch`, see `main.rs`'s `ServeArgs`); the
+//! production path never constructs a [`ReloadBroadcaster`] or spawns this thread, and
+//! keeps caching renderers for the life of the process.
+
+use crate::renderer::RendererPool;
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use tokio::sync::broadcast;
+
+/// Fans out a "something changed, reload" signal to every connected SSE client.
+///
+/// Wraps a [`broadcast::Sender`] rather than exposing it directly so callers can't
+/// observe lag or closed-channel errors that don't matter for a fire-and-forget
+/// notification: a client that isn't listening (not yet subscribed, or subscribed and
+/// dropped) simply doesn't get this one and picks up the next change, or just reloads
+/// later when it notices the page is stale.
+#[derive(Clone)]
+pub struct ReloadBroadcaster {
+    sender: broadcast::Sender<()>,
+}
+
+impl ReloadBroadcaster {
+    /// Creates a broadcaster. `capacity` bounds how many un-received notifications a
+    /// slow subscriber can lag behind before the oldest is dropped; a handful is
+    /// plenty since every notification carries the same "reload" meaning.
+    pub fn new(capacity: usize) -> Self {
+        let (sender, _receiver) = broadcast::channel(capacity);
+        Self { sender }
+    }
+
+    /// Subscribes a new client, typically one SSE connection.
+    pub fn subscribe(&self) -> broadcast::Receiver<()> {
+        self.sender.subscribe()
+    }
+
+    /// Notifies every current subscriber that they should reload.
+    pub fn notify(&self) {
+        // No subscribers is not an error - a browser may not have connected yet.
+        let _ = self.sender.send(());
+    }
+}
+
+/// Spawns a background thread that polls `dir` for file changes every `interval` and,
+/// on a change, invalidates `pool`'s cached renderers and notifies `broadcaster`'s
+/// subscribers.
+///
+/// Polling (rather than a filesystem-event watcher) keeps this dependency-free and is
+/// more than responsive enough for a developer saving a file; `interval` is exposed
+/// (`--watch-interval-ms`) for anyone who wants it tighter or looser.
+pub fn watch_static_dir(dir: PathBuf, interval: Duration, pool: RendererPool, broadcaster: ReloadBroadcaster) {
+    std::thread::spawn(move || {
+        let mut last_snapshot = snapshot_mtimes(&dir);
+        loop {
+            std::thread::sleep(interval);
+            let snapshot = snapshot_mtimes(&dir);
+            if snapshot != last_snapshot {
+                pool.invalidate();
+                broadcaster.notify();
+                last_snapshot = snapshot;
+            }
+        }
+    });
+}
+
+/// Maps every regular file under `dir` (recursively) to its last-modified time, used
+/// to detect a change between two polls. Missing/unreadable entries are skipped
+/// rather than failing the whole scan, since a file can legitimately disappear mid-save
+/// (editors often write via a temp file and rename).
+fn snapshot_mtimes(dir: &Path) -> HashMap<PathBuf, SystemTime> {
+    let mut snapshot = HashMap::new();
+    collect_mtimes(dir, &mut snapshot);
+    snapshot
+}
+
+fn collect_mtimes(dir: &Path, snapshot: &mut HashMap<PathBuf, SystemTime>) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            collect_mtimes(&path, snapshot);
+        } else if let Ok(metadata) = entry.metadata() {
+            if let Ok(modified) = metadata.modified() {
+                snapshot.insert(path, modified);
+            }
+        }
+    }
+}
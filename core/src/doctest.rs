@@ -0,0 +1,95 @@
+//! Fenced-code-block extraction and optional execution, modeled on rustdoc's
+//! `find_testable_code`/doctest runner - see
+//! [`crate::models::RenderSettings::doctest`].
+//!
+//! Every fenced block in the raw (pre-render) MDX source is recovered with its
+//! language tag, starting line, and body via [`extract_and_run`]. A block tagged
+//! `js`/`javascript`/`ts`/`typescript`/`jsx`/`tsx` - unless its info string carries
+//! `ignore` or `no_run` (see [`crate::fence::FenceInfo`]) - is additionally
+//! transformed and evaluated through the renderer's V8 isolate, so a broken example
+//! is reported against its own entry instead of failing the whole file.
+
+use std::sync::LazyLock;
+
+use regex::Regex;
+
+use crate::error::LineIndex;
+use crate::fence::{parse_fence_info, FenceInfo};
+use crate::models::DoctestResult;
+use crate::renderer::JsRenderer;
+use crate::transform::transform_component_function;
+
+/// Matches a fenced code block's opening or closing delimiter line: three or more
+/// backticks or tildes, followed by the raw info string. Same rule as
+/// [`crate::fence`]'s own delimiter pattern, duplicated locally because this pass also
+/// needs each block's body and starting line, not just its info string - opens and
+/// closes are told apart the same way, by relying on them alternating rather than
+/// tracking a stack (fences don't nest in CommonMark).
+///
+/// # Safety
+/// Pattern is compile-time constant and known to be valid.
+static FENCE_DELIMITER: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?m)^(?:`{3,}|~{3,})[ \t]*([^\n]*)$").expect("hardcoded regex pattern is valid")
+});
+
+/// Language tags treated as executable JavaScript/TypeScript.
+const EXECUTABLE_LANGUAGES: &[&str] =
+    &["js", "javascript", "ts", "typescript", "jsx", "tsx"];
+
+/// Extracts every fenced code block from `content`, in document order, and evaluates
+/// each executable one through `renderer` - see the module-level docs.
+pub(crate) fn extract_and_run(content: &str, renderer: &JsRenderer) -> Vec<DoctestResult> {
+    let line_index = LineIndex::new(content);
+    let delimiters: Vec<_> = FENCE_DELIMITER.captures_iter(content).collect();
+
+    let mut results = Vec::new();
+    let mut pairs = delimiters.chunks_exact(2);
+    for pair in &mut pairs {
+        let [open, close] = pair else {
+            unreachable!("chunks_exact(2) always yields slices of length 2")
+        };
+
+        let whole_open = open.get(0).expect("group 0 always matches");
+        let info = parse_fence_info(open[1].trim());
+        let code_start = (whole_open.end() + 1).min(content.len());
+        let code_end = close.get(0).expect("group 0 always matches").start();
+        let code = content.get(code_start..code_end).unwrap_or_default().trim_end_matches('\n');
+
+        let (line0, _) = line_index.line_col(whole_open.start() as u32);
+        results.push(run_one(info, code, line0 as usize + 1, renderer));
+    }
+    results
+}
+
+/// Builds one [`DoctestResult`], running `code` through `renderer` first if its
+/// language is executable and not opted out of via `ignore`/`no_run`.
+fn run_one(info: FenceInfo, code: &str, line: usize, renderer: &JsRenderer) -> DoctestResult {
+    let is_executable = !info.ignore
+        && !info.no_run
+        && info
+            .language
+            .as_deref()
+            .is_some_and(|lang| EXECUTABLE_LANGUAGES.contains(&lang.to_ascii_lowercase().as_str()));
+
+    let error = is_executable.then(|| evaluate(code, renderer)).flatten();
+
+    DoctestResult {
+        language: info.language,
+        line,
+        code: code.to_string(),
+        executed: is_executable,
+        error,
+    }
+}
+
+/// Transforms `code` from TSX/JSX to plain JavaScript (a no-op for code that's already
+/// plain JS) and evaluates it through `renderer`, returning the failure message if
+/// either step fails.
+fn evaluate(code: &str, renderer: &JsRenderer) -> Option<String> {
+    let transformed = match transform_component_function(code) {
+        Ok(js) => js,
+        Err(e) => return Some(format!("{e:#}")),
+    };
+
+    renderer.evaluate_snippet(&transformed).err().map(|e| format!("{e:#}"))
+}
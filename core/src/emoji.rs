@@ -0,0 +1,125 @@
+//! Emoji shortcode expansion, applied over rendered HTML.
+//!
+//! Expands GitHub/Slack-style `:name:` shortcodes in prose (`:tada:` -> 🎉, `:rocket:`
+//! -> 🚀) against a built-in name -> Unicode codepoint table, so authors can write
+//! portable ASCII shortcodes instead of pasting literal emoji into MDX source. This
+//! mirrors the `render_emoji` feature from zola's changelog.
+//!
+//! Expansion only ever touches prose text nodes: any `<pre>...</pre>` or
+//! `<code>...</code>` span, any other HTML tag, and any `{...}` JSX expression are
+//! passed through byte-for-byte - see [`SKIP_REGION`], the same pattern
+//! [`crate::typography`] uses for its own pass. A `:name:` whose name isn't in [`EMOJI_TABLE`] is left
+//! untouched, so URLs containing colons (`http://...`) and non-emoji uses of `:...:`
+//! are unaffected.
+
+use regex::Regex;
+use std::collections::HashMap;
+use std::sync::LazyLock;
+
+/// Matches a span that emoji expansion must leave untouched: a `<pre>` or `<code>`
+/// element's full contents, any other single HTML tag, or a `{...}` JSX expression.
+///
+/// # Safety
+/// Pattern is compile-time constant and known to be valid.
+static SKIP_REGION: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"(?s)<pre\b[^>]*>.*?</pre>|<code\b[^>]*>.*?</code>|<[^>]*>|\{[^{}]*\}"#)
+        .expect("hardcoded regex pattern is valid")
+});
+
+/// Matches a `:name:` shortcode - letters, digits, underscores, and `+`/`-` (e.g.
+/// `:+1:`, `:man-shrugging:`), consistent with gemoji naming.
+static SHORTCODE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?x):([a-zA-Z0-9_+-]+):").expect("hardcoded regex pattern is valid")
+});
+
+/// Name -> Unicode emoji table for the shortcodes [`expand_emoji`] recognizes.
+/// Not exhaustive - a small, commonly-used subset of the gemoji set.
+static EMOJI_TABLE: LazyLock<HashMap<&'static str, &'static str>> = LazyLock::new(|| {
+    HashMap::from([
+        ("tada", "\u{1F389}"),
+        ("rocket", "\u{1F680}"),
+        ("smile", "\u{1F604}"),
+        ("smiley", "\u{1F603}"),
+        ("grinning", "\u{1F600}"),
+        ("wink", "\u{1F609}"),
+        ("joy", "\u{1F602}"),
+        ("heart", "\u{2764}\u{FE0F}"),
+        ("thumbsup", "\u{1F44D}"),
+        ("+1", "\u{1F44D}"),
+        ("thumbsdown", "\u{1F44E}"),
+        ("-1", "\u{1F44E}"),
+        ("fire", "\u{1F525}"),
+        ("star", "\u{2B50}"),
+        ("sparkles", "\u{2728}"),
+        ("warning", "\u{26A0}\u{FE0F}"),
+        ("bug", "\u{1F41B}"),
+        ("checkmark", "\u{2714}\u{FE0F}"),
+        ("white_check_mark", "\u{2705}"),
+        ("x", "\u{274C}"),
+        ("bulb", "\u{1F4A1}"),
+        ("memo", "\u{1F4DD}"),
+        ("book", "\u{1F4D6}"),
+        ("books", "\u{1F4DA}"),
+        ("package", "\u{1F4E6}"),
+        ("wrench", "\u{1F527}"),
+        ("hammer", "\u{1F528}"),
+        ("lock", "\u{1F512}"),
+        ("unlock", "\u{1F513}"),
+        ("key", "\u{1F511}"),
+        ("zap", "\u{26A1}"),
+        ("eyes", "\u{1F440}"),
+        ("clap", "\u{1F44F}"),
+        ("raised_hands", "\u{1F64C}"),
+        ("pray", "\u{1F64F}"),
+        ("100", "\u{1F4AF}"),
+        ("question", "\u{2753}"),
+        ("exclamation", "\u{2757}"),
+        ("construction", "\u{1F6A7}"),
+        ("recycle", "\u{267B}\u{FE0F}"),
+        ("art", "\u{1F3A8}"),
+        ("camera", "\u{1F4F7}"),
+        ("email", "\u{1F4E7}"),
+        ("calendar", "\u{1F4C5}"),
+        ("chart_with_upwards_trend", "\u{1F4C8}"),
+        ("globe_with_meridians", "\u{1F30F}"),
+        ("coffee", "\u{2615}"),
+        ("pizza", "\u{1F355}"),
+        ("tv", "\u{1F4FA}"),
+        ("moon", "\u{1F319}"),
+        ("sun", "\u{2600}\u{FE0F}"),
+        ("cloud", "\u{2601}\u{FE0F}"),
+        ("snowflake", "\u{2744}\u{FE0F}"),
+        ("dog", "\u{1F436}"),
+        ("cat", "\u{1F431}"),
+    ])
+});
+
+/// Expands every recognized `:name:` shortcode in `html`, skipping `<pre>`/`<code>`
+/// content, other HTML tags, and `{...}` JSX expressions - see
+/// [`crate::models::RenderSettings::render_emoji`].
+pub(crate) fn expand_emoji(html: &str) -> String {
+    let mut out = String::with_capacity(html.len());
+    let mut last = 0;
+    for region in SKIP_REGION.find_iter(html) {
+        out.push_str(&expand_prose(&html[last..region.start()]));
+        out.push_str(region.as_str());
+        last = region.end();
+    }
+    out.push_str(&expand_prose(&html[last..]));
+    out
+}
+
+/// Expands shortcodes in a single run of prose text, leaving any unrecognized `:name:`
+/// exactly as written.
+fn expand_prose(text: &str) -> String {
+    SHORTCODE
+        .replace_all(text, |caps: &regex::Captures<'_>| {
+            let name = &caps[1];
+            EMOJI_TABLE
+                .get(name)
+                .copied()
+                .unwrap_or_else(|| caps.get(0).unwrap().as_str())
+                .to_string()
+        })
+        .into_owned()
+}
@@ -16,11 +16,59 @@
 //! - `TsxTransform`: TSX to JavaScript transformation errors (with location info)
 //! - `SourceType`: Source type detection failures
 //! - Resource limit errors: `ContentTooLarge`, `BatchTooLarge`, `ComponentCodeTooLarge`, `EngineCodeTooLarge`
+//! - `UnresolvedImport`: a component import with no entry in the configured import map
+//! - `SourceMap`: a source map failed to parse or re-serialize
+//! - `NotUtf8`: a batch input's bytes weren't valid UTF-8, recorded as a skipped-file
+//!   diagnostic rather than panicking (see [`crate::transform::process_component_batch`])
+//! - `UnknownDecorator`: a template referenced `@name(...)` with no matching entry in
+//!   the [`crate::decorators::DecoratorRegistry`]
+//! - `ComponentCycle`: a component (transitively) includes itself, named with the
+//!   offending reference chain (see [`crate::transform::component_dependency_order`])
+//! - `JsExprParse`: a registered [`crate::parser_hooks::ParserHookRegistry`] hook
+//!   rejected a `{...}` expression or ESM `import`/`export` block
+//! - `RewriteRuleParse`: a [`crate::rewrite::RewriteRule`] source string was malformed -
+//!   bad `==>` pattern/template syntax, or a metavariable mismatch between the two sides
+//! - `PartialInclude`: an `<Include name="..." />`/`{{> name}}` reference named a
+//!   partial missing from the supplied registry, or includes nested deeply enough to
+//!   suggest a cycle (see [`crate::partials::MAX_INCLUDE_DEPTH`])
 //!
 //! ## Source Location
 //!
 //! Parse and transform errors include source location information when available,
 //! allowing IDEs and tools to pinpoint exact error positions.
+//!
+//! ## Diagnostic Codes
+//!
+//! [`ParseError`] can additionally carry a stable [`diagnostic_codes`] code and a
+//! rendered code frame, populated by
+//! [`crate::transform::diagnose_component_code`]'s diagnostics-collecting mode.
+//!
+//! [`ParseError::render_snippet`] and [`MdxError::render`] go further, rendering a
+//! caret-underlined view of the offending source line on demand (rather than the
+//! fixed `frame` captured up front), for callers such as CLIs that want the bare
+//! `line:col: message` in one context and the full annotated snippet in another.
+//!
+//! ## Machine-Readable Classification
+//!
+//! [`MdxError::kind`] returns a `#[non_exhaustive]` [`MdxErrorKind`], and
+//! [`MdxError::location`] returns the error's [`SourceLocation`] if any, so API
+//! consumers can branch on error category or pinpoint it without matching on
+//! [`MdxError`] itself (also `#[non_exhaustive]`) or parsing the `Display` message.
+//!
+//! ## Editor Integration
+//!
+//! [`MdxError::to_lsp_diagnostics`] converts an error into Language Server Protocol
+//! [`LspDiagnostic`] structs - already `Serialize`, so a language server can forward
+//! them to an editor client as-is instead of re-deriving positions from the rendered
+//! message.
+//!
+//! ## Diagnostic Rendering Style
+//!
+//! [`DiagnosticStyle`] (set via [`crate::models::RenderSettings::diagnostics`]) chooses
+//! between the hand-rolled [`crate::service::Diagnostic::render_diagnostic`] frame
+//! (`Plain`, the default) and an `ariadne`-built report string populated into
+//! [`crate::service::Diagnostic::report`] (`Pretty`), for a CLI or LSP caller that wants
+//! a ready-made annotated snippet.
 
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
@@ -60,6 +108,58 @@ impl SourceLocation {
     }
 }
 
+/// How seriously a [`ParseError`] should be treated: whether it should fail the
+/// transform outright, or merely be surfaced to the caller alongside a successful
+/// result, in the spirit of linters that report warnings without failing the build.
+///
+/// Defaults to [`Severity::Error`] so existing diagnostics - all of which predate this
+/// enum and were implicitly fatal - keep their behavior.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    /// Fails the transform; the default for backward compatibility.
+    #[default]
+    Error,
+    /// Surfaced to the caller but doesn't prevent a successful result.
+    Warning,
+    /// Informational, non-actionable note.
+    Info,
+    /// A low-priority suggestion, typically for editor tooling.
+    Hint,
+}
+
+/// How a diagnostic's source context should be rendered for display (see
+/// [`crate::models::RenderSettings::diagnostics`]).
+///
+/// Defaults to [`DiagnosticStyle::Plain`] so existing callers - who only ever see
+/// [`crate::service::Diagnostic`]'s bare `line:column` fields - see no change in
+/// behavior until they opt in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum DiagnosticStyle {
+    /// Just the structured [`crate::service::Diagnostic`] fields - no extra rendered
+    /// report string.
+    #[default]
+    Plain,
+    /// Also populate [`crate::service::Diagnostic::report`] with an `ariadne`-rendered,
+    /// caret-underlined report built from the original source, for a CLI or LSP caller
+    /// that wants to print a ready-made snippet rather than re-deriving one from line
+    /// and column.
+    Pretty,
+}
+
+impl std::fmt::Display for Severity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let label = match self {
+            Self::Error => "error",
+            Self::Warning => "warning",
+            Self::Info => "info",
+            Self::Hint => "hint",
+        };
+        write!(f, "{label}")
+    }
+}
+
 /// A single parse or transform error with optional location
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ParseError {
@@ -71,6 +171,19 @@ pub struct ParseError {
     /// Help text or suggestion (if available)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub help: Option<String>,
+    /// Stable diagnostic code (see [`diagnostic_codes`]), set for diagnostics raised by
+    /// our own validation layer rather than passed through from Oxc.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub code: Option<String>,
+    /// A short rendered code frame (source line plus a caret underline) pointing at
+    /// the offending span, for diagnostics collected via
+    /// [`crate::transform::diagnose_component_code`].
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub frame: Option<String>,
+    /// How seriously this diagnostic should be treated. Defaults to
+    /// [`Severity::Error`] for diagnostics constructed before this field existed.
+    #[serde(default)]
+    pub severity: Severity,
 }
 
 impl ParseError {
@@ -80,6 +193,9 @@ impl ParseError {
             message: message.into(),
             location: None,
             help: None,
+            code: None,
+            frame: None,
+            severity: Severity::Error,
         }
     }
 
@@ -89,6 +205,22 @@ impl ParseError {
             message: message.into(),
             location: Some(location),
             help: None,
+            code: None,
+            frame: None,
+            severity: Severity::Error,
+        }
+    }
+
+    /// Creates a new parse error carrying a stable diagnostic code (see
+    /// [`diagnostic_codes`]), for use with the builder methods below.
+    pub fn with_code(code: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            message: message.into(),
+            location: None,
+            help: None,
+            code: Some(code.into()),
+            frame: None,
+            severity: Severity::Error,
         }
     }
 
@@ -97,6 +229,80 @@ impl ParseError {
         self.help = Some(help.into());
         self
     }
+
+    /// Attaches a source location to this error
+    pub fn located_at(mut self, location: SourceLocation) -> Self {
+        self.location = Some(location);
+        self
+    }
+
+    /// Attaches a rendered code frame to this error
+    pub fn with_frame(mut self, frame: impl Into<String>) -> Self {
+        self.frame = Some(frame.into());
+        self
+    }
+
+    /// Sets this diagnostic's [`Severity`], e.g. downgrading a validation issue to a
+    /// non-fatal [`Severity::Warning`] so it can be surfaced without aborting the
+    /// transform.
+    pub fn with_severity(mut self, severity: Severity) -> Self {
+        self.severity = severity;
+        self
+    }
+
+    /// Prefixes `context` onto this error's message, e.g. naming which component it
+    /// came from in a batch - leaves [`Self::location`], [`Self::code`] and
+    /// [`Self::frame`] untouched, unlike rebuilding the message from scratch.
+    pub fn prefixed(mut self, context: impl std::fmt::Display) -> Self {
+        self.message = format!("{context}: {}", self.message);
+        self
+    }
+
+    /// Renders a multi-line, rustc-style annotated source snippet for this error: the
+    /// offending line prefixed with a `N | ` gutter, a caret underline beneath it
+    /// spanning `location.length` bytes (clamped to the rest of the line, minimum one
+    /// caret), and a trailing `= help:` line when [`Self::help`] is set.
+    ///
+    /// Falls back to [`Self::message`] alone when no [`SourceLocation`] is attached.
+    pub fn render_snippet(&self, source: &str) -> String {
+        let Some(loc) = &self.location else {
+            return self.message.clone();
+        };
+
+        let offset = (loc.offset as usize).min(source.len());
+        let line_start = source[..offset].rfind('\n').map(|i| i + 1).unwrap_or(0);
+        let line_end = source[offset..]
+            .find('\n')
+            .map(|i| offset + i)
+            .unwrap_or(source.len());
+        let line_text = &source[line_start..line_end];
+
+        let gutter = format!("{} | ", loc.display_line());
+        let gutter_padding = " ".repeat(gutter.chars().count());
+        let column = loc.column as usize;
+        let remaining_on_line = line_text.chars().count().saturating_sub(column).max(1);
+        let caret_len = (loc.length.max(1) as usize).min(remaining_on_line);
+
+        let mut rendered = format!(
+            "{gutter}{line_text}\n{gutter_padding}{}{}",
+            " ".repeat(column),
+            "^".repeat(caret_len)
+        );
+        if let Some(help) = &self.help {
+            rendered.push_str(&format!("\n{gutter_padding}= help: {help}"));
+        }
+        rendered
+    }
+}
+
+/// Stable diagnostic codes for validation-layer errors, in the spirit of Babel's
+/// named error-message catalog: a code travels with the error so tooling can key off
+/// it instead of pattern-matching the human message, which may be reworded over time.
+pub mod diagnostic_codes {
+    /// `export default` didn't use the required `function Component() {{ ... }}` shape.
+    pub const INVALID_EXPORT_DEFAULT: &str = "invalid-export-default";
+    /// A bare import specifier had no entry in a caller-supplied import allow-list.
+    pub const DISALLOWED_IMPORT: &str = "disallowed-import";
 }
 
 impl std::fmt::Display for ParseError {
@@ -104,13 +310,14 @@ impl std::fmt::Display for ParseError {
         if let Some(loc) = &self.location {
             write!(
                 f,
-                "{}:{}: {}",
+                "{}: {}:{}: {}",
+                self.severity,
                 loc.display_line(),
                 loc.display_column(),
                 self.message
             )
         } else {
-            write!(f, "{}", self.message)
+            write!(f, "{}: {}", self.severity, self.message)
         }
     }
 }
@@ -154,7 +361,58 @@ pub fn byte_offset_to_line_col(source: &str, offset: u32) -> (u32, u32) {
     (line, col)
 }
 
+/// A precomputed index of line-start byte offsets, letting repeated offset-to-line/col
+/// lookups run in `O(log n)` instead of re-scanning the whole source on every call.
+///
+/// Built once per source and reused across every [`ParseError`] produced for that
+/// source, so a file with `k` diagnostics costs `O(n + k log n)` overall rather than
+/// [`byte_offset_to_line_col`]'s `O(n * k)`.
+#[derive(Debug, Clone)]
+pub struct LineIndex<'a> {
+    source: &'a str,
+    /// Byte offset of the start of each line, in ascending order; always begins with 0.
+    line_starts: Vec<u32>,
+}
+
+impl<'a> LineIndex<'a> {
+    /// Walks `source` once, recording the byte offset of every line start.
+    pub fn new(source: &'a str) -> Self {
+        let mut line_starts = vec![0u32];
+        for (i, byte) in source.bytes().enumerate() {
+            if byte == b'\n' {
+                line_starts.push(i as u32 + 1);
+            }
+        }
+        Self { source, line_starts }
+    }
+
+    /// Resolves a byte offset to its 0-indexed `(line, column)`, matching the
+    /// semantics of [`byte_offset_to_line_col`]. The line is found via binary search
+    /// over the precomputed line starts; the column is counted in `char`s (not bytes)
+    /// from that line's start, so multi-byte UTF-8 is handled correctly.
+    pub fn line_col(&self, offset: u32) -> (u32, u32) {
+        let line = match self.line_starts.binary_search(&offset) {
+            Ok(exact) => exact,
+            Err(insertion_point) => insertion_point - 1,
+        };
+        let line_start = self.line_starts[line] as usize;
+        let offset = (offset as usize).min(self.source.len());
+        let column = self.source[line_start..offset].chars().count() as u32;
+        (line as u32, column)
+    }
+}
+
+/// Validates that `bytes` is UTF-8 before it's handed to a string-based parser,
+/// returning [`MdxError::NotUtf8`] (naming `path`) instead of panicking or producing an
+/// opaque failure when it isn't - the first step of a batch's per-input error recovery.
+pub fn validate_utf8<'a>(path: &str, bytes: &'a [u8]) -> Result<&'a str, MdxError> {
+    std::str::from_utf8(bytes).map_err(|_| MdxError::NotUtf8 {
+        path: path.to_string(),
+    })
+}
+
 /// Custom error type for MDX processing
+#[non_exhaustive]
 #[derive(Error, Debug)]
 pub enum MdxError {
     /// Failed to parse YAML frontmatter from MDX content
@@ -196,9 +454,261 @@ pub enum MdxError {
     /// Invalid export default statement - must be `export default function Component`
     #[error("Invalid export: '{0}' violates the naming convention. Use 'export default function Component() {{ ... }}' instead")]
     InvalidExportDefault(String),
+
+    /// A component's static import had no entry in the configured import map
+    #[error("Unresolved import: '{0}' has no entry in the configured import map")]
+    UnresolvedImport(String),
+
+    /// Failed to parse or re-serialize a source map
+    #[error("Failed to process source map: {0}")]
+    SourceMap(String),
+
+    /// Input for `path` wasn't valid UTF-8, so it was skipped rather than processed
+    #[error("'{path}' is not valid UTF-8")]
+    NotUtf8 {
+        /// Name or path identifying the skipped input within its batch
+        path: String,
+    },
+
+    /// A template referenced `@{0}(...)`, but no decorator of that name is registered
+    /// on the service's [`crate::decorators::DecoratorRegistry`]
+    #[error("Unknown decorator '@{0}' - no matching entry in the decorator registry")]
+    UnknownDecorator(String),
+
+    /// A component (transitively) includes itself - `{0}` is the offending reference
+    /// chain, e.g. `"Card -> Footer -> Card"` - detected by
+    /// [`crate::transform::component_dependency_order`] before it would otherwise only
+    /// surface as a stack overflow in the JS engine at render time
+    #[error("Component reference cycle: {0}")]
+    ComponentCycle(String),
+
+    /// A Lua-scripted container directive or template utility (see
+    /// [`crate::scripting`]) failed - unknown name, compile failure, execution
+    /// timeout, or a runtime error raised by the script itself
+    #[error("{0}")]
+    LuaScript(String),
+
+    /// A registered [`crate::parser_hooks::ParserHookRegistry`] expression or ESM
+    /// parser hook rejected a `{{...}}` expression or `import`/`export` block outright
+    /// (see [`crate::parser_hooks::ParseSignal::Error`])
+    #[error("{0}")]
+    JsExprParse(String),
+
+    /// A [`crate::rewrite::RewriteRule`] failed to parse - malformed `==>` pattern or
+    /// template syntax, or a pattern/template metavariable mismatch
+    #[error("{0}")]
+    RewriteRuleParse(String),
+
+    /// An `<Include name="..." />`/`{{> name}}` reference (see [`crate::partials`])
+    /// named a partial absent from the supplied registry, or nested past
+    /// [`crate::partials::MAX_INCLUDE_DEPTH`] - almost always an include cycle
+    #[error("{0}")]
+    PartialInclude(String),
+
+    /// A `{{#each}}`/`{{#if}}`/`{{#with}}` block helper (see
+    /// [`crate::block_helpers`]) was malformed - an unrecognized helper name, or an
+    /// opening tag with no matching `{{/name}}` closing tag
+    #[error("{0}")]
+    BlockHelper(String),
+
+    /// An async render (see `crate::renderer::JsRenderer::render_component_async` and
+    /// its siblings) exceeded [`crate::models::ResourceLimits::max_render_time_ms`]
+    /// milliseconds while draining the JS event loop - typically a component awaiting
+    /// a promise that never settles
+    #[error("Render exceeded the {0}ms time limit")]
+    RenderTimeout(u64),
+
+    /// Component code attempted a capability denied by
+    /// [`crate::models::ComponentPermissions`] (`{0}` names the capability, e.g.
+    /// `"eval"` or `"network"`) - see
+    /// `crate::renderer::JsRenderer::apply_permissions`.
+    #[error("Permission denied: component code attempted to use '{0}'")]
+    PermissionDenied(String),
+
+    /// Failed to read a [`crate::models::HtmlFragmentSource::Path`] fragment for
+    /// [`crate::models::RenderSettings::external_html`]
+    #[error("Failed to read external HTML fragment: {0}")]
+    ExternalHtml(String),
+}
+
+/// Stable, machine-readable classification of an [`MdxError`]'s variant, for API
+/// consumers that want to branch on error category (e.g. to decide whether a failure
+/// is retryable) without string-matching [`MdxError`]'s `Display` message, which may be
+/// reworded over time. Marked `#[non_exhaustive]` so a new [`MdxError`] variant can get
+/// a new kind without breaking a downstream `match`.
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MdxErrorKind {
+    /// See [`MdxError::FrontmatterParse`]
+    FrontmatterParse,
+    /// See [`MdxError::MarkdownRender`]
+    MarkdownRender,
+    /// See [`MdxError::TsxParse`]
+    TsxParse,
+    /// See [`MdxError::TsxTransform`]
+    TsxTransform,
+    /// See [`MdxError::SourceType`]
+    SourceType,
+    /// See [`MdxError::ContentTooLarge`]
+    ContentTooLarge,
+    /// See [`MdxError::BatchTooLarge`]
+    BatchTooLarge,
+    /// See [`MdxError::ComponentCodeTooLarge`]
+    ComponentCodeTooLarge,
+    /// See [`MdxError::EngineCodeTooLarge`]
+    EngineCodeTooLarge,
+    /// See [`MdxError::InvalidExportDefault`]
+    InvalidExportDefault,
+    /// See [`MdxError::UnresolvedImport`]
+    UnresolvedImport,
+    /// See [`MdxError::SourceMap`]
+    SourceMap,
+    /// See [`MdxError::NotUtf8`]
+    NotUtf8,
+    /// See [`MdxError::UnknownDecorator`]
+    UnknownDecorator,
+    /// See [`MdxError::ComponentCycle`]
+    ComponentCycle,
+    /// See [`MdxError::LuaScript`]
+    LuaScript,
+    /// See [`MdxError::JsExprParse`]
+    JsExprParse,
+    /// See [`MdxError::RewriteRuleParse`]
+    RewriteRuleParse,
+    /// See [`MdxError::PartialInclude`]
+    PartialInclude,
+    /// See [`MdxError::BlockHelper`]
+    BlockHelper,
+    /// See [`MdxError::RenderTimeout`]
+    RenderTimeout,
+    /// See [`MdxError::PermissionDenied`]
+    PermissionDenied,
+    /// See [`MdxError::ExternalHtml`]
+    ExternalHtml,
+}
+
+impl MdxErrorKind {
+    /// Stable, kebab-case category code for this kind, in the same style as
+    /// [`diagnostic_codes`] - used as a [`crate::service::Diagnostic`]'s fallback code
+    /// when the underlying [`ParseError`] didn't already carry a more specific one.
+    pub fn diagnostic_code(self) -> &'static str {
+        match self {
+            Self::FrontmatterParse => "frontmatter-parse",
+            Self::MarkdownRender => "markdown-render",
+            Self::TsxParse => "jsx-parse",
+            Self::TsxTransform => "tsx-transform",
+            Self::SourceType => "source-type",
+            Self::ContentTooLarge => "content-too-large",
+            Self::BatchTooLarge => "batch-too-large",
+            Self::ComponentCodeTooLarge => "component-code-too-large",
+            Self::EngineCodeTooLarge => "engine-code-too-large",
+            Self::InvalidExportDefault => diagnostic_codes::INVALID_EXPORT_DEFAULT,
+            Self::UnresolvedImport => "unresolved-import",
+            Self::SourceMap => "source-map",
+            Self::NotUtf8 => "not-utf8",
+            Self::UnknownDecorator => "unknown-decorator",
+            Self::ComponentCycle => "component-cycle",
+            Self::LuaScript => "lua-script",
+            Self::JsExprParse => "js-expr-parse",
+            Self::RewriteRuleParse => "rewrite-rule-parse",
+            Self::PartialInclude => "partial-include",
+            Self::BlockHelper => "block-helper",
+            Self::RenderTimeout => "render-timeout",
+            Self::PermissionDenied => "permission-denied",
+            Self::ExternalHtml => "external-html",
+        }
+    }
+
+    /// This kind's coarse [`FailureCategory`], for monitoring and filtering - e.g. to
+    /// alert only on [`FailureCategory::Internal`] failures rather than every error a
+    /// batch produced.
+    pub fn category(self) -> FailureCategory {
+        match self {
+            Self::FrontmatterParse
+            | Self::MarkdownRender
+            | Self::TsxParse
+            | Self::TsxTransform
+            | Self::SourceType
+            | Self::NotUtf8
+            | Self::UnknownDecorator
+            | Self::LuaScript
+            | Self::JsExprParse
+            | Self::RewriteRuleParse
+            | Self::PartialInclude
+            | Self::BlockHelper => FailureCategory::UserContent,
+            Self::InvalidExportDefault | Self::UnresolvedImport | Self::ComponentCycle => {
+                FailureCategory::Component
+            }
+            Self::ContentTooLarge
+            | Self::BatchTooLarge
+            | Self::ComponentCodeTooLarge
+            | Self::EngineCodeTooLarge
+            | Self::RenderTimeout => FailureCategory::ResourceLimit,
+            Self::SourceMap | Self::ExternalHtml => FailureCategory::Internal,
+            Self::PermissionDenied => FailureCategory::Forbidden,
+        }
+    }
+}
+
+/// Coarse, stable classification of an [`MdxError`] derived from its
+/// [`MdxErrorKind::category`] - for monitoring and filtering (e.g. alerting only on
+/// [`Self::Internal`] failures) without branching on every individual
+/// [`MdxErrorKind`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FailureCategory {
+    /// The submitted MDX, frontmatter, or template syntax was malformed - a content
+    /// problem, not a service fault.
+    UserContent,
+    /// A referenced component's code, import, or naming convention was invalid.
+    Component,
+    /// The request exceeded a configured [`crate::models::ResourceLimits`] or render
+    /// timeout.
+    ResourceLimit,
+    /// An internal fault, unrelated to what the caller submitted - worth alerting on.
+    Internal,
+    /// Component code attempted an operation denied by
+    /// [`crate::models::ComponentPermissions`] - a policy violation, not a content or
+    /// resource problem.
+    Forbidden,
 }
 
 impl MdxError {
+    /// Returns this error's stable, machine-readable [`MdxErrorKind`].
+    pub fn kind(&self) -> MdxErrorKind {
+        match self {
+            Self::FrontmatterParse(_) => MdxErrorKind::FrontmatterParse,
+            Self::MarkdownRender(_) => MdxErrorKind::MarkdownRender,
+            Self::TsxParse(_) => MdxErrorKind::TsxParse,
+            Self::TsxTransform(_) => MdxErrorKind::TsxTransform,
+            Self::SourceType(_) => MdxErrorKind::SourceType,
+            Self::ContentTooLarge(_) => MdxErrorKind::ContentTooLarge,
+            Self::BatchTooLarge(_) => MdxErrorKind::BatchTooLarge,
+            Self::ComponentCodeTooLarge(_) => MdxErrorKind::ComponentCodeTooLarge,
+            Self::EngineCodeTooLarge(_) => MdxErrorKind::EngineCodeTooLarge,
+            Self::InvalidExportDefault(_) => MdxErrorKind::InvalidExportDefault,
+            Self::UnresolvedImport(_) => MdxErrorKind::UnresolvedImport,
+            Self::SourceMap(_) => MdxErrorKind::SourceMap,
+            Self::NotUtf8 { .. } => MdxErrorKind::NotUtf8,
+            Self::UnknownDecorator(_) => MdxErrorKind::UnknownDecorator,
+            Self::ComponentCycle(_) => MdxErrorKind::ComponentCycle,
+            Self::LuaScript(_) => MdxErrorKind::LuaScript,
+            Self::JsExprParse(_) => MdxErrorKind::JsExprParse,
+            Self::RewriteRuleParse(_) => MdxErrorKind::RewriteRuleParse,
+            Self::PartialInclude(_) => MdxErrorKind::PartialInclude,
+            Self::BlockHelper(_) => MdxErrorKind::BlockHelper,
+            Self::RenderTimeout(_) => MdxErrorKind::RenderTimeout,
+            Self::PermissionDenied(_) => MdxErrorKind::PermissionDenied,
+            Self::ExternalHtml(_) => MdxErrorKind::ExternalHtml,
+        }
+    }
+
+    /// Returns this error's [`SourceLocation`], if any - the first parse error's
+    /// location for `TsxParse`/`TsxTransform`, `None` for every other variant.
+    pub fn location(&self) -> Option<&SourceLocation> {
+        self.first_error().and_then(|error| error.location.as_ref())
+    }
+
     /// Creates a TsxParse error from a single message (without location)
     pub fn tsx_parse(message: impl Into<String>) -> Self {
         Self::TsxParse(vec![ParseError::new(message)])
@@ -209,6 +719,31 @@ impl MdxError {
         Self::TsxTransform(vec![ParseError::new(message)])
     }
 
+    /// Prefixes `context` onto this error's message(s), preserving any structured
+    /// [`ParseError`] list a `TsxParse`/`TsxTransform` error already carries - its
+    /// location, code and frame survive untouched - rather than collapsing it into a
+    /// single string via `format!("{context}: {self}")`, which would discard exactly
+    /// the span data downstream diagnostics (see [`crate::service::Diagnostic`]) rely
+    /// on. Every other variant, having no per-error structure to preserve, falls back
+    /// to that flat string form.
+    pub fn with_context(self, context: impl Into<String>) -> Self {
+        let context = context.into();
+        match self {
+            Self::TsxParse(errors) => Self::TsxParse(Self::prefix_messages(errors, &context)),
+            Self::TsxTransform(errors) => {
+                Self::TsxTransform(Self::prefix_messages(errors, &context))
+            }
+            other => Self::tsx_transform(format!("{context}: {other}")),
+        }
+    }
+
+    fn prefix_messages(errors: Vec<ParseError>, context: &str) -> Vec<ParseError> {
+        errors
+            .into_iter()
+            .map(|error| error.prefixed(context))
+            .collect()
+    }
+
     /// Returns the first parse error if this is a TsxParse or TsxTransform error
     pub fn first_error(&self) -> Option<&ParseError> {
         match self {
@@ -224,6 +759,121 @@ impl MdxError {
             _ => None,
         }
     }
+
+    /// Renders this error as an annotated, multi-line source snippet for diagnostics
+    /// that carry a [`SourceLocation`] (see [`ParseError::render_snippet`]), falling
+    /// back to the plain [`Display`](std::fmt::Display) output otherwise.
+    pub fn render(&self, source: &str) -> String {
+        match self.errors() {
+            Some(errors) if !errors.is_empty() => errors
+                .iter()
+                .map(|e| format!("{e}\n{}", e.render_snippet(source)))
+                .collect::<Vec<_>>()
+                .join("\n\n"),
+            _ => self.to_string(),
+        }
+    }
+
+    /// Converts this error into Language Server Protocol `Diagnostic`-shaped structs,
+    /// one per underlying [`ParseError`] (or a single zero-range diagnostic for an
+    /// `MdxError` variant with no per-error list), so an editor or build server can
+    /// consume structured diagnostics directly instead of parsing the rendered string.
+    pub fn to_lsp_diagnostics(&self, source: &str) -> Vec<LspDiagnostic> {
+        let line_index = LineIndex::new(source);
+        match self.errors() {
+            Some(errors) if !errors.is_empty() => {
+                errors.iter().map(|e| e.to_lsp_diagnostic(&line_index)).collect()
+            }
+            _ => vec![LspDiagnostic {
+                range: LspRange {
+                    start: LspPosition { line: 0, character: 0 },
+                    end: LspPosition { line: 0, character: 0 },
+                },
+                severity: Severity::Error.to_lsp_severity(),
+                message: self.to_string(),
+                related_information: None,
+            }],
+        }
+    }
+}
+
+/// A 0-indexed line/character position, matching the LSP `Position` shape.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub struct LspPosition {
+    /// 0-indexed line number
+    pub line: u32,
+    /// 0-indexed UTF-16 code unit offset within the line (we count `char`s, which is
+    /// exact for the common case and only diverges from UTF-16 for astral-plane code
+    /// points LSP clients rarely send through MDX source).
+    pub character: u32,
+}
+
+/// A start/end position pair, matching the LSP `Range` shape.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub struct LspRange {
+    /// Inclusive start position
+    pub start: LspPosition,
+    /// Exclusive end position
+    pub end: LspPosition,
+}
+
+/// An LSP-compatible `Diagnostic`, serialized in the shape editors and build servers
+/// expect so [`MdxError::to_lsp_diagnostics`] can be sent over the wire as-is.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct LspDiagnostic {
+    /// The span this diagnostic annotates
+    pub range: LspRange,
+    /// LSP `DiagnosticSeverity`: 1 = Error, 2 = Warning, 3 = Information, 4 = Hint
+    pub severity: u8,
+    /// Human-readable diagnostic message
+    pub message: String,
+    /// [`ParseError::help`] text, carried as related information when present
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub related_information: Option<String>,
+}
+
+impl Severity {
+    /// Maps to the LSP `DiagnosticSeverity` integer (1 = Error, 2 = Warning,
+    /// 3 = Information, 4 = Hint).
+    fn to_lsp_severity(self) -> u8 {
+        match self {
+            Self::Error => 1,
+            Self::Warning => 2,
+            Self::Info => 3,
+            Self::Hint => 4,
+        }
+    }
+}
+
+impl ParseError {
+    /// Converts this diagnostic into an [`LspDiagnostic`], resolving its byte-offset
+    /// [`SourceLocation`] to a 0-indexed line/character `range` via `line_index`
+    /// (shared across a file's errors - see [`LineIndex`]). An error with no location
+    /// gets a zero-width range at `0:0`.
+    fn to_lsp_diagnostic(&self, line_index: &LineIndex) -> LspDiagnostic {
+        let (start, end) = match &self.location {
+            Some(loc) => {
+                let (start_line, start_character) = line_index.line_col(loc.offset);
+                let (end_line, end_character) = line_index.line_col(loc.offset + loc.length);
+                (
+                    LspPosition { line: start_line, character: start_character },
+                    LspPosition { line: end_line, character: end_character },
+                )
+            }
+            None => {
+                let zero = LspPosition { line: 0, character: 0 };
+                (zero, zero)
+            }
+        };
+
+        LspDiagnostic {
+            range: LspRange { start, end },
+            severity: self.severity.to_lsp_severity(),
+            message: self.message.clone(),
+            related_information: self.help.clone(),
+        }
+    }
 }
 
 /// Formats a list of parse errors for display
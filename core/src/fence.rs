@@ -0,0 +1,385 @@
+//! Rich fenced code-block info-string parsing (extra classes, `ignore`/`no_run`,
+//! line-highlight ranges), modeled on rustdoc's `LangString::parse` - see
+//! [`crate::models::RenderSettings::fence_attributes`].
+//!
+//! CommonMark only gives a fence's first info-string word any meaning (the language,
+//! reflected as `markdown`'s `class="language-x"`, after leading whitespace before it
+//! is trimmed); everything after it - `` ```rust ignore {.no-run} {3,5-8} `` - is
+//! otherwise discarded by the underlying `markdown` crate's HTML output. This module
+//! recovers it straight from the raw MDX source, in document order, via
+//! [`extract_fence_infos`], then [`apply_fence_info`] consults it against the
+//! already-rendered `<pre><code class="language-x">` blocks to append extra classes,
+//! wrap specific output lines in a `highlighted-line` span, and stamp `data-ignore`/
+//! `data-no-run` attributes onto the `<pre>` so a downstream consumer can honor them;
+//! [`crate::highlight::highlight_code_blocks`] separately consults each entry's
+//! [`FenceInfo::ignore`] to suppress syntax highlighting for that one block.
+//!
+//! Only language-tagged fences carry rich attributes: a bare `` ``` `` fence gets no
+//! `class` attribute at all from `markdown`, so there's no hook in the rendered HTML to
+//! attach extra classes or line spans to.
+
+use std::collections::HashSet;
+use std::sync::LazyLock;
+
+use regex::Regex;
+
+/// One fenced code block's parsed info string (see [`parse_fence_info`]).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct FenceInfo {
+    /// The fence's language token - the info string's first word, if it has one.
+    pub(crate) language: Option<String>,
+    /// Extra CSS classes, from a `{.foo .bar}` group or bare `.foo` tokens.
+    pub(crate) classes: Vec<String>,
+    /// Set by a bare `ignore` token - suppresses syntax highlighting for this block.
+    pub(crate) ignore: bool,
+    /// Set by a bare `no_run` token - rustdoc's marker for an example that compiles
+    /// but shouldn't be executed. Not otherwise interpreted here; surfaced as a
+    /// `data-no-run` attribute on the block's `<pre>` for a consumer (e.g. the doctest
+    /// extractor) to honor.
+    pub(crate) no_run: bool,
+    /// 1-indexed output line numbers to wrap in a `highlighted-line` span, from a
+    /// `{3,5-8}` group or a bare `3,5-8` token.
+    pub(crate) highlighted_lines: Vec<usize>,
+}
+
+/// Matches a fenced code block's opening line: three or more backticks or tildes,
+/// followed by the raw info string (everything up to end of line). Also matches the
+/// closing delimiter of the same fence, which has an empty info string - fences don't
+/// nest in CommonMark, so [`extract_fence_infos`] relies on opens and closes
+/// alternating to tell them apart instead of tracking a stack.
+///
+/// # Safety
+/// Pattern is compile-time constant and known to be valid.
+static FENCE_DELIMITER: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?m)^(?:`{3,}|~{3,})[ \t]*([^\n]*)$").expect("hardcoded regex pattern is valid")
+});
+
+/// Extracts every language-tagged fenced code block's info string from raw (pre-render)
+/// MDX/markdown `content`, in document order, skipping fences with no language token -
+/// see the module-level note on why those can't carry rich attributes. The result is
+/// positionally aligned with the `language-x` blocks [`crate::highlight::FENCED_CODE_BLOCK`]
+/// later finds in the rendered HTML, as long as rendering doesn't reorder code blocks
+/// (it never does).
+pub(crate) fn extract_fence_infos(content: &str) -> Vec<FenceInfo> {
+    FENCE_DELIMITER
+        .captures_iter(content)
+        .enumerate()
+        .filter_map(|(i, caps)| (i % 2 == 0).then(|| caps[1].trim()).map(parse_fence_info))
+        .filter(|info| info.language.is_some())
+        .collect()
+}
+
+/// Parses a fenced code block's raw info string (the text after the opening
+/// backticks/tildes, e.g. `"rust ignore {.no-run} {3,5-8}"`) into a [`FenceInfo`],
+/// modeled on rustdoc's `LangString::parse`: tokens are separated by whitespace and/or
+/// commas, a `{...}` group is parsed as one token whose *interior* may itself contain
+/// whitespace (so `{.foo .bar}` survives as a single class group), braces are
+/// otherwise optional, and a token that isn't recognized as `ignore`, a `.class`, or a
+/// line-range list becomes the language (the first such token) or an extra class (any
+/// later one). An empty info string yields [`FenceInfo::default()`].
+pub(crate) fn parse_fence_info(info: &str) -> FenceInfo {
+    let mut result = FenceInfo::default();
+
+    for token in tokenize(info) {
+        let inner = token.strip_prefix('{').and_then(|t| t.strip_suffix('}')).unwrap_or(&token);
+
+        if inner.eq_ignore_ascii_case("ignore") {
+            result.ignore = true;
+        } else if inner.eq_ignore_ascii_case("no_run") {
+            result.no_run = true;
+        } else if let Some(classes) = parse_classes(inner) {
+            result.classes.extend(classes);
+        } else if let Some(lines) = parse_line_ranges(inner) {
+            result.highlighted_lines.extend(lines);
+        } else if result.language.is_none() {
+            result.language = Some(inner.to_string());
+        } else {
+            result.classes.push(inner.to_string());
+        }
+    }
+
+    result
+}
+
+/// Splits an info string into top-level tokens on whitespace/commas, except that a
+/// `{...}` group (which may itself contain whitespace, e.g. `{.foo .bar}`) is kept as
+/// one token including its braces.
+fn tokenize(info: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut current = String::new();
+    let mut chars = info.chars().peekable();
+
+    while let Some(&ch) = chars.peek() {
+        if ch == '{' {
+            if !current.is_empty() {
+                tokens.push(std::mem::take(&mut current));
+            }
+            let mut group = String::new();
+            for ch in chars.by_ref() {
+                group.push(ch);
+                if ch == '}' {
+                    break;
+                }
+            }
+            tokens.push(group);
+        } else if ch.is_whitespace() || ch == ',' {
+            chars.next();
+            if !current.is_empty() {
+                tokens.push(std::mem::take(&mut current));
+            }
+        } else {
+            current.push(ch);
+            chars.next();
+        }
+    }
+    if !current.is_empty() {
+        tokens.push(current);
+    }
+    tokens
+}
+
+/// Parses `inner` as one or more `.class` tokens (space-separated, e.g. from inside a
+/// `{.foo .bar}` group, or a single bare `.foo`), returning `None` if it isn't
+/// exclusively `.class` tokens.
+fn parse_classes(inner: &str) -> Option<Vec<String>> {
+    let parts: Vec<&str> = inner.split_whitespace().collect();
+    if parts.is_empty() || !parts.iter().all(|p| p.starts_with('.') && p.len() > 1) {
+        return None;
+    }
+    Some(parts.iter().map(|p| p[1..].to_string()).collect())
+}
+
+/// Parses `inner` as a comma-separated list of line numbers/ranges (e.g. `"3,5-8"`,
+/// from inside a `{3,5-8}` group or bare), returning `None` if any part isn't a valid
+/// number or `a-b` range.
+fn parse_line_ranges(inner: &str) -> Option<Vec<usize>> {
+    if inner.is_empty() {
+        return None;
+    }
+    let mut lines = Vec::new();
+    for part in inner.split(',') {
+        let part = part.trim();
+        if let Some((start, end)) = part.split_once('-') {
+            let start: usize = start.trim().parse().ok()?;
+            let end: usize = end.trim().parse().ok()?;
+            lines.extend(start..=end);
+        } else {
+            lines.push(part.parse().ok()?);
+        }
+    }
+    Some(lines)
+}
+
+/// Consults `infos` (see [`extract_fence_infos`]) against the language-tagged fenced
+/// code blocks already present in rendered `html`, appending each block's extra
+/// classes onto its `class` attribute, wrapping its requested line numbers in a
+/// `highlighted-line` span, and stamping `data-ignore`/`data-no-run` onto its `<pre>`
+/// for its [`FenceInfo::ignore`]/[`FenceInfo::no_run`] flags. A block with none of
+/// those is left untouched. Returns `html` unchanged if `infos` is empty.
+pub(crate) fn apply_fence_info(html: &str, infos: &[FenceInfo]) -> String {
+    if infos.is_empty() {
+        return html.to_string();
+    }
+
+    let mut out = String::with_capacity(html.len());
+    let mut last = 0;
+    for (index, caps) in crate::highlight::FENCED_CODE_BLOCK.captures_iter(html).enumerate() {
+        let whole = caps.get(0).expect("group 0 always matches");
+        out.push_str(&html[last..whole.start()]);
+        last = whole.end();
+
+        let Some(info) = infos.get(index) else {
+            out.push_str(whole.as_str());
+            continue;
+        };
+        if info.classes.is_empty() && info.highlighted_lines.is_empty() && !info.ignore && !info.no_run {
+            out.push_str(whole.as_str());
+            continue;
+        }
+
+        let lang = &caps[1];
+        let mut class_attr = format!("language-{lang}");
+        for class in &info.classes {
+            class_attr.push(' ');
+            class_attr.push_str(class);
+        }
+        let mut pre_attrs = String::new();
+        if info.ignore {
+            pre_attrs.push_str(r#" data-ignore="true""#);
+        }
+        if info.no_run {
+            pre_attrs.push_str(r#" data-no-run="true""#);
+        }
+        let body = wrap_highlighted_lines(&caps[2], &info.highlighted_lines);
+        out.push_str(&format!(r#"<pre{pre_attrs}><code class="{class_attr}">{body}</code></pre>"#));
+    }
+    out.push_str(&html[last..]);
+    out
+}
+
+/// Wraps each 1-indexed line in `line_numbers` within `content` in a
+/// `<span class="highlighted-line">`, preserving the line's own trailing newline (if
+/// any) outside the span. Returns `content` unchanged if `line_numbers` is empty.
+fn wrap_highlighted_lines(content: &str, line_numbers: &[usize]) -> String {
+    if line_numbers.is_empty() {
+        return content.to_string();
+    }
+    let targets: HashSet<usize> = line_numbers.iter().copied().collect();
+
+    let mut out = String::with_capacity(content.len() + targets.len() * 32);
+    for (index, line) in content.split_inclusive('\n').enumerate() {
+        let line_number = index + 1;
+        if targets.contains(&line_number) {
+            let (text, newline) = line.strip_suffix('\n').map_or((line, ""), |t| (t, "\n"));
+            out.push_str(r#"<span class="highlighted-line">"#);
+            out.push_str(text);
+            out.push_str("</span>");
+            out.push_str(newline);
+        } else {
+            out.push_str(line);
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_empty_info_string_yields_defaults() {
+        assert_eq!(parse_fence_info(""), FenceInfo::default());
+    }
+
+    #[test]
+    fn test_parse_bare_language() {
+        let info = parse_fence_info("rust");
+        assert_eq!(info.language.as_deref(), Some("rust"));
+        assert!(info.classes.is_empty());
+        assert!(!info.ignore);
+        assert!(info.highlighted_lines.is_empty());
+    }
+
+    #[test]
+    fn test_parse_ignore_flag() {
+        let info = parse_fence_info("rust ignore");
+        assert_eq!(info.language.as_deref(), Some("rust"));
+        assert!(info.ignore);
+    }
+
+    #[test]
+    fn test_parse_no_run_flag() {
+        let info = parse_fence_info("rust no_run");
+        assert_eq!(info.language.as_deref(), Some("rust"));
+        assert!(info.no_run);
+        assert!(!info.ignore);
+    }
+
+    #[test]
+    fn test_parse_trims_leading_whitespace_before_language() {
+        let info = parse_fence_info("   rust");
+        assert_eq!(info.language.as_deref(), Some("rust"));
+    }
+
+    #[test]
+    fn test_parse_braced_classes() {
+        let info = parse_fence_info("rust {.foo .bar}");
+        assert_eq!(info.language.as_deref(), Some("rust"));
+        assert_eq!(info.classes, vec!["foo".to_string(), "bar".to_string()]);
+    }
+
+    #[test]
+    fn test_parse_bare_class_without_braces() {
+        let info = parse_fence_info("rust .foo");
+        assert_eq!(info.classes, vec!["foo".to_string()]);
+    }
+
+    #[test]
+    fn test_parse_braced_line_ranges() {
+        let info = parse_fence_info("rust {3,5-8}");
+        assert_eq!(info.highlighted_lines, vec![3, 5, 6, 7, 8]);
+    }
+
+    #[test]
+    fn test_parse_bare_line_ranges_without_braces() {
+        let info = parse_fence_info("rust 3,5-8");
+        assert_eq!(info.highlighted_lines, vec![3, 5, 6, 7, 8]);
+    }
+
+    #[test]
+    fn test_parse_combines_all_attributes() {
+        let info = parse_fence_info("rust ignore {.foo} {3,5-8}");
+        assert_eq!(info.language.as_deref(), Some("rust"));
+        assert!(info.ignore);
+        assert_eq!(info.classes, vec!["foo".to_string()]);
+        assert_eq!(info.highlighted_lines, vec![3, 5, 6, 7, 8]);
+    }
+
+    #[test]
+    fn test_parse_unknown_token_becomes_extra_class() {
+        let info = parse_fence_info("rust mystery");
+        assert_eq!(info.language.as_deref(), Some("rust"));
+        assert_eq!(info.classes, vec!["mystery".to_string()]);
+    }
+
+    #[test]
+    fn test_extract_fence_infos_skips_languageless_fences() {
+        let content = "```\nplain\n```\n\n```rust\nfn main() {}\n```\n";
+        let infos = extract_fence_infos(content);
+        assert_eq!(infos.len(), 1);
+        assert_eq!(infos[0].language.as_deref(), Some("rust"));
+    }
+
+    #[test]
+    fn test_extract_fence_infos_preserves_document_order() {
+        let content = "```python\nfirst\n```\n\n```rust ignore\nsecond\n```\n";
+        let infos = extract_fence_infos(content);
+        assert_eq!(infos.len(), 2);
+        assert_eq!(infos[0].language.as_deref(), Some("python"));
+        assert_eq!(infos[1].language.as_deref(), Some("rust"));
+        assert!(infos[1].ignore);
+    }
+
+    #[test]
+    fn test_apply_fence_info_appends_classes_and_wraps_lines() {
+        let html = r#"<pre><code class="language-rust">line one
+line two
+line three
+</code></pre>"#;
+        let infos = vec![FenceInfo {
+            language: Some("rust".to_string()),
+            classes: vec!["foo".to_string()],
+            ignore: false,
+            no_run: false,
+            highlighted_lines: vec![2],
+        }];
+        let result = apply_fence_info(html, &infos);
+        assert!(result.contains(r#"class="language-rust foo""#));
+        assert!(result.contains("<span class=\"highlighted-line\">line two</span>\n"));
+        assert!(result.contains("line one\n"));
+    }
+
+    #[test]
+    fn test_apply_fence_info_stamps_data_attributes_for_ignore_and_no_run() {
+        let html = r#"<pre><code class="language-rust">fn main() {}</code></pre>"#;
+        let infos = vec![FenceInfo {
+            language: Some("rust".to_string()),
+            ignore: true,
+            no_run: true,
+            ..Default::default()
+        }];
+        let result = apply_fence_info(html, &infos);
+        assert!(result.contains(r#"data-ignore="true""#));
+        assert!(result.contains(r#"data-no-run="true""#));
+    }
+
+    #[test]
+    fn test_apply_fence_info_no_attributes_leaves_block_untouched() {
+        let html = r#"<pre><code class="language-rust">fn main() {}</code></pre>"#;
+        let infos = vec![FenceInfo {
+            language: Some("rust".to_string()),
+            ..Default::default()
+        }];
+        assert_eq!(apply_fence_info(html, &infos), html);
+    }
+}
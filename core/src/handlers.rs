@@ -1,23 +1,34 @@
 //! HTTP request handlers
 
+#[cfg(feature = "http")]
+use crate::dev_watch::ReloadBroadcaster;
 #[cfg(feature = "http")]
 use crate::models::{NamedMdxBatchInput, OutputFormat, RenderInput};
 #[cfg(feature = "http")]
-use crate::service::{RenderBatchError, RenderService};
+use crate::service::{RenderBatchError, RenderService, ServiceReconfigure};
+#[cfg(feature = "http")]
+use crate::upload::{UploadAccumulator, UploadError};
+#[cfg(feature = "http")]
+use actix_multipart::Multipart;
 #[cfg(feature = "http")]
-use actix_web::{get, http::StatusCode, post, web, HttpResponse, Responder};
+use actix_web::{get, http::StatusCode, post, put, web, HttpRequest, HttpResponse, Responder};
+#[cfg(feature = "http")]
+use futures_util::{stream, StreamExt as _};
 #[cfg(feature = "http")]
 use serde_json::json;
+#[cfg(feature = "http")]
+use tokio::sync::broadcast;
 
 /// Render MDX to HTML
 /// POST /render/html
 #[cfg(feature = "http")]
 #[post("/render/html")]
 pub async fn render_html(
+    req: HttpRequest,
     service: web::Data<RenderService>,
     input: web::Json<RenderInput>,
 ) -> impl Responder {
-    render_with_format(service, input.into_inner(), OutputFormat::Html)
+    render_with_format(&req, service, input.into_inner(), OutputFormat::Html)
 }
 
 /// Render MDX to JavaScript
@@ -25,10 +36,11 @@ pub async fn render_html(
 #[cfg(feature = "http")]
 #[post("/render/javascript")]
 pub async fn render_javascript(
+    req: HttpRequest,
     service: web::Data<RenderService>,
     input: web::Json<RenderInput>,
 ) -> impl Responder {
-    render_with_format(service, input.into_inner(), OutputFormat::Javascript)
+    render_with_format(&req, service, input.into_inner(), OutputFormat::Javascript)
 }
 
 /// Extract schema from MDX (component names)
@@ -36,10 +48,11 @@ pub async fn render_javascript(
 #[cfg(feature = "http")]
 #[post("/render/schema")]
 pub async fn render_schema(
+    req: HttpRequest,
     service: web::Data<RenderService>,
     input: web::Json<RenderInput>,
 ) -> impl Responder {
-    render_with_format(service, input.into_inner(), OutputFormat::Schema)
+    render_with_format(&req, service, input.into_inner(), OutputFormat::Schema)
 }
 
 /// Render MDX to JSON tree
@@ -47,10 +60,35 @@ pub async fn render_schema(
 #[cfg(feature = "http")]
 #[post("/render/json")]
 pub async fn render_json(
+    req: HttpRequest,
+    service: web::Data<RenderService>,
+    input: web::Json<RenderInput>,
+) -> impl Responder {
+    render_with_format(&req, service, input.into_inner(), OutputFormat::Json)
+}
+
+/// Render MDX to its parsed document tree as JSON
+/// POST /render/ast
+#[cfg(feature = "http")]
+#[post("/render/ast")]
+pub async fn render_ast(
+    req: HttpRequest,
     service: web::Data<RenderService>,
     input: web::Json<RenderInput>,
 ) -> impl Responder {
-    render_with_format(service, input.into_inner(), OutputFormat::Json)
+    render_with_format(&req, service, input.into_inner(), OutputFormat::Ast)
+}
+
+/// Render MDX to a standalone ES module
+/// POST /render/es-module
+#[cfg(feature = "http")]
+#[post("/render/es-module")]
+pub async fn render_es_module(
+    req: HttpRequest,
+    service: web::Data<RenderService>,
+    input: web::Json<RenderInput>,
+) -> impl Responder {
+    render_with_format(&req, service, input.into_inner(), OutputFormat::EsModule)
 }
 
 /// Legacy endpoint - render with settings in body
@@ -58,27 +96,186 @@ pub async fn render_json(
 #[cfg(feature = "http")]
 #[post("/render")]
 pub async fn render(
+    req: HttpRequest,
     service: web::Data<RenderService>,
     input: web::Json<NamedMdxBatchInput>,
 ) -> impl Responder {
     let payload = input.into_inner();
-    handle_render_result(service.render_batch(&payload))
+    handle_render_result(&req, &service, service.render_batch(&payload))
+}
+
+/// Content-negotiated rendering - picks [`OutputFormat`] from the request's `Accept`
+/// header instead of a fixed per-path format, for a client that can't vary the URL.
+/// `text/html` -> [`OutputFormat::Html`], `application/javascript`/`text/javascript`
+/// -> [`OutputFormat::Javascript`], `application/json` -> [`OutputFormat::Json`], and
+/// the custom `application/vnd.dinja.schema+json` -> [`OutputFormat::Schema`] - see
+/// [`crate::negotiation::select_output_format`]. An `Accept` header listing only
+/// unsupported types gets `406 Not Acceptable`; an absent or empty one falls back to
+/// [`OutputFormat::Json`].
+/// POST /render/v2
+#[cfg(feature = "http")]
+#[post("/render/v2")]
+pub async fn render_negotiated(
+    req: HttpRequest,
+    service: web::Data<RenderService>,
+    input: web::Json<NamedMdxBatchInput>,
+) -> impl Responder {
+    let accept = req
+        .headers()
+        .get(actix_web::http::header::ACCEPT)
+        .and_then(|value| value.to_str().ok());
+
+    let format = match crate::negotiation::select_output_format(accept) {
+        Ok(format) => format,
+        Err(()) => {
+            return error_response(
+                StatusCode::NOT_ACCEPTABLE,
+                "not-acceptable",
+                "Accept header lists no format this service supports".to_string(),
+            )
+        }
+    };
+
+    let mut payload = input.into_inner();
+    payload.settings.output = format;
+    handle_render_result(&req, &service, service.render_batch(&payload))
+}
+
+/// Streaming NDJSON batch rendering - writes one JSON line per file as it finishes
+/// instead of buffering the whole batch into one `BatchRenderOutcome` first, for a
+/// client rendering hundreds of files that wants low latency-to-first-byte and bounded
+/// peak memory. See [`crate::service::RenderService::render_batch_ndjson`] for the
+/// per-line and trailing-summary-line shapes.
+///
+/// Validation happens before the `200` is committed, so a rejected batch still gets its
+/// usual status code; once streaming starts, a mid-batch internal error surfaces as a
+/// final `{"summary": true, "status": "failure", ...}` line instead, since the status
+/// code can no longer change at that point.
+/// POST /render/stream
+#[cfg(feature = "http")]
+#[post("/render/stream")]
+pub async fn render_stream(
+    service: web::Data<RenderService>,
+    input: web::Json<NamedMdxBatchInput>,
+) -> impl Responder {
+    match service.render_batch_ndjson(input.into_inner()) {
+        Ok(lines) => HttpResponse::Ok()
+            .content_type("application/x-ndjson")
+            .streaming(stream::iter(
+                lines.into_iter().map(|line| Ok::<_, actix_web::Error>(web::Bytes::from(line))),
+            )),
+        Err(err) => render_batch_error_response(err),
+    }
+}
+
+/// Multipart upload rendering - each file part becomes a named MDX batch entry
+/// (`filename` -> entry name) and an optional `settings` text/JSON part supplies
+/// [`crate::models::RenderSettings`] (including the target [`OutputFormat`]), so a
+/// client can push raw `.mdx` files without first JSON/base64-encoding them into
+/// [`RenderInput`]. Parts are streamed into a [`UploadAccumulator`], which enforces
+/// `service`'s [`crate::upload::UploadConfig`] per-file and total-size limits as each
+/// chunk arrives - an oversized upload is rejected with `413 Payload Too Large` before
+/// the rest of the body is read. The assembled batch then routes through
+/// [`RenderService::render_batch`], so the same partial-success/`MULTI_STATUS`
+/// handling as `POST /render` applies.
+/// POST /render/upload
+#[cfg(feature = "http")]
+#[post("/render/upload")]
+pub async fn render_upload(
+    req: HttpRequest,
+    service: web::Data<RenderService>,
+    mut payload: Multipart,
+) -> impl Responder {
+    let mut accumulator = UploadAccumulator::new(service.config().upload.clone());
+    let mut settings_json: Option<Vec<u8>> = None;
+
+    while let Some(item) = payload.next().await {
+        let mut field = match item {
+            Ok(field) => field,
+            Err(err) => {
+                return error_response(
+                    StatusCode::BAD_REQUEST,
+                    "invalid-multipart-body",
+                    format!("Invalid multipart body: {err}"),
+                )
+            }
+        };
+
+        let field_name = field.name().unwrap_or_default().to_string();
+        let filename = field
+            .content_disposition()
+            .and_then(|cd| cd.get_filename())
+            .map(str::to_string);
+        let is_settings = field_name == "settings";
+
+        let mut settings_body = Vec::new();
+        while let Some(chunk) = field.next().await {
+            let chunk = match chunk {
+                Ok(chunk) => chunk,
+                Err(err) => {
+                    return error_response(
+                        StatusCode::BAD_REQUEST,
+                        "invalid-multipart-body",
+                        format!("Invalid multipart body: {err}"),
+                    )
+                }
+            };
+            if is_settings {
+                settings_body.extend_from_slice(&chunk);
+                continue;
+            }
+            let name = filename.clone().unwrap_or_else(|| field_name.clone());
+            if let Err(err) = accumulator.push_chunk(&name, &chunk) {
+                return upload_error_response(err);
+            }
+        }
+
+        if is_settings {
+            settings_json = Some(settings_body);
+        }
+    }
+
+    let batch_input = match accumulator.finish(settings_json.as_deref()) {
+        Ok(input) => input,
+        Err(err) => return upload_error_response(err),
+    };
+
+    handle_render_result(&req, &service, service.render_batch(&batch_input))
+}
+
+/// Maps an [`UploadError`] to its HTTP status/body - a size-limit violation gets `413
+/// Payload Too Large`, a malformed `settings` part gets `400 Bad Request`.
+#[cfg(feature = "http")]
+fn upload_error_response(err: UploadError) -> HttpResponse {
+    let (status, type_slug) = match err {
+        UploadError::FileTooLarge { .. } => (StatusCode::PAYLOAD_TOO_LARGE, "file-too-large"),
+        UploadError::TotalTooLarge { .. } => (StatusCode::PAYLOAD_TOO_LARGE, "total-too-large"),
+        UploadError::InvalidSettings(_) => (StatusCode::BAD_REQUEST, "invalid-settings"),
+    };
+    error_response(status, type_slug, err.to_string())
 }
 
 /// Internal helper for format-specific rendering
 #[cfg(feature = "http")]
 fn render_with_format(
+    req: &HttpRequest,
     service: web::Data<RenderService>,
     input: RenderInput,
     format: OutputFormat,
 ) -> HttpResponse {
     let batch_input = input.into_batch_input(format);
-    handle_render_result(service.render_batch(&batch_input))
+    let outcome = service.render_batch(&batch_input);
+    handle_render_result(req, &service, outcome)
 }
 
-/// Handle render result and convert to HTTP response
+/// Handle render result and convert to HTTP response, compressing the JSON body
+/// against the request's `Accept-Encoding` header when `service`'s
+/// [`crate::compression::CompressionConfig`] allows it - see
+/// [`crate::compression::select_encoding`].
 #[cfg(feature = "http")]
 fn handle_render_result(
+    req: &HttpRequest,
+    service: &RenderService,
     result: Result<crate::service::BatchRenderOutcome, RenderBatchError>,
 ) -> HttpResponse {
     match result {
@@ -90,20 +287,112 @@ fn handle_render_result(
             } else {
                 StatusCode::MULTI_STATUS
             };
-            HttpResponse::build(status)
-                .content_type("application/json")
-                .json(outcome)
+            let mut body = match serde_json::to_value(&outcome) {
+                Ok(body) => body,
+                Err(err) => {
+                    return error_response(
+                        StatusCode::INTERNAL_SERVER_ERROR,
+                        "internal",
+                        format!("Failed to serialize render outcome: {err}"),
+                    )
+                }
+            };
+            // `outcome.errors` (serialized above as part of the object already) is the
+            // per-item correlation RFC 7807 calls for; a non-`200` status additionally
+            // gets the same `type`/`title`/`status`/`detail` envelope `error_response`
+            // uses, so a partial/complete failure is still a problem+json document.
+            if status != StatusCode::OK {
+                if let Some(object) = body.as_object_mut() {
+                    let type_slug =
+                        if outcome.is_complete_failure() { "internal" } else { "partial-failure" };
+                    object.insert("type".to_string(), json!(problem_type(type_slug)));
+                    object.insert(
+                        "title".to_string(),
+                        json!(status.canonical_reason().unwrap_or("Error")),
+                    );
+                    object.insert("status".to_string(), json!(status.as_u16()));
+                    object.insert(
+                        "detail".to_string(),
+                        json!(format!(
+                            "{} of {} files failed to render",
+                            outcome.failed, outcome.total
+                        )),
+                    );
+                }
+            }
+            let body = match serde_json::to_vec(&body) {
+                Ok(body) => body,
+                Err(err) => {
+                    return error_response(
+                        StatusCode::INTERNAL_SERVER_ERROR,
+                        "internal",
+                        format!("Failed to serialize render outcome: {err}"),
+                    )
+                }
+            };
+            let content_type = if status == StatusCode::OK {
+                "application/json"
+            } else {
+                "application/problem+json"
+            };
+            compressed_json_response(req, service, status, content_type, body)
+        }
+        Err(err) => render_batch_error_response(err),
+    }
+}
+
+/// Maps a [`RenderBatchError`] to its HTTP status/body, shared by [`handle_render_result`]
+/// and [`render_stream`] (which only needs the error path - a success means streaming
+/// has already started).
+#[cfg(feature = "http")]
+fn render_batch_error_response(err: RenderBatchError) -> HttpResponse {
+    match err {
+        RenderBatchError::Forbidden(message) => {
+            error_response(StatusCode::FORBIDDEN, "forbidden", message)
         }
-        Err(RenderBatchError::Forbidden(message)) => error_response(StatusCode::FORBIDDEN, message),
-        Err(RenderBatchError::InvalidRequest(message)) => {
-            error_response(StatusCode::BAD_REQUEST, message)
+        RenderBatchError::InvalidRequest(message) => {
+            error_response(StatusCode::BAD_REQUEST, "invalid-request", message)
         }
-        Err(RenderBatchError::Internal(err)) => {
-            error_response(StatusCode::INTERNAL_SERVER_ERROR, err.to_string())
+        RenderBatchError::Internal(err) => {
+            error_response(StatusCode::INTERNAL_SERVER_ERROR, "internal", err.to_string())
         }
     }
 }
 
+/// Builds a response from an already-serialized `body` under `content_type`,
+/// negotiating and applying response compression per `service`'s
+/// [`crate::compression::CompressionConfig`] - skipped for a body smaller than
+/// [`crate::compression::CompressionConfig::min_size_bytes`] regardless of what the
+/// client accepts, since compressing a tiny payload costs more CPU than it saves.
+#[cfg(feature = "http")]
+fn compressed_json_response(
+    req: &HttpRequest,
+    service: &RenderService,
+    status: StatusCode,
+    content_type: &'static str,
+    body: Vec<u8>,
+) -> HttpResponse {
+    let compression = &service.config().compression;
+    let encoding = if body.len() < compression.min_size_bytes {
+        crate::compression::ContentEncoding::Identity
+    } else {
+        let accept_encoding = req
+            .headers()
+            .get(actix_web::http::header::ACCEPT_ENCODING)
+            .and_then(|value| value.to_str().ok());
+        crate::compression::select_encoding(accept_encoding, compression)
+    };
+
+    let body = crate::compression::compress(&body, encoding);
+    let mut response = HttpResponse::build(status);
+    response.content_type(content_type);
+    response.append_header((actix_web::http::header::VARY, "Accept-Encoding"));
+    if let Some(header_value) = encoding.header_value() {
+        response.append_header((actix_web::http::header::CONTENT_ENCODING, header_value));
+    }
+    response.body(body)
+}
+
 /// Health check endpoint
 #[cfg(feature = "http")]
 #[get("/health")]
@@ -113,10 +402,75 @@ pub async fn health() -> impl Responder {
         .json(json!({ "status": "ok" }))
 }
 
-/// Helper function to create error responses with consistent formatting
+/// Returns a snapshot of the service's configuration and runtime state - see
+/// [`RenderService::describe`].
+/// GET /status
 #[cfg(feature = "http")]
-fn error_response(status: StatusCode, message: String) -> HttpResponse {
-    HttpResponse::build(status)
-        .content_type("application/json")
-        .json(json!({ "error": message }))
+#[get("/status")]
+pub async fn status(service: web::Data<RenderService>) -> impl Responder {
+    HttpResponse::Ok().content_type("application/json").json(service.describe())
+}
+
+/// Applies a partial configuration update without restarting the service - see
+/// [`RenderService::reconfigure`]. Responds with the configuration that was in
+/// effect before this update, so a caller can `PUT` it back to roll back.
+/// PUT /status
+#[cfg(feature = "http")]
+#[put("/status")]
+pub async fn reconfigure(
+    service: web::Data<RenderService>,
+    partial: web::Json<ServiceReconfigure>,
+) -> impl Responder {
+    match service.reconfigure(partial.into_inner()) {
+        Ok(previous) => HttpResponse::Ok().content_type("application/json").json(previous),
+        Err(message) => error_response(StatusCode::BAD_REQUEST, "invalid-configuration", message),
+    }
+}
+
+/// Server-sent events stream that emits `data: reload` whenever
+/// [`crate::dev_watch::watch_static_dir`] detects a `static_dir` change, so a page
+/// loaded during local development can listen for it and refresh itself - the
+/// `--watch`-mode analogue of the reload notification a bundler's dev server pushes.
+/// Always registered, but silent (no events) unless `dinja serve --watch` is running,
+/// since that's the only thing that ever calls [`ReloadBroadcaster::notify`].
+/// GET /dev/reload
+#[cfg(feature = "http")]
+#[get("/dev/reload")]
+pub async fn dev_reload(broadcaster: web::Data<ReloadBroadcaster>) -> impl Responder {
+    let receiver = broadcaster.subscribe();
+    let stream = stream::unfold(receiver, |mut receiver| async move {
+        loop {
+            match receiver.recv().await {
+                Ok(()) => return Some((Ok::<_, actix_web::Error>(web::Bytes::from_static(b"data: reload\n\n")), receiver)),
+                Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                Err(broadcast::error::RecvError::Closed) => return None,
+            }
+        }
+    });
+    HttpResponse::Ok().content_type("text/event-stream").streaming(stream)
+}
+
+/// Builds an RFC 7807 (`application/problem+json`) error body: a stable `type` slug
+/// per error variant, a `title` derived from `status`'s canonical reason phrase, the
+/// numeric `status` itself, and a `detail` message specific to this occurrence.
+/// `error` is kept alongside at the top level as a compatibility shim carrying the
+/// same text as `detail`, for an existing consumer that only reads the flat string
+/// this endpoint used to return.
+#[cfg(feature = "http")]
+fn error_response(status: StatusCode, type_slug: &str, detail: String) -> HttpResponse {
+    HttpResponse::build(status).content_type("application/problem+json").json(json!({
+        "type": problem_type(type_slug),
+        "title": status.canonical_reason().unwrap_or("Error"),
+        "status": status.as_u16(),
+        "detail": detail,
+        "error": detail,
+    }))
+}
+
+/// The stable `type` URI for a `type_slug` - a relative reference rather than an
+/// absolute URL, since this service doesn't (yet) publish a problem-type registry for
+/// clients to dereference; still unique per error variant, as RFC 7807 requires.
+#[cfg(feature = "http")]
+fn problem_type(type_slug: &str) -> String {
+    format!("/problems/{type_slug}")
 }
@@ -0,0 +1,170 @@
+//! Rustdoc-style hidden lines in fenced/indented code blocks, per
+//! [`crate::models::RenderSettings::hidden_code_lines`].
+//!
+//! A code-block line that starts with `# ` (hash-space) or is a lone `#` is
+//! [`Line::Hidden`] - present in the source but omitted from the rendered `<pre>`, the
+//! same convention rustdoc uses to keep a doctest's visible listing short while still
+//! compiling the full example. A literal leading `#` is escaped as `##`, so it survives
+//! rendering as a plain `#` rather than being hidden.
+//!
+//! [`rendered_lines`] is what [`crate::mdx::mdx_to_writer_with_frontmatter`] applies to
+//! each code block's content before it's written out; [`full_source`] is the companion
+//! API for a caller that wants the complete, un-stripped example instead (e.g. a
+//! doctest runner extracting a runnable snippet from a rendered document).
+
+use regex::Regex;
+use std::sync::LazyLock;
+
+/// Matches a `<pre><code>`/`<pre><code class="language-x">` block emitted by
+/// markdown's fenced or indented code-block rendering, capturing its (HTML-escaped)
+/// content. `#` isn't an HTML-escaped character, so [`strip_hidden_lines`] can match
+/// against this captured text directly without unescaping it first.
+///
+/// # Safety
+/// Pattern is compile-time constant and known to be valid.
+static CODE_BLOCK: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"(?s)<pre><code( class="language-[A-Za-z0-9_+-]+")?>(.*?)</code></pre>"#)
+        .expect("hardcoded regex pattern is valid")
+});
+
+/// Applies [`rendered_lines`] to every code block's content in `html`, omitting `# `
+/// lines from rendered output.
+pub(crate) fn strip_hidden_lines(html: &str) -> String {
+    CODE_BLOCK
+        .replace_all(html, |caps: &regex::Captures| {
+            let class = caps.get(1).map_or("", |m| m.as_str());
+            format!("<pre><code{class}>{}</code></pre>", rendered_lines(&caps[2]))
+        })
+        .into_owned()
+}
+
+/// One line of a code block's raw content, classified per the module docs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Line {
+    /// Kept in the rendered `<pre>` output.
+    Shown(String),
+    /// Omitted from rendered output, but present in [`full_source`].
+    Hidden(String),
+}
+
+/// Classifies every line of `code` per the module docs, unescaping a `##` prefix down
+/// to a literal `#` on the line it classifies.
+pub(crate) fn classify_lines(code: &str) -> Vec<Line> {
+    code.lines()
+        .map(|line| {
+            if let Some(rest) = line.strip_prefix("##") {
+                Line::Shown(format!("#{rest}"))
+            } else if line == "#" {
+                Line::Hidden(String::new())
+            } else if let Some(rest) = line.strip_prefix("# ") {
+                Line::Hidden(rest.to_string())
+            } else {
+                Line::Shown(line.to_string())
+            }
+        })
+        .collect()
+}
+
+/// Renders `code` with every [`Line::Hidden`] line omitted, for display in a `<pre>`.
+/// Preserves `code`'s trailing newline, if it had one.
+pub(crate) fn rendered_lines(code: &str) -> String {
+    let shown: Vec<String> = classify_lines(code)
+        .into_iter()
+        .filter_map(|line| match line {
+            Line::Shown(text) => Some(text),
+            Line::Hidden(_) => None,
+        })
+        .collect();
+    let refs: Vec<&str> = shown.iter().map(String::as_str).collect();
+    join_lines(&refs, code.ends_with('\n'))
+}
+
+/// Renders `code` with every line present (hidden lines included, `# `/`##` markers
+/// stripped), for a caller that wants the complete runnable example rather than the
+/// shortened listing [`rendered_lines`] produces.
+pub(crate) fn full_source(code: &str) -> String {
+    let lines: Vec<String> = classify_lines(code)
+        .into_iter()
+        .map(|line| match line {
+            Line::Shown(text) | Line::Hidden(text) => text,
+        })
+        .collect();
+    let refs: Vec<&str> = lines.iter().map(String::as_str).collect();
+    join_lines(&refs, code.ends_with('\n'))
+}
+
+/// Joins `lines` with `\n`, appending a trailing newline if `trailing_newline` is set
+/// and `lines` isn't empty.
+fn join_lines(lines: &[&str], trailing_newline: bool) -> String {
+    if lines.is_empty() {
+        return String::new();
+    }
+    let mut out = lines.join("\n");
+    if trailing_newline {
+        out.push('\n');
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify_lines_marks_hash_space_as_hidden() {
+        let lines = classify_lines("# hidden\nshown\n");
+        assert_eq!(
+            lines,
+            vec![Line::Hidden("hidden".to_string()), Line::Shown("shown".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_classify_lines_lone_hash_is_hidden_and_empty() {
+        let lines = classify_lines("#\nshown\n");
+        assert_eq!(lines[0], Line::Hidden(String::new()));
+    }
+
+    #[test]
+    fn test_classify_lines_double_hash_escapes_to_literal() {
+        let lines = classify_lines("## not hidden\n");
+        assert_eq!(lines, vec![Line::Shown("# not hidden".to_string())]);
+    }
+
+    #[test]
+    fn test_rendered_lines_omits_hidden_lines() {
+        let code = "# use std::io;\nfn main() {\n    println!(\"hi\");\n}\n";
+        assert_eq!(rendered_lines(code), "fn main() {\n    println!(\"hi\");\n}\n");
+    }
+
+    #[test]
+    fn test_full_source_keeps_hidden_lines_unmarked() {
+        let code = "# use std::io;\nfn main() {}\n";
+        assert_eq!(full_source(code), "use std::io;\nfn main() {}\n");
+    }
+
+    #[test]
+    fn test_rendered_lines_without_trailing_newline() {
+        assert_eq!(rendered_lines("# hidden\nshown"), "shown");
+    }
+
+    #[test]
+    fn test_rendered_lines_all_hidden_yields_empty() {
+        assert_eq!(rendered_lines("# only\n# hidden\n"), "");
+    }
+
+    #[test]
+    fn test_strip_hidden_lines_rewrites_code_block_in_html() {
+        let html = "<pre><code class=\"language-rust\"># use std::io;\nfn main() {}\n</code></pre>";
+        assert_eq!(
+            strip_hidden_lines(html),
+            "<pre><code class=\"language-rust\">fn main() {}\n</code></pre>"
+        );
+    }
+
+    #[test]
+    fn test_strip_hidden_lines_leaves_language_free_blocks_untouched_when_no_hidden_lines() {
+        let html = "<pre><code>plain text</code></pre>";
+        assert_eq!(strip_hidden_lines(html), html);
+    }
+}
@@ -0,0 +1,246 @@
+//! Syntax highlighting for fenced code blocks, via `syntect`.
+//!
+//! [`crate::mdx::render_markdown`]'s underlying Markdown engine already emits every
+//! fenced code block as `<pre><code class="language-xxx">` with its content HTML-escaped
+//! (see the `test_code_fenced_with_language` test) - this module's job is only to
+//! recognize that shape and replace the escaped text inside it with `syntect`-highlighted
+//! HTML, per [`crate::models::HighlightSettings`]. A block whose language `syntect`
+//! doesn't recognize, or a theme name that isn't loaded, is left exactly as it was:
+//! plain escaped `<code>`.
+//!
+//! The default syntax and theme sets are each expensive to build (`syntect` parses a
+//! bundled set of `.sublime-syntax`/`.tmTheme` files) and never change at runtime, so
+//! both are loaded once per process behind a [`std::sync::LazyLock`] rather than
+//! rebuilt per file, per batch, or per [`crate::service::RenderService`].
+
+use crate::models::HighlightSettings;
+use regex::Regex;
+use std::sync::LazyLock;
+use syntect::easy::HighlightLines;
+use syntect::highlighting::{Style, Theme, ThemeSet};
+use syntect::html::{
+    styled_line_to_highlighted_html, ClassStyle, ClassedHTMLGenerator, IncludeBackground,
+};
+use syntect::parsing::{SyntaxReference, SyntaxSet};
+use syntect::util::LinesWithEndings;
+
+/// Process-wide, lazily-built syntax definition set (one load covers every theme/request).
+static SYNTAX_SET: LazyLock<SyntaxSet> = LazyLock::new(SyntaxSet::load_defaults_newlines);
+
+/// Process-wide, lazily-built theme set (`syntect`'s bundled themes, keyed by name).
+static THEME_SET: LazyLock<ThemeSet> = LazyLock::new(ThemeSet::load_defaults);
+
+/// Matches a fenced code block markdown emitted with a language info-string, capturing
+/// the language token and the (HTML-escaped) code content. Also reused by
+/// [`crate::fence::apply_fence_info`], which needs to find the same blocks by the same
+/// document-order index [`crate::fence::extract_fence_infos`] assigns them.
+///
+/// # Safety
+/// Pattern is compile-time constant and known to be valid.
+pub(crate) static FENCED_CODE_BLOCK: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"(?s)<pre><code class="language-([A-Za-z0-9_+-]+)">(.*?)</code></pre>"#)
+        .expect("hardcoded regex pattern is valid")
+});
+
+/// Replaces every fenced code block in `html` (markdown's `<pre><code class="language-x">`
+/// output) whose language `syntect` recognizes with highlighted HTML, per `settings`.
+/// A block whose language isn't recognized, or whose content can't be highlighted for
+/// any other reason, is passed through unchanged - as is any block at a position where
+/// `ignore` is `true` (see [`crate::models::RenderSettings::fence_attributes`]), since
+/// a fence's `ignore` info-string token means "show me, but don't highlight me".
+///
+/// Returns `html` unchanged entirely if `settings.theme` isn't a loaded theme name.
+pub(crate) fn highlight_code_blocks(html: &str, settings: &HighlightSettings, ignore: &[bool]) -> String {
+    let Some(theme) = THEME_SET.themes.get(&settings.theme) else {
+        return html.to_string();
+    };
+
+    let mut out = String::with_capacity(html.len());
+    let mut last = 0;
+    for (index, caps) in FENCED_CODE_BLOCK.captures_iter(html).enumerate() {
+        let whole = caps.get(0).expect("group 0 always matches");
+        out.push_str(&html[last..whole.start()]);
+        last = whole.end();
+
+        if ignore.get(index).copied().unwrap_or(false) {
+            out.push_str(whole.as_str());
+            continue;
+        }
+
+        let lang = &caps[1];
+        // `syntect`'s token table is lowercase (`"python"`, not `"Python"`), but a
+        // fence's info string is free-form text a writer may capitalize - look up
+        // case-insensitively so `` ```Python `` highlights the same as `` ```python ``
+        // while the emitted `language-xxx` class keeps the writer's original casing.
+        let Some(syntax) = SYNTAX_SET.find_syntax_by_token(&lang.to_lowercase()) else {
+            out.push_str(whole.as_str());
+            continue;
+        };
+        let code = unescape_html(&caps[2]);
+        match highlight_block(
+            &code,
+            lang,
+            syntax,
+            theme,
+            settings.inline_styles,
+            settings.simple_classes,
+        ) {
+            Ok(highlighted) => out.push_str(&highlighted),
+            Err(_) => out.push_str(whole.as_str()),
+        }
+    }
+    out.push_str(&html[last..]);
+    out
+}
+
+/// Highlights `code` (already unescaped) as `lang` against `theme`, wrapping the result
+/// back in the same `<pre><code class="language-x">` shell the plain-escaped version used.
+fn highlight_block(
+    code: &str,
+    lang: &str,
+    syntax: &SyntaxReference,
+    theme: &Theme,
+    inline_styles: bool,
+    simple_classes: bool,
+) -> Result<String, syntect::Error> {
+    let body = if inline_styles {
+        let mut highlighter = HighlightLines::new(syntax, theme);
+        let mut body = String::new();
+        for line in LinesWithEndings::from(code) {
+            let ranges: Vec<(Style, &str)> = highlighter.highlight_line(line, &SYNTAX_SET)?;
+            body.push_str(&styled_line_to_highlighted_html(
+                &ranges[..],
+                IncludeBackground::No,
+            )?);
+        }
+        body
+    } else {
+        let mut generator =
+            ClassedHTMLGenerator::new_with_class_style(syntax, &SYNTAX_SET, ClassStyle::Spaced);
+        for line in LinesWithEndings::from(code) {
+            generator.parse_html_for_line_which_includes_newline(line)?;
+        }
+        let body = generator.finalize();
+        if simple_classes {
+            simplify_classes(&body)
+        } else {
+            body
+        }
+    };
+
+    Ok(format!(r#"<pre><code class="language-{lang}">{body}</code></pre>"#))
+}
+
+/// Matches a `<span class="...">` tag emitted by [`ClassedHTMLGenerator`], capturing
+/// its space-separated `syntect` scope classes.
+///
+/// # Safety
+/// Pattern is compile-time constant and known to be valid.
+static SPAN_CLASS: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"<span class="([^"]*)">"#).expect("hardcoded regex pattern is valid"));
+
+/// Collapses every `<span class="...">`'s `syntect` scope classes (e.g. `"storage
+/// modifier rust"`, `"string quoted double rust"`) down to one of a small rustdoc-style
+/// token set - `kw`, `str`, `comment`, `number`, `ident`, `op` - so a caller can ship
+/// one stylesheet that works across every highlighted language rather than one keyed to
+/// `syntect`'s own scope names. A span whose classes don't match any known scope keeps
+/// the `ident` bucket, the same treatment plain identifiers get.
+pub(crate) fn simplify_classes(html: &str) -> String {
+    SPAN_CLASS
+        .replace_all(html, |caps: &regex::Captures| {
+            format!(r#"<span class="{}">"#, simple_class_for(&caps[1]))
+        })
+        .into_owned()
+}
+
+/// Maps one `syntect` scope-class string to its simplified rustdoc-style bucket, by
+/// checking the scope's atoms (space-separated by `ClassStyle::Spaced`, rejoined with
+/// `.` from each atom onward) against the same dotted prefixes `syntect`'s bundled
+/// `.sublime-syntax` files use for scope naming.
+fn simple_class_for(scope_classes: &str) -> &'static str {
+    let atoms: Vec<&str> = scope_classes.split(' ').collect();
+    for start in 0..atoms.len() {
+        let class = atoms[start..].join(".");
+        return match class.as_str() {
+            c if c.starts_with("comment") => "comment",
+            c if c.starts_with("string") => "str",
+            c if c.starts_with("constant.numeric") || c.starts_with("number") => "number",
+            c if c.starts_with("keyword.operator") || c.starts_with("operator") => "op",
+            c if c.starts_with("keyword") || c.starts_with("storage") => "kw",
+            c if c.starts_with("variable") || c.starts_with("entity") => "ident",
+            _ => continue,
+        };
+    }
+    "ident"
+}
+
+/// Reverses the HTML-entity escaping markdown applied to fenced code content, so
+/// `syntect` highlights the original source rather than the escaped text. `&amp;` is
+/// unescaped last so `&amp;lt;` round-trips to the literal text `&lt;` rather than `<`.
+fn unescape_html(escaped: &str) -> String {
+    escaped
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&amp;", "&")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::HighlightSettings;
+
+    #[test]
+    fn test_highlight_code_blocks_wraps_recognized_language() {
+        let html = r#"<pre><code class="language-rust">fn main() {}</code></pre>"#;
+        let settings = HighlightSettings::default();
+        let result = highlight_code_blocks(html, &settings, &[false]);
+        assert!(result.contains("<span"), "expected highlighted spans, got: {result}");
+        assert!(result.contains(r#"class="language-rust""#));
+    }
+
+    #[test]
+    fn test_highlight_code_blocks_passes_through_unknown_language() {
+        let html = r#"<pre><code class="language-not-a-real-language">x</code></pre>"#;
+        let settings = HighlightSettings::default();
+        assert_eq!(highlight_code_blocks(html, &settings, &[false]), html);
+    }
+
+    #[test]
+    fn test_highlight_code_blocks_passes_through_unknown_theme() {
+        let html = r#"<pre><code class="language-rust">fn main() {}</code></pre>"#;
+        let mut settings = HighlightSettings::default();
+        settings.theme = "not-a-real-theme".to_string();
+        assert_eq!(highlight_code_blocks(html, &settings, &[false]), html);
+    }
+
+    #[test]
+    fn test_highlight_code_blocks_honors_ignore_flag() {
+        let html = r#"<pre><code class="language-rust">fn main() {}</code></pre>"#;
+        let settings = HighlightSettings::default();
+        assert_eq!(highlight_code_blocks(html, &settings, &[true]), html);
+    }
+
+    #[test]
+    fn test_highlight_code_blocks_unescapes_entities_before_highlighting() {
+        let html = r#"<pre><code class="language-rust">let x = &amp;1 &lt; 2;</code></pre>"#;
+        let settings = HighlightSettings::default();
+        let result = highlight_code_blocks(html, &settings, &[false]);
+        assert!(!result.contains("&amp;amp;"));
+    }
+
+    #[test]
+    fn test_simplify_classes_maps_known_scopes_to_buckets() {
+        let html = r#"<span class="comment line rust">// hi</span><span class="storage modifier rust">fn</span>"#;
+        let simplified = simplify_classes(html);
+        assert!(simplified.contains(r#"class="comment""#));
+        assert!(simplified.contains(r#"class="kw""#));
+    }
+
+    #[test]
+    fn test_simplify_classes_defaults_unknown_scope_to_ident() {
+        let html = r#"<span class="some-unknown-scope">x</span>"#;
+        assert_eq!(simplify_classes(html), r#"<span class="ident">x</span>"#);
+    }
+}
@@ -0,0 +1,120 @@
+//! Marker-prefixed leading metadata headers, per
+//! [`crate::models::RenderSettings::leading_metadata_marker`].
+//!
+//! Mirrors rustdoc's `extract_leading_metadata` technique, generalized to a
+//! configurable marker instead of a single fixed one: scan lines from the top of the
+//! document, accumulating every line that starts with the marker into a key/value map,
+//! stopping at the first line that doesn't. The remaining slice (everything from that
+//! line onward) is the renderable body; if every line matched, the body is empty.
+//!
+//! This runs before YAML frontmatter parsing and is a separate mechanism from it - a
+//! document normally uses one style or the other, not both, but nothing here prevents
+//! a leading `%`/`#` header from being followed by a `---`-fenced YAML block further
+//! down, which [`crate::mdx::mdx_to_writer_with_frontmatter`] still parses from
+//! whatever body this leaves behind.
+
+use std::collections::BTreeMap;
+
+/// Which per-line marker [`extract_leading_metadata`] recognizes.
+#[derive(serde::Deserialize, serde::Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum LeadingMetadataMarker {
+    /// Pandoc-style `% key: value` lines (its title-block convention, one field per
+    /// line).
+    Percent,
+    /// `# key: value` lines.
+    Hash,
+}
+
+impl LeadingMetadataMarker {
+    fn prefix(self) -> char {
+        match self {
+            Self::Percent => '%',
+            Self::Hash => '#',
+        }
+    }
+}
+
+/// Peels `marker`-prefixed lines off the top of `source` into a key/value map,
+/// returning it alongside the remaining, un-consumed source - see the module docs for
+/// the algorithm. A matched line without a `:` separator contributes no entry but is
+/// still consumed (treated as metadata with no value worth keeping), so an
+/// odd/malformed header line doesn't leak into the rendered body.
+pub(crate) fn extract_leading_metadata(
+    source: &str,
+    marker: LeadingMetadataMarker,
+) -> (serde_json::Value, &str) {
+    let prefix = marker.prefix();
+    let mut metadata = BTreeMap::new();
+    let mut consumed = 0;
+
+    for line in source.split_inclusive('\n') {
+        let trimmed = line.trim_end_matches(['\n', '\r']);
+        let Some(rest) = trimmed.strip_prefix(prefix) else {
+            break;
+        };
+        if let Some((key, value)) = rest.trim_start().split_once(':') {
+            metadata.insert(key.trim().to_string(), value.trim().to_string());
+        }
+        consumed += line.len();
+    }
+
+    let body = &source[consumed..];
+    let metadata = serde_json::to_value(metadata).unwrap_or(serde_json::Value::Null);
+    (metadata, body)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn extracts_percent_style_title_block() {
+        let source = "% Title: Hello World\n% Author: Jane\n\n# Body\n";
+        let (metadata, body) = extract_leading_metadata(source, LeadingMetadataMarker::Percent);
+        assert_eq!(metadata["Title"], "Hello World");
+        assert_eq!(metadata["Author"], "Jane");
+        assert_eq!(body, "\n# Body\n");
+    }
+
+    #[test]
+    fn extracts_hash_style_lines() {
+        let source = "# layout: post\n# title: My Post\nBody content\n";
+        let (metadata, body) = extract_leading_metadata(source, LeadingMetadataMarker::Hash);
+        assert_eq!(metadata["layout"], "post");
+        assert_eq!(metadata["title"], "My Post");
+        assert_eq!(body, "Body content\n");
+    }
+
+    #[test]
+    fn stops_at_first_non_matching_line() {
+        let source = "% Title: Hello\nNot metadata\n% Ignored: after body\n";
+        let (metadata, body) = extract_leading_metadata(source, LeadingMetadataMarker::Percent);
+        assert_eq!(metadata["Title"], "Hello");
+        assert_eq!(metadata.get("Ignored"), None);
+        assert_eq!(body, "Not metadata\n% Ignored: after body\n");
+    }
+
+    #[test]
+    fn all_lines_metadata_yields_empty_body() {
+        let source = "% Title: Hello\n% Author: Jane\n";
+        let (_, body) = extract_leading_metadata(source, LeadingMetadataMarker::Percent);
+        assert_eq!(body, "");
+    }
+
+    #[test]
+    fn no_matching_lines_yields_unchanged_body() {
+        let source = "# Heading\n\nParagraph.\n";
+        let (metadata, body) = extract_leading_metadata(source, LeadingMetadataMarker::Percent);
+        assert_eq!(metadata, serde_json::json!({}));
+        assert_eq!(body, source);
+    }
+
+    #[test]
+    fn malformed_line_is_consumed_without_an_entry() {
+        let source = "% just some words\n% Title: Hello\nBody\n";
+        let (metadata, body) = extract_leading_metadata(source, LeadingMetadataMarker::Percent);
+        assert_eq!(metadata, serde_json::json!({"Title": "Hello"}));
+        assert_eq!(body, "Body\n");
+    }
+}
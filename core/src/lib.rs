@@ -8,11 +8,33 @@
 //! The library is organized into several key modules:
 //!
 //! - **`service`**: High-level batch rendering service with resource limits and error handling
+//! - **`batch_cache`**: Opt-in content-addressed cache of whole-file render results
+//! - **`compression`**: `Accept-Encoding` negotiation and response body compression
 //! - **`mdx`**: MDX parsing, frontmatter extraction, and rendering pipeline orchestration
 //! - **`renderer`**: JavaScript runtime management using Deno Core for component rendering
 //! - **`transform`**: TSX/JSX to JavaScript transformation using Oxc compiler
+//! - **`transform_cache`**: Process-wide cache of transform results shared across threads
 //! - **`models`**: Data structures for MDX content, components, and configuration
 //! - **`error`**: Domain-specific error types for MDX processing
+//! - **`decorators`**: Open registry of user-registered template decorators and helpers
+//! - **`scripting`**: Open registry of `mlua`-backed container directives and template utilities
+//! - **`highlight`**: Syntax highlighting of fenced code blocks via `syntect`
+//! - **`hidden_lines`**: Rustdoc-style `# `-hidden lines in fenced code blocks
+//! - **`unindent`**: Common-indentation stripping for fenced code blocks, rustdoc-style
+//! - **`toc`**: Heading-anchor generation and table-of-contents extraction
+//! - **`search`**: Full-text search index generation for a rendered batch
+//! - **`fence`**: Rich fenced code-block info-string parsing (extra classes, `ignore`, line ranges)
+//! - **`typography`**: Smart typographic punctuation (curly quotes, dashes, ellipses) over rendered HTML
+//! - **`links`**: External-link `target`/`rel` attribute hardening over rendered HTML
+//! - **`parser_hooks`**: Pluggable hooks for parsing JS expressions and ESM `import`/`export` blocks
+//! - **`summary`**: Plain-text summary/excerpt generation over rendered HTML
+//! - **`emoji`**: `:name:` shortcode expansion to Unicode emoji over rendered HTML
+//! - **`negotiation`**: `Accept` header content negotiation for [`models::OutputFormat`]
+//! - **`upload`**: Assembling a [`models::NamedMdxBatchInput`] from `multipart/form-data` parts
+//! - **`rewrite`**: Structural search-and-replace rules over the rendered JSON document tree
+//! - **`render_cache`**: Opt-in, per-render content-hash cache of whole-file render results
+//! - **`partials`**: Recursive expansion of `<Include name="..." />`/`{{> name}}` partial references
+//! - **`tower_adapter`**: `tower::Service` adapter that coalesces single-file renders into batches
 //!
 //! ### Rendering Pipeline
 //!
@@ -96,6 +118,7 @@
 //!     settings: Default::default(),
 //!     mdx: std::collections::HashMap::new(),
 //!     components: None,
+//!     partials: None,
 //! };
 //!
 //! let outcome = service.render_batch(&input)?;
@@ -105,11 +128,42 @@
 
 #![deny(missing_docs)]
 
+pub mod batch_cache;
+pub mod batch_worker_pool;
+pub mod block_helpers;
+pub mod compression;
+pub mod decorators;
+#[cfg(feature = "http")]
+pub mod dev_watch;
+pub mod doctest;
+pub mod emoji;
 pub mod error;
+pub mod fence;
 #[cfg(feature = "http")]
 pub mod handlers;
+pub mod hidden_lines;
+pub mod highlight;
+pub mod leading_metadata;
+pub mod links;
 pub mod mdx;
+pub mod minify;
 pub mod models;
+pub mod negotiation;
+pub mod parser_hooks;
+pub mod partials;
+pub mod render_cache;
 pub mod renderer;
+pub mod rewrite;
+pub mod sanitize;
+pub mod scripting;
+pub mod search;
 pub mod service;
+pub mod summary;
+pub mod toc;
+#[cfg(feature = "tower")]
+pub mod tower_adapter;
 pub mod transform;
+pub mod transform_cache;
+pub mod typography;
+pub mod unindent;
+pub mod upload;
@@ -0,0 +1,186 @@
+//! External-link attribute hardening, applied over rendered HTML.
+//!
+//! Rewrites each `<a href="...">` tag whose `href` is an absolute, scheme-qualified
+//! URL pointing somewhere other than [`ExternalLinkRewrite::site_host`]: `target`
+//! becomes `"_blank"` (unless the author already set one), and `rel` gains whichever
+//! of `nofollow`, `noreferrer`, and `noopener` the caller opted into - merged into any
+//! existing `rel` tokens rather than clobbering them. A relative link like the
+//! `[click here](/)` in `test_table_with_jsx_and_code`, or one whose host matches
+//! `site_host`, is left untouched. This is the external-link hardening set from the
+//! zola markdown config - see [`crate::models::RenderSettings::external_links_target_blank`].
+
+use regex::Regex;
+use std::sync::LazyLock;
+
+/// Matches an anchor tag's opening `<a ...>`, capturing its attribute text.
+///
+/// # Safety
+/// Pattern is compile-time constant and known to be valid.
+static ANCHOR_TAG: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"<a\b([^>]*)>"#).expect("hardcoded regex pattern is valid"));
+
+/// Matches a double-quoted `href` attribute, capturing its value.
+static HREF_ATTR: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"(?i)\bhref\s*=\s*"([^"]*)""#).expect("hardcoded regex pattern is valid")
+});
+
+/// Matches a double-quoted `rel` attribute, capturing its value.
+static REL_ATTR: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"(?i)\brel\s*=\s*"([^"]*)""#).expect("hardcoded regex pattern is valid")
+});
+
+/// Matches a `target` attribute of any value, to detect one the author already set.
+static TARGET_ATTR: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"(?i)\btarget\s*=\s*"[^"]*""#).expect("hardcoded regex pattern is valid")
+});
+
+/// Matches an absolute `http(s)://` URL, capturing its host (and port, if any).
+static ABSOLUTE_HTTP_URL: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"(?i)^https?://([^/?#]+)"#).expect("hardcoded regex pattern is valid")
+});
+
+/// Which anchor-attribute rewrites [`rewrite_external_links`] applies, and what counts
+/// as "external" - see [`crate::models::RenderSettings::external_links_target_blank`],
+/// [`crate::models::RenderSettings::external_links_nofollow`],
+/// [`crate::models::RenderSettings::external_links_noreferrer`], and
+/// [`crate::models::RenderSettings::external_links_site_host`].
+pub(crate) struct ExternalLinkRewrite<'a> {
+    /// Add `target="_blank"` to external links that don't already have a `target`.
+    pub target_blank: bool,
+    /// Merge `nofollow` into external links' `rel`.
+    pub nofollow: bool,
+    /// Merge `noreferrer` into external links' `rel`.
+    pub noreferrer: bool,
+    /// A link's host is compared against this (case-insensitively) to decide whether
+    /// it's "internal"; `None` treats every absolute `http(s)` URL as external.
+    pub site_host: Option<&'a str>,
+}
+
+/// Rewrites every external `<a href="...">` tag in `html` per `rewrite`. A tag with no
+/// `href`, a relative `href`, or an `href` whose host matches `rewrite.site_host` is
+/// passed through unchanged.
+pub(crate) fn rewrite_external_links(html: &str, rewrite: &ExternalLinkRewrite) -> String {
+    let mut out = String::with_capacity(html.len());
+    let mut last = 0;
+    for caps in ANCHOR_TAG.captures_iter(html) {
+        let whole = caps.get(0).expect("group 0 always matches");
+        out.push_str(&html[last..whole.start()]);
+        last = whole.end();
+        out.push_str(&rewrite_anchor_tag(whole.as_str(), &caps[1], rewrite));
+    }
+    out.push_str(&html[last..]);
+    out
+}
+
+/// Rewrites a single already-matched `<a ...>` tag, or returns it unchanged if its
+/// `href` isn't external.
+fn rewrite_anchor_tag(whole: &str, attrs: &str, rewrite: &ExternalLinkRewrite) -> String {
+    let Some(href) = HREF_ATTR.captures(attrs).and_then(|c| c.get(1)) else {
+        return whole.to_string();
+    };
+    if !is_external_url(href.as_str(), rewrite.site_host) {
+        return whole.to_string();
+    }
+
+    let mut new_attrs = attrs.to_string();
+
+    if rewrite.target_blank && !TARGET_ATTR.is_match(&new_attrs) {
+        new_attrs.push_str(r#" target="_blank""#);
+    }
+
+    let mut rel_tokens: Vec<String> = REL_ATTR
+        .captures(attrs)
+        .and_then(|c| c.get(1))
+        .map(|value| value.as_str().split_whitespace().map(str::to_string).collect())
+        .unwrap_or_default();
+    for token in desired_rel_tokens(rewrite) {
+        if !rel_tokens.iter().any(|existing| existing.eq_ignore_ascii_case(token)) {
+            rel_tokens.push(token.to_string());
+        }
+    }
+
+    if !rel_tokens.is_empty() {
+        let merged = format!(r#"rel="{}""#, rel_tokens.join(" "));
+        new_attrs = if REL_ATTR.is_match(&new_attrs) {
+            REL_ATTR.replace(&new_attrs, merged.as_str()).into_owned()
+        } else {
+            new_attrs + " " + &merged
+        };
+    }
+
+    format!("<a{new_attrs}>")
+}
+
+/// The `rel` tokens `rewrite` wants present - `noopener` always accompanies
+/// `target="_blank"`, since leaving it out is the classic `target="_blank"` security
+/// gap (the opened page can reach back via `window.opener`).
+fn desired_rel_tokens(rewrite: &ExternalLinkRewrite) -> Vec<&'static str> {
+    let mut tokens = Vec::new();
+    if rewrite.nofollow {
+        tokens.push("nofollow");
+    }
+    if rewrite.noreferrer {
+        tokens.push("noreferrer");
+    }
+    if rewrite.target_blank {
+        tokens.push("noopener");
+    }
+    tokens
+}
+
+/// Whether `href` is an absolute `http(s)` URL whose host doesn't match `site_host`.
+fn is_external_url(href: &str, site_host: Option<&str>) -> bool {
+    let Some(host) = ABSOLUTE_HTTP_URL.captures(href).and_then(|c| c.get(1)) else {
+        return false;
+    };
+    match site_host {
+        Some(site) => !host.as_str().eq_ignore_ascii_case(site),
+        None => true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rewrite<'a>(site_host: Option<&'a str>) -> ExternalLinkRewrite<'a> {
+        ExternalLinkRewrite { target_blank: true, nofollow: true, noreferrer: true, site_host }
+    }
+
+    #[test]
+    fn test_rewrites_external_link_with_every_rel_token() {
+        let html = r#"<a href="https://example.com/docs">docs</a>"#;
+        let out = rewrite_external_links(html, &rewrite(None));
+        assert_eq!(
+            out,
+            r#"<a href="https://example.com/docs" target="_blank" rel="nofollow noreferrer noopener">docs</a>"#
+        );
+    }
+
+    #[test]
+    fn test_leaves_relative_link_untouched() {
+        let html = r#"<a href="/about">about</a>"#;
+        assert_eq!(rewrite_external_links(html, &rewrite(None)), html);
+    }
+
+    #[test]
+    fn test_leaves_site_host_link_untouched() {
+        let html = r#"<a href="https://example.com/docs">docs</a>"#;
+        assert_eq!(rewrite_external_links(html, &rewrite(Some("example.com"))), html);
+    }
+
+    #[test]
+    fn test_merges_rel_without_clobbering_existing_tokens() {
+        let html = r#"<a href="https://example.com" rel="author">x</a>"#;
+        let out = rewrite_external_links(html, &rewrite(None));
+        assert!(out.contains(r#"rel="author nofollow noreferrer noopener""#));
+    }
+
+    #[test]
+    fn test_respects_author_supplied_target() {
+        let html = r#"<a href="https://example.com" target="_self">x</a>"#;
+        let out = rewrite_external_links(html, &rewrite(None));
+        assert!(out.contains(r#"target="_self""#));
+        assert!(!out.contains("_blank"));
+    }
+}
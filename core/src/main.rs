@@ -6,15 +6,21 @@
 #[cfg(feature = "http")]
 use actix_web::{web, App, HttpServer};
 #[cfg(feature = "http")]
-use clap::Parser;
+use clap::{Parser, Subcommand, ValueEnum};
+#[cfg(feature = "http")]
+use dinja_core::dev_watch::{self, ReloadBroadcaster};
 #[cfg(feature = "http")]
 use dinja_core::handlers;
 #[cfg(feature = "http")]
-use dinja_core::models::ResourceLimits;
+use dinja_core::models::{NamedMdxBatchInput, OutputFormat, ResourceLimits};
+#[cfg(feature = "http")]
+use dinja_core::service::{ConfigWatchOptions, RenderService, RenderServiceConfig};
+#[cfg(feature = "http")]
+use std::collections::HashMap;
 #[cfg(feature = "http")]
-use dinja_core::service::{RenderService, RenderServiceConfig};
+use std::path::{Path, PathBuf};
 #[cfg(feature = "http")]
-use std::path::PathBuf;
+use std::time::Duration;
 
 /// Dinja MDX Rendering Server
 #[cfg(feature = "http")]
@@ -22,6 +28,23 @@ use std::path::PathBuf;
 #[command(name = "dinja")]
 #[command(author, version, about = "High-performance MDX rendering service", long_about = None)]
 struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[cfg(feature = "http")]
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Start the HTTP rendering server (previously the only thing `dinja` did)
+    Serve(ServeArgs),
+    /// Render MDX file(s) to disk or stdout without starting a server, for static-site
+    /// prebuild pipelines - see [`run_render`]
+    Render(RenderArgs),
+}
+
+#[cfg(feature = "http")]
+#[derive(Parser, Debug)]
+struct ServeArgs {
     /// Host address to bind to
     #[arg(short = 'H', long, default_value = "0.0.0.0", env = "HOST")]
     host: String,
@@ -42,6 +65,10 @@ struct Cli {
     #[arg(long, default_value = "4", env = "DINJA_MAX_CACHED_RENDERERS")]
     max_cached_renderers: usize,
 
+    /// Maximum number of files rendered concurrently within one batch request
+    #[arg(long, default_value = "1", env = "DINJA_MAX_BATCH_CONCURRENCY")]
+    max_batch_concurrency: usize,
+
     /// Maximum files per batch request
     #[arg(long, default_value = "1000", env = "DINJA_MAX_BATCH_SIZE")]
     max_batch_size: usize,
@@ -53,45 +80,371 @@ struct Cli {
     /// Maximum component code size in bytes (default: 1MB)
     #[arg(long, default_value = "1048576", env = "DINJA_MAX_COMPONENT_SIZE")]
     max_component_size: usize,
+
+    /// Maximum time an async render may await the JS event loop before it's aborted
+    #[arg(long, default_value = "5000", env = "DINJA_MAX_RENDER_TIME_MS")]
+    max_render_time_ms: u64,
+
+    /// Directory to persist built V8 startup snapshots to, reloaded on later starts
+    /// instead of rebuilding (unset disables disk caching; snapshots stay in-memory only)
+    #[arg(long, env = "RUST_CMS_SNAPSHOT_CACHE_DIR")]
+    snapshot_cache_dir: Option<PathBuf>,
+
+    /// Stack size, in bytes, given to each batch worker thread spawned when
+    /// `max_batch_concurrency` is greater than 1 (unset uses the platform default)
+    #[arg(long, env = "RUST_CMS_WORKER_STACK_SIZE_BYTES")]
+    worker_stack_size_bytes: Option<usize>,
+
+    /// Number of persistent threads backing batch rendering's concurrent path (unset
+    /// derives it from `--max-cached-renderers`) - see
+    /// `RenderServiceConfig::worker_threads`
+    #[arg(long, env = "RUST_CMS_WORKER_THREADS")]
+    worker_threads: Option<usize>,
+
+    /// Skip V8 startup snapshot use, creating every renderer cold instead - see
+    /// `RenderServiceConfig::snapshot_enabled`. Mainly useful for isolating
+    /// snapshot-related issues or measuring cold-start cost.
+    #[arg(long)]
+    disable_snapshot: bool,
+
+    /// Dev mode: poll `static_dir` for changes and hot-reload cached renderers
+    /// instead of requiring a server restart (see `dinja_core::dev_watch`)
+    #[arg(long)]
+    watch: bool,
+
+    /// Poll interval for `--watch`, in milliseconds
+    #[arg(long, default_value = "300")]
+    watch_interval_ms: u64,
+
+    /// Attach per-phase timing (pool checkout, per-file render, slowest files) to
+    /// every batch outcome - see `RenderServiceConfig::enable_profiling`
+    #[arg(long)]
+    enable_profiling: bool,
+
+    /// Poll `--config`'s file for changes and hot-apply `max_cached_renderers`/
+    /// resource limit updates without a restart, instead of requiring `PUT
+    /// /status` or a process restart (see
+    /// `dinja_core::service::RenderService::watch_config`). Has no effect without
+    /// `--config`.
+    #[arg(long)]
+    watch_config: bool,
+
+    /// Poll interval for `--watch-config`, in milliseconds
+    #[arg(long, default_value = "2000")]
+    watch_config_interval_ms: u64,
 }
 
 #[cfg(feature = "http")]
-impl Cli {
+impl ServeArgs {
     fn into_config(self) -> RenderServiceConfig {
         RenderServiceConfig {
             static_dir: self.static_dir,
             max_cached_renderers: self.max_cached_renderers,
+            max_batch_concurrency: self.max_batch_concurrency,
             resource_limits: ResourceLimits {
                 max_batch_size: self.max_batch_size,
                 max_mdx_content_size: self.max_mdx_size,
                 max_component_code_size: self.max_component_size,
+                max_render_time_ms: self.max_render_time_ms,
             },
+            compression: dinja_core::compression::CompressionConfig::default(),
+            upload: dinja_core::upload::UploadConfig::default(),
+            snapshot_cache_dir: self.snapshot_cache_dir,
+            snapshot_enabled: !self.disable_snapshot,
+            enable_profiling: self.enable_profiling,
+            worker_stack_size_bytes: self.worker_stack_size_bytes,
+            worker_threads: self.worker_threads,
         }
     }
 }
 
-/// Entry point for the Actix Web server
+/// `dinja render`'s `--format` values - the subset of [`OutputFormat`] that makes sense
+/// as a one-shot file-to-file conversion target.
+#[cfg(feature = "http")]
+#[derive(Clone, Copy, Debug, ValueEnum)]
+enum RenderFormat {
+    Html,
+    Js,
+    Json,
+    Schema,
+}
+
+#[cfg(feature = "http")]
+impl From<RenderFormat> for OutputFormat {
+    fn from(format: RenderFormat) -> Self {
+        match format {
+            RenderFormat::Html => OutputFormat::Html,
+            RenderFormat::Js => OutputFormat::Javascript,
+            RenderFormat::Json => OutputFormat::Json,
+            RenderFormat::Schema => OutputFormat::Schema,
+        }
+    }
+}
+
+#[cfg(feature = "http")]
+#[derive(Parser, Debug)]
+struct RenderArgs {
+    /// MDX file to render, or a directory to render recursively (every `.mdx` file
+    /// under it)
+    input: PathBuf,
+
+    /// Directory to write rendered files into, mirroring `input`'s relative layout;
+    /// omit to print each file's output to stdout
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+
+    /// Output format
+    #[arg(short, long, value_enum, default_value = "html")]
+    format: RenderFormat,
+
+    /// JSON file of props made available to every rendered file as frontmatter
+    /// defaults - a file's own frontmatter still overrides matching keys
+    #[arg(long)]
+    props: Option<PathBuf>,
+
+    /// Path to configuration file (TOML), same format as `serve`
+    #[arg(short, long, env = "DINJA_CONFIG")]
+    config: Option<PathBuf>,
+
+    /// Directory containing static files (engine.min.js)
+    #[arg(short, long, default_value = "static", env = "RUST_CMS_STATIC_DIR")]
+    static_dir: PathBuf,
+
+    /// Maximum files rendered in this run
+    #[arg(long, default_value = "1000", env = "DINJA_MAX_BATCH_SIZE")]
+    max_batch_size: usize,
+
+    /// Maximum MDX content size in bytes (default: 10MB)
+    #[arg(long, default_value = "10485760", env = "DINJA_MAX_MDX_SIZE")]
+    max_mdx_size: usize,
+
+    /// Maximum component code size in bytes (default: 1MB)
+    #[arg(long, default_value = "1048576", env = "DINJA_MAX_COMPONENT_SIZE")]
+    max_component_size: usize,
+
+    /// Maximum time an async render may await the JS event loop before it's aborted
+    #[arg(long, default_value = "5000", env = "DINJA_MAX_RENDER_TIME_MS")]
+    max_render_time_ms: u64,
+}
+
+#[cfg(feature = "http")]
+impl RenderArgs {
+    fn into_config(&self) -> RenderServiceConfig {
+        RenderServiceConfig {
+            static_dir: self.static_dir.clone(),
+            resource_limits: ResourceLimits {
+                max_batch_size: self.max_batch_size,
+                max_mdx_content_size: self.max_mdx_size,
+                max_component_code_size: self.max_component_size,
+                max_render_time_ms: self.max_render_time_ms,
+            },
+            ..RenderServiceConfig::default()
+        }
+    }
+}
+
+/// File extension each [`RenderFormat`] is written out with, when writing to an
+/// `--output` directory rather than stdout.
+#[cfg(feature = "http")]
+fn output_extension(format: RenderFormat) -> &'static str {
+    match format {
+        RenderFormat::Html => "html",
+        RenderFormat::Js => "js",
+        RenderFormat::Json | RenderFormat::Schema => "json",
+    }
+}
+
+/// Recursively collects every `.mdx` file under `path`, or returns `path` itself if
+/// it's already a file - sorted for deterministic output across runs.
+#[cfg(feature = "http")]
+fn collect_mdx_files(path: &Path) -> std::io::Result<Vec<PathBuf>> {
+    if path.is_file() {
+        return Ok(vec![path.to_path_buf()]);
+    }
+
+    let mut files = Vec::new();
+    let mut pending = vec![path.to_path_buf()];
+    while let Some(dir) = pending.pop() {
+        for entry in std::fs::read_dir(&dir)? {
+            let entry_path = entry?.path();
+            if entry_path.is_dir() {
+                pending.push(entry_path);
+            } else if entry_path.extension().and_then(|ext| ext.to_str()) == Some("mdx") {
+                files.push(entry_path);
+            }
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// Merges `props` into `mdx_content`'s frontmatter as defaults: the file's own
+/// frontmatter keys (if any) override matching `props` keys. Frontmatter in this
+/// renderer is just the YAML block parsed into a JSON value (see
+/// `mdx::mdx_to_writer_with_frontmatter`), and JSON is valid YAML, so the merged
+/// object is written back out as a compact JSON frontmatter block rather than
+/// requiring a YAML serializer this crate doesn't otherwise depend on.
+#[cfg(feature = "http")]
+fn apply_default_props(mdx_content: &str, props: &serde_json::Value) -> String {
+    use gray_matter::{engine::YAML, Matter};
+
+    let matter = Matter::<YAML>::new();
+    let Ok(parsed) = matter.parse::<serde_json::Value>(mdx_content) else {
+        return mdx_content.to_string();
+    };
+
+    let mut merged = props.clone();
+    if let (Some(base), Some(serde_json::Value::Object(overrides))) =
+        (merged.as_object_mut(), parsed.data)
+    {
+        base.extend(overrides);
+    }
+
+    format!(
+        "---\n{}\n---\n{}",
+        serde_json::to_string(&merged).unwrap_or_else(|_| "{}".to_string()),
+        parsed.content
+    )
+}
+
+/// Implements `dinja render`: gathers `args.input`'s MDX file(s), renders them through
+/// the same [`RenderService`] the HTTP server uses, and writes each result to
+/// `args.output` (mirroring `args.input`'s relative layout) or to stdout.
+#[cfg(feature = "http")]
+fn run_render(args: RenderArgs) -> std::io::Result<()> {
+    let base_dir = if args.input.is_dir() {
+        args.input.clone()
+    } else {
+        args.input.parent().map(Path::to_path_buf).unwrap_or_default()
+    };
+
+    let files = collect_mdx_files(&args.input)?;
+    if files.is_empty() {
+        eprintln!("No .mdx files found at {}", args.input.display());
+        std::process::exit(1);
+    }
+
+    let props = match &args.props {
+        Some(path) => {
+            let raw = std::fs::read_to_string(path)?;
+            Some(serde_json::from_str::<serde_json::Value>(&raw).unwrap_or_else(|e| {
+                eprintln!("❌ Failed to parse props file {}: {}", path.display(), e);
+                std::process::exit(1);
+            }))
+        }
+        None => None,
+    };
+
+    let mut mdx = HashMap::with_capacity(files.len());
+    for file in &files {
+        let content = std::fs::read_to_string(file)?;
+        let content = match &props {
+            Some(props) => apply_default_props(&content, props),
+            None => content,
+        };
+        let name = file.strip_prefix(&base_dir).unwrap_or(file).to_string_lossy().into_owned();
+        mdx.insert(name, content);
+    }
+
+    let format = args.format;
+    let config = match &args.config {
+        Some(config_path) => RenderServiceConfig::from_file_and_env(config_path).unwrap_or_else(|e| {
+            eprintln!("⚠️  Failed to load config file: {}", e);
+            eprintln!("   Falling back to CLI arguments");
+            args.into_config()
+        }),
+        None => args.into_config(),
+    };
+    let service = match RenderService::new(config) {
+        Ok(service) => service,
+        Err(err) => {
+            eprintln!("❌ Failed to initialize render service: {}", err);
+            std::process::exit(1);
+        }
+    };
+
+    let input = NamedMdxBatchInput {
+        settings: dinja_core::models::RenderSettings {
+            output: format.into(),
+            ..Default::default()
+        },
+        mdx,
+        components: None,
+        partials: None,
+    };
+
+    let outcome = match service.render_batch(&input) {
+        Ok(outcome) => outcome,
+        Err(err) => {
+            eprintln!("❌ Render failed: {}", err);
+            std::process::exit(1);
+        }
+    };
+
+    if let Some(output_dir) = &args.output {
+        std::fs::create_dir_all(output_dir)?;
+    }
+
+    for (name, file_outcome) in &outcome.files {
+        let Some(rendered) = file_outcome.result.as_ref().and_then(|r| r.output.as_ref()) else {
+            eprintln!("⚠️  {}: {}", name, file_outcome.error.as_deref().unwrap_or("render failed"));
+            continue;
+        };
+
+        match &args.output {
+            Some(output_dir) => {
+                let mut dest = output_dir.join(name);
+                dest.set_extension(output_extension(format));
+                if let Some(parent) = dest.parent() {
+                    std::fs::create_dir_all(parent)?;
+                }
+                std::fs::write(&dest, rendered)?;
+                println!("{} -> {}", name, dest.display());
+            }
+            None => {
+                println!("=== {} ===", name);
+                println!("{}", rendered);
+            }
+        }
+    }
+
+    if outcome.failed > 0 {
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+/// Starts the Actix Web server for `dinja serve`.
 #[cfg(feature = "http")]
 #[actix_web::main]
-async fn main() -> std::io::Result<()> {
-    let cli = Cli::parse();
-    let bind_addr = format!("{}:{}", cli.host, cli.port);
+async fn run_serve(args: ServeArgs) -> std::io::Result<()> {
+    let bind_addr = format!("{}:{}", args.host, args.port);
 
     println!("🦀 Dinja MDX Server");
     println!("   Listening on http://{}", bind_addr);
-    println!("   Static dir:  {}", cli.static_dir.display());
-    println!("   Max renderers: {}", cli.max_cached_renderers);
-    println!("   Max batch: {}", cli.max_batch_size);
+    println!("   Static dir:  {}", args.static_dir.display());
+    println!("   Max renderers: {}", args.max_cached_renderers);
+    println!("   Max batch: {}", args.max_batch_size);
+    if args.watch {
+        println!("   Watch mode: polling every {}ms, hot-reloading renderers", args.watch_interval_ms);
+    }
 
-    let config = if let Some(ref config_path) = cli.config {
+    let watch = args.watch;
+    let watch_interval_ms = args.watch_interval_ms;
+    let watch_dir = args.static_dir.clone();
+    let watch_config = args.watch_config;
+    let watch_config_interval_ms = args.watch_config_interval_ms;
+    let config_path = args.config.clone();
+
+    let config = if let Some(ref config_path) = args.config {
         println!("   Config file: {}", config_path.display());
         RenderServiceConfig::from_file_and_env(config_path).unwrap_or_else(|e| {
             eprintln!("⚠️  Failed to load config file: {}", e);
             eprintln!("   Falling back to CLI arguments");
-            cli.into_config()
+            args.into_config()
         })
     } else {
-        cli.into_config()
+        args.into_config()
     };
 
     let service = match RenderService::new(config) {
@@ -103,21 +456,70 @@ async fn main() -> std::io::Result<()> {
         }
     };
 
+    let reload_broadcaster = ReloadBroadcaster::new(16);
+    if watch {
+        dev_watch::watch_static_dir(
+            watch_dir,
+            Duration::from_millis(watch_interval_ms),
+            service.pool().clone(),
+            reload_broadcaster.clone(),
+        );
+    }
+
+    if watch_config {
+        match config_path {
+            Some(config_path) => {
+                println!(
+                    "   Watching config file for changes: polling every {}ms",
+                    watch_config_interval_ms
+                );
+                service.watch_config(
+                    config_path,
+                    ConfigWatchOptions {
+                        poll_interval: Duration::from_millis(watch_config_interval_ms),
+                        ..ConfigWatchOptions::default()
+                    },
+                );
+            }
+            None => {
+                eprintln!("⚠️  --watch-config has no effect without --config");
+            }
+        }
+    }
+
     HttpServer::new(move || {
         App::new()
             .app_data(web::Data::new(service.clone()))
+            .app_data(web::Data::new(reload_broadcaster.clone()))
             .service(handlers::health)
+            .service(handlers::status)
+            .service(handlers::reconfigure)
+            .service(handlers::dev_reload)
             .service(handlers::render)
             .service(handlers::render_html)
             .service(handlers::render_javascript)
             .service(handlers::render_schema)
             .service(handlers::render_json)
+            .service(handlers::render_ast)
+            .service(handlers::render_es_module)
+            .service(handlers::render_negotiated)
+            .service(handlers::render_stream)
+            .service(handlers::render_upload)
     })
     .bind(&bind_addr)?
     .run()
     .await
 }
 
+/// Entry point: dispatches to `dinja serve` or `dinja render`.
+#[cfg(feature = "http")]
+fn main() -> std::io::Result<()> {
+    match Cli::parse().command {
+        Command::Serve(args) => run_serve(args),
+        Command::Render(args) => run_render(args),
+    }
+}
+
 #[cfg(not(feature = "http"))]
 fn main() {
     eprintln!("Error: This binary requires the 'http' feature to be enabled.");
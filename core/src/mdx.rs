@@ -17,7 +17,7 @@
 //! All domain-specific errors use `MdxError`. Errors are converted to `anyhow::Error` at the
 //! service boundary for consistent error handling in HTTP handlers.
 
-use crate::error::MdxError;
+use crate::error::{LineIndex, MdxError};
 use crate::models::{
     ComponentDefinition, OutputFormat, RenderSettings, RenderedMdx, TsxTransformConfig,
 };
@@ -27,7 +27,7 @@ use gray_matter::{engine::YAML, Matter};
 use markdown::{to_html_with_options, CompileOptions, Constructs, Options, ParseOptions};
 use regex::Regex;
 use serde_json::json;
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::sync::LazyLock;
 
 // =============================================================================
@@ -42,30 +42,6 @@ const MAX_JSX_PLACEHOLDERS: usize = 1000;
 /// Prevents stack overflow from deeply nested components.
 const MAX_JSX_NESTING_DEPTH: usize = 100;
 
-/// Compiled regex for self-closing JSX components with expression attributes.
-/// Pattern: <ComponentName attr={...} />
-/// - Component names must start with uppercase (JSX convention)
-/// - Must have at least one expression attribute (curly braces)
-/// - Must be self-closing (ends with />)
-///
-/// # Safety
-/// Pattern is compile-time constant and known to be valid.
-static SELF_CLOSING_JSX_PATTERN: LazyLock<Regex> = LazyLock::new(|| {
-    Regex::new(r"<([A-Z][a-zA-Z0-9]*)\s+[^>]*\{[^}]*\}[^>]*/\s*>")
-        .expect("hardcoded regex pattern is valid")
-});
-
-/// Compiled regex for opening JSX tags with expression attributes.
-/// Pattern: <ComponentName attr={...}>
-/// Used to find JSX components with children that need protection.
-///
-/// # Safety
-/// Pattern is compile-time constant and known to be valid.
-static OPENING_JSX_PATTERN: LazyLock<Regex> = LazyLock::new(|| {
-    Regex::new(r"<([A-Z][a-zA-Z0-9]*)\s+[^>]*\{[^}]*\}[^>]*>")
-        .expect("hardcoded regex pattern is valid")
-});
-
 /// Compiled regex for extracting component names from HTML.
 /// Used for schema extraction.
 ///
@@ -80,6 +56,14 @@ struct RenderContext<'a> {
     components: Option<&'a HashMap<String, ComponentDefinition>>,
     props_json: &'a str,
     settings: &'a RenderSettings,
+    /// Component name -> module specifier map, merged from
+    /// [`RenderSettings::component_imports`] and the document's own frontmatter
+    /// `imports:` key - see [`merge_component_imports`].
+    component_imports: &'a HashMap<String, String>,
+    /// The document's own parsed frontmatter, for [`OutputFormat::Schema`] to fold
+    /// into [`SchemaResult::metadata`] - every other output format gets it back
+    /// directly as [`FrontmatterResult::metadata`] instead.
+    metadata: &'a serde_json::Value,
 }
 
 /// Creates markdown parsing and compilation options.
@@ -100,7 +84,30 @@ struct RenderContext<'a> {
 /// - **CommonMark**: All standard markdown features (headings, lists, code blocks, etc.)
 /// - **GFM Extensions**: Tables, strikethrough, task lists, autolinks, footnotes
 /// - **HTML/JSX**: Block and inline HTML elements for component embedding
-fn markdown_options() -> Options {
+/// Toggles for the two GFM constructs callers might reasonably want CommonMark-pure
+/// prose to opt out of - see [`crate::models::RenderSettings::enable_tables`]/
+/// [`crate::models::RenderSettings::enable_footnotes`]. Everything else
+/// [`markdown_options`] turns on (autolinks, strikethrough, task lists, raw HTML) has
+/// no CommonMark equivalent a caller could be relying on instead, so those stay
+/// unconditional.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct MarkdownExtensions {
+    /// Pipe tables (`| a | b |`), reflected as `gfm_table`.
+    pub(crate) tables: bool,
+    /// `[^1]`-style footnote references/definitions, reflected as
+    /// `gfm_footnote_definition`/`gfm_label_start_footnote`.
+    pub(crate) footnotes: bool,
+}
+
+impl Default for MarkdownExtensions {
+    /// Both constructs on, matching [`markdown_options`]'s long-standing unconditional
+    /// defaults - this is what every caller of [`render_markdown`] has always gotten.
+    fn default() -> Self {
+        Self { tables: true, footnotes: true }
+    }
+}
+
+fn markdown_options(extensions: &MarkdownExtensions) -> Options {
     Options {
         parse: ParseOptions {
             constructs: Constructs {
@@ -108,11 +115,11 @@ fn markdown_options() -> Options {
                 html_text: true, // Allow inline HTML/JSX
                 // GFM (GitHub Flavored Markdown) extensions
                 gfm_autolink_literal: true, // Auto-linkify URLs without angle brackets
-                gfm_footnote_definition: true, // Footnotes: [^a]: footnote text
-                gfm_label_start_footnote: true, // Footnote references: [^a]
-                gfm_strikethrough: true,    // Strikethrough: ~text~ or ~~text~~
-                gfm_table: true,            // Tables with | pipes |
-                gfm_task_list_item: true,   // Task lists: - [x] done
+                gfm_footnote_definition: extensions.footnotes, // Footnotes: [^a]: footnote text
+                gfm_label_start_footnote: extensions.footnotes, // Footnote references: [^a]
+                gfm_strikethrough: true,  // Strikethrough: ~text~ or ~~text~~
+                gfm_table: extensions.tables, // Tables with | pipes |
+                gfm_task_list_item: true, // Task lists: - [x] done
                 ..Constructs::default()
             },
             ..ParseOptions::default()
@@ -130,11 +137,167 @@ fn markdown_options() -> Options {
 // JSX Protection - Core Functions
 // =============================================================================
 
+/// A scanned JSX opening or self-closing tag header, as produced by
+/// [`scan_jsx_tag_header`].
+struct JsxTagHeader {
+    /// The component name (text right after `<`).
+    name: String,
+    /// Index just past the tag header's closing `>` (or `/>`).
+    end: usize,
+    /// Whether the header ended in `/>` rather than `>`.
+    self_closing: bool,
+    /// Whether the header carried at least one `{...}` expression attribute - JSX
+    /// without one is left to `markdown`'s own HTML passthrough untouched.
+    has_expr_attr: bool,
+}
+
+/// Scans a JSX opening/self-closing tag header starting at `chars[start] == '<'`
+/// (already confirmed to be followed by an uppercase letter), finding its true end by
+/// tracking brace depth through `{...}` expression attributes - skipping over
+/// single/double/backtick-quoted string contents and `{/* ... */}` comments within
+/// them, so a `>`, `}`, or nested `{` embedded in one of those can't be mistaken for
+/// the end of an expression or of the tag itself.
+///
+/// Returns `None` if there's no tag name or the header runs off the end of the
+/// document without a closing `>`.
+fn scan_jsx_tag_header(chars: &[char], start: usize) -> Option<JsxTagHeader> {
+    let name_start = start + 1;
+    let mut i = name_start;
+    while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+        i += 1;
+    }
+    if i == name_start {
+        return None;
+    }
+    let name: String = chars[name_start..i].iter().collect();
+
+    let mut brace_depth = 0usize;
+    let mut has_expr_attr = false;
+
+    loop {
+        let c = *chars.get(i)?;
+        match c {
+            '"' | '\'' | '`' => i = skip_quoted_string(chars, i),
+            '/' if brace_depth > 0 && chars.get(i + 1) == Some(&'*') => {
+                i = skip_block_comment(chars, i);
+            }
+            '{' => {
+                if brace_depth == 0 {
+                    has_expr_attr = true;
+                }
+                brace_depth += 1;
+                i += 1;
+            }
+            '}' => {
+                brace_depth = brace_depth.saturating_sub(1);
+                i += 1;
+            }
+            '/' if brace_depth == 0 && chars.get(i + 1) == Some(&'>') => {
+                return Some(JsxTagHeader {
+                    name,
+                    end: i + 2,
+                    self_closing: true,
+                    has_expr_attr,
+                });
+            }
+            '>' if brace_depth == 0 => {
+                return Some(JsxTagHeader {
+                    name,
+                    end: i + 1,
+                    self_closing: false,
+                    has_expr_attr,
+                });
+            }
+            _ => i += 1,
+        }
+    }
+}
+
+/// Skips a single/double/backtick-quoted string starting at `chars[start]`, honoring
+/// backslash escapes, and returns the index just past the closing quote (or the end of
+/// `chars` if it's never terminated).
+fn skip_quoted_string(chars: &[char], start: usize) -> usize {
+    let quote = chars[start];
+    let mut i = start + 1;
+    while i < chars.len() {
+        match chars[i] {
+            '\\' if i + 1 < chars.len() => i += 2,
+            c if c == quote => return i + 1,
+            _ => i += 1,
+        }
+    }
+    chars.len()
+}
+
+/// Skips a `/* ... */` block comment starting at `chars[start] == '/'` and returns the
+/// index just past its closing `*/` (or the end of `chars` if it's never terminated).
+/// Used inside `{...}` expression attributes, where a JSX comment like `{/* note */}`
+/// is just a block comment followed by (or standing in for) the expression.
+fn skip_block_comment(chars: &[char], start: usize) -> usize {
+    let mut i = start + 2; // past "/*"
+    while i + 1 < chars.len() && !(chars[i] == '*' && chars[i + 1] == '/') {
+        i += 1;
+    }
+    (i + 2).min(chars.len())
+}
+
+/// Finds a component's own matching closing tag, starting the search at `start` (just
+/// past the opening tag's header). Tracks nesting of same-named components via
+/// [`scan_jsx_tag_header`], so a nested `<Name ...>`/`<Name ... />` of the same
+/// component pushes/resolves the depth counter instead of being mistaken for plain
+/// text, and a differently-named component's header is skipped wholesale so a stray
+/// `<`, `>`, or `}` inside one of *its* expression attributes can't be mistaken for
+/// this tag's closing tag. Quote-tracking only happens inside those tag headers - the
+/// children text between tags is markdown/prose, not JS, so an apostrophe in ordinary
+/// text (e.g. "It's") must not be mistaken for the start of a string literal.
+///
+/// Returns the index of the closing tag's leading `<` and the maximum nesting depth
+/// observed, or `None` if no balanced closing tag exists.
+fn find_matching_jsx_close(chars: &[char], start: usize, tag_name: &str) -> Option<(usize, usize)> {
+    let closing_tag: Vec<char> = format!("</{tag_name}>").chars().collect();
+    let mut depth = 1usize;
+    let mut max_depth = depth;
+    let mut i = start;
+
+    while i < chars.len() {
+        match chars[i] {
+            '<' if chars[i..].starts_with(closing_tag.as_slice()) => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some((i, max_depth));
+                }
+                i += closing_tag.len();
+            }
+            '<' if chars.get(i + 1).map(|c| c.is_ascii_uppercase()).unwrap_or(false) => {
+                match scan_jsx_tag_header(chars, i) {
+                    Some(header) => {
+                        if header.name == tag_name && !header.self_closing {
+                            depth += 1;
+                            max_depth = max_depth.max(depth);
+                        }
+                        i = header.end;
+                    }
+                    None => i += 1,
+                }
+            }
+            _ => i += 1,
+        }
+    }
+
+    None
+}
+
 /// Protects JSX components from markdown processing by replacing them with placeholders.
 ///
-/// JSX components are identified as tags starting with a capital letter that contain
-/// expression attributes (curly braces). This prevents markdown from escaping the
-/// curly braces which would break the JSX syntax.
+/// JSX components are tags starting with a capital letter that carry at least one
+/// expression attribute (curly braces). This prevents markdown from escaping the
+/// curly braces, which would break the JSX syntax.
+///
+/// A single left-to-right scan ([`scan_jsx_tag_header`], [`find_matching_jsx_close`])
+/// walks the source tracking brace depth through attribute expressions and skipping
+/// quoted strings and `{/* ... */}` comments within them, so nested braces (`{{a:
+/// 1}}`, `{f({x})}`), a `>` or `}` inside a string literal, and JSX comments no longer
+/// truncate a match early or desynchronize self-closing tags from child-bearing ones.
 ///
 /// # Safety Limits
 /// - Maximum `MAX_JSX_PLACEHOLDERS` placeholders per document
@@ -146,211 +309,68 @@ fn markdown_options() -> Options {
 /// # Returns
 /// A tuple of (processed content, placeholder map)
 fn protect_jsx_components(content: &str) -> (String, HashMap<String, String>) {
-    // Early return for empty content
     if content.is_empty() {
         return (String::new(), HashMap::new());
     }
 
-    // Pre-allocate with estimated capacity
-    let estimated_placeholders = content.matches('<').count().min(MAX_JSX_PLACEHOLDERS) / 4;
-    let mut placeholders: HashMap<String, String> =
-        HashMap::with_capacity(estimated_placeholders.max(8));
-    let mut result = content.to_string();
+    let chars: Vec<char> = content.chars().collect();
+    let mut result = String::with_capacity(content.len());
+    let mut placeholders: HashMap<String, String> = HashMap::new();
     let mut counter: usize = 0;
+    let mut i = 0usize;
 
-    // Phase 1: Protect self-closing JSX components
-    // These are the most common and safest to handle
-    let matches: Vec<_> = SELF_CLOSING_JSX_PATTERN.find_iter(content).collect();
-
-    for mat in matches.into_iter().rev() {
-        // Check placeholder limit
-        if counter >= MAX_JSX_PLACEHOLDERS {
-            eprintln!(
-                "Warning: JSX placeholder limit ({}) reached, some JSX may not be protected",
-                MAX_JSX_PLACEHOLDERS
-            );
-            break;
-        }
-
-        let jsx = mat.as_str();
-        let placeholder = format!("<!--JSX:{}-->", counter);
-
-        // Use positional replacement to avoid issues with duplicate JSX
-        let start = mat.start();
-        let end = mat.end();
-
-        // Adjust positions based on previous replacements
-        // Since we iterate in reverse, positions should still be valid
-        if start < result.len() && end <= result.len() {
-            // Verify the content at this position still matches
-            if result.get(start..end).map(|s| s == jsx).unwrap_or(false) {
-                result.replace_range(start..end, &placeholder);
-                placeholders.insert(placeholder, jsx.to_string());
-                counter += 1;
-            }
-        }
-    }
-
-    // Phase 2: Protect JSX components with children
-    // This requires finding matching closing tags
-    protect_jsx_with_children(&mut result, &mut placeholders, &mut counter);
+    while i < chars.len() {
+        let is_jsx_start =
+            chars[i] == '<' && chars.get(i + 1).map(|c| c.is_ascii_uppercase()).unwrap_or(false);
 
-    (result, placeholders)
-}
-
-/// Protects JSX components that have children (non-self-closing).
-/// Uses a more careful approach to match opening and closing tags.
-fn protect_jsx_with_children(
-    content: &mut String,
-    placeholders: &mut HashMap<String, String>,
-    counter: &mut usize,
-) {
-    let mut depth = 0;
-    let mut iterations = 0;
-    let max_iterations = MAX_JSX_PLACEHOLDERS;
-
-    // Keep processing until no more matches or limits reached
-    loop {
-        iterations += 1;
-        if iterations > max_iterations || *counter >= MAX_JSX_PLACEHOLDERS {
-            break;
-        }
-
-        // Find the next opening tag with expression attributes
-        let content_snapshot = content.clone();
-        let capture = match OPENING_JSX_PATTERN.captures(&content_snapshot) {
-            Some(cap) => cap,
-            None => break,
-        };
-
-        let tag_name = match capture.get(1) {
-            Some(m) => m.as_str(),
-            None => break,
-        };
-
-        let opening_tag = match capture.get(0) {
-            Some(m) => m.as_str(),
-            None => break,
-        };
-
-        // Find positions
-        let open_pos = match content.find(opening_tag) {
-            Some(pos) => pos,
-            None => break,
-        };
-
-        // Find matching closing tag with proper nesting
-        let closing_tag = format!("</{}>", tag_name);
-        let search_start = open_pos + opening_tag.len();
-
-        if let Some(close_pos) =
-            find_matching_close_tag(content, search_start, tag_name, &closing_tag, &mut depth)
-        {
-            // Check nesting depth limit
-            if depth > MAX_JSX_NESTING_DEPTH {
+        if is_jsx_start {
+            if counter >= MAX_JSX_PLACEHOLDERS {
                 eprintln!(
-                    "Warning: JSX nesting depth ({}) exceeded limit ({})",
-                    depth, MAX_JSX_NESTING_DEPTH
+                    "Warning: JSX placeholder limit ({}) reached, some JSX may not be protected",
+                    MAX_JSX_PLACEHOLDERS
                 );
+                result.extend(&chars[i..]);
                 break;
             }
 
-            let full_end = close_pos + closing_tag.len();
-
-            // Validate bounds
-            if full_end > content.len() {
-                break;
-            }
-
-            let full_jsx = content[open_pos..full_end].to_string();
-            let placeholder = format!("<!--JSX:{}-->", counter);
-
-            // Replace in content
-            content.replace_range(open_pos..full_end, &placeholder);
-            placeholders.insert(placeholder, full_jsx);
-            *counter += 1;
-        } else {
-            // No matching close tag found - this JSX is malformed
-            // Skip this tag and continue (don't protect malformed JSX)
-            break;
-        }
-    }
-}
-
-/// Finds the matching closing tag, accounting for nested tags of the same type.
-///
-/// # Arguments
-/// * `content` - The content to search in
-/// * `start` - Position to start searching from (after opening tag)
-/// * `tag_name` - The tag name to match
-/// * `closing_tag` - The full closing tag string (e.g., "</Component>")
-/// * `depth` - Tracks current nesting depth for limit checking
-///
-/// # Returns
-/// Position of the matching closing tag, or None if not found
-fn find_matching_close_tag(
-    content: &str,
-    start: usize,
-    tag_name: &str,
-    closing_tag: &str,
-    depth: &mut usize,
-) -> Option<usize> {
-    let search_region = &content[start..];
-
-    // Build pattern for nested opening tags of same type
-    let nested_open_pattern = format!("<{}", tag_name);
-
-    let mut nesting = 1;
-    let mut pos = 0;
-
-    while nesting > 0 && pos < search_region.len() {
-        // Find next occurrence of either opening or closing tag
-        let next_open = search_region[pos..].find(&nested_open_pattern);
-        let next_close = search_region[pos..].find(closing_tag);
-
-        match (next_open, next_close) {
-            (Some(open_offset), Some(close_offset)) => {
-                if open_offset < close_offset {
-                    // Found nested opening tag first
-                    nesting += 1;
-                    *depth = (*depth).max(nesting);
-                    pos += open_offset + nested_open_pattern.len();
-                } else {
-                    // Found closing tag first
-                    nesting -= 1;
-                    if nesting == 0 {
-                        return Some(start + pos + close_offset);
+            if let Some(header) = scan_jsx_tag_header(&chars, i) {
+                if header.has_expr_attr {
+                    let span_end = if header.self_closing {
+                        Some(header.end)
+                    } else {
+                        find_matching_jsx_close(&chars, header.end, &header.name).and_then(
+                            |(close_start, max_depth)| {
+                                if max_depth > MAX_JSX_NESTING_DEPTH {
+                                    eprintln!(
+                                        "Warning: JSX nesting depth ({}) exceeded limit ({})",
+                                        max_depth, MAX_JSX_NESTING_DEPTH
+                                    );
+                                    None
+                                } else {
+                                    Some(close_start + format!("</{}>", header.name).chars().count())
+                                }
+                            },
+                        )
+                    };
+
+                    if let Some(end) = span_end {
+                        let jsx: String = chars[i..end].iter().collect();
+                        let placeholder = format!("<!--JSX:{}-->", counter);
+                        result.push_str(&placeholder);
+                        placeholders.insert(placeholder, jsx);
+                        counter += 1;
+                        i = end;
+                        continue;
                     }
-                    pos += close_offset + closing_tag.len();
-                }
-            }
-            (None, Some(close_offset)) => {
-                // Only closing tag found
-                nesting -= 1;
-                if nesting == 0 {
-                    return Some(start + pos + close_offset);
                 }
-                pos += close_offset + closing_tag.len();
-            }
-            (Some(open_offset), None) => {
-                // Only opening tag found - unbalanced
-                nesting += 1;
-                *depth = (*depth).max(nesting);
-                pos += open_offset + nested_open_pattern.len();
-            }
-            (None, None) => {
-                // Neither found - unbalanced
-                break;
             }
         }
 
-        // Safety limit on nesting
-        if nesting > MAX_JSX_NESTING_DEPTH {
-            return None;
-        }
+        result.push(chars[i]);
+        i += 1;
     }
 
-    None
+    (result, placeholders)
 }
 
 /// Restores JSX components from placeholders after markdown processing.
@@ -428,11 +448,54 @@ fn unwrap_fragment(html: &str) -> String {
     result.into_owned()
 }
 
-fn render_markdown(content: &str) -> Result<String, MdxError> {
+/// Turns `component_js` - a bare `function View(context = {}) { ... }` declaration, the
+/// same shape [`crate::transform::wrap_in_component`] produces for every other output
+/// format - into a
+/// standalone ES module by appending a default export, so [`OutputFormat::EsModule`]'s
+/// result can be written straight to a `.mjs` file and `import`ed without the caller
+/// gluing an export statement onto it themselves.
+fn as_es_module(component_js: &str) -> String {
+    format!("{component_js}\n\nexport default View;\n")
+}
+
+/// Merges a document's frontmatter `imports:` map (component name -> module
+/// specifier, e.g. `imports: { Card: "./Card.tsx" }`) over
+/// [`RenderSettings::component_imports`], the service-wide default - a per-document
+/// entry wins on a name collision, since it's overriding the default for this file
+/// specifically. A non-object `imports:` value, or a non-string map entry, is ignored
+/// rather than failing the render.
+fn merge_component_imports(
+    frontmatter: &serde_json::Value,
+    settings: &RenderSettings,
+) -> HashMap<String, String> {
+    let mut merged = settings.component_imports.clone().unwrap_or_default();
+
+    if let Some(imports) = frontmatter.get("imports").and_then(serde_json::Value::as_object) {
+        for (name, source) in imports {
+            if let Some(source) = source.as_str() {
+                merged.insert(name.clone(), source.to_string());
+            }
+        }
+    }
+
+    merged
+}
+
+pub(crate) fn render_markdown(content: &str) -> Result<String, MdxError> {
+    render_markdown_with_extensions(content, &MarkdownExtensions::default())
+}
+
+/// Same as [`render_markdown`], but with tables/footnotes toggled per `extensions` -
+/// see [`crate::models::RenderSettings::enable_tables`]/
+/// [`crate::models::RenderSettings::enable_footnotes`].
+pub(crate) fn render_markdown_with_extensions(
+    content: &str,
+    extensions: &MarkdownExtensions,
+) -> Result<String, MdxError> {
     // Protect JSX components with expression attributes from markdown processing
     let (protected_content, placeholders) = protect_jsx_components(content);
 
-    let options = markdown_options();
+    let options = markdown_options(extensions);
     let html = to_html_with_options(&protected_content, &options)
         .map_err(|e| MdxError::MarkdownRender(e.to_string()))?;
 
@@ -447,13 +510,41 @@ fn log_render_error(e: &anyhow::Error, js_output: &str, context: &str) {
     eprintln!("JavaScript output: {js_output}");
 }
 
+/// Result of [`mdx_to_writer_with_frontmatter`]: the file's parsed frontmatter plus,
+/// when [`RenderSettings::headings`] is set, its heading table of contents, and when
+/// [`RenderSettings::summary_length`] is set, its plain-text excerpt.
+#[derive(Debug, Clone)]
+pub struct FrontmatterResult {
+    /// Parsed YAML frontmatter metadata, merged with any
+    /// [`RenderSettings::leading_metadata_marker`] header the document carried.
+    pub metadata: serde_json::Value,
+    /// Table of contents entries - see [`crate::toc::inject_heading_ids`]. Empty
+    /// unless [`RenderSettings::headings`] was set.
+    pub toc: Vec<crate::models::TocEntry>,
+    /// Plain-text excerpt - see [`crate::summary::plain_text_summary`]. `None` unless
+    /// [`RenderSettings::summary_length`] was set.
+    pub summary: Option<String>,
+    /// Fenced code blocks and their evaluation results - see [`crate::doctest`].
+    /// Empty unless [`RenderSettings::doctest`] was set.
+    pub doctests: Vec<crate::models::DoctestResult>,
+}
+
 /// Converts MDX content to HTML and JavaScript with frontmatter extraction
 ///
+/// When [`RenderSettings::render_cache`] is set, consults (and populates) the
+/// process-wide cache in [`crate::render_cache`] first, keyed on `mdx_content`, the
+/// component definitions it references, `partials`, and `settings` - see that module
+/// for why this is a separate tier from [`crate::transform_cache`]/[`crate::batch_cache`].
+///
 /// # Arguments
 /// * `mdx_content` - Raw MDX content with optional YAML frontmatter
 /// * `renderer` - JavaScript renderer instance for component rendering
 /// * `components` - Optional map of component definitions to inject
-/// * `settings` - Rendering settings including output format
+/// * `partials` - Optional map of partial names to MDX source, resolved against
+///   `<Include name="..." />`/`{{> name}}` references - see [`crate::partials`]
+/// * `settings` - Rendering settings including output format; a frontmatter
+///   `imports:` map (component name -> module specifier) is merged over
+///   [`RenderSettings::component_imports`] - see [`merge_component_imports`]
 ///
 /// # Returns
 /// A `RenderedMdx` struct containing rendered output and metadata
@@ -461,37 +552,290 @@ pub fn mdx_to_html_with_frontmatter(
     mdx_content: &str,
     renderer: &JsRenderer,
     components: Option<&HashMap<String, ComponentDefinition>>,
+    partials: Option<&HashMap<String, String>>,
+    settings: &RenderSettings,
+) -> Result<RenderedMdx, MdxError> {
+    if !settings.render_cache {
+        return render_mdx_to_html_uncached(mdx_content, renderer, components, partials, settings);
+    }
+
+    let referenced: Vec<(&str, &ComponentDefinition)> = components
+        .map(|map| {
+            crate::transform::referenced_component_names(mdx_content, map)
+                .into_iter()
+                .filter_map(|name| map.get(name).map(|component| (name, component)))
+                .collect()
+        })
+        .unwrap_or_default();
+    let key = crate::render_cache::cache_key(mdx_content, &referenced, partials, settings);
+
+    crate::render_cache::get_or_insert_with(key, || {
+        render_mdx_to_html_uncached(mdx_content, renderer, components, partials, settings)
+    })
+}
+
+/// The uncached render path [`mdx_to_html_with_frontmatter`] runs on a cache miss (or
+/// always, when [`RenderSettings::render_cache`] is unset).
+fn render_mdx_to_html_uncached(
+    mdx_content: &str,
+    renderer: &JsRenderer,
+    components: Option<&HashMap<String, ComponentDefinition>>,
+    partials: Option<&HashMap<String, String>>,
     settings: &RenderSettings,
 ) -> Result<RenderedMdx, MdxError> {
+    let mut output = String::new();
+    let FrontmatterResult {
+        metadata,
+        toc,
+        summary,
+        doctests,
+    } = mdx_to_writer_with_frontmatter(
+        mdx_content,
+        renderer,
+        components,
+        partials,
+        settings,
+        &mut output,
+    )?;
+
+    Ok(RenderedMdx {
+        metadata,
+        output: Some(output),
+        toc,
+        summary,
+        doctests,
+    })
+}
+
+/// Converts MDX content to rendered output and frontmatter like
+/// [`mdx_to_html_with_frontmatter`], but writes the rendered content directly into
+/// `out` rather than returning it as an owned `String` - for a caller (e.g.
+/// [`crate::service::RenderService::render_file_to`]) that wants to stream a render
+/// straight into its own response buffer instead of allocating an intermediate one.
+/// [`mdx_to_html_with_frontmatter`] is a thin wrapper around this for callers who do
+/// want an owned result.
+///
+/// # Arguments
+/// * `mdx_content` - Raw MDX content with optional YAML frontmatter
+/// * `renderer` - JavaScript renderer instance for component rendering
+/// * `components` - Optional map of component definitions to inject
+/// * `partials` - Optional map of partial names to MDX source, resolved against
+///   `<Include name="..." />`/`{{> name}}` references - see [`crate::partials`]
+/// * `settings` - Rendering settings including output format
+/// * `out` - Sink the rendered content is written into
+///
+/// # Returns
+/// The parsed frontmatter metadata and table of contents; the rendered content itself
+/// is only available through `out`.
+pub fn mdx_to_writer_with_frontmatter<W: std::fmt::Write>(
+    mdx_content: &str,
+    renderer: &JsRenderer,
+    components: Option<&HashMap<String, ComponentDefinition>>,
+    partials: Option<&HashMap<String, String>>,
+    settings: &RenderSettings,
+    out: &mut W,
+) -> Result<FrontmatterResult, MdxError> {
+    // Peel a `%`/`#`-prefixed leading metadata header off the top of the document, if
+    // configured - see `crate::leading_metadata`. Runs before YAML frontmatter parsing
+    // so a `---` block further down (if any) still parses out of whatever's left.
+    let (leading_metadata, mdx_content) = match settings.leading_metadata_marker {
+        Some(marker) => crate::leading_metadata::extract_leading_metadata(mdx_content, marker),
+        None => (serde_json::Value::Null, mdx_content),
+    };
+
     // Parse YAML frontmatter
     let matter = Matter::<YAML>::new();
     let parsed = matter
         .parse::<serde_json::Value>(mdx_content)
         .map_err(|e| MdxError::FrontmatterParse(e.to_string()))?;
 
-    let frontmatter = parsed
+    let mut frontmatter = parsed
         .data
         .unwrap_or_else(|| serde_json::Value::Object(serde_json::Map::with_capacity(0)));
 
+    // Fill in any key the YAML block didn't already set from the leading metadata
+    // header - see `RenderSettings::leading_metadata_marker`.
+    if let (Some(leading_obj), Some(frontmatter_obj)) =
+        (leading_metadata.as_object(), frontmatter.as_object_mut())
+    {
+        for (key, value) in leading_obj {
+            frontmatter_obj.entry(key.clone()).or_insert_with(|| value.clone());
+        }
+    }
+
+    // Resolve `@name`/`@name(arg, ...)` decorator expressions in the frontmatter
+    // against the service's registry, if any decorators are registered
+    if let Some(registry) = settings.decorators.as_ref() {
+        crate::decorators::apply_to_frontmatter(&mut frontmatter, registry)?;
+    }
+
+    // Expand `<Include name="..." />`/`{{> name}}` partial references against the
+    // supplied registry, if any, before anything else sees the document - so an
+    // included fragment's own directives/utils/markdown are processed identically to
+    // the host document's - see `crate::partials`.
+    let mut markdown_source = parsed.content;
+    if let Some(partials) = partials {
+        markdown_source = crate::partials::expand_includes(&markdown_source, partials)?;
+    }
+
+    // Resolve Lua-scripted container directives (`:::name ... :::`) and inline
+    // template utility calls (`{name(arg, ...)}`) against their registries, if any are
+    // registered, before the document is rendered as markdown - see
+    // [`crate::scripting`].
+    if let Some(registry) = settings.lua_directives.as_ref() {
+        markdown_source = crate::scripting::expand_directives(&markdown_source, registry)?;
+    }
+    if let Some(registry) = settings.lua_utils.as_ref() {
+        markdown_source = crate::scripting::expand_utils(&markdown_source, registry)?;
+    }
+
+    // Expand `{{#each}}`/`{{#if}}`/`{{#with}}` block helpers against the parsed
+    // frontmatter, if opted into - see `crate::block_helpers`.
+    if settings.block_helpers {
+        markdown_source = crate::block_helpers::expand_block_helpers(&markdown_source, &frontmatter)?;
+    }
+
+    // Validate `{...}` expressions and ESM `import`/`export` blocks against any
+    // registered parser hooks, if any are registered - see `crate::parser_hooks`.
+    if let Some(registry) = settings.parser_hooks.as_ref() {
+        crate::parser_hooks::validate(&markdown_source, registry)?;
+    }
+
+    // Recover fenced code blocks from the final markdown source and, for executable
+    // ones, run them through the renderer's V8 isolate - see `crate::doctest`. Done
+    // before anything below touches `markdown_source` further, and regardless of
+    // output format, since it's independent metadata rather than part of the
+    // rendered document itself.
+    let doctests = if settings.doctest {
+        crate::doctest::extract_and_run(&markdown_source, renderer)
+    } else {
+        Vec::new()
+    };
+
     // Render markdown to HTML with HTML/JSX components enabled
-    let html_output = render_markdown(&parsed.content)?;
+    let mut html_output = render_markdown_with_extensions(
+        &markdown_source,
+        &MarkdownExtensions { tables: settings.enable_tables, footnotes: settings.enable_footnotes },
+    )?;
+
+    // Strip every code block's common leading indentation, on by default - see
+    // `crate::unindent`. Done before the hidden-line pass below, so a hidden `# ` line's
+    // own indentation is measured the same way as the lines around it.
+    if settings.unindent_code_blocks {
+        html_output = crate::unindent::unindent_code_blocks(&html_output);
+    }
+
+    // Omit rustdoc-style `# `-hidden lines from every code block, if opted into - see
+    // `crate::hidden_lines`. Done before highlighting/fence-attribute handling below,
+    // so those passes only ever see the lines a reader actually sees.
+    if settings.hidden_code_lines {
+        html_output = crate::hidden_lines::strip_hidden_lines(&html_output);
+    }
+
+    // Parse each fenced code block's full info string (extra classes, `ignore`,
+    // line-highlight ranges), if opted into - see `crate::fence`.
+    let fence_infos = if settings.fence_attributes {
+        crate::fence::extract_fence_infos(&markdown_source)
+    } else {
+        Vec::new()
+    };
+
+    // Syntax-highlight fenced code blocks, if opted into - a block whose fence carried
+    // an `ignore` token is left plain even though highlighting is otherwise enabled.
+    if let Some(highlight) = settings.highlight.as_ref() {
+        let ignore: Vec<bool> = fence_infos.iter().map(|info| info.ignore).collect();
+        html_output = crate::highlight::highlight_code_blocks(&html_output, highlight, &ignore);
+    }
+
+    // Append each fence's extra classes and wrap its highlighted line ranges
+    if !fence_infos.is_empty() {
+        html_output = crate::fence::apply_fence_info(&html_output, &fence_infos);
+    }
+
+    // Rewrite straight quotes, dashes, and ellipses into their typographic forms, if
+    // opted into - see `crate::typography`.
+    if settings.smart_punctuation {
+        html_output = crate::typography::apply_smart_punctuation(&html_output);
+    }
+
+    // Expand `:name:` shortcodes into Unicode emoji, if opted into - see
+    // `crate::emoji`.
+    if settings.render_emoji {
+        html_output = crate::emoji::expand_emoji(&html_output);
+    }
+
+    // Harden external links' target/rel attributes, if opted into - see `crate::links`.
+    if settings.external_links_target_blank
+        || settings.external_links_nofollow
+        || settings.external_links_noreferrer
+    {
+        html_output = crate::links::rewrite_external_links(
+            &html_output,
+            &crate::links::ExternalLinkRewrite {
+                target_blank: settings.external_links_target_blank,
+                nofollow: settings.external_links_nofollow,
+                noreferrer: settings.external_links_noreferrer,
+                site_host: settings.external_links_site_host.as_deref(),
+            },
+        );
+    }
+
+    // Build a plain-text excerpt of the rendered document, if opted into - see
+    // `crate::summary`. Computed before heading ids are injected, since the `id=`
+    // attribute doesn't affect the stripped text.
+    let summary = settings
+        .summary_length
+        .map(|max_chars| crate::summary::plain_text_summary(&html_output, max_chars));
+
+    // Inject heading id= slugs and collect the table of contents, if opted into (or
+    // if `OutputFormat::Toc` was requested, since it has nothing else to report).
+    let mut toc = Vec::new();
+    if settings.headings || matches!(settings.output, OutputFormat::Toc) {
+        let (with_ids, headings) =
+            crate::toc::inject_heading_ids(&html_output, settings.heading_offset);
+        html_output = with_ids;
+        toc = headings;
+    }
+
+    // `OutputFormat::Toc` reports the document's heading outline and nothing else -
+    // skip the TSX transform/JS engine entirely, since the outline is already fully
+    // derived from `toc` above.
+    if matches!(settings.output, OutputFormat::Toc) {
+        let tree = crate::toc::build_toc_tree(&toc);
+        let output = serde_json::to_string(&tree)
+            .map_err(|e| MdxError::FrontmatterParse(format!("Failed to serialize toc: {e}")))?;
+        out.write_str(&output)
+            .map_err(|e| MdxError::tsx_transform(format!("Failed to write rendered output: {e}")))?;
+        return Ok(FrontmatterResult { metadata: frontmatter, toc, summary, doctests });
+    }
 
     // Convert frontmatter to JSON string for props
     let props_json = serde_json::to_string(&frontmatter)
         .map_err(|e| MdxError::FrontmatterParse(format!("Failed to serialize frontmatter: {e}")))?;
 
+    // Resolve the document's declared component sources: its own frontmatter
+    // `imports:` map layered over the service-wide `component_imports` default - see
+    // `merge_component_imports`.
+    let component_imports = merge_component_imports(&frontmatter, settings);
+
     let context = RenderContext {
         renderer,
         components,
         props_json: &props_json,
         settings,
+        component_imports: &component_imports,
+        metadata: &frontmatter,
     };
 
     let output = render_with_engine_pipeline(&context, &html_output)?;
+    out.write_str(&output)
+        .map_err(|e| MdxError::tsx_transform(format!("Failed to write rendered output: {e}")))?;
 
-    Ok(RenderedMdx {
+    Ok(FrontmatterResult {
         metadata: frontmatter,
-        output: Some(output),
+        toc,
+        summary,
+        doctests,
     })
 }
 
@@ -521,16 +865,69 @@ pub fn create_error_response(error: &anyhow::Error) -> RenderedMdx {
             "error_chain": error_chain
         }),
         output: Some(error_html),
+        toc: Vec::new(),
+        summary: None,
+        doctests: Vec::new(),
     }
 }
 
 /// Schema extraction result containing components and directives information
 #[derive(serde::Serialize, Default)]
 struct SchemaResult {
-    /// Unique component names (elements starting with capital letters)
-    components: Vec<String>,
+    /// Unique component names, sorted - kept for backward compatibility with
+    /// consumers that only want the flat list [`Self::components`] used to provide.
+    names: Vec<String>,
+    /// Per-component usage analysis: how many times it was used, its inferred prop
+    /// shapes, and where each usage occurs in the source.
+    components: BTreeMap<String, ComponentUsage>,
+    /// Parent -> sorted unique child component names observed nested directly inside
+    /// it (e.g. `<Container><Header/>...` records `Container -> [Header]`), for a
+    /// documentation UI that wants to show which components compose which.
+    tree: BTreeMap<String, Vec<String>>,
     /// Directive information extracted based on settings.directives prefixes
     directives: DirectivesResult,
+    /// Component names seen in the tree with no matching entry in `components` and no
+    /// entry in the merged import map (see [`merge_component_imports`]) - a build-time
+    /// list of dangling component references, sorted, so a caller can surface missing
+    /// imports instead of discovering them as a silent runtime failure.
+    unresolved: Vec<String>,
+    /// The document's own parsed frontmatter, duplicated here from
+    /// [`FrontmatterResult::metadata`] so a caller that only reads
+    /// [`OutputFormat::Schema`]'s output string has title/description/layout and the
+    /// rest of the document's metadata without a second request for the sibling
+    /// [`crate::models::RenderedMdx::metadata`] field.
+    #[serde(skip_serializing_if = "serde_json::Value::is_null")]
+    metadata: serde_json::Value,
+}
+
+/// A single component's usage analysis within [`SchemaResult::components`].
+#[derive(serde::Serialize, Default)]
+struct ComponentUsage {
+    /// Number of times this component appears in the rendered tree.
+    count: usize,
+    /// Inferred shape of each prop this component was given, keyed by prop name.
+    props: BTreeMap<String, PropUsage>,
+    /// Source location of each usage, in the order encountered.
+    locations: Vec<SchemaLocation>,
+}
+
+/// How a single prop was used across every occurrence of its component.
+#[derive(serde::Serialize)]
+struct PropUsage {
+    /// The prop value's inferred shape, from the JSON value it evaluated to at
+    /// render time: a string literal, a boolean/number literal, or any other
+    /// expression (an object, array, function reference, or `null`).
+    seen_as: &'static str,
+    /// Whether every usage of this component provided the prop, rather than just
+    /// some - a usage-based stand-in for "required" absent real TypeScript prop types.
+    required: bool,
+}
+
+/// A 0-indexed line/column a component usage was found at in the source.
+#[derive(serde::Serialize, Clone, Copy)]
+struct SchemaLocation {
+    line: u32,
+    column: u32,
 }
 
 /// Directive extraction results
@@ -544,22 +941,44 @@ struct DirectivesResult {
     values: Vec<serde_json::Value>,
 }
 
+/// Accumulates [`ComponentUsage`] fields while traversing the JSON tree, before the
+/// per-prop "required" verdict (which needs the component's final usage count) can be
+/// decided - see [`finalize_component_usage`].
+#[derive(Default)]
+struct ComponentUsageBuilder {
+    count: usize,
+    /// Name -> (seen_as, number of usages that provided it).
+    props: HashMap<String, (&'static str, usize)>,
+    locations: Vec<SchemaLocation>,
+}
+
 /// Extracts schema information from JSON tree including components and directives
 ///
 /// # Arguments
 /// * `json_tree` - The rendered JSON tree from core.js engine
+/// * `html_output` - The original JSX source, used only to recover each component
+///   usage's source location (the JSON tree itself carries no position info)
 /// * `directive_prefixes` - Optional list of directive prefixes to extract (e.g., ["v-", "@", "x-"])
+/// * `component_defs` - Registered component definitions - a usage is "resolved" if its
+///   name (or [`ComponentDefinition::name`] override) appears here
+/// * `component_imports` - The merged frontmatter/settings import map (see
+///   [`merge_component_imports`]) - a usage is also "resolved" if its name appears here
 ///
 /// # Returns
 /// A JSON string containing components and directives schema
 fn extract_schema_from_json(
     json_tree: &str,
+    html_output: &str,
     directive_prefixes: Option<&Vec<String>>,
+    component_defs: Option<&HashMap<String, ComponentDefinition>>,
+    component_imports: &HashMap<String, String>,
+    metadata: &serde_json::Value,
 ) -> Result<String, MdxError> {
     let tree: serde_json::Value = serde_json::from_str(json_tree)
         .map_err(|e| MdxError::FrontmatterParse(format!("Failed to parse JSON tree: {e}")))?;
 
-    let mut components: HashSet<String> = HashSet::new();
+    let mut usage: HashMap<String, ComponentUsageBuilder> = HashMap::new();
+    let mut tree_edges: HashMap<String, HashSet<String>> = HashMap::new();
     let mut directive_keys: HashSet<String> = HashSet::new();
     let mut directive_patterns: HashSet<String> = HashSet::new();
     let mut directive_values: HashSet<String> = HashSet::new(); // Store as JSON strings for dedup
@@ -573,15 +992,55 @@ fn extract_schema_from_json(
     traverse_json_tree(
         &tree,
         &prefixes,
-        &mut components,
+        None,
+        &mut usage,
+        &mut tree_edges,
         &mut directive_keys,
         &mut directive_patterns,
         &mut directive_values,
     );
 
-    // Convert to sorted vectors for consistent output
-    let mut sorted_components: Vec<String> = components.into_iter().collect();
-    sorted_components.sort();
+    // Recover each usage's source location from the original JSX, since the rendered
+    // JSON tree itself has no position info.
+    let line_index = LineIndex::new(html_output);
+    for cap in COMPONENT_NAME_PATTERN.captures_iter(html_output) {
+        let Some(name) = cap.get(1) else { continue };
+        if let Some(builder) = usage.get_mut(name.as_str()) {
+            let (line, column) = line_index.line_col(name.start() as u32);
+            builder.locations.push(SchemaLocation { line, column });
+        }
+    }
+
+    let mut sorted_names: Vec<String> = usage.keys().cloned().collect();
+    sorted_names.sort();
+
+    let mut resolved_names: HashSet<&str> = HashSet::new();
+    if let Some(component_defs) = component_defs {
+        for (key, def) in component_defs {
+            resolved_names.insert(def.name.as_deref().unwrap_or(key.as_str()));
+        }
+    }
+    resolved_names.extend(component_imports.keys().map(String::as_str));
+
+    let unresolved: Vec<String> = sorted_names
+        .iter()
+        .filter(|name| !resolved_names.contains(name.as_str()))
+        .cloned()
+        .collect();
+
+    let components: BTreeMap<String, ComponentUsage> = usage
+        .into_iter()
+        .map(|(name, builder)| (name, builder.finalize()))
+        .collect();
+
+    let tree: BTreeMap<String, Vec<String>> = tree_edges
+        .into_iter()
+        .map(|(parent, children)| {
+            let mut sorted_children: Vec<String> = children.into_iter().collect();
+            sorted_children.sort();
+            (parent, sorted_children)
+        })
+        .collect();
 
     let mut sorted_keys: Vec<String> = directive_keys.into_iter().collect();
     sorted_keys.sort();
@@ -598,23 +1057,69 @@ fn extract_schema_from_json(
     sorted_values.sort_by_key(|a| a.to_string());
 
     let result = SchemaResult {
-        components: sorted_components,
+        names: sorted_names,
+        components,
+        tree,
+        unresolved,
         directives: DirectivesResult {
             keys: sorted_keys,
             patterns: sorted_patterns,
             values: sorted_values,
         },
+        metadata: metadata.clone(),
     };
 
     serde_json::to_string(&result)
         .map_err(|e| MdxError::FrontmatterParse(format!("Failed to serialize schema: {e}")))
 }
 
-/// Recursively traverses the JSON tree to extract components and directives
+impl ComponentUsageBuilder {
+    /// Converts the accumulated counts into the public [`ComponentUsage`] shape,
+    /// deciding each prop's `required` verdict now that the component's final usage
+    /// count is known.
+    fn finalize(self) -> ComponentUsage {
+        let props = self
+            .props
+            .into_iter()
+            .map(|(name, (seen_as, seen_count))| {
+                (
+                    name,
+                    PropUsage {
+                        seen_as,
+                        required: seen_count == self.count,
+                    },
+                )
+            })
+            .collect();
+
+        ComponentUsage {
+            count: self.count,
+            props,
+            locations: self.locations,
+        }
+    }
+}
+
+/// Infers a [`PropUsage::seen_as`] label from the JSON value a prop evaluated to at
+/// render time.
+fn infer_prop_seen_as(value: &serde_json::Value) -> &'static str {
+    match value {
+        serde_json::Value::String(_) => "string",
+        serde_json::Value::Bool(_) => "boolean",
+        serde_json::Value::Number(_) => "number",
+        _ => "expression",
+    }
+}
+
+/// Recursively traverses the JSON tree to extract per-component usage (count, prop
+/// shapes), parent/child nesting, and directives.
+#[allow(clippy::too_many_arguments)]
 fn traverse_json_tree(
     node: &serde_json::Value,
     prefixes: &[&str],
-    components: &mut HashSet<String>,
+    parent: Option<&str>,
+    usage: &mut HashMap<String, ComponentUsageBuilder>,
+    tree_edges: &mut HashMap<String, HashSet<String>>,
     directive_keys: &mut HashSet<String>,
     directive_patterns: &mut HashSet<String>,
     directive_values: &mut HashSet<String>,
@@ -622,6 +1127,7 @@ fn traverse_json_tree(
     match node {
         serde_json::Value::Object(obj) => {
             // Check for component type (capitalized tag names, excluding built-in elements)
+            let mut current_component: Option<&str> = None;
             if let Some(serde_json::Value::String(tag)) = obj.get("type") {
                 if !tag.is_empty()
                     && tag
@@ -632,9 +1138,28 @@ fn traverse_json_tree(
                     && tag != "Fragment"
                 // Exclude built-in Fragment
                 {
-                    components.insert(tag.clone());
+                    let builder = usage.entry(tag.clone()).or_default();
+                    builder.count += 1;
+
+                    if let Some(serde_json::Value::Object(attrs)) = obj.get("attributes") {
+                        for (key, value) in attrs {
+                            let seen_as = infer_prop_seen_as(value);
+                            let entry = builder.props.entry(key.clone()).or_insert((seen_as, 0));
+                            entry.1 += 1;
+                        }
+                    }
+
+                    if let Some(parent_name) = parent {
+                        tree_edges
+                            .entry(parent_name.to_string())
+                            .or_default()
+                            .insert(tag.clone());
+                    }
+
+                    current_component = Some(tag.as_str());
                 }
             }
+            let child_parent = current_component.or(parent);
 
             // Check attributes for directives
             if let Some(serde_json::Value::Object(attrs)) = obj.get("attributes") {
@@ -668,7 +1193,9 @@ fn traverse_json_tree(
                 traverse_json_tree(
                     children,
                     prefixes,
-                    components,
+                    child_parent,
+                    usage,
+                    tree_edges,
                     directive_keys,
                     directive_patterns,
                     directive_values,
@@ -676,11 +1203,16 @@ fn traverse_json_tree(
             }
 
             // Recurse into all object values
-            for value in obj.values() {
+            for (key, value) in obj {
+                if key == "children" {
+                    continue;
+                }
                 traverse_json_tree(
                     value,
                     prefixes,
-                    components,
+                    child_parent,
+                    usage,
+                    tree_edges,
                     directive_keys,
                     directive_patterns,
                     directive_values,
@@ -692,7 +1224,9 @@ fn traverse_json_tree(
                 traverse_json_tree(
                     item,
                     prefixes,
-                    components,
+                    parent,
+                    usage,
+                    tree_edges,
                     directive_keys,
                     directive_patterns,
                     directive_values,
@@ -708,12 +1242,15 @@ fn render_with_engine_pipeline(
     html_output: &str,
 ) -> Result<String, MdxError> {
     // HOT PATH: TSX transformation - called for every MDX file with Html/Javascript output
-    let mut transform_config = TsxTransformConfig::for_engine(false);
+    let mut transform_config = TsxTransformConfig::for_engine(false)
+        .with_compiler_options(context.settings.compiler_options.as_ref())
+        .with_source_maps(context.settings.source_maps);
 
     match context.settings.output {
-        OutputFormat::Schema => {
-            // For schema output, render to JSON first then extract schema information
-            // This allows us to extract both components and directives from the tree
+        OutputFormat::Schema | OutputFormat::Ast => {
+            // For schema/AST output, render to JSON first then (for schema) extract
+            // schema information - this allows us to extract both components and
+            // directives from the tree
 
             // For schema, convert component function references to strings
             // Start by extracting component names from the HTML content (JSX tags starting with capital letters)
@@ -743,17 +1280,37 @@ fn render_with_engine_pipeline(
             }
 
             let javascript_output = transform_tsx_to_js_with_config(html_output, transform_config)
-                .map_err(|e| {
-                    MdxError::tsx_transform(format!("Failed to transform TSX to JavaScript: {e}"))
-                })?;
+                .map_err(|e| e.with_context("Failed to transform TSX to JavaScript"))?;
 
             // Render to JSON tree using core.js engine
             let json_tree = render_template_to_schema(context, &javascript_output)?;
 
+            // Apply any registered structural rewrite rules before the tree is
+            // inspected further - see `crate::rewrite`.
+            let json_tree = apply_rewrite_rules(context, json_tree)?;
+
+            if matches!(context.settings.output, OutputFormat::Ast) {
+                // The AST output is the rendered document tree itself, not the
+                // component/directive usage summary `extract_schema_from_json` derives
+                // from it - already stable JSON, so it's returned as-is.
+                return Ok(json_tree);
+            }
+
             // Extract schema from JSON tree (components + directives)
-            extract_schema_from_json(&json_tree, context.settings.directives.as_ref())
+            extract_schema_from_json(
+                &json_tree,
+                html_output,
+                context.settings.directives.as_ref(),
+                context.components,
+                context.component_imports,
+                context.metadata,
+            )
         }
-        OutputFormat::Html | OutputFormat::Javascript | OutputFormat::Json => {
+        OutputFormat::Html
+        | OutputFormat::Javascript
+        | OutputFormat::Json
+        | OutputFormat::EsModule
+        | OutputFormat::SearchIndex => {
             // For json output, convert component function references to strings
             // For HTML output, keep as function references so they can be rendered
             // For JavaScript output, keep Preact syntax with h() and Fragment
@@ -786,9 +1343,7 @@ fn render_with_engine_pipeline(
             }
 
             let javascript_output = transform_tsx_to_js_with_config(html_output, transform_config)
-                .map_err(|e| {
-                    MdxError::tsx_transform(format!("Failed to transform TSX to JavaScript: {e}"))
-                })?;
+                .map_err(|e| e.with_context("Failed to transform TSX to JavaScript"))?;
 
             // HOT PATH: Component rendering - executes JavaScript and renders to HTML
             let template_output = render_template(context, &javascript_output)?;
@@ -796,23 +1351,52 @@ fn render_with_engine_pipeline(
             match context.settings.output {
                 OutputFormat::Html => {
                     // Unwrap Fragment wrapper if present - only return children of first Fragment
-                    Ok(unwrap_fragment(&template_output))
+                    let mut unwrapped = unwrap_fragment(&template_output);
+                    if let Some(sanitize) = context.settings.sanitize.as_ref() {
+                        unwrapped = crate::sanitize::sanitize_html(&unwrapped, sanitize);
+                    }
+                    if let Some(external_html) = context.settings.external_html.as_ref() {
+                        unwrapped = external_html
+                            .splice(&unwrapped, context.settings.external_html_root.as_deref())?;
+                    }
+                    Ok(if context.settings.minify {
+                        crate::minify::minify_html(&unwrapped)
+                    } else {
+                        unwrapped
+                    })
+                }
+                OutputFormat::SearchIndex => {
+                    let unwrapped = unwrap_fragment(&template_output);
+                    let index = crate::search::build_document_index(&unwrapped);
+                    serde_json::to_string(&index)
+                        .map_err(|e| MdxError::tsx_transform(format!("Failed to serialize search index: {e}")))
                 }
                 OutputFormat::Javascript => {
                     transform_tsx_to_js_for_output(&template_output, context.settings.minify)
-                        .map_err(|e| {
-                            MdxError::tsx_transform(format!(
-                                "Failed to transform template to JavaScript: {e}"
-                            ))
-                        })
+                        .map_err(|e| e.with_context("Failed to transform template to JavaScript"))
                 }
                 OutputFormat::Json => {
-                    // Render using core.js engine for json output
-                    render_template_to_schema(context, &javascript_output)
+                    // Render using core.js engine for json output, then apply any
+                    // registered structural rewrite rules - see `crate::rewrite`.
+                    let json_tree = render_template_to_schema(context, &javascript_output)?;
+                    apply_rewrite_rules(context, json_tree)
+                }
+                OutputFormat::EsModule => {
+                    let component_js =
+                        transform_tsx_to_js_for_output(&template_output, context.settings.minify)
+                            .map_err(|e| {
+                                e.with_context("Failed to transform template to JavaScript")
+                            })?;
+                    Ok(as_es_module(&component_js))
+                }
+                OutputFormat::Schema | OutputFormat::Ast | OutputFormat::Toc => {
+                    unreachable!("Schema/Ast/Toc handled in outer match")
                 }
-                OutputFormat::Schema => unreachable!("Schema handled in outer match"),
             }
         }
+        OutputFormat::Toc => {
+            unreachable!("Toc output is handled before the engine pipeline is ever invoked")
+        }
     }
 }
 
@@ -822,18 +1406,35 @@ fn render_template(
 ) -> Result<String, MdxError> {
     context
         .renderer
-        .render_transformed_component(
-            javascript_output,
-            Some(context.props_json),
-            context.components,
-            context.settings.utils.as_deref(),
-        )
+        .render_transformed_component(javascript_output, Some(context.props_json), context.components)
         .map_err(|e| {
             log_render_error(&e, javascript_output, "Component");
-            MdxError::tsx_transform(format!("Failed to render component template: {:#}", e))
+            render_error_to_mdx_error(e, "Failed to render component template")
         })
 }
 
+/// Converts a renderer's [`anyhow::Error`] into an [`MdxError`], preserving a
+/// [`MdxError::TsxTransform`]'s [`crate::error::SourceLocation`] (see
+/// `renderer::runtime::translate_execution_error`) instead of collapsing it into a
+/// flat string - the source-mapped position is exactly what
+/// [`RenderSettings::source_maps`] exists to produce.
+fn render_error_to_mdx_error(error: anyhow::Error, context: &str) -> MdxError {
+    match error.downcast::<MdxError>() {
+        Ok(mdx_error) => mdx_error.with_context(context),
+        Err(error) => MdxError::tsx_transform(format!("{context}: {error:#}")),
+    }
+}
+
+/// Applies `context.settings`'s registered [`crate::rewrite::RewriteRegistry`] rules
+/// (if any) to `json_tree`, a rendered document tree - a no-op returning `json_tree`
+/// unchanged if none are registered.
+fn apply_rewrite_rules(context: &RenderContext<'_>, json_tree: String) -> Result<String, MdxError> {
+    match context.settings.rewrite_rules.as_ref() {
+        Some(registry) => crate::rewrite::apply_rewrites(&json_tree, registry),
+        None => Ok(json_tree),
+    }
+}
+
 fn render_template_to_schema(
     context: &RenderContext<'_>,
     javascript_output: &str,
@@ -844,11 +1445,10 @@ fn render_template_to_schema(
             javascript_output,
             Some(context.props_json),
             context.components,
-            context.settings.utils.as_deref(),
         )
         .map_err(|e| {
             log_render_error(&e, javascript_output, "Schema");
-            MdxError::tsx_transform(format!("Failed to render component to schema: {:#}", e))
+            render_error_to_mdx_error(e, "Failed to render component to schema")
         })
 }
 
@@ -939,13 +1539,76 @@ Some text
     }
 
     #[test]
-    fn test_find_matching_close_tag_nested() {
-        let content = "<div><div>inner</div>outer</div>";
-        let mut depth = 0;
-        let result = find_matching_close_tag(content, 5, "div", "</div>", &mut depth);
-        // Should find the outer closing tag, not the inner one
-        assert_eq!(result, Some(26));
-        assert!(depth >= 2); // Detected nesting
+    fn test_protect_jsx_nested_same_component() {
+        let content = r#"<Tabs active={0}><Tabs active={1}>inner</Tabs>outer</Tabs>"#;
+        let (result, placeholders) = protect_jsx_components(content);
+
+        // The whole outer <Tabs>...</Tabs> span (including the nested <Tabs>) is
+        // captured as a single placeholder, not split at the inner closing tag.
+        assert_eq!(placeholders.len(), 1);
+        assert!(result.contains("<!--JSX:0-->"));
+        let jsx = placeholders.values().next().unwrap();
+        assert!(jsx.starts_with("<Tabs active={0}>"));
+        assert!(jsx.ends_with("</Tabs>"));
+        assert!(jsx.contains("<Tabs active={1}>inner</Tabs>"));
+    }
+
+    #[test]
+    fn test_protect_jsx_nested_braces_in_expression() {
+        let content = r#"<Hero config={{a: 1}} />"#;
+        let (result, placeholders) = protect_jsx_components(content);
+
+        assert_eq!(placeholders.len(), 1);
+        let jsx = placeholders.values().next().unwrap();
+        assert_eq!(jsx, content);
+        assert!(result.contains("<!--JSX:0-->"));
+    }
+
+    #[test]
+    fn test_protect_jsx_function_call_with_nested_braces() {
+        let content = r#"<Card onClick={f({x})} />"#;
+        let (result, placeholders) = protect_jsx_components(content);
+
+        assert_eq!(placeholders.len(), 1);
+        let jsx = placeholders.values().next().unwrap();
+        assert_eq!(jsx, content);
+        assert!(result.contains("<!--JSX:0-->"));
+    }
+
+    #[test]
+    fn test_protect_jsx_string_literal_with_special_chars() {
+        // The `>` and `}` inside the string literal must not end the tag early.
+        let content = r#"<Hero label={"a > b } c"} />"#;
+        let (result, placeholders) = protect_jsx_components(content);
+
+        assert_eq!(placeholders.len(), 1);
+        let jsx = placeholders.values().next().unwrap();
+        assert_eq!(jsx, content);
+        assert!(result.contains("<!--JSX:0-->"));
+    }
+
+    #[test]
+    fn test_protect_jsx_children_with_apostrophe() {
+        // An apostrophe in ordinary prose inside the children must not be mistaken
+        // for the start of a string literal while searching for the closing tag.
+        let content = r#"<Card onClick={f(x)}>It's great</Card>"#;
+        let (result, placeholders) = protect_jsx_components(content);
+
+        assert_eq!(placeholders.len(), 1);
+        let jsx = placeholders.values().next().unwrap();
+        assert_eq!(jsx, content);
+        assert!(result.contains("<!--JSX:0-->"));
+    }
+
+    #[test]
+    fn test_protect_jsx_skips_comment_in_expression_attribute() {
+        let content = r#"<Hero title={/* note: a > b */ "ok"} />"#;
+        let (result, placeholders) = protect_jsx_components(content);
+
+        assert_eq!(placeholders.len(), 1);
+        let jsx = placeholders.values().next().unwrap();
+        assert_eq!(jsx, content);
+        assert!(result.contains("<!--JSX:0-->"));
     }
 
     #[test]
@@ -1181,6 +1844,50 @@ Some text
         );
     }
 
+    #[test]
+    fn test_extensions_tables_enabled_round_trips_pipe_table() {
+        let content = "| a | b |\n| --- | --- |\n| 1 | 2 |";
+        let result =
+            render_markdown_with_extensions(content, &MarkdownExtensions { tables: true, footnotes: true })
+                .unwrap();
+        assert!(result.contains("<table>"), "Should render a pipe table when enabled");
+    }
+
+    #[test]
+    fn test_extensions_tables_disabled_leaves_pipe_table_as_text() {
+        let content = "| a | b |\n| --- | --- |\n| 1 | 2 |";
+        let result = render_markdown_with_extensions(
+            content,
+            &MarkdownExtensions { tables: false, footnotes: true },
+        )
+        .unwrap();
+        assert!(!result.contains("<table>"), "Should leave a pipe table as plain text when disabled");
+    }
+
+    #[test]
+    fn test_extensions_footnotes_enabled_round_trips_reference_and_definition() {
+        let content = "Here is a footnote reference[^1].\n\n[^1]: This is the footnote content.";
+        let result =
+            render_markdown_with_extensions(content, &MarkdownExtensions { tables: true, footnotes: true })
+                .unwrap();
+        assert!(result.contains("footnote-ref") || result.contains("fn-"), "Should link the reference");
+        assert!(result.contains("This is the footnote content"), "Should render the definition");
+    }
+
+    #[test]
+    fn test_extensions_footnotes_disabled_leaves_reference_as_text() {
+        let content = "Here is a footnote reference[^1].\n\n[^1]: This is the footnote content.";
+        let result = render_markdown_with_extensions(
+            content,
+            &MarkdownExtensions { tables: true, footnotes: false },
+        )
+        .unwrap();
+        assert!(
+            result.contains("[^1]"),
+            "Should leave the footnote marker as plain text when disabled"
+        );
+    }
+
     #[test]
     fn test_gfm_footnote_multiple() {
         let content = r#"First[^1] and second[^2] footnotes.
@@ -1360,6 +2067,34 @@ const greet = () => console.log("Hi");
         assert!(result.contains("<code>println!()</code>"));
     }
 
+    #[test]
+    fn test_code_inline_strips_one_leading_and_trailing_space() {
+        let content = "Use ` foo ` here.";
+        let result = render_markdown(content).unwrap();
+        assert!(result.contains("<code>foo</code>"));
+    }
+
+    #[test]
+    fn test_code_inline_preserves_interior_spaces() {
+        let content = "Use `  a  b  ` here.";
+        let result = render_markdown(content).unwrap();
+        assert!(result.contains("<code> a  b </code>"));
+    }
+
+    #[test]
+    fn test_code_inline_all_spaces_is_untouched() {
+        let content = "Use `   ` here.";
+        let result = render_markdown(content).unwrap();
+        assert!(result.contains("<code>   </code>"));
+    }
+
+    #[test]
+    fn test_code_inline_collapses_internal_line_endings_to_spaces() {
+        let content = "Use `foo\nbar` here.";
+        let result = render_markdown(content).unwrap();
+        assert!(result.contains("<code>foo bar</code>"));
+    }
+
     #[test]
     fn test_code_indented() {
         // 4-space indented code block (CommonMark)
@@ -1371,4 +2106,58 @@ const greet = () => console.log("Hi");
         );
         assert!(result.contains("<code>"));
     }
+
+    #[test]
+    fn test_merge_component_imports_frontmatter_overrides_settings() {
+        let mut settings = RenderSettings::default();
+        settings.component_imports = Some(HashMap::from([(
+            "Card".to_string(),
+            "./settings/Card.tsx".to_string(),
+        )]));
+        let frontmatter = serde_json::json!({ "imports": { "Card": "./local/Card.tsx" } });
+
+        let merged = merge_component_imports(&frontmatter, &settings);
+
+        assert_eq!(merged.get("Card"), Some(&"./local/Card.tsx".to_string()));
+    }
+
+    #[test]
+    fn test_merge_component_imports_keeps_settings_entries_not_overridden() {
+        let mut settings = RenderSettings::default();
+        settings.component_imports = Some(HashMap::from([(
+            "Hero".to_string(),
+            "./Hero.tsx".to_string(),
+        )]));
+        let frontmatter = serde_json::json!({ "imports": { "Card": "./Card.tsx" } });
+
+        let merged = merge_component_imports(&frontmatter, &settings);
+
+        assert_eq!(merged.get("Hero"), Some(&"./Hero.tsx".to_string()));
+        assert_eq!(merged.get("Card"), Some(&"./Card.tsx".to_string()));
+    }
+
+    #[test]
+    fn test_merge_component_imports_ignores_non_string_entries() {
+        let settings = RenderSettings::default();
+        let frontmatter = serde_json::json!({ "imports": { "Card": 42 } });
+
+        let merged = merge_component_imports(&frontmatter, &settings);
+
+        assert!(merged.is_empty());
+    }
+
+    #[test]
+    fn test_merge_component_imports_no_imports_key_returns_settings_only() {
+        let mut settings = RenderSettings::default();
+        settings.component_imports = Some(HashMap::from([(
+            "Hero".to_string(),
+            "./Hero.tsx".to_string(),
+        )]));
+        let frontmatter = serde_json::json!({ "title": "No imports here" });
+
+        let merged = merge_component_imports(&frontmatter, &settings);
+
+        assert_eq!(merged.get("Hero"), Some(&"./Hero.tsx".to_string()));
+        assert_eq!(merged.len(), 1);
+    }
 }
@@ -0,0 +1,89 @@
+//! HTML whitespace minification, applied over rendered HTML.
+//!
+//! Removes runs of whitespace *between* tags that contain a newline, and drops
+//! HTML comments, the same light-touch minification
+//! [`crate::transform::transform_tsx_to_js_for_output`] already applies to
+//! [`crate::models::OutputFormat::Javascript`] output via `oxc`'s minifier - see
+//! [`crate::models::RenderSettings::minify`]. `<pre>`, `<code>`, and `<textarea>`
+//! elements are whitespace-significant, so their full contents (including nested
+//! markup) are copied through byte-for-byte, and a conditional comment
+//! (`<!--[if ...]>`/`<![endif]-->`) is kept rather than dropped as an ordinary one.
+
+use regex::Regex;
+use std::sync::LazyLock;
+
+/// Matches a region minification must copy through unchanged: a `<pre>`, `<code>`, or
+/// `<textarea>` element's full contents, or a conditional comment.
+///
+/// # Safety
+/// Pattern is compile-time constant and known to be valid.
+static PRESERVED_REGION: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r#"(?s)<pre\b[^>]*>.*?</pre>|<code\b[^>]*>.*?</code>|<textarea\b[^>]*>.*?</textarea>|<!--\[if\b.*?<!\[endif\]-->"#,
+    )
+    .expect("hardcoded regex pattern is valid")
+});
+
+/// Matches an ordinary (non-conditional) HTML comment.
+static HTML_COMMENT: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?s)<!--.*?-->").expect("hardcoded regex pattern is valid"));
+
+/// Matches a run of whitespace that includes at least one newline, between two tags
+/// (i.e. immediately preceded by `>` and followed by `<`) - the inter-tag indentation a
+/// templating engine emits, as opposed to a single meaningful space inside prose text
+/// like `Hello <b>world</b>`.
+static INTER_TAG_WHITESPACE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r">[ \t\r\n]*\n[ \t\r\n]*<").expect("hardcoded regex pattern is valid"));
+
+/// Minifies `html`: drops ordinary HTML comments and collapses inter-tag whitespace
+/// runs that contain a newline down to nothing, leaving `<pre>`/`<code>`/`<textarea>`
+/// content and conditional comments untouched - see
+/// [`crate::models::RenderSettings::minify`].
+pub(crate) fn minify_html(html: &str) -> String {
+    let mut out = String::with_capacity(html.len());
+    let mut last = 0;
+    for region in PRESERVED_REGION.find_iter(html) {
+        out.push_str(&minify_region(&html[last..region.start()]));
+        out.push_str(region.as_str());
+        last = region.end();
+    }
+    out.push_str(&minify_region(&html[last..]));
+    out.trim().to_string()
+}
+
+/// Minifies a single run of HTML known not to straddle a preserved region: drops
+/// ordinary comments, then collapses inter-tag whitespace.
+fn minify_region(html: &str) -> String {
+    let without_comments = HTML_COMMENT.replace_all(html, "");
+    INTER_TAG_WHITESPACE.replace_all(&without_comments, "><").into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_collapses_inter_tag_whitespace() {
+        let html = "<div>\n    <p>Hello</p>\n    <p>World</p>\n</div>";
+        assert_eq!(minify_html(html), "<div><p>Hello</p><p>World</p></div>");
+    }
+
+    #[test]
+    fn test_keeps_single_space_inside_prose() {
+        assert_eq!(minify_html("<p>Hello <b>world</b></p>"), "<p>Hello <b>world</b></p>");
+    }
+
+    #[test]
+    fn test_preserves_pre_and_code_whitespace() {
+        let html = "<pre><code>line one\n  line two\n</code></pre>";
+        assert_eq!(minify_html(html), html);
+    }
+
+    #[test]
+    fn test_drops_ordinary_comments_but_keeps_conditional_ones() {
+        let html = "<!-- drop me -->\n<div>x</div>\n<!--[if lte IE 9]><p>old</p><![endif]-->";
+        let out = minify_html(html);
+        assert!(!out.contains("drop me"));
+        assert!(out.contains("<!--[if lte IE 9]><p>old</p><![endif]-->"));
+    }
+}
@@ -12,8 +12,10 @@
 //! Resource limits are enforced at the library level to prevent memory exhaustion.
 //! These are reliability measures, not security controls (security is handled at the web layer).
 
+use oxc_transformer::ReactRefreshOptions;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::path::PathBuf;
 
 /// Component definition with code and metadata
 #[derive(Deserialize, Serialize, Clone, Debug)]
@@ -45,6 +47,25 @@ pub enum OutputFormat {
     /// Return JSON schema representation (alias for Schema)
     #[serde(alias = "json")]
     Json,
+    /// Return the rendered document tree as stable JSON, rather than the component/
+    /// directive usage summary [`OutputFormat::Schema`] extracts from it
+    Ast,
+    /// Return the rendered template as a standalone ES module (`export default`
+    /// instead of the bare function declaration [`OutputFormat::Javascript`] produces)
+    #[serde(rename = "es_module", alias = "esmodule")]
+    EsModule,
+    /// Return the document's heading outline as a nested [`TocNode`] tree (see
+    /// [`crate::toc::build_toc_tree`]), instead of rendering the document itself -
+    /// forces heading-id injection/TOC extraction on for this file even when
+    /// [`RenderSettings::headings`] is unset, since it's the entire point of asking
+    /// for this output.
+    Toc,
+    /// Return a per-document [`crate::search::DocumentSearchIndex`] instead of the
+    /// rendered document itself: the document split into heading-bounded sections
+    /// plus an inverted term -> postings map over them - the single-file analogue of
+    /// [`RenderSettings::build_search_index`]'s batch-wide [`crate::search::SearchIndex`].
+    #[serde(rename = "search_index", alias = "searchindex")]
+    SearchIndex,
 }
 
 /// Rendering settings
@@ -56,21 +77,555 @@ pub struct RenderSettings {
     /// Enable minification
     #[serde(default = "default_minify_true")]
     pub minify: bool,
+    /// Tsconfig-style compiler options (`jsx`, `jsxFactory`, `jsxFragmentFactory`,
+    /// `jsxImportSource`, `experimentalDecorators`, ...) deep-merged over the crate's
+    /// defaults and applied to the document's JSX/TS transform - see
+    /// [`TsxTransformConfig::with_compiler_options`]. Options with no equivalent in a
+    /// pure transform (no module resolution, no disk emit - e.g. `target`,
+    /// `useDefineForClassFields`) are accepted but have no effect, the same way a real
+    /// TS toolchain drops options a given build step doesn't use.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub compiler_options: Option<serde_json::Value>,
+    /// The decorator registry frontmatter decorator expressions (`@name`,
+    /// `@name(arg, ...)`) resolve against - see
+    /// [`crate::decorators::apply_to_frontmatter`]. Not part of the wire format:
+    /// populated internally by [`crate::service::RenderService`] from the decorators
+    /// registered on it via
+    /// [`RenderService::register_decorator`][crate::service::RenderService::register_decorator],
+    /// so the same set applies across every file in a batch.
+    #[serde(skip)]
+    pub decorators: Option<crate::decorators::DecoratorRegistry>,
+    /// When set, syntax-highlights every fenced code block markdown emits (one whose
+    /// language info-string syntect recognizes) via
+    /// [`crate::highlight::highlight_code_blocks`] instead of leaving it as plain
+    /// escaped `<code>`. `None` (the default) skips highlighting entirely.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub highlight: Option<HighlightSettings>,
+    /// When set, peels `%`/`#`-prefixed metadata lines off the top of the document
+    /// (rustdoc's `extract_leading_metadata` technique) before YAML frontmatter
+    /// parsing - see [`crate::leading_metadata::extract_leading_metadata`]. Its
+    /// entries are merged into [`FrontmatterResult::metadata`][crate::mdx::FrontmatterResult::metadata]
+    /// (and so into [`OutputFormat::Schema`] output too), filling in any key a YAML
+    /// `---` block doesn't already set. `None` (the default) leaves the document's
+    /// first line alone, the same as before this existed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub leading_metadata_marker: Option<crate::leading_metadata::LeadingMetadataMarker>,
+    /// When set, every `h1..h6` markdown produces gets a GitHub-style `id=` slug
+    /// injected (see [`crate::toc::inject_heading_ids`]) and [`RenderedMdx::toc`] is
+    /// populated with one entry per heading. `false` (the default) leaves heading
+    /// output untouched, since a [`NamedMdxBatchInput`] consumer that doesn't expect
+    /// its rendered markup to be mutated shouldn't have `id` attributes appear in it
+    /// unasked.
+    #[serde(default)]
+    pub headings: bool,
+    /// Shifts every heading level markdown produces down by this many levels (e.g. an
+    /// offset of `1` turns a source `<h1>` into an `<h2>`, capped at `<h6>`) before
+    /// `id`s are injected and [`RenderedMdx::toc`]/[`OutputFormat::Toc`] see it - the
+    /// rustdoc `HeadingOffset` technique for nesting a rendered fragment under a
+    /// caller's own `<h1>` instead of competing with it. `0` (the default) leaves
+    /// heading levels untouched.
+    #[serde(default)]
+    pub heading_offset: u8,
+    /// How richly a failed render's [`crate::service::Diagnostic`]s should be
+    /// rendered. [`crate::error::DiagnosticStyle::Plain`] (the default) leaves
+    /// [`crate::service::Diagnostic::report`] unset; `Pretty` additionally renders an
+    /// `ariadne` report (a caret-underlined snippet against the original source) into
+    /// it, for a CLI or LSP caller that wants a ready-made annotated view instead of
+    /// re-deriving one from bare line/column numbers.
+    #[serde(default)]
+    pub diagnostics: crate::error::DiagnosticStyle,
+    /// Attribute-name prefixes (e.g. `["v-", "@", "x-"]`) that
+    /// [`OutputFormat::Schema`]/[`OutputFormat::Json`] output collects into its
+    /// `directives` section - JSX/Vue/Alpine-style directive attributes the rendered
+    /// component tree uses. `None` (the default) collects none.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub directives: Option<Vec<String>>,
+    /// The registry `:::name ... :::` markdown container directives resolve against -
+    /// see [`crate::scripting::expand_directives`]. Not part of the wire format:
+    /// populated internally by [`crate::service::RenderService`] from the directives
+    /// registered on it via
+    /// [`RenderService::register_lua_directive`][crate::service::RenderService::register_lua_directive].
+    #[serde(skip)]
+    pub lua_directives: Option<crate::scripting::LuaDirectiveRegistry>,
+    /// The registry inline `{name(arg, ...)}` template utility calls resolve against -
+    /// see [`crate::scripting::expand_utils`]. Not part of the wire format: populated
+    /// internally by [`crate::service::RenderService`] from the utilities registered on
+    /// it via
+    /// [`RenderService::register_lua_util`][crate::service::RenderService::register_lua_util].
+    #[serde(skip)]
+    pub lua_utils: Option<crate::scripting::LuaUtilsRegistry>,
+    /// The registry `<Pattern> ==> <Template>` structural rewrite rules resolve
+    /// against - see [`crate::rewrite`]. Applied to the rendered JSON document tree
+    /// that backs [`OutputFormat::Schema`]/[`OutputFormat::Json`]/[`OutputFormat::Ast`]
+    /// output, before it's returned. Not part of the wire format: populated internally
+    /// by [`crate::service::RenderService`] from the rules registered on it via
+    /// [`RenderService::register_rewrite_rule`][crate::service::RenderService::register_rewrite_rule].
+    #[serde(skip)]
+    pub rewrite_rules: Option<crate::rewrite::RewriteRegistry>,
+    /// Caps how many files of this batch [`crate::service::RenderService::render_batch`]
+    /// renders concurrently, overriding
+    /// [`RenderServiceConfig::max_batch_concurrency`][crate::service::RenderServiceConfig::max_batch_concurrency]
+    /// for this request only. `None` (the default) leaves the service's configured
+    /// concurrency in effect; `Some(1)` forces the original strictly-sequential path,
+    /// for an embedding application that already manages its own thread pool and
+    /// doesn't want this batch competing with it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parallelism: Option<usize>,
+    /// When set, builds a [`crate::search::SearchIndex`] over every successfully
+    /// rendered HTML file in the batch and populates
+    /// [`crate::service::BatchRenderOutcome::search_index`] with it. `false` (the
+    /// default) leaves it `None`, since indexing every file costs a full pass over
+    /// its rendered text that most callers don't need.
+    #[serde(default)]
+    pub build_search_index: bool,
+    /// When set, parses each fenced code block's full info string (rustdoc
+    /// `LangString`-style: extra `{.foo .bar}` classes, an `ignore` flag that
+    /// suppresses syntax highlighting, and `{3,5-8}` line-highlight ranges) and
+    /// applies it to the rendered block - see [`crate::fence`]. `false` (the
+    /// default) leaves a fence's info string exactly as plain CommonMark treats it:
+    /// only the first word matters, as the `language-x` class.
+    #[serde(default)]
+    pub fence_attributes: bool,
+    /// When set, recovers every fenced code block from the document's raw markdown
+    /// (see [`crate::doctest`]) into [`RenderedMdx::doctests`], and for any tagged
+    /// `js`/`javascript`/`ts`/`typescript`/`jsx`/`tsx` - unless its info string
+    /// carries `ignore` or `no_run` (see [`crate::fence::FenceInfo`]) - runs it
+    /// through this renderer's V8 isolate to confirm it evaluates without throwing,
+    /// recording a failure message on that one entry instead of failing the whole
+    /// file. `false` (the default) skips the extraction pass entirely.
+    #[serde(default)]
+    pub doctest: bool,
+    /// When set, rewrites straight quotes, `--`/`---`, and `...` in the rendered HTML's
+    /// prose text into curly quotes, en/em dashes, and an ellipsis glyph - see
+    /// [`crate::typography`]. `<pre>`/`<code>` content, other HTML tags, and `{...}`
+    /// JSX expressions are left untouched. `false` (the default) leaves punctuation
+    /// exactly as written.
+    #[serde(default)]
+    pub smart_punctuation: bool,
+    /// When set, every rendered `<a>` whose `href` is an absolute URL external to
+    /// [`external_links_site_host`][Self::external_links_site_host] gets
+    /// `target="_blank"` (unless the author already set a `target`) - see
+    /// [`crate::links`]. `false` (the default) leaves every link's `target` alone.
+    #[serde(default)]
+    pub external_links_target_blank: bool,
+    /// When set, merges `nofollow` into external links' `rel`, without clobbering any
+    /// existing `rel` tokens - see [`crate::links`]. `false` (the default) adds none.
+    #[serde(default)]
+    pub external_links_nofollow: bool,
+    /// When set, merges `noreferrer` into external links' `rel`, without clobbering
+    /// any existing `rel` tokens - see [`crate::links`]. `false` (the default) adds
+    /// none.
+    #[serde(default)]
+    pub external_links_noreferrer: bool,
+    /// The site's own host (e.g. `"example.com"`), compared case-insensitively against
+    /// a link's host to decide whether it's "external" for
+    /// `external_links_target_blank`/`external_links_nofollow`/
+    /// `external_links_noreferrer`. `None` (the default) treats every absolute
+    /// `http(s)` URL as external.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub external_links_site_host: Option<String>,
+    /// The registry `{...}` expression/ESM `import`/`export` parser hooks validate
+    /// against - see [`crate::parser_hooks`]. Not part of the wire format: populated
+    /// internally by [`crate::service::RenderService`] from the hooks registered on it
+    /// via
+    /// [`RenderService::register_expression_parser`][crate::service::RenderService::register_expression_parser]/
+    /// [`RenderService::register_esm_parser`][crate::service::RenderService::register_esm_parser].
+    #[serde(skip)]
+    pub parser_hooks: Option<crate::parser_hooks::ParserHookRegistry>,
+    /// When set, [`RenderedMdx::summary`] is populated with a plain-text excerpt of
+    /// the rendered document - code blocks dropped, markup stripped, whitespace
+    /// collapsed, truncated to this many characters on a word boundary - see
+    /// [`crate::summary::plain_text_summary`]. `None` (the default) leaves it unset,
+    /// since computing an excerpt costs a second pass over the rendered HTML that most
+    /// callers don't need.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub summary_length: Option<usize>,
+    /// When set, expands `:name:` shortcodes in the rendered prose into their Unicode
+    /// emoji (`:tada:` -> 🎉) against the built-in name table - see
+    /// [`crate::emoji::expand_emoji`]. An unrecognized name is left untouched. `false`
+    /// (the default) leaves every `:name:` sequence exactly as written.
+    #[serde(default)]
+    pub render_emoji: bool,
+    /// When set, [`crate::mdx::mdx_to_html_with_frontmatter`] consults a process-wide,
+    /// content-hash-keyed cache (see [`crate::render_cache`]) before re-running the
+    /// render pipeline, and stores its result there - so a caller that re-renders the
+    /// same MDX source, component set, and settings (e.g. a dev server re-rendering on
+    /// every request regardless of whether the file changed) skips straight to a
+    /// cached [`RenderedMdx`]. `false` (the default) always runs the full pipeline, the
+    /// same as before this setting existed.
+    #[serde(default)]
+    pub render_cache: bool,
+    /// Default component name -> module specifier map (e.g. `{"Card": "./Card.tsx"}`),
+    /// analogous to a JSX `jsxImportSource` import map, applied across every file
+    /// rendered with these settings. A document's own frontmatter `imports:` map (see
+    /// [`crate::mdx::mdx_to_writer_with_frontmatter`]) is merged on top of this, with
+    /// the frontmatter entry winning on a name collision. `None` (the default) leaves
+    /// it empty, so only `components` entries and a document's own `imports:` count
+    /// toward resolution.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub component_imports: Option<HashMap<String, String>>,
+    /// When set, expands `{{#each ...}}`/`{{#if ...}}`/`{{#with ...}}` block helpers
+    /// in the raw markdown source against the document's frontmatter, before it's
+    /// rendered - see [`crate::block_helpers`]. `false` (the default) leaves any
+    /// literal `{{...}}` text untouched.
+    #[serde(default)]
+    pub block_helpers: bool,
+    /// When set, runs [`crate::sanitize::sanitize_html`] over the rendered HTML before
+    /// it's returned - see [`SanitizeSettings`]. `None` (the default) leaves rendered
+    /// HTML untouched, the same trust model [`crate::mdx::markdown_options`]'s
+    /// `allow_dangerous_html` documents: safe for trusted MDX authors, not for
+    /// untrusted ones.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sanitize: Option<SanitizeSettings>,
+    /// When set, every fenced/indented code block's lines starting with `# `
+    /// (hash-space) or a lone `#` are omitted from the rendered `<pre>` output - a
+    /// literal leading `#` can be written as `##` to survive rendering - see
+    /// [`crate::hidden_lines`]. `false` (the default) renders every line exactly as
+    /// written, the same as before this setting existed.
+    #[serde(default)]
+    pub hidden_code_lines: bool,
+    /// When set, every code block's common leading indentation (the minimum number of
+    /// columns shared by every non-blank line, tabs expanded) is stripped before it's
+    /// rendered, the same way rustdoc's `unindent` flushes a nested doctest left - see
+    /// [`crate::unindent::unindent`]. `true` (the default) keeps nested code blocks
+    /// flush-left the same way rustdoc always has; a caller relying on a code block's
+    /// exact original indentation can opt back out.
+    #[serde(default = "default_unindent_code_blocks_true")]
+    pub unindent_code_blocks: bool,
+    /// Whether pipe tables (`| a | b |`) are parsed as GFM tables rather than left as
+    /// literal text - mirrors established renderers' `ENABLE_TABLES` option. `true`
+    /// (the default) matches this renderer's behavior since tables were first
+    /// supported; set `false` for CommonMark-pure prose where a stray `|`-delimited
+    /// line shouldn't be mistaken for a table.
+    #[serde(default = "default_true")]
+    pub enable_tables: bool,
+    /// Whether `[^1]`-style footnote references/definitions are collected and
+    /// rendered at the document end rather than left as literal text - mirrors
+    /// established renderers' `ENABLE_FOOTNOTES` option. `true` (the default) matches
+    /// this renderer's behavior since footnotes were first supported; set `false` for
+    /// CommonMark-pure prose.
+    #[serde(default = "default_true")]
+    pub enable_footnotes: bool,
+    /// When set, the document's TSX->JS transform (see
+    /// [`TsxTransformConfig::with_source_maps`]) carries a source map into the
+    /// renderer, so a runtime error thrown while executing the generated code is
+    /// reported against the author's original MDX/TSX line rather than the generated
+    /// JavaScript (`renderer::runtime::translate_execution_error`). `false` (the
+    /// default) skips the extra codegen work for the common case where nothing fails.
+    #[serde(default)]
+    pub source_maps: bool,
+    /// Capability grants for component JavaScript executed while rendering this
+    /// batch - see [`ComponentPermissions`]. Deny-by-default (the default value of
+    /// every field), the same posture [`ResourceLimits`] takes toward resource
+    /// consumption but for what categories of operation component code is allowed to
+    /// attempt at all.
+    #[serde(default)]
+    pub permissions: ComponentPermissions,
+    /// When set, instruments every registered component with an invocation counter
+    /// before rendering the batch and populates
+    /// [`crate::service::BatchRenderOutcome::coverage`] with the per-component counts
+    /// plus the names of any that were never invoked - useful for pruning dead
+    /// component definitions out of a large MDX corpus. `false` (the default) skips
+    /// the instrumentation pass entirely.
+    #[serde(default)]
+    pub coverage: bool,
+    /// Raw HTML fragments to splice into [`OutputFormat::Html`] output, rustdoc
+    /// `--html-in-header`/`--html-before-content`/`--html-after-content`-style - see
+    /// [`ExternalHtml`]. `None` (the default) leaves rendered output untouched.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub external_html: Option<ExternalHtml>,
+    /// Directory [`HtmlFragmentSource::Path`] is resolved and confined to - see
+    /// [`HtmlFragmentSource::resolve`]. Not part of the wire format: populated
+    /// internally by [`crate::service::RenderService`] from
+    /// [`crate::service::RenderServiceConfig::static_dir`], the same way
+    /// [`Self::lua_directives`]/[`Self::lua_utils`] are populated from registrations
+    /// rather than deserialized. A caller supplying `external_html` through an
+    /// untrusted channel (the HTTP batch/upload endpoints, the Python/JS bindings)
+    /// can therefore never point `Path` anywhere outside this directory, however this
+    /// field is left unset if constructed by hand.
+    #[serde(skip)]
+    pub external_html_root: Option<PathBuf>,
+}
+
+/// Raw HTML fragments an embedder supplies to surround [`OutputFormat::Html`] output -
+/// analytics snippets, stylesheet links, or layout chrome - without post-processing the
+/// rendered string themselves. Since this renderer returns a document fragment rather
+/// than a full `<html>`/`<head>`/`<body>` page, [`Self::in_header`] is spliced in first
+/// rather than into an actual `<head>`; an embedder assembling a full page should lift
+/// it into their own `<head>` instead of leaving it in the fragment. Every fragment
+/// passes through [`RenderSettings::minify`] along with the rendered document.
+#[derive(Deserialize, Serialize, Clone, Debug, Default)]
+pub struct ExternalHtml {
+    /// Spliced in first, ahead of [`Self::before_content`] - intended for an embedder's
+    /// own `<head>` once lifted out of the fragment (see the struct-level note).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub in_header: Option<HtmlFragmentSource>,
+    /// Spliced in right before the rendered document (rustdoc's "before content", i.e.
+    /// right after `<body>` on a full page).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub before_content: Option<HtmlFragmentSource>,
+    /// Spliced in right after the rendered document.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub after_content: Option<HtmlFragmentSource>,
+}
+
+/// One [`ExternalHtml`] fragment's content, either inline or loaded fresh from disk on
+/// every render - see [`Self::resolve`].
+#[derive(Deserialize, Serialize, Clone, Debug)]
+#[serde(untagged)]
+pub enum HtmlFragmentSource {
+    /// Fragment content, verbatim.
+    Inline(String),
+    /// Path to a file containing the fragment content.
+    Path {
+        /// Path to read the fragment from.
+        path: PathBuf,
+    },
+}
+
+impl HtmlFragmentSource {
+    /// Returns this fragment's content, reading [`Self::Path`] from disk.
+    ///
+    /// `root` is [`RenderSettings::external_html_root`] - `Path` is resolved relative
+    /// to it and, after canonicalizing, must still land inside it (the same
+    /// canonicalize-and-prefix-check [`crate::renderer::module_loader`] uses to confine
+    /// a module's `static_dir` imports). An absolute `path`, or one whose `..`
+    /// components escape `root`, is rejected rather than followed - without this, a
+    /// caller supplying `external_html` through an untrusted channel (the HTTP
+    /// batch/upload endpoints, the bindings) could read arbitrary files the process
+    /// has access to. With no `root` configured, `Path` is refused outright.
+    fn resolve(&self, root: Option<&std::path::Path>) -> Result<String, crate::error::MdxError> {
+        match self {
+            Self::Inline(content) => Ok(content.clone()),
+            Self::Path { path } => {
+                let root = root.ok_or_else(|| {
+                    crate::error::MdxError::ExternalHtml(format!(
+                        "{}: HtmlFragmentSource::Path requires a configured static_dir root",
+                        path.display()
+                    ))
+                })?;
+                if path.is_absolute() {
+                    return Err(crate::error::MdxError::ExternalHtml(format!(
+                        "{}: absolute paths are not allowed",
+                        path.display()
+                    )));
+                }
+                let canonical_root = root.canonicalize().map_err(|e| {
+                    crate::error::MdxError::ExternalHtml(format!("{}: {e}", root.display()))
+                })?;
+                let candidate = root.join(path);
+                let canonical = candidate.canonicalize().map_err(|e| {
+                    crate::error::MdxError::ExternalHtml(format!("{}: {e}", candidate.display()))
+                })?;
+                if !canonical.starts_with(&canonical_root) {
+                    return Err(crate::error::MdxError::ExternalHtml(format!(
+                        "{}: path escapes the configured static_dir root",
+                        path.display()
+                    )));
+                }
+                std::fs::read_to_string(&canonical).map_err(|e| {
+                    crate::error::MdxError::ExternalHtml(format!("{}: {e}", canonical.display()))
+                })
+            }
+        }
+    }
+}
+
+impl ExternalHtml {
+    /// Resolves every configured fragment (reading any [`HtmlFragmentSource::Path`]
+    /// from disk, confined to `root` - see [`HtmlFragmentSource::resolve`]) and
+    /// concatenates them around `content` in [`Self`]'s documented order:
+    /// [`Self::in_header`], then [`Self::before_content`], then `content`, then
+    /// [`Self::after_content`]. An unset fragment contributes nothing.
+    pub(crate) fn splice(
+        &self,
+        content: &str,
+        root: Option<&std::path::Path>,
+    ) -> Result<String, crate::error::MdxError> {
+        let mut spliced = String::with_capacity(content.len());
+        if let Some(fragment) = &self.in_header {
+            spliced.push_str(&fragment.resolve(root)?);
+        }
+        if let Some(fragment) = &self.before_content {
+            spliced.push_str(&fragment.resolve(root)?);
+        }
+        spliced.push_str(content);
+        if let Some(fragment) = &self.after_content {
+            spliced.push_str(&fragment.resolve(root)?);
+        }
+        Ok(spliced)
+    }
+}
+
+const fn default_true() -> bool {
+    true
 }
 
 const fn default_minify_true() -> bool {
     true
 }
 
+const fn default_unindent_code_blocks_true() -> bool {
+    true
+}
+
 impl Default for RenderSettings {
     fn default() -> Self {
         Self {
             output: OutputFormat::default(),
             minify: true,
+            compiler_options: None,
+            decorators: None,
+            highlight: None,
+            leading_metadata_marker: None,
+            headings: false,
+            heading_offset: 0,
+            diagnostics: crate::error::DiagnosticStyle::default(),
+            directives: None,
+            lua_directives: None,
+            lua_utils: None,
+            rewrite_rules: None,
+            parallelism: None,
+            build_search_index: false,
+            fence_attributes: false,
+            doctest: false,
+            smart_punctuation: false,
+            external_links_target_blank: false,
+            external_links_nofollow: false,
+            external_links_noreferrer: false,
+            external_links_site_host: None,
+            parser_hooks: None,
+            summary_length: None,
+            render_emoji: false,
+            render_cache: false,
+            component_imports: None,
+            block_helpers: false,
+            sanitize: None,
+            hidden_code_lines: false,
+            unindent_code_blocks: true,
+            enable_tables: true,
+            enable_footnotes: true,
+            source_maps: false,
+            permissions: ComponentPermissions::default(),
+            coverage: false,
+            external_html: None,
+            external_html_root: None,
+        }
+    }
+}
+
+/// Capability grants for component JavaScript execution, deny-by-default - modeled on
+/// Deno's `Permissions` system. Unlike [`ResourceLimits`] (which bounds how much of a
+/// resource component code may consume), this bounds which categories of operation it
+/// may attempt at all: when [`crate::renderer::JsRenderer`] prepares an isolate, every
+/// denied capability's corresponding globals are trapped so an attempt to use one
+/// throws immediately, naming the capability, rather than running partway.
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Eq)]
+#[serde(default)]
+pub struct ComponentPermissions {
+    /// Allow network access (`fetch`, `XMLHttpRequest`, `WebSocket`). `false` (the
+    /// default) traps these globals so calling them throws instead of running.
+    pub network: bool,
+    /// Allow filesystem access. `false` (the default) traps the globals a future
+    /// filesystem API would be exposed under, so component code can't assume one will
+    /// ever be reachable from the isolate.
+    pub filesystem: bool,
+    /// Allow reading process/environment state (e.g. `process.env`). `false` (the
+    /// default) traps it.
+    pub environment: bool,
+    /// Allow dynamic code execution via `eval` or the `Function` constructor. `false`
+    /// (the default) traps both - legitimate component code has no need for either,
+    /// and both are common injection vectors for untrusted component source.
+    pub eval: bool,
+}
+
+impl Default for ComponentPermissions {
+    fn default() -> Self {
+        Self {
+            network: false,
+            filesystem: false,
+            environment: false,
+            eval: false,
         }
     }
 }
 
+/// Settings controlling [`crate::highlight::highlight_code_blocks`]'s syntax
+/// highlighting pass over fenced code blocks.
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct HighlightSettings {
+    /// Name of a `syntect` theme to highlight against (e.g. `"InspiredGitHub"`,
+    /// `"base16-ocean.dark"` - any key present in `ThemeSet::load_defaults()`'s
+    /// `themes` map). A name that isn't a loaded theme leaves the code block
+    /// unhighlighted, the same as an unrecognized language.
+    #[serde(default = "default_highlight_theme")]
+    pub theme: String,
+    /// Emit `style="..."` inline on each token's `<span>` instead of a `class="..."`
+    /// naming a `syntect` token class, for a caller that doesn't want to ship a
+    /// separate theme stylesheet.
+    #[serde(default)]
+    pub inline_styles: bool,
+    /// Collapse `syntect`'s full scope-hierarchy classes (e.g. `"storage modifier
+    /// rust"`) down to a small rustdoc-style token set - `kw`, `str`, `comment`,
+    /// `number`, `ident`, `op` - instead of emitting `syntect`'s own class names, so a
+    /// caller can ship one small stylesheet that works across every language rather
+    /// than a per-syntax one. Has no effect when `inline_styles` is set, since there
+    /// are no classes to simplify. See [`crate::highlight::simplify_classes`].
+    #[serde(default)]
+    pub simple_classes: bool,
+}
+
+fn default_highlight_theme() -> String {
+    "InspiredGitHub".to_string()
+}
+
+impl Default for HighlightSettings {
+    fn default() -> Self {
+        Self {
+            theme: default_highlight_theme(),
+            inline_styles: false,
+            simple_classes: false,
+        }
+    }
+}
+
+/// Settings controlling [`crate::sanitize::sanitize_html`]'s HTML sanitization pass,
+/// for rendering MDX from untrusted authors - see [`RenderSettings::sanitize`].
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct SanitizeSettings {
+    /// Tag names (case-insensitive) allowed to survive sanitization; any other tag is
+    /// stripped (its content is kept, unwrapped). `<script>`, `<style>`, `<iframe>`,
+    /// `<object>`, and `<embed>` are always dropped with their content regardless of
+    /// this list, since allowing them back in would defeat the point.
+    #[serde(default = "default_tag_allowlist")]
+    pub tag_allowlist: Vec<String>,
+    /// Rewrite every `<img>`'s `src` attribute to `data-src`, so the image doesn't
+    /// load until a caller's own script opts it back in.
+    #[serde(default)]
+    pub neutralize_images: bool,
+}
+
+/// A reasonably permissive default allow-list covering MDX's common prose/structure
+/// elements - headings, text-level semantics, lists, tables, and media - but no
+/// scripting or embedding elements.
+fn default_tag_allowlist() -> Vec<String> {
+    [
+        "a", "p", "br", "hr", "span", "div", "h1", "h2", "h3", "h4", "h5", "h6", "ul", "ol", "li",
+        "strong", "em", "b", "i", "u", "s", "code", "pre", "blockquote", "table", "thead", "tbody",
+        "tr", "td", "th", "img", "figure", "figcaption", "button", "small", "sub", "sup", "mark",
+        "kbd", "q", "cite", "abbr", "del", "ins", "details", "summary",
+    ]
+    .iter()
+    .map(|tag| tag.to_string())
+    .collect()
+}
+
+impl Default for SanitizeSettings {
+    fn default() -> Self {
+        Self { tag_allowlist: default_tag_allowlist(), neutralize_images: false }
+    }
+}
+
 /// Input structure for batch MDX rendering requests
 #[derive(Deserialize, Serialize)]
 pub struct NamedMdxBatchInput {
@@ -82,10 +637,63 @@ pub struct NamedMdxBatchInput {
     /// Optional map of component names to their definitions
     #[serde(skip_serializing_if = "Option::is_none")]
     pub components: Option<HashMap<String, ComponentDefinition>>,
+    /// Optional map of partial names to their MDX source, resolved against
+    /// `<Include name="..." />`/`{{> name}}` references in every file of this batch -
+    /// see [`crate::partials`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub partials: Option<HashMap<String, String>>,
+}
+
+/// Entry name a [`RenderInput`] is given in the single-entry [`NamedMdxBatchInput`]
+/// [`RenderInput::into_batch_input`] builds, when [`RenderInput::name`] is unset.
+const DEFAULT_RENDER_INPUT_NAME: &str = "input.mdx";
+
+/// Input structure for the single-document render endpoints (`/render/html`,
+/// `/render/javascript`, etc.), a convenience wrapper around [`NamedMdxBatchInput`] for
+/// a client rendering exactly one document that would otherwise have to invent a name
+/// for it - see [`Self::into_batch_input`].
+#[derive(Deserialize, Serialize)]
+pub struct RenderInput {
+    /// Entry name this document is given once wrapped into a [`NamedMdxBatchInput`] -
+    /// surfaced back in per-file outcome data, but otherwise inert for a single-file
+    /// request. Defaults to [`DEFAULT_RENDER_INPUT_NAME`] when unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// MDX source to render.
+    pub mdx: String,
+    /// Rendering settings - [`RenderSettings::output`] is overwritten by
+    /// [`Self::into_batch_input`]'s `format` argument regardless of what's set here,
+    /// since the target endpoint already pins it.
+    #[serde(default)]
+    pub settings: RenderSettings,
+    /// Optional map of component names to their definitions.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub components: Option<HashMap<String, ComponentDefinition>>,
+    /// Optional map of partial names to their MDX source - see
+    /// [`NamedMdxBatchInput::partials`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub partials: Option<HashMap<String, String>>,
+}
+
+impl RenderInput {
+    /// Wraps this single document into a one-entry [`NamedMdxBatchInput`], pinning
+    /// [`RenderSettings::output`] to `format` - the per-format endpoints
+    /// (`render_html`, `render_javascript`, ...) use this so the request body never
+    /// needs to repeat the format the URL already names.
+    pub(crate) fn into_batch_input(self, format: OutputFormat) -> NamedMdxBatchInput {
+        let name = self.name.unwrap_or_else(|| DEFAULT_RENDER_INPUT_NAME.to_string());
+        let mut settings = self.settings;
+        settings.output = format;
+
+        let mut mdx = HashMap::with_capacity(1);
+        mdx.insert(name, self.mdx);
+
+        NamedMdxBatchInput { settings, mdx, components: self.components, partials: self.partials }
+    }
 }
 
 /// Output structure containing rendered output and metadata
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct RenderedMdx {
     /// Parsed YAML frontmatter metadata
     pub metadata: serde_json::Value,
@@ -98,6 +706,73 @@ pub struct RenderedMdx {
     /// - `OutputFormat::Schema` → JavaScript code after TSX transformation (before rendering)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub output: Option<String>,
+    /// Table of contents entries, one per `h1..h6` heading, in document order - only
+    /// populated when [`RenderSettings::headings`] is set. Empty otherwise.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub toc: Vec<TocEntry>,
+    /// Plain-text excerpt of the rendered document - only populated when
+    /// [`RenderSettings::summary_length`] is set. `None` otherwise.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub summary: Option<String>,
+    /// Fenced code blocks recovered from the document and, for executable ones, the
+    /// result of evaluating them - only populated when [`RenderSettings::doctest`] is
+    /// set. Empty otherwise.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub doctests: Vec<DoctestResult>,
+}
+
+/// A single fenced code block recovered from a document's raw markdown source for
+/// [`RenderSettings::doctest`], with the result of evaluating it through the
+/// renderer's V8 engine if it was executable - see [`crate::doctest::extract_and_run`].
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct DoctestResult {
+    /// The fence's language tag (its info string's first word), if it had one.
+    pub language: Option<String>,
+    /// 1-indexed line the fence's opening delimiter starts on, in the original
+    /// document.
+    pub line: usize,
+    /// The block's raw source text, unprocessed.
+    pub code: String,
+    /// Whether this block was actually evaluated - `false` for a non-executable
+    /// language or one marked `ignore`/`no_run` in its info string.
+    pub executed: bool,
+    /// The thrown error's message, if evaluating it failed. `None` if it wasn't run,
+    /// or ran and completed without throwing.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// A single heading's table-of-contents entry (see [`RenderSettings::headings`] and
+/// [`crate::toc::inject_heading_ids`]).
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct TocEntry {
+    /// Heading level, `1..=6` (from `<h1>`..`<h6>`).
+    pub level: u8,
+    /// The heading's text content, with any inline markup (e.g. `<code>`, `<em>`)
+    /// stripped and HTML entities unescaped.
+    pub text: String,
+    /// GitHub-style slug (lowercased, punctuation stripped, whitespace collapsed to
+    /// `-`), deduplicated against earlier headings in the same file by appending
+    /// `-1`, `-2`, ... on collision - this is the same string injected as the
+    /// heading's `id=` attribute.
+    pub slug: String,
+}
+
+/// One node of the nested table-of-contents tree [`crate::toc::build_toc_tree`]
+/// builds from a flat [`TocEntry`] list - each heading nests under the nearest
+/// preceding heading of a shallower level, the way a markdown outline (and
+/// [`OutputFormat::Toc`]'s output) is conventionally rendered.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct TocNode {
+    /// Heading level, `1..=6` (from `<h1>`..`<h6>`).
+    pub level: u8,
+    /// The heading's text content - see [`TocEntry::text`].
+    pub text: String,
+    /// The heading's `id=` slug - see [`TocEntry::slug`].
+    pub slug: String,
+    /// Headings of a deeper level nested under this one, in document order.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<TocNode>,
 }
 
 /// Resource limits for preventing resource exhaustion.
@@ -105,7 +780,7 @@ pub struct RenderedMdx {
 /// These limits are enforced at the library level to prevent memory exhaustion
 /// and ensure reliable operation. They are not HTTP security controls, but rather
 /// internal reliability measures.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct ResourceLimits {
     /// Maximum number of files in a batch request
     pub max_batch_size: usize,
@@ -113,6 +788,12 @@ pub struct ResourceLimits {
     pub max_mdx_content_size: usize,
     /// Maximum component code size (in bytes)
     pub max_component_code_size: usize,
+    /// Maximum time an async render (see `JsRenderer::render_component_async` and its
+    /// siblings in `crate::renderer`) may spend draining `deno_core`'s event loop, in
+    /// milliseconds, before it's aborted with [`crate::error::MdxError::RenderTimeout`].
+    /// Guards against a component that `await`s a promise that never settles - a
+    /// stalled `fetch`, or a `setInterval`-driven loop with nothing to end it.
+    pub max_render_time_ms: u64,
 }
 
 impl Default for ResourceLimits {
@@ -121,6 +802,7 @@ impl Default for ResourceLimits {
             max_batch_size: 1000,
             max_mdx_content_size: 10 * 1024 * 1024, // 10 MB
             max_component_code_size: 1024 * 1024,   // 1 MB
+            max_render_time_ms: 5_000,              // 5 seconds
         }
     }
 }
@@ -143,6 +825,10 @@ impl ResourceLimits {
             return Err("max_component_code_size must be greater than 0".to_string());
         }
 
+        if self.max_render_time_ms == 0 {
+            return Err("max_render_time_ms must be greater than 0".to_string());
+        }
+
         // Enforce maximum recommended limits to prevent memory exhaustion
         const MAX_RECOMMENDED_BATCH_SIZE: usize = 100_000;
         if self.max_batch_size > MAX_RECOMMENDED_BATCH_SIZE {
@@ -164,6 +850,101 @@ impl ResourceLimits {
     }
 }
 
+/// A single binding an [`ImportDescriptor`] introduces into scope.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ImportedName {
+    /// `import Foo from "..."` - binds `Foo` to the module's default export.
+    Default(String),
+    /// `import * as ns from "..."` - binds `ns` to the whole module namespace.
+    Namespace(String),
+    /// `import { Foo }` or `import { Foo as Bar }` - binds `local` to the module's
+    /// `imported` export.
+    Named { imported: String, local: String },
+}
+
+/// A single static `import` collected from component source before
+/// `cleanup_generated_code` strips or resolves it - analogous to swc/deno's
+/// `analyze_dependencies`. See
+/// [`transform_tsx_to_js_with_imports`](crate::transform::transform_tsx_to_js_with_imports).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ImportDescriptor {
+    /// The module specifier as written, e.g. `"./Button"`.
+    pub specifier: String,
+    /// Every binding this import introduces, in source order.
+    pub imported_names: Vec<ImportedName>,
+    /// Whether this is a type-only import (`import type { ... }`), which erases at
+    /// runtime and so shouldn't be expected to resolve against
+    /// [`TsxTransformConfig::import_map`] or count toward an import allow-list.
+    pub type_only: bool,
+    /// Byte offset of the `import` statement's start in the parsed source.
+    pub start: u32,
+    /// Byte offset just past the end of the `import` statement in the parsed source.
+    pub end: u32,
+}
+
+/// Import-graph analysis of a component's source, as produced by
+/// [`analyze_imports`](crate::transform::analyze_imports) - similar to what an
+/// import-linting tool reports, for a host that wants to inspect or police a
+/// component's dependencies before running it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ImportReport {
+    /// Every static `import` and re-export-from (`export ... from`/`export * from`)
+    /// specifier found, in source order.
+    pub imports: Vec<ImportDescriptor>,
+    /// Specifiers imported or re-exported-from more than once, in first-seen order.
+    pub duplicate_specifiers: Vec<String>,
+}
+
+/// A single prop read off a component's single parameter type, as extracted by
+/// [`extract_component_props`](crate::transform::extract_component_props) -
+/// react-docgen-style metadata for building prop tables or validating usage.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PropInfo {
+    /// The prop's name.
+    pub name: String,
+    /// The prop's type, rendered back from its TypeScript type annotation.
+    pub type_string: String,
+    /// Whether the prop is optional (`name?: T`) or its type is a union with `undefined`.
+    pub optional: bool,
+    /// The prop's default value, if one is discoverable from a destructuring default
+    /// in the parameter pattern (e.g. `{ count = 0 }`).
+    pub default_value: Option<String>,
+}
+
+/// Deep-merges `overrides` into `base` in place: an object key present in both merges
+/// recursively, while a scalar or array in `overrides` replaces the corresponding
+/// value in `base` outright - used to layer [`RenderSettings::compiler_options`] over
+/// [`TsxTransformConfig`]'s current settings (see
+/// [`TsxTransformConfig::with_compiler_options`]).
+fn merge_json(base: &mut serde_json::Value, overrides: &serde_json::Value) {
+    match (base, overrides) {
+        (serde_json::Value::Object(base_map), serde_json::Value::Object(override_map)) => {
+            for (key, value) in override_map {
+                merge_json(
+                    base_map.entry(key.clone()).or_insert(serde_json::Value::Null),
+                    value,
+                );
+            }
+        }
+        (base, overrides) => {
+            *base = overrides.clone();
+        }
+    }
+}
+
+/// Selects how JSX is compiled to JavaScript.
+#[derive(Deserialize, Serialize, Clone, Debug, Default, PartialEq, Eq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum JsxRuntimeMode {
+    /// Classic factory-call transform (e.g. `engine.h('div', null, ...)`), resolved
+    /// via `jsx_pragma`/`jsx_pragma_frag`.
+    #[default]
+    Classic,
+    /// Automatic runtime emitting `_jsx`/`_jsxs`/`_Fragment` calls, resolved by a
+    /// shim injected ahead of the transformed code rather than an import.
+    Automatic,
+}
+
 /// Configuration for TSX transformation
 pub struct TsxTransformConfig {
     /// JSX pragma function name (e.g., "engine.h" or "h")
@@ -174,8 +955,59 @@ pub struct TsxTransformConfig {
     pub minify: bool,
     /// Component names to convert from function references to strings (for schema output)
     pub component_names: Option<std::collections::HashSet<String>>,
+    /// Classic (factory-call) or automatic (`_jsx`/`_jsxs`) JSX compilation
+    pub jsx_runtime: JsxRuntimeMode,
+    /// Module specifier the automatic runtime's `_jsx`/`_jsxs`/`_Fragment` import is
+    /// generated against (ignored in [`JsxRuntimeMode::Classic`]). The import itself
+    /// never runs - generated code executes as a script, not a module - but it still
+    /// has to name something for Oxc to emit, and `cleanup_generated_code` strips it
+    /// and substitutes a shim resolving those names against the engine instead.
+    pub jsx_import_source: String,
+    /// Whether to generate a source map and embed it in the output as an inline
+    /// `//# sourceMappingURL=data:...` comment, so runtime errors can be translated
+    /// back to the author's original TSX instead of pointing at generated JS.
+    pub with_source_maps: bool,
+    /// Optional specifier -> resolved global map (e.g. `"./Button" -> "components.Button"`)
+    /// used to link a component's static imports to real bindings instead of
+    /// `cleanup_generated_code` silently dropping them. A specifier with no entry here
+    /// fails the transform with [`MdxError::UnresolvedImport`](crate::error::MdxError::UnresolvedImport).
+    /// `None` (the default) preserves the old drop-every-import behavior.
+    pub import_map: Option<HashMap<String, String>>,
+    /// Whether to keep author comments (JSDoc, `@license`/`@preserve` banners, etc.)
+    /// in the generated code. Off by default, matching Oxc's codegen default, since
+    /// most generated output is fed straight to V8 rather than shipped; build
+    /// pipelines that need a license banner or type-doc comments to survive into
+    /// shipped output should set this.
+    pub keep_comments: bool,
+    /// Whether to compile JSX in development mode: injects `__source`/`__self` debug
+    /// props, uses the `jsxDEV` factory when combined with
+    /// [`JsxRuntimeMode::Automatic`], and - together with `refresh` - registers Fast
+    /// Refresh boundaries. Off by default; production renders have no use for either.
+    pub development: bool,
+    /// Fast Refresh options passed to Oxc when `development` is set. `None` uses
+    /// Oxc's defaults; ignored when `development` is `false`.
+    pub refresh: Option<ReactRefreshOptions>,
+    /// Whether to lower decorators using legacy (stage-1, TypeScript
+    /// `experimentalDecorators`) semantics, matching the two distinct lowerings SWC
+    /// exposes. Defaults to `true`: Oxc's transformer only implements the legacy
+    /// lowering so far (TC39 decorator syntax parses but isn't transformed - see
+    /// <https://github.com/oxc-project/oxc/issues/9170>), so setting this to `false`
+    /// currently leaves TC39 decorator syntax untouched by the transform rather than
+    /// producing a TC39-semantics lowering.
+    pub decorators_legacy: bool,
+    /// Whether to emit `Reflect.metadata("design:type", ...)` /
+    /// `"design:paramtypes"` calls derived from a decorated member's TypeScript type
+    /// annotations - TypeScript's `emitDecoratorMetadata`, needed by
+    /// dependency-injection-style helper classes (NestJS, TypeORM, etc.) inside a
+    /// component. Only takes effect when `decorators_legacy` is `true`. Defaults to
+    /// `true`.
+    pub emit_decorator_metadata: bool,
 }
 
+/// Default module specifier the automatic JSX runtime's import is generated against.
+/// Never actually resolved at runtime - see [`TsxTransformConfig::jsx_import_source`].
+const DEFAULT_JSX_IMPORT_SOURCE: &str = "engine/jsx-runtime";
+
 impl Default for TsxTransformConfig {
     fn default() -> Self {
         Self {
@@ -183,6 +1015,15 @@ impl Default for TsxTransformConfig {
             jsx_pragma_frag: "engine.Fragment".to_string(),
             minify: false,
             component_names: None,
+            jsx_runtime: JsxRuntimeMode::Classic,
+            jsx_import_source: DEFAULT_JSX_IMPORT_SOURCE.to_string(),
+            with_source_maps: false,
+            import_map: None,
+            keep_comments: false,
+            development: false,
+            refresh: None,
+            decorators_legacy: true,
+            emit_decorator_metadata: true,
         }
     }
 }
@@ -195,6 +1036,15 @@ impl TsxTransformConfig {
             jsx_pragma_frag: "Fragment".to_string(),
             minify,
             component_names: None,
+            jsx_runtime: JsxRuntimeMode::Classic,
+            jsx_import_source: DEFAULT_JSX_IMPORT_SOURCE.to_string(),
+            with_source_maps: false,
+            import_map: None,
+            keep_comments: false,
+            development: false,
+            refresh: None,
+            decorators_legacy: true,
+            emit_decorator_metadata: true,
         }
     }
 
@@ -206,4 +1056,128 @@ impl TsxTransformConfig {
             ..Self::default()
         }
     }
+
+    /// Like [`TsxTransformConfig::for_engine`], but compiles JSX with the
+    /// automatic runtime (`_jsx`/`_jsxs`/`_Fragment`) instead of classic factory
+    /// calls, so components can be authored without importing a pragma function.
+    pub fn for_engine_automatic(minify: bool) -> Self {
+        Self {
+            jsx_runtime: JsxRuntimeMode::Automatic,
+            ..Self::for_engine(minify)
+        }
+    }
+
+    /// Overrides the module specifier the automatic runtime's import is generated
+    /// against. Only meaningful alongside [`JsxRuntimeMode::Automatic`].
+    pub fn with_jsx_import_source(mut self, import_source: impl Into<String>) -> Self {
+        self.jsx_import_source = import_source.into();
+        self
+    }
+
+    /// Enables or disables inline source map generation. Off by default, since it adds
+    /// codegen overhead that's only worth paying when debugging component errors.
+    pub fn with_source_maps(mut self, enabled: bool) -> Self {
+        self.with_source_maps = enabled;
+        self
+    }
+
+    /// Sets the specifier -> resolved global map used to link static imports instead
+    /// of dropping them. See [`TsxTransformConfig::import_map`].
+    pub fn with_import_map(mut self, import_map: HashMap<String, String>) -> Self {
+        self.import_map = Some(import_map);
+        self
+    }
+
+    /// Enables or disables keeping author comments in the generated code. See
+    /// [`TsxTransformConfig::keep_comments`].
+    pub fn with_keep_comments(mut self, enabled: bool) -> Self {
+        self.keep_comments = enabled;
+        self
+    }
+
+    /// Selects legacy vs TC39 decorator semantics. See
+    /// [`TsxTransformConfig::decorators_legacy`].
+    pub fn with_decorators_legacy(mut self, legacy: bool) -> Self {
+        self.decorators_legacy = legacy;
+        self
+    }
+
+    /// Enables or disables `emitDecoratorMetadata`-style `Reflect.metadata` calls. See
+    /// [`TsxTransformConfig::emit_decorator_metadata`].
+    pub fn with_emit_decorator_metadata(mut self, enabled: bool) -> Self {
+        self.emit_decorator_metadata = enabled;
+        self
+    }
+
+    /// Applies a [`RenderSettings::compiler_options`] tsconfig-style object on top of
+    /// this config's current settings, no-op if `overrides` is `None`. Only the
+    /// options with an equivalent in this transform are read back out afterward -
+    /// `jsx` (`"react-jsx"`/`"react-jsxdev"` select [`JsxRuntimeMode::Automatic`],
+    /// anything else [`JsxRuntimeMode::Classic`]), `jsxFactory`, `jsxFragmentFactory`,
+    /// `jsxImportSource`, and `experimentalDecorators`; everything else (`target`,
+    /// `useDefineForClassFields`, module-resolution options, ...) is accepted in
+    /// `overrides` but has no effect, mirroring how a TS toolchain silently drops
+    /// options a given build step doesn't use.
+    ///
+    /// The merge itself is a deep JSON merge: object keys merge recursively, while
+    /// scalars and arrays in `overrides` replace the corresponding default outright.
+    pub fn with_compiler_options(mut self, overrides: Option<&serde_json::Value>) -> Self {
+        let Some(overrides) = overrides else {
+            return self;
+        };
+
+        let mut merged = serde_json::json!({
+            "jsx": match self.jsx_runtime {
+                JsxRuntimeMode::Automatic => "react-jsx",
+                JsxRuntimeMode::Classic => "react",
+            },
+            "jsxFactory": self.jsx_pragma,
+            "jsxFragmentFactory": self.jsx_pragma_frag,
+            "jsxImportSource": self.jsx_import_source,
+            "experimentalDecorators": self.decorators_legacy,
+        });
+        merge_json(&mut merged, overrides);
+
+        if let Some(jsx) = merged.get("jsx").and_then(serde_json::Value::as_str) {
+            self.jsx_runtime = match jsx {
+                "react-jsx" | "react-jsxdev" => JsxRuntimeMode::Automatic,
+                _ => JsxRuntimeMode::Classic,
+            };
+        }
+        if let Some(factory) = merged.get("jsxFactory").and_then(serde_json::Value::as_str) {
+            self.jsx_pragma = factory.to_string();
+        }
+        if let Some(frag) = merged
+            .get("jsxFragmentFactory")
+            .and_then(serde_json::Value::as_str)
+        {
+            self.jsx_pragma_frag = frag.to_string();
+        }
+        if let Some(source) = merged
+            .get("jsxImportSource")
+            .and_then(serde_json::Value::as_str)
+        {
+            self.jsx_import_source = source.to_string();
+        }
+        if let Some(experimental) = merged
+            .get("experimentalDecorators")
+            .and_then(serde_json::Value::as_bool)
+        {
+            self.decorators_legacy = experimental;
+        }
+
+        self
+    }
+
+    /// Configuration for a development build: development-mode JSX with Fast Refresh
+    /// boundaries registered, for dev servers that want better error locations and
+    /// component-level hot reloading instead of the production transform. Pass
+    /// `refresh` to override Oxc's default Fast Refresh options.
+    pub fn for_development(minify: bool, refresh: Option<ReactRefreshOptions>) -> Self {
+        Self {
+            development: true,
+            refresh,
+            ..Self::for_engine(minify)
+        }
+    }
 }
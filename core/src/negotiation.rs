@@ -0,0 +1,79 @@
+//! `Accept` header content negotiation for [`crate::models::OutputFormat`].
+//!
+//! Each output format historically got its own fixed path (`render_html`,
+//! `render_javascript`, ...), all delegating to the same render pipeline with a
+//! hardcoded format. [`select_output_format`] lets a single endpoint pick the format
+//! instead from a request's standard `Accept` header, for a client that can't vary the
+//! URL per format.
+
+use crate::models::OutputFormat;
+
+/// One `Accept` header media range together with its `q` weight (defaults to `1.0`
+/// when the `;q=` parameter is absent, per RFC 7231 ยง5.3.2).
+struct MediaRange<'a> {
+    media_type: &'a str,
+    q: f32,
+}
+
+/// Parses an `Accept` header value into its media ranges, highest `q` first. Ranges
+/// with equal `q` keep their original relative order (the header's own left-to-right
+/// preference), since `sort_by` is stable.
+fn parse_accept(accept: &str) -> Vec<MediaRange<'_>> {
+    let mut ranges: Vec<MediaRange<'_>> = accept
+        .split(',')
+        .filter_map(|part| {
+            let part = part.trim();
+            if part.is_empty() {
+                return None;
+            }
+            let mut segments = part.split(';');
+            let media_type = segments.next().unwrap_or("").trim();
+            let q = segments
+                .filter_map(|param| {
+                    let param = param.trim();
+                    param.strip_prefix("q=").and_then(|v| v.trim().parse().ok())
+                })
+                .next()
+                .unwrap_or(1.0);
+            Some(MediaRange { media_type, q })
+        })
+        .collect();
+    ranges.sort_by(|a, b| b.q.total_cmp(&a.q));
+    ranges
+}
+
+/// Maps a single media type to the [`OutputFormat`] it selects, if any.
+fn format_for_media_type(media_type: &str) -> Option<OutputFormat> {
+    match media_type {
+        "text/html" | "*/*" => Some(OutputFormat::Html),
+        "application/javascript" | "text/javascript" => Some(OutputFormat::Javascript),
+        "application/json" => Some(OutputFormat::Json),
+        "application/vnd.dinja.schema+json" => Some(OutputFormat::Schema),
+        _ => None,
+    }
+}
+
+/// Picks the [`OutputFormat`] a request's `Accept` header asks for, trying each media
+/// range in descending `q` order and returning the first one a format is defined for.
+/// An absent/empty header falls back to [`OutputFormat::Json`] (note that
+/// [`crate::models::RenderSettings`]'s own default output is [`OutputFormat::Html`]).
+///
+/// Returns `Err(())` only when the header explicitly lists media ranges and none of
+/// them (nor a wildcard) maps to a known format, so the caller can reply
+/// `406 Not Acceptable` instead of silently guessing.
+pub(crate) fn select_output_format(accept: Option<&str>) -> Result<OutputFormat, ()> {
+    let Some(accept) = accept.filter(|value| !value.trim().is_empty()) else {
+        return Ok(OutputFormat::Json);
+    };
+
+    let ranges = parse_accept(accept);
+    if ranges.is_empty() {
+        return Ok(OutputFormat::Json);
+    }
+
+    ranges
+        .iter()
+        .filter(|range| range.q > 0.0)
+        .find_map(|range| format_for_media_type(range.media_type))
+        .ok_or(())
+}
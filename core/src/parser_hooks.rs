@@ -0,0 +1,188 @@
+//! Extension point for pluggable JS expression/ESM parser hooks.
+//!
+//! Today `{context('title')}`-shaped calls are the only part of a document's curly-brace
+//! expressions this crate understands - resolved against [`crate::scripting::LuaUtilsRegistry`]
+//! - and bare `import`/`export` lines are just markdown text. Real MDX expects both to be
+//! real JavaScript grammar, which this crate doesn't carry a JS parser for. Rather than bundle
+//! one in, [`ParserHookRegistry`] lets a host plug one in (e.g. an SWC-backed parser) via
+//! [`crate::service::RenderService::register_expression_parser`]/
+//! [`crate::service::RenderService::register_esm_parser`], in the spirit of the hooks
+//! `markdown-rs` itself exposes for the same problem: each hook receives a candidate
+//! substring and its byte offset into the document and returns a [`ParseSignal`] - complete,
+//! "valid so far but cut off" (the construct may continue on a following line), or a hard
+//! syntax error.
+//!
+//! [`scan_expressions`] and [`scan_esm_blocks`] find the candidate substrings in raw MDX
+//! source; [`validate`] runs a registered hook over all of them, turning the first hard
+//! error into an [`MdxError::JsExprParse`].
+
+use crate::error::MdxError;
+use regex::Regex;
+use std::fmt;
+use std::sync::{Arc, LazyLock};
+
+/// Matches a block of one or more consecutive `import`/`export` lines starting at the
+/// beginning of a line - the same "flow" position `markdown-rs`'s `mdxjsEsm` construct
+/// requires.
+///
+/// # Safety
+/// Pattern is compile-time constant and known to be valid.
+static ESM_BLOCK: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?m)^(?:import|export)\b.*(?:\n(?:[ \t].*|(?:import|export)\b.*))*")
+        .expect("hardcoded regex pattern is valid")
+});
+
+/// The outcome of a [`ParseHook`] call against one candidate substring.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseSignal {
+    /// The substring parses as a complete, valid construct.
+    Ok,
+    /// The substring is a valid prefix that ran out of input before its grammar
+    /// production closed (e.g. an expression whose closing `}` is on a later line) -
+    /// recoverable, in that the construct may continue being fed more text.
+    Eof,
+    /// The substring is invalid, full stop. Surfaces as [`MdxError::JsExprParse`].
+    Error(String),
+}
+
+/// A hook called with a candidate expression or ESM substring (without delimiters) and
+/// its byte offset into the document, returning whether it parses - see
+/// [`crate::parser_hooks`].
+pub trait ParseHook: Fn(&str, usize) -> ParseSignal + Send + Sync {}
+
+impl<F> ParseHook for F where F: Fn(&str, usize) -> ParseSignal + Send + Sync {}
+
+/// Holds at most one expression-parser hook and one ESM-parser hook (see
+/// [`crate::parser_hooks`]). Cheap to clone - each hook is held behind an [`Arc`], so
+/// cloning a [`crate::service::RenderService`] doesn't copy them.
+#[derive(Clone, Default)]
+pub struct ParserHookRegistry {
+    expression: Option<Arc<dyn ParseHook>>,
+    esm: Option<Arc<dyn ParseHook>>,
+}
+
+impl fmt::Debug for ParserHookRegistry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ParserHookRegistry")
+            .field("expression", &self.expression.is_some())
+            .field("esm", &self.esm.is_some())
+            .finish()
+    }
+}
+
+impl ParserHookRegistry {
+    /// Creates an empty registry - neither hook set.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the expression-parser hook, replacing any previously set one.
+    pub fn set_expression_parser(
+        &mut self,
+        hook: impl Fn(&str, usize) -> ParseSignal + Send + Sync + 'static,
+    ) -> &mut Self {
+        self.expression = Some(Arc::new(hook));
+        self
+    }
+
+    /// Sets the ESM-parser hook, replacing any previously set one.
+    pub fn set_esm_parser(
+        &mut self,
+        hook: impl Fn(&str, usize) -> ParseSignal + Send + Sync + 'static,
+    ) -> &mut Self {
+        self.esm = Some(Arc::new(hook));
+        self
+    }
+
+    /// Returns true if neither hook is set.
+    pub fn is_empty(&self) -> bool {
+        self.expression.is_none() && self.esm.is_none()
+    }
+}
+
+/// Finds every top-level `{...}` expression in `source` (balanced-brace span, not the
+/// contents of a backtick-delimited fenced/inline code span), returning each one's inner
+/// text (without the surrounding braces) and its byte offset.
+fn scan_expressions(source: &str) -> Vec<(String, usize)> {
+    let mut expressions = Vec::new();
+    let bytes = source.as_bytes();
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'`' {
+            // Skip a code span/fence: a run of N backticks up to the next run of at
+            // least N. An unclosed run is literal text and is just stepped over.
+            let run = bytes[i..].iter().take_while(|&&b| b == b'`').count();
+            let mut j = i + run;
+            i += run;
+            while j < bytes.len() {
+                let len = bytes[j..].iter().take_while(|&&b| b == b'`').count();
+                if len >= run {
+                    i = j + len;
+                    break;
+                }
+                j += len.max(1);
+            }
+            continue;
+        }
+        if bytes[i] == b'{' {
+            let mut depth = 1;
+            let mut j = i + 1;
+            while j < bytes.len() && depth > 0 {
+                match bytes[j] {
+                    b'{' => depth += 1,
+                    b'}' => depth -= 1,
+                    _ => {}
+                }
+                j += 1;
+            }
+            if depth == 0 {
+                expressions.push((source[i + 1..j - 1].to_string(), i + 1));
+                i = j;
+                continue;
+            }
+        }
+        i += 1;
+    }
+    expressions
+}
+
+/// Finds every ESM `import`/`export` block in `source`, returning each one's text and
+/// its byte offset - see [`ESM_BLOCK`].
+fn scan_esm_blocks(source: &str) -> Vec<(String, usize)> {
+    ESM_BLOCK
+        .find_iter(source)
+        .map(|m| (m.as_str().to_string(), m.start()))
+        .collect()
+}
+
+/// Runs `registry`'s hooks over every `{...}` expression and ESM block found in
+/// `source`, in document order. A hook's [`ParseSignal::Eof`] is treated the same as
+/// `Ok` here - a single-pass validator can't itself feed a hook more text across a
+/// document boundary, so "may still be valid, just needs more input" is accepted rather
+/// than failed. [`ParseSignal::Error`] fails the whole document with
+/// [`MdxError::JsExprParse`], naming the offending substring.
+///
+/// A no-op if neither hook is registered.
+pub(crate) fn validate(source: &str, registry: &ParserHookRegistry) -> Result<(), MdxError> {
+    if let Some(hook) = &registry.expression {
+        for (expr, offset) in scan_expressions(source) {
+            if let ParseSignal::Error(message) = hook(&expr, offset) {
+                return Err(MdxError::JsExprParse(format!(
+                    "invalid expression at byte {offset}: {message}"
+                )));
+            }
+        }
+    }
+
+    if let Some(hook) = &registry.esm {
+        for (block, offset) in scan_esm_blocks(source) {
+            if let ParseSignal::Error(message) = hook(&block, offset) {
+                return Err(MdxError::JsExprParse(format!(
+                    "invalid ESM block at byte {offset}: {message}"
+                )));
+            }
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,122 @@
+//! Recursive expansion of `<Include name="..." />`/`{{> name}}` partial references.
+//!
+//! A partial is a named, pre-registered MDX snippet (see
+//! [`crate::models::NamedMdxBatchInput::partials`]), supplied alongside `components`
+//! rather than through [`crate::models::RenderSettings`] - it's per-request content,
+//! not a service-wide extension point like [`crate::decorators::DecoratorRegistry`] or
+//! [`crate::scripting::LuaDirectiveRegistry`].
+//!
+//! [`expand_includes`] runs on the raw markdown source, before [`crate::mdx::render_markdown`]
+//! and every other markdown/JSX processing step, substituting each include reference
+//! with its partial's source and recursing into the result so an included fragment may
+//! itself include others. Frontmatter/props aren't threaded through here at all: since
+//! expansion happens before the single engine render pass over the whole document, an
+//! included fragment's JSX expressions see the same `props` the host document does,
+//! simply by both having been inlined into the same source before rendering.
+//!
+//! ## Cycle Guard
+//!
+//! Expansion recurses at most [`MAX_INCLUDE_DEPTH`] levels deep - the same style of
+//! fixed bound [`crate::mdx`] uses for JSX nesting - and fails with
+//! [`MdxError::PartialInclude`] rather than overflowing the stack if two partials
+//! include each other (directly or transitively).
+
+use crate::error::MdxError;
+use regex::Regex;
+use std::collections::HashMap;
+use std::sync::LazyLock;
+
+/// Maximum recursion depth for partial includes, mirroring `MAX_JSX_NESTING_DEPTH` in
+/// [`crate::mdx`]. Exceeding it almost always means two partials include each other.
+pub const MAX_INCLUDE_DEPTH: usize = 100;
+
+// Matches `<Include name="header" />`, `<Include name='header'/>`, and the
+// `<Include name="header"></Include>` long form (any content between the tags is
+// dropped, since an include reference has no children of its own).
+static INCLUDE_TAG_PATTERN: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"(?is)<Include\s+name\s*=\s*["']([^"']+)["']\s*(?:/>|>(?:.*?</Include>)?)"#)
+        .expect("hardcoded regex pattern is valid")
+});
+
+// Matches the Handlebars-style `{{> name}}` partial token.
+static INCLUDE_MUSTACHE_PATTERN: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"\{\{>\s*([^\s}]+)\s*\}\}").expect("hardcoded regex pattern is valid")
+});
+
+/// Expands every `<Include name="..." />`/`{{> name}}` reference in `content` against
+/// `partials`, recursively, returning the fully-expanded source.
+///
+/// # Errors
+/// Returns [`MdxError::PartialInclude`] if a reference names a partial absent from
+/// `partials`, or if expansion recurses past [`MAX_INCLUDE_DEPTH`].
+pub(crate) fn expand_includes(
+    content: &str,
+    partials: &HashMap<String, String>,
+) -> Result<String, MdxError> {
+    expand_includes_at_depth(content, partials, 0)
+}
+
+fn expand_includes_at_depth(
+    content: &str,
+    partials: &HashMap<String, String>,
+    depth: usize,
+) -> Result<String, MdxError> {
+    if depth > MAX_INCLUDE_DEPTH {
+        return Err(MdxError::PartialInclude(format!(
+            "Partial include nesting exceeded {MAX_INCLUDE_DEPTH} levels - likely an include cycle"
+        )));
+    }
+
+    if !INCLUDE_TAG_PATTERN.is_match(content) && !INCLUDE_MUSTACHE_PATTERN.is_match(content) {
+        return Ok(content.to_string());
+    }
+
+    let mut expansion_error = None;
+    let expanded = INCLUDE_TAG_PATTERN.replace_all(content, |caps: &regex::Captures| {
+        if expansion_error.is_some() {
+            return String::new();
+        }
+        match resolve_partial(&caps[1], partials, depth) {
+            Ok(resolved) => resolved,
+            Err(err) => {
+                expansion_error = Some(err);
+                String::new()
+            }
+        }
+    });
+    if let Some(err) = expansion_error {
+        return Err(err);
+    }
+
+    let mut expansion_error = None;
+    let expanded = INCLUDE_MUSTACHE_PATTERN.replace_all(&expanded, |caps: &regex::Captures| {
+        if expansion_error.is_some() {
+            return String::new();
+        }
+        match resolve_partial(&caps[1], partials, depth) {
+            Ok(resolved) => resolved,
+            Err(err) => {
+                expansion_error = Some(err);
+                String::new()
+            }
+        }
+    });
+    if let Some(err) = expansion_error {
+        return Err(err);
+    }
+
+    Ok(expanded.into_owned())
+}
+
+fn resolve_partial(
+    name: &str,
+    partials: &HashMap<String, String>,
+    depth: usize,
+) -> Result<String, MdxError> {
+    let source = partials.get(name).ok_or_else(|| {
+        MdxError::PartialInclude(format!(
+            "Unknown partial '{name}' - no matching entry in the partial registry"
+        ))
+    })?;
+    expand_includes_at_depth(source, partials, depth + 1)
+}
@@ -0,0 +1,191 @@
+//! Process-wide cache of whole-file [`RenderedMdx`] results, keyed by content hash.
+//!
+//! This sits alongside the two existing cache tiers but fills a different gap:
+//!
+//! - [`crate::transform_cache`] is always-on and caches only the TSX-to-JS transform
+//!   step, not the full render.
+//! - [`crate::batch_cache`] caches full [`RenderedMdx`] results too, but is opt-in
+//!   *per [`crate::service::RenderService`]* (via
+//!   [`crate::service::RenderService::with_cache`]) and keys on a SHA-512 digest -
+//!   appropriate for a cache a caller explicitly owns and clears.
+//!
+//! This cache is opt-in *per render* instead, via [`RenderSettings::render_cache`], for
+//! callers of [`crate::mdx::mdx_to_html_with_frontmatter`] directly (outside
+//! `RenderService`) who want repeated calls over unchanged content to skip the pipeline
+//! without wiring up a `RenderService` cache themselves. Like
+//! [`crate::transform_cache`], it hashes its key with [`DefaultHasher`] rather than a
+//! cryptographic hash - the key only needs to identify "have I rendered this exact
+//! input before", not resist deliberate collision.
+//!
+//! ## Eviction
+//!
+//! Bounded at [`DEFAULT_CAPACITY`] entries (overridable with [`set_capacity`]), evicted
+//! **LRU** rather than FIFO: a lookup hit moves its key to the back of the recency
+//! queue, so a document re-rendered often survives even if many other documents are
+//! rendered in between. The two FIFO caches above don't need this because their keys
+//! are either process-lifetime-stable inputs ([`crate::transform_cache`], shared
+//! component code) or bounded by a caller-owned capacity the caller already sized to
+//! its working set ([`crate::batch_cache`]); this cache's callers don't control batch
+//! size, so true recency tracking is worth the extra bookkeeping.
+
+use crate::error::MdxError;
+use crate::models::{ComponentDefinition, RenderSettings, RenderedMdx};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, VecDeque};
+use std::hash::{Hash, Hasher};
+use std::sync::{Mutex, OnceLock};
+
+/// Default number of distinct render results retained before the least-recently-used
+/// entry is evicted. Overridable at runtime with [`set_capacity`].
+const DEFAULT_CAPACITY: usize = 256;
+
+/// Computes the cache key for one render: the MDX source, the component definitions it
+/// references (name-sorted so reference order can't change the key), the registered
+/// partials (also name-sorted - unlike components, a caller can't tell in advance which
+/// ones `mdx_content` transitively includes, so the whole registry is hashed), and the
+/// active render settings - serialized to JSON, the same representation used on the
+/// wire. Settings fields that are not serialized (the `#[serde(skip)]` registries and
+/// `external_html_root`) are not part of the key, so two renders that differ only in
+/// those share one cache entry.
+pub(crate) fn cache_key(
+    mdx_content: &str,
+    referenced_components: &[(&str, &ComponentDefinition)],
+    partials: Option<&HashMap<String, String>>,
+    settings: &RenderSettings,
+) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    mdx_content.hash(&mut hasher);
+
+    let mut sorted = referenced_components.to_vec();
+    sorted.sort_by_key(|(name, _)| *name);
+    for (name, component) in sorted {
+        name.hash(&mut hasher);
+        if let Ok(json) = serde_json::to_vec(component) {
+            json.hash(&mut hasher);
+        }
+    }
+
+    if let Some(partials) = partials {
+        let mut sorted: Vec<(&String, &String)> = partials.iter().collect();
+        sorted.sort_by_key(|(name, _)| *name);
+        sorted.hash(&mut hasher);
+    }
+
+    if let Ok(json) = serde_json::to_vec(settings) {
+        json.hash(&mut hasher);
+    }
+
+    hasher.finish()
+}
+
+struct CacheState {
+    map: HashMap<u64, RenderedMdx>,
+    /// Recency order, least-recently-used first; a hit moves its key to the back.
+    order: VecDeque<u64>,
+    capacity: usize,
+}
+
+impl CacheState {
+    fn touch(&mut self, key: u64) {
+        if let Some(pos) = self.order.iter().position(|k| *k == key) {
+            self.order.remove(pos);
+        }
+        self.order.push_back(key);
+    }
+
+    fn evict_to_capacity(&mut self) {
+        while self.order.len() > self.capacity {
+            if let Some(oldest) = self.order.pop_front() {
+                self.map.remove(&oldest);
+            }
+        }
+    }
+}
+
+struct RenderCache {
+    state: Mutex<CacheState>,
+}
+
+impl RenderCache {
+    fn new(capacity: usize) -> Self {
+        Self {
+            state: Mutex::new(CacheState {
+                map: HashMap::new(),
+                order: VecDeque::new(),
+                capacity: capacity.max(1),
+            }),
+        }
+    }
+
+    fn get_or_insert_with(
+        &self,
+        key: u64,
+        compute: impl FnOnce() -> Result<RenderedMdx, MdxError>,
+    ) -> Result<RenderedMdx, MdxError> {
+        {
+            let mut state = self.state.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+            if let Some(cached) = state.map.get(&key).cloned() {
+                state.touch(key);
+                return Ok(cached);
+            }
+        }
+
+        let result = compute()?;
+
+        let mut state = self.state.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        state.map.insert(key, result.clone());
+        state.touch(key);
+        state.evict_to_capacity();
+
+        Ok(result)
+    }
+
+    fn set_capacity(&self, capacity: usize) {
+        let mut state = self.state.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        state.capacity = capacity.max(1);
+        state.evict_to_capacity();
+    }
+
+    fn len(&self) -> usize {
+        let state = self.state.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        state.map.len()
+    }
+
+    fn clear(&self) {
+        let mut state = self.state.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        state.map.clear();
+        state.order.clear();
+    }
+}
+
+static RENDER_CACHE: OnceLock<RenderCache> = OnceLock::new();
+
+fn cache() -> &'static RenderCache {
+    RENDER_CACHE.get_or_init(|| RenderCache::new(DEFAULT_CAPACITY))
+}
+
+/// Returns the cached render result for `key`, computing and storing it via `compute`
+/// on a cache miss. Shared across all threads in the process.
+pub(crate) fn get_or_insert_with(
+    key: u64,
+    compute: impl FnOnce() -> Result<RenderedMdx, MdxError>,
+) -> Result<RenderedMdx, MdxError> {
+    cache().get_or_insert_with(key, compute)
+}
+
+/// Sets the maximum number of render results retained, evicting least-recently-used
+/// entries immediately if the cache is currently over the new limit. Clamped to at
+/// least one.
+pub fn set_capacity(capacity: usize) {
+    cache().set_capacity(capacity);
+}
+
+/// Number of render results currently cached.
+pub fn len() -> usize {
+    cache().len()
+}
+
+/// Discards every cached render result.
+pub fn clear_cache() {
+    cache().clear();
+}
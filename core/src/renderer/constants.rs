@@ -5,7 +5,24 @@ pub(super) mod script_tags {
     pub const SETUP_CONTEXT: &str = "<setup_context>";
     pub const CLEANUP_RUNTIME: &str = "<cleanup_runtime>";
     pub const SETUP: &str = "<setup>";
+    /// Tag for the capability-trap script [`super::JsRenderer::apply_permissions`]
+    /// (via [`super::scripts::build_permissions_script`]) runs before a component is
+    /// rendered.
+    pub const PERMISSIONS: &str = "<permissions>";
+    /// Tag for the coverage-instrumentation script
+    /// [`super::JsRenderer::start_coverage`] (via
+    /// [`super::scripts::coverage_init_script`]) runs before a batch renders, and the
+    /// tag for the readback script [`super::JsRenderer::collect_coverage`] runs once
+    /// the batch is done.
+    pub const COVERAGE: &str = "<coverage>";
+    /// Tag for the script [`super::JsRenderer::evaluate_snippet`] runs for each
+    /// [`crate::models::RenderSettings::doctest`] code block it evaluates.
+    pub const DOCTEST: &str = "<doctest>";
     pub const RENDER: &str = "<render>";
+    /// Tag for the static render-finish script executed right after `RENDER`
+    /// resolves the target component - kept separate so a V8 stack frame naming it
+    /// can't be confused with one from the dynamic resolution script.
+    pub const RENDER_FINISH: &str = "<render_finish>";
     pub const HELPERS: &str = "<helpers>";
     pub const ENGINE: &str = "<engine>";
     pub const CHECK_ENGINE: &str = "<check_engine>";
@@ -4,12 +4,16 @@
 //! and initializing custom engines.
 
 use crate::error::MdxError;
+use crate::models::ComponentDefinition;
 use anyhow::{Context, Result as AnyhowResult};
-use deno_core::JsRuntime;
+use deno_core::{JsRuntime, RuntimeOptions};
+use sha2::{Digest as _, Sha256};
+use std::collections::HashMap;
 use std::fs;
 use std::path::Path;
 
 use super::constants::{script_tags, static_files};
+use super::scripts::{build_component_registration_script, RegistrationMode};
 
 /// Sets up global JavaScript objects needed by the libraries
 pub(super) fn setup_globals(runtime: &mut JsRuntime) -> Result<(), MdxError> {
@@ -25,19 +29,31 @@ pub(super) fn setup_globals(runtime: &mut JsRuntime) -> Result<(), MdxError> {
             globalThis.self = globalThis;
         }
         
-        // Add minimal timer functions for engine
+        // Real timers backed by `op_dinja_set_timeout` (see `renderer::timers`): the
+        // delay is awaited on the Rust side via `tokio::time::sleep`, with the
+        // callback chained onto the op's promise, so a component awaiting a
+        // `setTimeout`-based delay resolves once `rt.run_event_loop(...)` drives that
+        // promise to completion instead of hanging forever.
         if (typeof setTimeout === 'undefined') {
+            globalThis.__dinjaNextTimerId = 1;
+            globalThis.__dinjaCancelledTimers = new Set();
             globalThis.setTimeout = function(fn, delay) {
-                // For SSR, execute immediately (we don't need real timers)
-                if (delay === 0 || delay === undefined) {
-                    fn();
-                }
-                return 0;
+                const id = globalThis.__dinjaNextTimerId++;
+                Deno.core.ops.op_dinja_set_timeout(delay || 0).then(function() {
+                    if (!globalThis.__dinjaCancelledTimers.has(id)) {
+                        fn();
+                    }
+                });
+                return id;
             };
         }
         if (typeof clearTimeout === 'undefined') {
-            globalThis.clearTimeout = function() {};
+            globalThis.clearTimeout = function(id) {
+                globalThis.__dinjaCancelledTimers.add(id);
+            };
         }
+        // setInterval/requestAnimationFrame stay no-ops: a recurring timer has no
+        // natural end for a render that produces one HTML string and stops.
         if (typeof setInterval === 'undefined') {
             globalThis.setInterval = function(fn, delay) {
                 return 0;
@@ -164,7 +180,20 @@ pub(super) fn load_js_file(
     let code = fs::read_to_string(&file_path)
         .with_context(|| format!("Failed to read {}", file_path.display()))?;
 
-    let wrapped_code = wrap_js_code(&code, file_name);
+    execute_js_source(runtime, &code, file_name, script_tag)
+}
+
+/// Wraps and executes already-in-memory JavaScript source as one of the static engine
+/// files, the same way [`load_js_file`] does for source read from disk - so a caller
+/// that already has the engine's JavaScript embedded as a string constant (e.g. via
+/// `include_str!`) can load it without writing it to a file first.
+fn execute_js_source(
+    runtime: &mut JsRuntime,
+    code: &str,
+    file_name: &str,
+    script_tag: &'static str,
+) -> AnyhowResult<()> {
+    let wrapped_code = wrap_js_code(code, file_name);
 
     runtime
         .execute_script(script_tag, wrapped_code)
@@ -198,13 +227,18 @@ pub(super) fn load_engine_library(
     runtime: &mut JsRuntime,
     static_path: &Path,
 ) -> Result<(), MdxError> {
-    load_js_file(
-        runtime,
-        static_path,
-        static_files::ENGINE_MIN_JS,
-        script_tags::ENGINE,
-    )
-    .map_err(|e| MdxError::TsxTransform(format!("Failed to load engine: {e:?}")))?;
+    let file_path = static_path.join(static_files::ENGINE_MIN_JS);
+    let code = fs::read_to_string(&file_path)
+        .map_err(|e| MdxError::TsxTransform(format!("Failed to read {}: {e}", file_path.display())))?;
+
+    load_engine_library_from_source(runtime, &code)
+}
+
+/// Same as [`load_engine_library`], but takes the engine source directly instead of
+/// reading it from `static_path` - see [`load_static_files_from_sources`].
+fn load_engine_library_from_source(runtime: &mut JsRuntime, engine_min_js: &str) -> Result<(), MdxError> {
+    execute_js_source(runtime, engine_min_js, static_files::ENGINE_MIN_JS, script_tags::ENGINE)
+        .map_err(|e| MdxError::TsxTransform(format!("Failed to load engine: {e:?}")))?;
 
     verify_global_var(
         runtime,
@@ -302,9 +336,22 @@ pub(super) fn load_engine_render_library(
     runtime: &mut JsRuntime,
     static_path: &Path,
 ) -> Result<(), MdxError> {
-    load_js_file(
+    let file_path = static_path.join(static_files::ENGINE_TO_STRING_MIN_JS);
+    let code = fs::read_to_string(&file_path)
+        .map_err(|e| MdxError::TsxTransform(format!("Failed to read {}: {e}", file_path.display())))?;
+
+    load_engine_render_library_from_source(runtime, &code)
+}
+
+/// Same as [`load_engine_render_library`], but takes the source directly instead of
+/// reading it from `static_path` - see [`load_static_files_from_sources`].
+fn load_engine_render_library_from_source(
+    runtime: &mut JsRuntime,
+    engine_to_string_min_js: &str,
+) -> Result<(), MdxError> {
+    execute_js_source(
         runtime,
-        static_path,
+        engine_to_string_min_js,
         static_files::ENGINE_TO_STRING_MIN_JS,
         script_tags::ENGINE_TO_STRING,
     )
@@ -328,13 +375,18 @@ pub(super) fn load_core_engine_library(
     runtime: &mut JsRuntime,
     static_path: &Path,
 ) -> Result<(), MdxError> {
-    load_js_file(
-        runtime,
-        static_path,
-        static_files::CORE_JS,
-        script_tags::CORE_ENGINE,
-    )
-    .map_err(|e| MdxError::TsxTransform(format!("Failed to load core.js: {e:?}")))?;
+    let file_path = static_path.join(static_files::CORE_JS);
+    let code = fs::read_to_string(&file_path)
+        .map_err(|e| MdxError::TsxTransform(format!("Failed to read {}: {e}", file_path.display())))?;
+
+    load_core_engine_library_from_source(runtime, &code)
+}
+
+/// Same as [`load_core_engine_library`], but takes the source directly instead of
+/// reading it from `static_path` - see [`load_static_files_from_sources`].
+fn load_core_engine_library_from_source(runtime: &mut JsRuntime, core_js: &str) -> Result<(), MdxError> {
+    execute_js_source(runtime, core_js, static_files::CORE_JS, script_tags::CORE_ENGINE)
+        .map_err(|e| MdxError::TsxTransform(format!("Failed to load core.js: {e:?}")))?;
 
     verify_global_var(
         runtime,
@@ -368,7 +420,46 @@ pub(super) fn load_static_files_internal(
     load_engine_library(runtime, static_path).map_err(anyhow::Error::from)?;
     load_engine_render_library(runtime, static_path).map_err(anyhow::Error::from)?;
 
-    // Save preact engine reference before loading core.js (which overwrites engine)
+    save_preact_engine_reference(runtime)?;
+
+    // Load core.js engine (used for schema rendering, overwrites engine variable)
+    load_core_engine_library(runtime, static_path).map_err(anyhow::Error::from)?;
+
+    setup_dual_engines(runtime)?;
+
+    Ok(())
+}
+
+/// Same loading sequence as [`load_static_files_internal`], but takes each static
+/// file's source as an in-memory string instead of reading it from a directory.
+///
+/// Lets a caller that already embeds the engine's JavaScript as string constants
+/// (e.g. via `include_str!`, as the napi binding does) build a renderer or a startup
+/// snapshot without first writing those strings to a temporary directory just so
+/// [`load_static_files_internal`] can read them back.
+pub(super) fn load_static_files_from_sources(
+    runtime: &mut JsRuntime,
+    engine_min_js: &str,
+    engine_to_string_min_js: &str,
+    core_js: &str,
+) -> AnyhowResult<()> {
+    setup_globals(runtime)?;
+
+    load_engine_library_from_source(runtime, engine_min_js).map_err(anyhow::Error::from)?;
+    load_engine_render_library_from_source(runtime, engine_to_string_min_js).map_err(anyhow::Error::from)?;
+
+    save_preact_engine_reference(runtime)?;
+
+    load_core_engine_library_from_source(runtime, core_js).map_err(anyhow::Error::from)?;
+
+    setup_dual_engines(runtime)?;
+
+    Ok(())
+}
+
+/// Saves the just-loaded preact `engine`/`engine_to_string` globals aside before
+/// `core.js` overwrites them, so [`setup_dual_engines`] can restore them afterward.
+fn save_preact_engine_reference(runtime: &mut JsRuntime) -> AnyhowResult<()> {
     const SAVE_PREACT_ENGINE: &str = r#"
         if (typeof engine !== 'undefined') {
             globalThis.__preactEngine = engine;
@@ -382,11 +473,13 @@ pub(super) fn load_static_files_internal(
                 "Failed to save preact engine reference: {e:?}"
             )))
         })?;
+    Ok(())
+}
 
-    // Load core.js engine (used for schema rendering, overwrites engine variable)
-    load_core_engine_library(runtime, static_path).map_err(anyhow::Error::from)?;
-
-    // Save core engine and restore preact engine
+/// Saves `core.js`'s `engine` as `coreEngine` (used for schema rendering) and restores
+/// the preact `engine`/`engine_to_string` globals saved by
+/// [`save_preact_engine_reference`] (used for HTML/JavaScript rendering).
+fn setup_dual_engines(runtime: &mut JsRuntime) -> AnyhowResult<()> {
     const SETUP_DUAL_ENGINES: &str = r#"
         // Save core.js engine as coreEngine for schema rendering
         if (typeof engine !== 'undefined' && engine.render) {
@@ -407,6 +500,101 @@ pub(super) fn load_static_files_internal(
                 "Failed to setup dual engines: {e:?}"
             )))
         })?;
-
     Ok(())
 }
+
+/// Hashes the content of every static engine file [`load_static_files_internal`]
+/// reads from `static_dir` (`engine.min.js`, `engine_to_string.min.js`, `core.js`),
+/// for keying a disk-cached snapshot blob against the files that produced it - see
+/// [`super::pool::RendererPool::with_snapshot_cache_dir`]. Changing any one of these
+/// files changes the digest, so a stale cached blob built from an older version is
+/// naturally treated as a miss rather than served.
+pub(super) fn hash_static_files(static_dir: impl AsRef<Path>) -> AnyhowResult<String> {
+    let dir = static_dir.as_ref();
+    let mut hasher = Sha256::new();
+    for name in [
+        static_files::ENGINE_MIN_JS,
+        static_files::ENGINE_TO_STRING_MIN_JS,
+        static_files::CORE_JS,
+    ] {
+        let path = dir.join(name);
+        let bytes = fs::read(&path)
+            .with_context(|| format!("Failed to read {} for snapshot cache key", path.display()))?;
+        hasher.update(&bytes);
+    }
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+/// Builds a V8 startup snapshot with the engine fully initialized.
+///
+/// Creating a `JsRenderer` normally means compiling and executing `engine.min.js`,
+/// `engine_to_string.min.js`, and `core.js` from scratch every time
+/// (see [`load_static_files_internal`]). A startup snapshot captures the heap after
+/// that work is done, so a fresh isolate can be deserialized from the blob instead
+/// of re-running the JavaScript, turning renderer creation into "mmap a blob".
+///
+/// The returned bytes are a plain `Box<[u8]>` (no interior `JsRuntime` state), so
+/// callers are free to wrap them in an `Arc` to share across threads.
+pub(super) fn build_engine_snapshot(static_dir: impl AsRef<Path>) -> AnyhowResult<Box<[u8]>> {
+    let mut runtime = JsRuntime::new(RuntimeOptions {
+        will_snapshot: true,
+        extensions: vec![super::timers::dinja_timers::init_ops()],
+        ..Default::default()
+    });
+
+    load_static_files_internal(&mut runtime, static_dir)?;
+
+    let snapshot = runtime.snapshot();
+    Ok(Box::from(&snapshot[..]))
+}
+
+/// Builds a V8 startup snapshot with the engine initialized and `components`
+/// pre-registered as globals.
+///
+/// Mirrors [`build_engine_snapshot`], but also runs the same registration script
+/// [`build_component_registration_script`] would build for a cold render, once, in
+/// the builder runtime before snapshotting - so every renderer deserialized from the
+/// result already has each component resolved and assigned to `globalThis`, and
+/// rendering only needs [`super::scripts::wrap_transformed_component`] (with an empty
+/// bootstrap) plus [`super::scripts::build_render_script_wrapper`], not a fresh
+/// registration run.
+///
+/// The registry's tracking array (`__registered_component_names`, consulted by
+/// [`super::runtime::cleanup_runtime`] to undo per-render registration) is reset to
+/// empty before snapshotting: these components are baked into every renderer booted
+/// from this snapshot for as long as the profile's cached, not registered fresh each
+/// render, so per-render cleanup must never delete them.
+pub(super) fn build_component_snapshot(
+    static_dir: impl AsRef<Path>,
+    components: &HashMap<String, ComponentDefinition>,
+    mode: RegistrationMode,
+) -> AnyhowResult<Box<[u8]>> {
+    let mut runtime = JsRuntime::new(RuntimeOptions {
+        will_snapshot: true,
+        extensions: vec![super::timers::dinja_timers::init_ops()],
+        ..Default::default()
+    });
+
+    load_static_files_internal(&mut runtime, static_dir)?;
+
+    let registration_script =
+        build_component_registration_script(components, mode).map_err(anyhow::Error::from)?;
+
+    if !registration_script.trim().is_empty() {
+        runtime.execute_script(script_tags::SETUP, registration_script).map_err(|e| {
+            anyhow::anyhow!("Failed to bake component registration into snapshot: {e:?}")
+        })?;
+    }
+
+    const RESET_REGISTRATION_TRACKING: &str = r#"
+        if (Array.isArray(globalThis.__registered_component_names)) {
+            globalThis.__registered_component_names.length = 0;
+        }
+    "#;
+    runtime
+        .execute_script(script_tags::SETUP, RESET_REGISTRATION_TRACKING)
+        .map_err(|e| anyhow::anyhow!("Failed to reset component registry tracking: {e:?}"))?;
+
+    let snapshot = runtime.snapshot();
+    Ok(Box::from(&snapshot[..]))
+}
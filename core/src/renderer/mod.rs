@@ -12,6 +12,7 @@
 //! - **`scripts`**: JavaScript code generation for component rendering
 //! - **`engine`**: Static file loading and engine initialization
 //! - **`constants`**: Script tags and constants for runtime operations
+//! - **`module_loader`**: In-memory `deno_core::ModuleLoader` for ES module components
 //!
 //! ## Thread Safety
 //!
@@ -37,32 +38,170 @@
 //! # Ok(())
 //! # }
 //! ```
+//!
+//! ## Streaming Output
+//!
+//! The `_to_writer` variants (e.g. [`JsRenderer::render_component_to_writer`]) write
+//! rendered bytes into a caller-provided `std::io::Write` instead of returning them.
+//! Each render still produces its output as a `String` first, which is then copied
+//! into the writer. Pair them with [`Buffer`], pre-sized using
+//! [`pool::RendererPool`]'s per-profile size hint, to reuse one output buffer across
+//! every file in a large batch.
+//!
+//! ## Async Rendering
+//!
+//! The synchronous render methods extract their result as soon as `execute_script`
+//! returns, so a component that returns a Promise - e.g. one using top-level `await`
+//! to fetch data during SSR - yields an unresolved `[object Promise]` rather than
+//! its eventual value. The `_async` variants (e.g.
+//! [`JsRenderer::render_component_async`]) drive the runtime's event loop to
+//! completion and resolve the result first. Because `JsRuntime` is `!Send`, await
+//! them from a single-threaded executor (e.g. a per-thread
+//! `tokio::runtime::Builder::new_current_thread`) rather than a multi-threaded one.
+//!
+//! ## ES Modules
+//!
+//! [`JsRenderer::render_transformed_component`] and its siblings flatten every
+//! component into one wrapped classic script via
+//! [`scripts::component_bootstrap_script`], so components can't `import`/`export`
+//! between each other. [`JsRenderer::render_module_component_async`] instead loads
+//! the entry component - and whatever it imports, directly or transitively - as a
+//! real ES module graph through a [`module_loader::ComponentModuleLoader`], so
+//! component authors can write idiomatic multi-file trees and share helper modules.
+//! Like the other `_async` methods, it must run to completion before its result is
+//! valid, since `deno_core` only supports loading and evaluating modules
+//! asynchronously.
 
 mod constants;
 mod engine;
+mod module_loader;
 pub mod pool;
+mod registration_cache;
 mod runtime;
 mod scripts;
+mod source_map;
+mod timers;
 
-pub use pool::{RendererPool, RendererProfile};
+pub use pool::{PoolKeyStats, PoolStats, RendererPool, RendererProfile};
+pub use scripts::{RegistrationMode, PERMISSION_DENIED_PREFIX};
 
-use crate::error::MdxError;
 use crate::models::ComponentDefinition;
 use anyhow::Result as AnyhowResult;
-use deno_core::{JsRuntime, RuntimeOptions};
+use deno_core::{JsRuntime, RuntimeOptions, Snapshot};
 use std::cell::RefCell;
 use std::collections::HashMap;
+use std::io::Write;
 use std::path::Path;
 use std::rc::Rc;
+use std::sync::Arc;
 
 use constants::script_tags;
-use engine::load_static_files_internal;
-use runtime::{extract_string_from_v8, setup_context, with_runtime};
+use engine::{load_static_files_from_sources, load_static_files_internal};
+use module_loader::ComponentModuleLoader;
+use runtime::{
+    execute_and_extract_async, execute_module_and_extract_async, extract_string_from_v8,
+    extract_value_from_v8, setup_context, translate_execution_error, with_runtime,
+};
 use scripts::{
-    component_bootstrap_script, component_render_script, schema_render_script,
-    wrap_transformed_component,
+    build_component_registration_script, build_permissions_script, component_bootstrap_script,
+    component_render_script, coverage_init_script, schema_render_script, wrap_transformed_component,
+    COVERAGE_COLLECT_SCRIPT, MODULE_RENDER_RESULT_SCRIPT, RENDER_COMPONENT_FINISH_SCRIPT,
+    RENDER_SCHEMA_FINISH_SCRIPT,
 };
 
+/// Extracts each component's registration name (its `name` field, defaulting to its
+/// map key) - the identifier the component's registered-globals code can be referenced
+/// by once registered, either per-render or baked into a snapshot.
+fn component_names(components: Option<&HashMap<String, ComponentDefinition>>) -> Vec<String> {
+    components
+        .map(|comp_map| {
+            comp_map
+                .iter()
+                .map(|(key, comp_def)| {
+                    comp_def
+                        .name
+                        .as_ref()
+                        .cloned()
+                        .unwrap_or_else(|| key.clone())
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Reusable, growable output buffer for streaming render results.
+///
+/// Wraps a `Vec<u8>` and implements `std::io::Write`. It exists so callers can
+/// pre-`reserve` capacity (e.g. from [`pool::RendererPool`]'s per-profile size
+/// hint) once and reuse the same allocation across many renders in a batch job,
+/// rather than allocating a fresh `String` per file.
+///
+/// Once bytes are written they are never rewound - partial output on a failed
+/// render is still valid UTF-8 up to that point, but callers that need to discard
+/// it should start a new `Buffer` rather than attempt to roll one back.
+#[derive(Default)]
+pub struct Buffer(Vec<u8>);
+
+impl Buffer {
+    /// Creates an empty buffer.
+    pub fn new() -> Self {
+        Self(Vec::new())
+    }
+
+    /// Creates an empty buffer with at least `capacity` bytes pre-reserved.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self(Vec::with_capacity(capacity))
+    }
+
+    /// Reserves capacity for at least `additional` more bytes.
+    pub fn reserve(&mut self, additional: usize) {
+        self.0.reserve(additional);
+    }
+
+    /// Clears the buffer, keeping its allocated capacity for reuse.
+    pub fn clear(&mut self) {
+        self.0.clear();
+    }
+
+    /// Returns the number of bytes currently written.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns `true` if no bytes have been written.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Returns the written bytes.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+
+    /// Consumes the buffer, returning its contents as a `String`.
+    ///
+    /// # Errors
+    /// Returns an error if the written bytes are not valid UTF-8, which should
+    /// never happen for renderer output (always HTML, JavaScript, or JSON text).
+    pub fn into_string(self) -> Result<String, std::string::FromUtf8Error> {
+        String::from_utf8(self.0)
+    }
+}
+
+impl Write for Buffer {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.write(buf)
+    }
+
+    fn write_all(&mut self, buf: &[u8]) -> std::io::Result<()> {
+        self.0.write_all(buf)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
 /// A renderer that manages a Deno Core runtime with engine libraries loaded
 ///
 /// Note: Uses `Rc<RefCell<JsRuntime>>` instead of `Arc<Mutex<JsRuntime>>` because
@@ -70,22 +209,64 @@ use scripts::{
 /// Each request handler creates its own renderer instance.
 pub struct JsRenderer {
     runtime: Rc<RefCell<JsRuntime>>,
+    /// Installed on the runtime at construction (`deno_core` only accepts a
+    /// `ModuleLoader` at `JsRuntime::new` time); its component map and entry source
+    /// are swapped in right before each [`JsRenderer::render_module_component_async`]
+    /// call rather than rebuilding the runtime per render.
+    module_loader: Rc<ComponentModuleLoader>,
 }
 
 impl JsRenderer {
     fn create_with_engine(static_dir: impl AsRef<Path>) -> AnyhowResult<Self> {
-        let mut runtime = JsRuntime::new(RuntimeOptions::default());
+        let module_loader = ComponentModuleLoader::new(Some(static_dir.as_ref().to_path_buf()));
+        let mut runtime = JsRuntime::new(RuntimeOptions {
+            module_loader: Some(Rc::clone(&module_loader)),
+            extensions: vec![timers::dinja_timers::init_ops()],
+            ..Default::default()
+        });
 
         // Load static JavaScript files into the context
         load_static_files_internal(&mut runtime, static_dir)?;
 
         let renderer = Self {
             runtime: Rc::new(RefCell::new(runtime)),
+            module_loader,
         };
 
         Ok(renderer)
     }
 
+    /// Creates a renderer from a pre-built V8 startup snapshot, skipping the
+    /// compile-and-run step for `engine.min.js`, `engine_to_string.min.js`, and
+    /// `core.js`.
+    ///
+    /// The snapshot bytes are cheap to clone into the `Box<[u8]>` the underlying
+    /// `JsRuntime` expects; deserializing that blob is still far cheaper than
+    /// re-parsing and re-executing the static libraries from scratch.
+    ///
+    /// `static_dir` is the same directory the snapshot was built from; a module
+    /// render still reads shared, non-component modules off it directly (see
+    /// [`module_loader::ComponentModuleLoader`]), since those aren't baked into the
+    /// snapshot.
+    pub(crate) fn create_from_snapshot(
+        snapshot: &Arc<[u8]>,
+        static_dir: impl AsRef<Path>,
+    ) -> AnyhowResult<Self> {
+        let snapshot_bytes: Box<[u8]> = snapshot.as_ref().into();
+        let module_loader = ComponentModuleLoader::new(Some(static_dir.as_ref().to_path_buf()));
+        let runtime = JsRuntime::new(RuntimeOptions {
+            startup_snapshot: Some(Snapshot::Boxed(snapshot_bytes)),
+            module_loader: Some(Rc::clone(&module_loader)),
+            extensions: vec![timers::dinja_timers::init_ops()],
+            ..Default::default()
+        });
+
+        Ok(Self {
+            runtime: Rc::new(RefCell::new(runtime)),
+            module_loader,
+        })
+    }
+
     /// Creates a new renderer instance and loads the static JavaScript files
     ///
     /// # Arguments
@@ -97,6 +278,41 @@ impl JsRenderer {
         Self::create_with_engine(static_dir)
     }
 
+    /// Creates a new renderer instance from the engine's JavaScript source already
+    /// held in memory, instead of reading it from a `static_dir` on disk.
+    ///
+    /// For a caller that embeds `engine.min.js`, `engine_to_string.min.js`, and
+    /// `core.js` as string constants (e.g. via `include_str!`), this skips writing
+    /// them to a temporary directory just to have [`JsRenderer::new`] read them back.
+    ///
+    /// Not currently wired into the napi binding: it still needs a real `static_dir`
+    /// on disk regardless, since `ComponentModuleLoader`'s `static_dir` fallback
+    /// resolves a component's shared-module imports (e.g. `helpers.js`) against an
+    /// actual directory, not an in-memory source map - so this alone wouldn't remove
+    /// its temp-directory write. What that binding's cold start actually pays for is
+    /// re-executing these scripts into a fresh V8 heap, which
+    /// `RenderServiceConfig::snapshot_cache_dir` addresses by persisting the built
+    /// startup snapshot across process restarts instead.
+    pub fn from_sources(
+        engine_min_js: &str,
+        engine_to_string_min_js: &str,
+        core_js: &str,
+    ) -> AnyhowResult<Self> {
+        let module_loader = ComponentModuleLoader::new(None);
+        let mut runtime = JsRuntime::new(RuntimeOptions {
+            module_loader: Some(Rc::clone(&module_loader)),
+            extensions: vec![timers::dinja_timers::init_ops()],
+            ..Default::default()
+        });
+
+        load_static_files_from_sources(&mut runtime, engine_min_js, engine_to_string_min_js, core_js)?;
+
+        Ok(Self {
+            runtime: Rc::new(RefCell::new(runtime)),
+            module_loader,
+        })
+    }
+
     /// Renders a JavaScript component to HTML string
     ///
     /// # Arguments
@@ -110,21 +326,41 @@ impl JsRenderer {
         component_code: &str,
         props: Option<&str>,
     ) -> AnyhowResult<String> {
+        debug_assert!(
+            RENDER_COMPONENT_FINISH_SCRIPT.is_ascii(),
+            "finish script must be pure ASCII to be handed to V8 as an external one-byte string"
+        );
+
         let props_json = props.unwrap_or("{}");
         with_runtime(Rc::clone(&self.runtime), |runtime| {
             // Set up the context variable globally before executing component code
             setup_context(runtime, props_json).map_err(anyhow::Error::from)?;
 
-            let render_script =
+            let (render_script, source_map) =
                 component_render_script(component_code, props_json).map_err(anyhow::Error::from)?;
 
-            // Evaluate and get the result
-            let result = runtime
+            // Resolution script: resolves the component to render and publishes it to
+            // `globalThis` for the finish script below.
+            runtime
                 .execute_script(script_tags::RENDER, render_script)
                 .map_err(|e| {
-                    anyhow::Error::from(MdxError::TsxTransform(format!(
-                        "Failed to render component: {e:?}"
-                    )))
+                    anyhow::Error::from(translate_execution_error(
+                        &format!("{e:?}"),
+                        source_map.as_deref(),
+                        "Failed to render component",
+                    ))
+                })?;
+
+            // Finish script: static across every render, so it's handed to V8 as a
+            // genuine `&'static str` instead of a freshly built `String`.
+            let result = runtime
+                .execute_script(script_tags::RENDER_FINISH, RENDER_COMPONENT_FINISH_SCRIPT)
+                .map_err(|e| {
+                    anyhow::Error::from(translate_execution_error(
+                        &format!("{e:?}"),
+                        source_map.as_deref(),
+                        "Failed to render component",
+                    ))
                 })?;
 
             extract_string_from_v8(result, runtime, "Failed to convert result to string")
@@ -132,6 +368,84 @@ impl JsRenderer {
         })
     }
 
+    /// Renders a JavaScript component to HTML, writing the result into `writer`
+    /// instead of returning it. The HTML is rendered to a `String` first (as by
+    /// [`JsRenderer::render_component`]) and then copied into `writer`.
+    ///
+    /// Useful for large batch jobs: pair with a pre-reserved [`Buffer`] (sized from
+    /// [`pool::RendererPool`]'s per-profile size hint) or a socket writer to reuse
+    /// one output buffer across files.
+    ///
+    /// # Arguments
+    /// * `component_code` - JavaScript code that defines and exports a component
+    /// * `props` - Optional JSON string of props to pass to the component
+    /// * `writer` - Destination for the rendered bytes
+    pub fn render_component_to_writer(
+        &self,
+        component_code: &str,
+        props: Option<&str>,
+        writer: &mut impl Write,
+    ) -> AnyhowResult<()> {
+        let html = self.render_component(component_code, props)?;
+        writer.write_all(html.as_bytes()).map_err(anyhow::Error::from)
+    }
+
+    /// Async counterpart to [`JsRenderer::render_component`]: drives the event loop
+    /// to completion and resolves the result before extracting it, so a component
+    /// that returns a Promise - e.g. one using top-level `await` to fetch data during
+    /// SSR - yields its resolved value instead of an unresolved `[object Promise]`.
+    ///
+    /// Must be awaited on a single-threaded executor (e.g.
+    /// `tokio::runtime::Builder::new_current_thread`) owned by the calling thread, the
+    /// same way [`pool::RendererPool`] owns one renderer per thread: `JsRuntime` is
+    /// `!Send`, so it can't be moved across threads between polls the way a
+    /// multi-threaded executor might.
+    ///
+    /// `max_render_time_ms` (typically
+    /// [`crate::models::ResourceLimits::max_render_time_ms`]), if set, bounds how long
+    /// the render may await the event loop before failing with
+    /// [`crate::error::MdxError::RenderTimeout`].
+    ///
+    /// # Returns
+    /// Rendered HTML string
+    pub async fn render_component_async(
+        &self,
+        component_code: &str,
+        props: Option<&str>,
+        max_render_time_ms: Option<u64>,
+    ) -> AnyhowResult<String> {
+        let props_json = props.unwrap_or("{}");
+        let (render_script, source_map) =
+            component_render_script(component_code, props_json).map_err(anyhow::Error::from)?;
+
+        execute_and_extract_async(
+            Rc::clone(&self.runtime),
+            props_json,
+            script_tags::RENDER,
+            render_script,
+            RENDER_COMPONENT_FINISH_SCRIPT,
+            source_map.as_deref(),
+            "Failed to render component",
+            max_render_time_ms,
+        )
+        .await
+    }
+
+    /// Renders a component asynchronously, writing the result directly into
+    /// `writer`. See [`JsRenderer::render_component_to_writer`] for why this exists.
+    pub async fn render_component_to_writer_async(
+        &self,
+        component_code: &str,
+        props: Option<&str>,
+        writer: &mut impl Write,
+        max_render_time_ms: Option<u64>,
+    ) -> AnyhowResult<()> {
+        let html = self
+            .render_component_async(component_code, props, max_render_time_ms)
+            .await?;
+        writer.write_all(html.as_bytes()).map_err(anyhow::Error::from)
+    }
+
     /// Renders a JavaScript component using the transformed code from TSX
     ///
     /// # Arguments
@@ -148,28 +462,290 @@ impl JsRenderer {
         components: Option<&HashMap<String, ComponentDefinition>>,
     ) -> AnyhowResult<String> {
         let component_bootstrap = component_bootstrap_script(components)?;
+        let names = component_names(components);
+        let wrapped_code = wrap_transformed_component(&component_bootstrap, transformed_js, &names);
 
-        // Extract component names for variable declarations
-        let component_names: Vec<String> = components
-            .map(|comp_map| {
-                comp_map
-                    .iter()
-                    .map(|(key, comp_def)| {
-                        comp_def
-                            .name
-                            .as_ref()
-                            .cloned()
-                            .unwrap_or_else(|| key.clone())
-                    })
-                    .collect()
-            })
-            .unwrap_or_default();
+        self.render_component(&wrapped_code, props)
+    }
 
-        let wrapped_code = wrap_transformed_component(&component_bootstrap, transformed_js, &component_names);
+    /// Renders a transformed component, writing the result directly into `writer`.
+    ///
+    /// See [`JsRenderer::render_component_to_writer`] for why this exists.
+    pub fn render_transformed_component_to_writer(
+        &self,
+        transformed_js: &str,
+        props: Option<&str>,
+        components: Option<&HashMap<String, ComponentDefinition>>,
+        writer: &mut impl Write,
+    ) -> AnyhowResult<()> {
+        let html = self.render_transformed_component(transformed_js, props, components)?;
+        writer.write_all(html.as_bytes()).map_err(anyhow::Error::from)
+    }
+
+    /// Async counterpart to [`JsRenderer::render_transformed_component`] - see
+    /// [`JsRenderer::render_component_async`] for why this exists and the executor
+    /// requirement it comes with.
+    pub async fn render_transformed_component_async(
+        &self,
+        transformed_js: &str,
+        props: Option<&str>,
+        components: Option<&HashMap<String, ComponentDefinition>>,
+    ) -> AnyhowResult<String> {
+        let component_bootstrap = component_bootstrap_script(components)?;
+        let names = component_names(components);
+        let wrapped_code = wrap_transformed_component(&component_bootstrap, transformed_js, &names);
+
+        self.render_component_async(&wrapped_code, props, None).await
+    }
+
+    /// Renders a transformed component asynchronously, writing the result directly
+    /// into `writer`. See [`JsRenderer::render_component_to_writer`] for why this
+    /// exists.
+    pub async fn render_transformed_component_to_writer_async(
+        &self,
+        transformed_js: &str,
+        props: Option<&str>,
+        components: Option<&HashMap<String, ComponentDefinition>>,
+        writer: &mut impl Write,
+    ) -> AnyhowResult<()> {
+        let html = self
+            .render_transformed_component_async(transformed_js, props, components)
+            .await?;
+        writer.write_all(html.as_bytes()).map_err(anyhow::Error::from)
+    }
+
+    /// Renders already-transformed component code against a renderer whose
+    /// `components` are already registered as globals - e.g. one checked out from a
+    /// [`pool::RendererProfile::with_components`] profile, whose V8 startup snapshot
+    /// already has them baked in. Unlike [`JsRenderer::render_transformed_component`],
+    /// this never runs [`scripts::build_component_registration_script`]: per-request
+    /// work really does collapse down to wrapping `transformed_js` and building the
+    /// render script.
+    ///
+    /// `components` is only consulted for each component's registration name (the
+    /// identifier `transformed_js` references it by) - it must describe the same set
+    /// the renderer's components were registered from, not be re-registered here.
+    ///
+    /// # Returns
+    /// Rendered HTML string
+    pub fn render_preregistered_component(
+        &self,
+        transformed_js: &str,
+        props: Option<&str>,
+        components: Option<&HashMap<String, ComponentDefinition>>,
+    ) -> AnyhowResult<String> {
+        let names = component_names(components);
+        let wrapped_code = wrap_transformed_component("", transformed_js, &names);
 
         self.render_component(&wrapped_code, props)
     }
 
+    /// Renders a preregistered-component request, writing the result directly into
+    /// `writer`. See [`JsRenderer::render_component_to_writer`] for why this exists.
+    pub fn render_preregistered_component_to_writer(
+        &self,
+        transformed_js: &str,
+        props: Option<&str>,
+        components: Option<&HashMap<String, ComponentDefinition>>,
+        writer: &mut impl Write,
+    ) -> AnyhowResult<()> {
+        let html = self.render_preregistered_component(transformed_js, props, components)?;
+        writer.write_all(html.as_bytes()).map_err(anyhow::Error::from)
+    }
+
+    /// Async counterpart to [`JsRenderer::render_preregistered_component`] - see
+    /// [`JsRenderer::render_component_async`] for why this exists and the executor
+    /// requirement it comes with.
+    pub async fn render_preregistered_component_async(
+        &self,
+        transformed_js: &str,
+        props: Option<&str>,
+        components: Option<&HashMap<String, ComponentDefinition>>,
+    ) -> AnyhowResult<String> {
+        let names = component_names(components);
+        let wrapped_code = wrap_transformed_component("", transformed_js, &names);
+
+        self.render_component_async(&wrapped_code, props, None).await
+    }
+
+    /// Renders a preregistered-component request asynchronously, writing the result
+    /// directly into `writer`. See [`JsRenderer::render_component_to_writer`] for why
+    /// this exists.
+    pub async fn render_preregistered_component_to_writer_async(
+        &self,
+        transformed_js: &str,
+        props: Option<&str>,
+        components: Option<&HashMap<String, ComponentDefinition>>,
+        writer: &mut impl Write,
+    ) -> AnyhowResult<()> {
+        let html = self
+            .render_preregistered_component_async(transformed_js, props, components)
+            .await?;
+        writer.write_all(html.as_bytes()).map_err(anyhow::Error::from)
+    }
+
+    /// Registers `components` as globals in this renderer's runtime right now, the
+    /// same way a per-render [`JsRenderer::render_transformed_component`] call would.
+    ///
+    /// Used by [`pool::RendererPool`] as the cold-path fallback for a
+    /// [`pool::RendererProfile::with_components`] profile when building or applying a
+    /// component-baked V8 snapshot isn't available, so the profile's contract (this
+    /// renderer's components are already registered) still holds either way.
+    pub(super) fn register_components(
+        &self,
+        components: &HashMap<String, ComponentDefinition>,
+        mode: scripts::RegistrationMode,
+    ) -> AnyhowResult<()> {
+        let script =
+            build_component_registration_script(components, mode).map_err(anyhow::Error::from)?;
+        if script.trim().is_empty() {
+            return Ok(());
+        }
+
+        with_runtime(Rc::clone(&self.runtime), |runtime| {
+            runtime
+                .execute_script(script_tags::SETUP, script)
+                .map_err(|e| anyhow::anyhow!("Failed to register components: {e:?}"))?;
+            Ok(())
+        })
+    }
+
+    /// Installs this renderer's capability traps for the render about to happen, per
+    /// `permissions` - see [`scripts::build_permissions_script`]. Every denied
+    /// capability's global is overwritten so using it throws an error prefixed with
+    /// [`PERMISSION_DENIED_PREFIX`] naming the capability, instead of running.
+    ///
+    /// Cheap and idempotent, so callers run it before every render rather than only
+    /// once per renderer: this renderer's isolate is pooled and reused across
+    /// requests that may each carry different [`crate::models::ComponentPermissions`],
+    /// so the traps in effect have to be re-applied per request rather than baked in
+    /// once at renderer creation.
+    pub(crate) fn apply_permissions(
+        &self,
+        permissions: &crate::models::ComponentPermissions,
+    ) -> AnyhowResult<()> {
+        let script = build_permissions_script(permissions);
+
+        with_runtime(Rc::clone(&self.runtime), |runtime| {
+            runtime
+                .execute_script(script_tags::PERMISSIONS, script)
+                .map_err(|e| anyhow::anyhow!("Failed to apply component permissions: {e:?}"))?;
+            Ok(())
+        })
+    }
+
+    /// Instruments every currently-registered component with an invocation counter,
+    /// per [`crate::models::RenderSettings::coverage`] - see
+    /// [`scripts::coverage_init_script`]. Run once per renderer checkout, after
+    /// components are registered and before the batch's files are rendered; pair with
+    /// [`JsRenderer::collect_coverage`] once the batch finishes.
+    pub(crate) fn start_coverage(&self) -> AnyhowResult<()> {
+        let script = coverage_init_script();
+
+        with_runtime(Rc::clone(&self.runtime), |runtime| {
+            runtime
+                .execute_script(script_tags::COVERAGE, script)
+                .map_err(|e| anyhow::anyhow!("Failed to start component coverage: {e:?}"))?;
+            Ok(())
+        })
+    }
+
+    /// Reads back the invocation counts [`JsRenderer::start_coverage`] collected,
+    /// keyed by component name - every registered component is present, `0` if it was
+    /// never invoked.
+    pub(crate) fn collect_coverage(&self) -> AnyhowResult<HashMap<String, u32>> {
+        with_runtime(Rc::clone(&self.runtime), |runtime| {
+            let result = runtime
+                .execute_script(script_tags::COVERAGE, COVERAGE_COLLECT_SCRIPT)
+                .map_err(|e| anyhow::anyhow!("Failed to collect component coverage: {e:?}"))?;
+            extract_value_from_v8(result, runtime, "Failed to read back component coverage")
+                .map_err(anyhow::Error::from)
+        })
+    }
+
+    /// Evaluates already-transformed JavaScript `code` through this renderer's
+    /// isolate, for [`crate::doctest::extract_and_run`] to confirm a fenced code block
+    /// runs without throwing. Wrapped in its own IIFE so a snippet's top-level
+    /// `const`/`let` declarations can't collide with anything else run in this
+    /// isolate.
+    pub(crate) fn evaluate_snippet(&self, code: &str) -> AnyhowResult<()> {
+        let wrapped = format!("(function() {{\n{code}\n}})();");
+
+        with_runtime(Rc::clone(&self.runtime), |runtime| {
+            runtime
+                .execute_script(script_tags::DOCTEST, wrapped)
+                .map_err(|e| {
+                    anyhow::Error::from(translate_execution_error(
+                        &format!("{e:?}"),
+                        None,
+                        "Doctest snippet threw",
+                    ))
+                })?;
+            Ok(())
+        })
+    }
+
+    /// Renders `entry_component` - a key into `components` - as a real ES module
+    /// tree instead of one flattened classic script: its code is loaded as the
+    /// default export of a synthetic `dinja:component/<entry_component>` module, and
+    /// any sibling it `import`s (by name, directly or transitively, including
+    /// dynamic `import()`) is resolved against `components` the same way by the
+    /// installed [`module_loader::ComponentModuleLoader`]. An import that isn't a
+    /// registered component name instead falls back to a real file under this
+    /// renderer's `static_dir`, so a component tree can pull in a shared helper
+    /// module the same way it'd `import` one of its siblings; an import that's
+    /// neither fails with a module-not-found error.
+    ///
+    /// Unlike [`JsRenderer::render_transformed_component`], there's no synchronous
+    /// counterpart: `deno_core` only loads and evaluates modules asynchronously, so
+    /// this must be awaited on a single-threaded executor - see
+    /// [`JsRenderer::render_component_async`].
+    ///
+    /// `max_render_time_ms` bounds the render the same way as in
+    /// [`JsRenderer::render_component_async`].
+    ///
+    /// # Returns
+    /// Rendered HTML string
+    pub async fn render_module_component_async(
+        &self,
+        entry_component: &str,
+        props: Option<&str>,
+        components: &HashMap<String, ComponentDefinition>,
+        max_render_time_ms: Option<u64>,
+    ) -> AnyhowResult<String> {
+        let props_json = props.unwrap_or("{}");
+        let entry_url = self
+            .module_loader
+            .begin_render(entry_component, components.clone())?;
+
+        execute_module_and_extract_async(
+            Rc::clone(&self.runtime),
+            props_json,
+            &entry_url,
+            MODULE_RENDER_RESULT_SCRIPT,
+            "Failed to render module component",
+            max_render_time_ms,
+        )
+        .await
+    }
+
+    /// Renders a module component tree asynchronously, writing the result directly
+    /// into `writer`. See [`JsRenderer::render_component_to_writer`] for why this
+    /// exists.
+    pub async fn render_module_component_to_writer_async(
+        &self,
+        entry_component: &str,
+        props: Option<&str>,
+        components: &HashMap<String, ComponentDefinition>,
+        writer: &mut impl Write,
+        max_render_time_ms: Option<u64>,
+    ) -> AnyhowResult<()> {
+        let html = self
+            .render_module_component_async(entry_component, props, components, max_render_time_ms)
+            .await?;
+        writer.write_all(html.as_bytes()).map_err(anyhow::Error::from)
+    }
+
     /// Renders a JavaScript component to schema (JSON string) using core.js engine
     ///
     /// # Arguments
@@ -183,21 +759,41 @@ impl JsRenderer {
         component_code: &str,
         props: Option<&str>,
     ) -> AnyhowResult<String> {
+        debug_assert!(
+            RENDER_SCHEMA_FINISH_SCRIPT.is_ascii(),
+            "finish script must be pure ASCII to be handed to V8 as an external one-byte string"
+        );
+
         let props_json = props.unwrap_or("{}");
         with_runtime(Rc::clone(&self.runtime), |runtime| {
             // Set up the context variable globally before executing component code
             setup_context(runtime, props_json).map_err(anyhow::Error::from)?;
 
-            let render_script =
+            let (render_script, source_map) =
                 schema_render_script(component_code, props_json).map_err(anyhow::Error::from)?;
 
-            // Evaluate and get the result
-            let result = runtime
+            // Resolution script: resolves the component to render and publishes it to
+            // `globalThis` for the finish script below.
+            runtime
                 .execute_script(script_tags::RENDER, render_script)
                 .map_err(|e| {
-                    anyhow::Error::from(MdxError::TsxTransform(format!(
-                        "Failed to render component to schema: {e:?}"
-                    )))
+                    anyhow::Error::from(translate_execution_error(
+                        &format!("{e:?}"),
+                        source_map.as_deref(),
+                        "Failed to render component to schema",
+                    ))
+                })?;
+
+            // Finish script: static across every render, so it's handed to V8 as a
+            // genuine `&'static str` instead of a freshly built `String`.
+            let result = runtime
+                .execute_script(script_tags::RENDER_FINISH, RENDER_SCHEMA_FINISH_SCRIPT)
+                .map_err(|e| {
+                    anyhow::Error::from(translate_execution_error(
+                        &format!("{e:?}"),
+                        source_map.as_deref(),
+                        "Failed to render component to schema",
+                    ))
                 })?;
 
             extract_string_from_v8(result, runtime, "Failed to convert result to string")
@@ -205,6 +801,60 @@ impl JsRenderer {
         })
     }
 
+    /// Renders a component to schema, writing the result directly into `writer`.
+    ///
+    /// See [`JsRenderer::render_component_to_writer`] for why this exists.
+    pub fn render_component_to_schema_to_writer(
+        &self,
+        component_code: &str,
+        props: Option<&str>,
+        writer: &mut impl Write,
+    ) -> AnyhowResult<()> {
+        let schema = self.render_component_to_schema(component_code, props)?;
+        writer.write_all(schema.as_bytes()).map_err(anyhow::Error::from)
+    }
+
+    /// Async counterpart to [`JsRenderer::render_component_to_schema`] - see
+    /// [`JsRenderer::render_component_async`] for why this exists, the executor
+    /// requirement it comes with, and what `max_render_time_ms` bounds.
+    pub async fn render_component_to_schema_async(
+        &self,
+        component_code: &str,
+        props: Option<&str>,
+        max_render_time_ms: Option<u64>,
+    ) -> AnyhowResult<String> {
+        let props_json = props.unwrap_or("{}");
+        let (render_script, source_map) =
+            schema_render_script(component_code, props_json).map_err(anyhow::Error::from)?;
+
+        execute_and_extract_async(
+            Rc::clone(&self.runtime),
+            props_json,
+            script_tags::RENDER,
+            render_script,
+            RENDER_SCHEMA_FINISH_SCRIPT,
+            source_map.as_deref(),
+            "Failed to render component to schema",
+            max_render_time_ms,
+        )
+        .await
+    }
+
+    /// Renders a component to schema asynchronously, writing the result directly into
+    /// `writer`. See [`JsRenderer::render_component_to_writer`] for why this exists.
+    pub async fn render_component_to_schema_to_writer_async(
+        &self,
+        component_code: &str,
+        props: Option<&str>,
+        writer: &mut impl Write,
+        max_render_time_ms: Option<u64>,
+    ) -> AnyhowResult<()> {
+        let schema = self
+            .render_component_to_schema_async(component_code, props, max_render_time_ms)
+            .await?;
+        writer.write_all(schema.as_bytes()).map_err(anyhow::Error::from)
+    }
+
     /// Renders a JavaScript component to schema using the transformed code from TSX
     ///
     /// # Arguments
@@ -242,12 +892,61 @@ impl JsRenderer {
 
         self.render_component_to_schema(&wrapped_code, props)
     }
+
+    /// Renders a transformed component to schema, writing the result directly into
+    /// `writer`.
+    ///
+    /// See [`JsRenderer::render_component_to_writer`] for why this exists.
+    pub fn render_transformed_component_to_schema_to_writer(
+        &self,
+        transformed_js: &str,
+        props: Option<&str>,
+        components: Option<&HashMap<String, ComponentDefinition>>,
+        writer: &mut impl Write,
+    ) -> AnyhowResult<()> {
+        let schema =
+            self.render_transformed_component_to_schema(transformed_js, props, components)?;
+        writer.write_all(schema.as_bytes()).map_err(anyhow::Error::from)
+    }
+
+    /// Async counterpart to [`JsRenderer::render_transformed_component_to_schema`] -
+    /// see [`JsRenderer::render_component_async`] for why this exists and the
+    /// executor requirement it comes with.
+    pub async fn render_transformed_component_to_schema_async(
+        &self,
+        transformed_js: &str,
+        props: Option<&str>,
+        components: Option<&HashMap<String, ComponentDefinition>>,
+    ) -> AnyhowResult<String> {
+        let component_bootstrap = component_bootstrap_script(components)?;
+        let names = component_names(components);
+        let wrapped_code = wrap_transformed_component(&component_bootstrap, transformed_js, &names);
+
+        self.render_component_to_schema_async(&wrapped_code, props, None).await
+    }
+
+    /// Renders a transformed component to schema asynchronously, writing the result
+    /// directly into `writer`. See [`JsRenderer::render_component_to_writer`] for why
+    /// this exists.
+    pub async fn render_transformed_component_to_schema_to_writer_async(
+        &self,
+        transformed_js: &str,
+        props: Option<&str>,
+        components: Option<&HashMap<String, ComponentDefinition>>,
+        writer: &mut impl Write,
+    ) -> AnyhowResult<()> {
+        let schema = self
+            .render_transformed_component_to_schema_async(transformed_js, props, components)
+            .await?;
+        writer.write_all(schema.as_bytes()).map_err(anyhow::Error::from)
+    }
 }
 
 impl Clone for JsRenderer {
     fn clone(&self) -> Self {
         Self {
             runtime: Rc::clone(&self.runtime),
+            module_loader: Rc::clone(&self.module_loader),
         }
     }
 }
@@ -0,0 +1,344 @@
+//! In-memory ES module loader for component trees.
+//!
+//! Components rendered through [`super::JsRenderer::render_component`] (and its
+//! `_transformed`/`_preregistered` siblings) are fed to V8 as classic scripts, so
+//! they can't use static `import`/`export` or dynamic `import()` - everything has to
+//! be flattened into one wrapped script by
+//! [`super::scripts::component_bootstrap_script`]. [`ComponentModuleLoader`] is the
+//! `deno_core::ModuleLoader` used by
+//! [`super::JsRenderer::render_module_component_async`] instead: it resolves bare
+//! component names (e.g. `"Button"`, or `"./Button"`) against an in-memory component
+//! map under a synthetic `dinja:component/<generation>/<name>` specifier, so a
+//! component tree can `import` its siblings by name like any other ES module graph.
+//! A specifier that isn't a registered component name (e.g. `"./format-date"`, a
+//! shared helper that doesn't define a component) falls back to a real file read from
+//! `static_dir` - see [`ComponentModuleLoader::resolve_static_specifier`].
+//!
+//! It also resolves `dinja:engine/preact` and `dinja:engine/core` - small synthetic
+//! modules that `export` the bindings [`super::engine`] installs on `globalThis`
+//! (`h`, `Fragment`, `engine`, `engine_to_string`, `coreEngine`). `engine.min.js` and
+//! `core.js` are opaque third-party bundles read from `static_dir` at runtime, not
+//! checked into this repo, so they can't be rewritten to use `export` themselves; this
+//! loader gives module-based renders a named, explicit boundary to `import` those
+//! bindings from instead of reading `globalThis` directly, the way
+//! [`super::scripts::module_entry_script`] used to. The classic script-injection path
+//! (`component_bootstrap_script`) still reads those globals directly and still needs
+//! `engine.rs`'s `findComponentName` scan and dual-engine save/restore dance - a
+//! flattened script has no module namespace to resolve an import against, so there's
+//! no way around globals there.
+//!
+//! ## Generation tagging
+//!
+//! `deno_core` caches a loaded module under its resolved specifier for the lifetime
+//! of the `JsRuntime` it's loaded into - and, because [`super::pool::RendererPool`]
+//! reuses a `JsRenderer` (and its `JsRuntime`) across many renders, the same renderer
+//! ends up driving [`super::JsRenderer::render_module_component_async`] more than
+//! once with a *different* entry component and component map each time. If the entry
+//! and component specifiers stayed fixed across renders, the second render would
+//! silently get back the first render's cached module instead of the new one.
+//! [`ComponentModuleLoader::begin_render`] sidesteps this by minting a fresh
+//! generation number per render and folding it into the entry/component specifiers
+//! (`dinja:entry/<generation>`, `dinja:component/<generation>/<name>`), so every
+//! render's modules look brand new to `deno_core` and are always re-resolved against
+//! the just-swapped-in component map. `static_dir` file modules are deliberately left
+//! out of this scheme and cache normally across renders, the same way the engine
+//! libraries loaded once at renderer construction do - they're real files that don't
+//! change within a renderer's lifetime.
+
+use super::scripts::MODULE_ENTRY_SPECIFIER_PREFIX;
+use crate::models::ComponentDefinition;
+use deno_core::{
+    ModuleLoadResponse, ModuleLoader, ModuleSource, ModuleSourceCode, ModuleSpecifier, ModuleType,
+    RequestedModuleType, ResolutionKind,
+};
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+/// URL scheme synthetic component specifiers are resolved under.
+const COMPONENT_SCHEME: &str = "dinja";
+
+/// Path prefix a component's synthetic specifier carries after the scheme, e.g.
+/// `dinja:component/3/Button`.
+const COMPONENT_PATH_PREFIX: &str = "component/";
+
+/// Path prefix an engine module's synthetic specifier carries after the scheme, e.g.
+/// `dinja:engine/preact`.
+const ENGINE_PATH_PREFIX: &str = "engine/";
+
+/// File extensions tried, in order, when a specifier resolved under `static_dir`
+/// doesn't already name an existing file - mirrors the order a bundler typically
+/// prefers a typed source file over its compiled output.
+const STATIC_MODULE_EXTENSIONS: &[&str] = &["", ".ts", ".tsx", ".js", ".jsx"];
+
+/// Synthetic specifier exporting the Preact-based engine's `h`, `Fragment`, `engine`,
+/// and `engine_to_string` bindings - see [module-level docs](self).
+pub(super) const PREACT_ENGINE_SPECIFIER: &str = "dinja:engine/preact";
+
+/// Resolves and loads component modules from an in-memory map instead of disk or
+/// network, falling back to real files under `static_dir` for specifiers that aren't
+/// registered component names - see the [module-level docs](self).
+///
+/// `deno_core` installs a `ModuleLoader` once, at [`deno_core::JsRuntime`]
+/// construction, and calls its methods with `&self` - so rather than rebuild the
+/// runtime for every render (defeating [`super::pool::RendererPool`] reuse), the
+/// component map and the synthetic entry module's specifier/source (see
+/// [`super::scripts::module_entry_script`]) are held behind `RefCell`s and swapped in
+/// right before each [`super::JsRenderer::render_module_component_async`] call via
+/// [`ComponentModuleLoader::begin_render`].
+#[derive(Default)]
+pub(super) struct ComponentModuleLoader {
+    components: RefCell<HashMap<String, ComponentDefinition>>,
+    entry_specifier: RefCell<String>,
+    entry_source: RefCell<String>,
+    /// Bumped by every [`ComponentModuleLoader::begin_render`] call - see the
+    /// [module-level docs](self) for why this is folded into the entry/component
+    /// specifiers each render uses.
+    generation: Cell<u64>,
+    /// Directory shared component/utility modules (that aren't registered components)
+    /// are read from. `None` for a renderer built from in-memory sources only (see
+    /// [`super::JsRenderer::from_sources`]), in which case such an import fails with a
+    /// clear error instead of attempting disk access.
+    static_dir: Option<PathBuf>,
+}
+
+impl ComponentModuleLoader {
+    /// Creates a loader with no components registered and no entry source set - both
+    /// must be set via [`ComponentModuleLoader::begin_render`] before a module-based
+    /// render. `static_dir` is fixed for the loader's whole lifetime, mirroring how
+    /// the engine libraries are loaded once at renderer construction.
+    pub(super) fn new(static_dir: Option<PathBuf>) -> Rc<Self> {
+        Rc::new(Self {
+            static_dir,
+            ..Self::default()
+        })
+    }
+
+    /// Swaps in `components` and mints a fresh generation's entry specifier/source
+    /// ahead of a module-based render - see the [module-level docs](self). Returns
+    /// the entry specifier [`super::JsRenderer::render_module_component_async`]
+    /// should load as the main module.
+    pub(super) fn begin_render(
+        &self,
+        entry_component: &str,
+        components: HashMap<String, ComponentDefinition>,
+    ) -> Result<ModuleSpecifier, anyhow::Error> {
+        let generation = self.generation.get().wrapping_add(1);
+        self.generation.set(generation);
+        *self.components.borrow_mut() = components;
+
+        let entry_component_specifier = Self::component_specifier(generation, entry_component);
+        let entry_source = super::scripts::module_entry_script(&entry_component_specifier)
+            .map_err(anyhow::Error::from)?;
+        let entry_specifier = format!("{MODULE_ENTRY_SPECIFIER_PREFIX}{generation}");
+
+        *self.entry_specifier.borrow_mut() = entry_specifier.clone();
+        *self.entry_source.borrow_mut() = entry_source;
+
+        ModuleSpecifier::parse(&entry_specifier).map_err(anyhow::Error::from)
+    }
+
+    /// Builds the synthetic `dinja:component/<generation>/<name>` specifier for
+    /// `name` under the given `generation` - see the [module-level docs](self).
+    fn component_specifier(generation: u64, name: &str) -> String {
+        format!("{COMPONENT_SCHEME}:{COMPONENT_PATH_PREFIX}{generation}/{name}")
+    }
+
+    /// Extracts the component name out of a synthetic `dinja:component/<generation>/<name>`
+    /// specifier, or `None` if `specifier` doesn't use this loader's scheme. The
+    /// generation segment is only ever consulted by `deno_core`'s own module cache
+    /// (via the specifier's identity), never by this loader, since `components`
+    /// always holds the current render's map by the time a load for it lands.
+    fn component_name(specifier: &ModuleSpecifier) -> Option<&str> {
+        if specifier.scheme() != COMPONENT_SCHEME {
+            return None;
+        }
+        let rest = specifier.path().strip_prefix(COMPONENT_PATH_PREFIX)?;
+        let (_generation, name) = rest.split_once('/')?;
+        Some(name)
+    }
+
+    /// Extracts the engine name out of a synthetic `dinja:engine/<name>` specifier, or
+    /// `None` if `specifier` doesn't name one of the known engine modules.
+    fn engine_name(specifier: &ModuleSpecifier) -> Option<&str> {
+        if specifier.scheme() != COMPONENT_SCHEME {
+            return None;
+        }
+        specifier.path().strip_prefix(ENGINE_PATH_PREFIX)
+    }
+
+    /// Source served for `dinja:engine/<name>`: a thin `export` wrapper around the
+    /// globals [`super::engine`] installs while loading the static engine libraries.
+    fn engine_module_source(name: &str) -> Result<&'static str, anyhow::Error> {
+        match name {
+            "preact" => Ok(r#"
+                export const h = globalThis.h;
+                export const engine = globalThis.engine;
+                export const engine_to_string = globalThis.engine_to_string;
+                export const Fragment = globalThis.engine ? globalThis.engine.Fragment : undefined;
+            "#),
+            "core" => Ok(r#"
+                export const engine = globalThis.coreEngine;
+            "#),
+            other => Err(anyhow::anyhow!("Unknown engine module: dinja:engine/{other}")),
+        }
+    }
+
+    /// Resolves a specifier that isn't a registered component name to a `file:`
+    /// specifier under `static_dir` - the "fall back to files under `static_dir`"
+    /// half of this loader's resolution order. Tries `raw_path` as given first, then
+    /// each of [`STATIC_MODULE_EXTENSIONS`] appended to it, and rejects anything that
+    /// would resolve outside `static_dir` (e.g. via a `../` specifier) so a component
+    /// can't read arbitrary files off disk.
+    fn resolve_static_specifier(&self, raw_path: &str) -> Result<ModuleSpecifier, anyhow::Error> {
+        let static_dir = self.static_dir.as_deref().ok_or_else(|| {
+            anyhow::anyhow!(
+                "Cannot resolve module \"{raw_path}\": this renderer has no static_dir configured"
+            )
+        })?;
+
+        let relative = raw_path.strip_prefix("./").unwrap_or(raw_path);
+        let candidate = static_dir.join(relative);
+        let resolved = Self::find_static_module_file(static_dir, &candidate).ok_or_else(|| {
+            anyhow::anyhow!("Module not found: no file for \"{raw_path}\" under static_dir")
+        })?;
+
+        ModuleSpecifier::from_file_path(&resolved).map_err(|()| {
+            anyhow::anyhow!("Failed to build a module specifier for {}", resolved.display())
+        })
+    }
+
+    /// Tries `candidate`, then `candidate` with each of [`STATIC_MODULE_EXTENSIONS`]
+    /// appended, returning the first that both exists and canonicalizes to somewhere
+    /// under `static_dir`.
+    fn find_static_module_file(static_dir: &Path, candidate: &Path) -> Option<PathBuf> {
+        let canonical_root = static_dir.canonicalize().ok()?;
+
+        STATIC_MODULE_EXTENSIONS.iter().find_map(|extension| {
+            let mut file_name = candidate.as_os_str().to_owned();
+            file_name.push(extension);
+            let path = PathBuf::from(file_name);
+
+            let canonical = path.canonicalize().ok()?;
+            canonical.starts_with(&canonical_root).then_some(canonical)
+        })
+    }
+
+    /// Reads and transforms the static file a `file:` specifier (built by
+    /// [`ComponentModuleLoader::resolve_static_specifier`]) points at.
+    fn load_static_file(module_specifier: &ModuleSpecifier) -> Result<ModuleSource, anyhow::Error> {
+        let path = module_specifier
+            .to_file_path()
+            .map_err(|()| anyhow::anyhow!("Invalid static module specifier: {module_specifier}"))?;
+
+        let source = std::fs::read_to_string(&path)
+            .map_err(|e| anyhow::anyhow!("Failed to read static module \"{}\": {e}", path.display()))?;
+
+        let transformed = crate::transform::transform_component_function(&source).map_err(|e| {
+            anyhow::anyhow!("Failed to transform static module \"{}\": {e:?}", path.display())
+        })?;
+
+        Ok(ModuleSource::new(
+            ModuleType::JavaScript,
+            ModuleSourceCode::String(transformed.into()),
+            module_specifier,
+            None,
+        ))
+    }
+}
+
+impl ModuleLoader for ComponentModuleLoader {
+    fn resolve(
+        &self,
+        specifier: &str,
+        referrer: &str,
+        _kind: ResolutionKind,
+    ) -> Result<ModuleSpecifier, anyhow::Error> {
+        if specifier == *self.entry_specifier.borrow() {
+            return ModuleSpecifier::parse(specifier).map_err(anyhow::Error::from);
+        }
+
+        // A bare or relative specifier (e.g. "Button", "./Button", "./format-date")
+        // resolves against the current render's component map first, then against
+        // `static_dir`; anything else (an absolute `dinja:`/`file:` specifier) falls
+        // through to normal URL resolution against the referrer.
+        if !specifier.contains(':') {
+            let normalized = specifier.strip_prefix("./").unwrap_or(specifier);
+            if !normalized.contains('/') {
+                let components = self.components.borrow();
+                if components.contains_key(normalized) {
+                    let generation = self.generation.get();
+                    return ModuleSpecifier::parse(&Self::component_specifier(generation, normalized))
+                        .map_err(anyhow::Error::from);
+                }
+            }
+            return self.resolve_static_specifier(specifier);
+        }
+
+        deno_core::resolve_import(specifier, referrer).map_err(anyhow::Error::from)
+    }
+
+    fn load(
+        &self,
+        module_specifier: &ModuleSpecifier,
+        _maybe_referrer: Option<&ModuleSpecifier>,
+        _is_dyn_import: bool,
+        _requested_module_type: RequestedModuleType,
+    ) -> ModuleLoadResponse {
+        if module_specifier.as_str() == *self.entry_specifier.borrow() {
+            let code = ModuleSourceCode::String(self.entry_source.borrow().clone().into());
+            return ModuleLoadResponse::Sync(Ok(ModuleSource::new(
+                ModuleType::JavaScript,
+                code,
+                module_specifier,
+                None,
+            )));
+        }
+
+        if let Some(engine_name) = Self::engine_name(module_specifier) {
+            return ModuleLoadResponse::Sync(Self::engine_module_source(engine_name).map(|src| {
+                ModuleSource::new(
+                    ModuleType::JavaScript,
+                    ModuleSourceCode::String(src.into()),
+                    module_specifier,
+                    None,
+                )
+            }));
+        }
+
+        if module_specifier.scheme() == "file" {
+            return ModuleLoadResponse::Sync(Self::load_static_file(module_specifier));
+        }
+
+        let Some(name) = Self::component_name(module_specifier) else {
+            return ModuleLoadResponse::Sync(Err(anyhow::anyhow!(
+                "Module not found: {module_specifier}"
+            )));
+        };
+
+        let components = self.components.borrow();
+        let Some(definition) = components.get(name) else {
+            return ModuleLoadResponse::Sync(Err(anyhow::anyhow!(
+                "Module not found: component \"{name}\" is not registered for this render"
+            )));
+        };
+
+        let transformed = match crate::transform::transform_component_function(&definition.code) {
+            Ok(js) => js,
+            Err(e) => {
+                return ModuleLoadResponse::Sync(Err(anyhow::anyhow!(
+                    "Failed to transform component \"{name}\": {e:?}"
+                )))
+            }
+        };
+
+        let code = ModuleSourceCode::String(transformed.into());
+        ModuleLoadResponse::Sync(Ok(ModuleSource::new(
+            ModuleType::JavaScript,
+            code,
+            module_specifier,
+            None,
+        )))
+    }
+}
@@ -24,10 +24,28 @@
 //!
 //! ## Performance Considerations
 //!
-//! - Renderers are cached per profile (Engine)
+//! - Renderers are cached per profile, keyed off a hash of the profile's configuration
 //! - LRU eviction prevents unbounded memory growth
 //! - Pool warming reduces first-request latency
 //! - Maximum cache size per profile prevents excessive memory usage
+//! - A V8 startup snapshot is built once per distinct profile and reused for every cold
+//!   renderer creation, turning "compile and run three JS files" into "deserialize a blob"
+//!   for first-request and post-eviction latency
+//! - [`RendererProfile::with_components`] extends this to a fixed set of components: the
+//!   snapshot also has their registration baked in, so renderers booted from it skip
+//!   running the registration script at all - see [`super::JsRenderer::render_preregistered_component`].
+//!   A different component set (by name or source) or engine naturally gets its own
+//!   snapshot and LRU bucket, since it hashes to a different profile key.
+//! - `reap()` evicts renderers idle past a TTL, bounding memory on threads with bursty
+//!   traffic; `stats()` reports per-profile cached counts, checkouts, hits/misses, and
+//!   both eviction reasons
+//! - [`RendererPool::with_snapshot_cache_dir`] persists each built snapshot to disk,
+//!   keyed by a hash of the static engine files it was built from, so a later process
+//!   start can load it instead of paying the build cost again
+//! - [`RendererPool::set_snapshot_enabled`] (and [`crate::service::RenderServiceConfig::snapshot_enabled`]
+//!   at the service layer) turns snapshot use off entirely, falling every renderer
+//!   back to the cold `JsRenderer::new` path - `core/benches/render_benchmark.rs`'s
+//!   `renderer_startup` group measures the difference
 //!
 //! ## Example
 //!
@@ -35,29 +53,44 @@
 //! use dinja_core::renderer::pool::{RendererPool, RendererProfile};
 //!
 //! # fn main() -> Result<(), Box<dyn std::error::Error>> {
-//! let pool = RendererPool::new("static", 4);
-//! pool.warm(1); // Pre-create renderers for common profiles
+//! let pool = RendererPool::new(4);
+//! let profile = RendererProfile::engine("static");
+//! pool.warm(&[profile.clone()], 1); // Pre-create renderers for common profiles
 //!
-//! let lease = pool.checkout(RendererProfile::Engine)?;
+//! let lease = pool.checkout(&profile)?;
 //! let renderer = lease.renderer()?;
 //! // Use renderer...
 //! // Renderer is automatically returned to pool when lease is dropped
 //! # Ok(())
 //! # }
 //! ```
+use super::engine::{build_component_snapshot, build_engine_snapshot};
+use super::scripts::RegistrationMode;
 use super::JsRenderer;
 use crate::error::MdxError;
+use crate::models::ComponentDefinition;
 use anyhow::Result as AnyhowResult;
 use std::cell::RefCell;
+use std::collections::hash_map::DefaultHasher;
 use std::collections::{HashMap, VecDeque};
-use std::hash::Hash;
-use std::path::PathBuf;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
 use std::thread_local;
+use std::time::{Duration, Instant};
+
+/// A cached renderer paired with the instant it was last returned to the pool, used
+/// for idle (TTL) eviction in [`CacheEntry::reap`].
+struct PooledRenderer {
+    renderer: JsRenderer,
+    last_used: Instant,
+}
 
 /// Cache entry tracking renderers and their access order for LRU eviction
 struct CacheEntry {
     /// Stack of available renderers (most recently used at the end)
-    renderers: VecDeque<JsRenderer>,
+    renderers: VecDeque<PooledRenderer>,
 }
 
 impl CacheEntry {
@@ -69,22 +102,47 @@ impl CacheEntry {
 
     /// Pops the most recently used renderer (LRU: remove from front)
     fn pop(&mut self) -> Option<JsRenderer> {
-        self.renderers.pop_back()
+        self.renderers.pop_back().map(|pooled| pooled.renderer)
     }
 
-    /// Pushes a renderer, evicting the least recently used if at capacity
-    fn push_with_limit(&mut self, renderer: JsRenderer, max_size: usize) {
+    /// Pushes a renderer, evicting the least recently used if at capacity.
+    ///
+    /// Returns `true` if an existing renderer was evicted to make room.
+    fn push_with_limit(&mut self, renderer: JsRenderer, max_size: usize) -> bool {
         // If at capacity, remove least recently used (front of deque)
-        if self.renderers.len() >= max_size {
-            let _ = self.renderers.pop_front();
-        }
+        let evicted = if self.renderers.len() >= max_size {
+            self.renderers.pop_front().is_some()
+        } else {
+            false
+        };
         // Add most recently used to the back
-        self.renderers.push_back(renderer);
+        self.renderers.push_back(PooledRenderer {
+            renderer,
+            last_used: Instant::now(),
+        });
+        evicted
     }
 
     fn len(&self) -> usize {
         self.renderers.len()
     }
+
+    /// Evicts renderers that have been idle longer than `max_idle`.
+    ///
+    /// Idle renderers accumulate at the front of the deque (least recently used),
+    /// so this pops from the front - the same end count-based eviction already uses -
+    /// until it finds one that's still within the TTL. Returns the number evicted.
+    fn reap(&mut self, max_idle: Duration) -> usize {
+        let mut evicted = 0;
+        while let Some(front) = self.renderers.front() {
+            if front.last_used.elapsed() < max_idle {
+                break;
+            }
+            self.renderers.pop_front();
+            evicted += 1;
+        }
+        evicted
+    }
 }
 
 impl Drop for CacheEntry {
@@ -94,29 +152,227 @@ impl Drop for CacheEntry {
     }
 }
 
+/// Per-key counters backing [`PoolStats`], tracked alongside the thread-local cache.
+#[derive(Default, Clone, Copy)]
+struct KeyStats {
+    checkouts: u64,
+    hits: u64,
+    misses: u64,
+    count_evictions: u64,
+    idle_evictions: u64,
+}
+
+/// Exponentially-decayed running estimate of rendered output size for a profile.
+///
+/// Used to pre-`reserve` a [`super::Buffer`] (or any writer that supports it)
+/// before the next render, so streaming output avoids the repeated reallocation
+/// churn of growing from empty on every file.
+#[derive(Clone, Copy)]
+struct SizeHint {
+    estimate: f64,
+}
+
+impl SizeHint {
+    /// Decay factor for the running estimate: weights the most recent output at
+    /// 25% and the prior estimate at 75%, so a handful of outliers don't cause the
+    /// hint to whipsaw between over- and under-reserving.
+    const DECAY: f64 = 0.25;
+
+    fn new() -> Self {
+        Self { estimate: 0.0 }
+    }
+
+    /// Folds a newly observed output length into the running estimate.
+    fn record(&mut self, actual_len: usize) {
+        let actual_len = actual_len as f64;
+        self.estimate = if self.estimate == 0.0 {
+            actual_len
+        } else {
+            actual_len * Self::DECAY + self.estimate * (1.0 - Self::DECAY)
+        };
+    }
+
+    /// Returns the current estimate, rounded up for use as a `reserve` hint.
+    fn estimate(&self) -> usize {
+        self.estimate.ceil() as usize
+    }
+}
+
 thread_local! {
     static RENDERER_CACHE: RefCell<HashMap<RendererKey, CacheEntry>> =
         RefCell::new(HashMap::new());
+    static RENDERER_STATS: RefCell<HashMap<RendererKey, KeyStats>> =
+        RefCell::new(HashMap::new());
+    static RENDERER_SIZE_HINTS: RefCell<HashMap<RendererKey, SizeHint>> =
+        RefCell::new(HashMap::new());
+    /// The [`RendererPool::generation`] value this thread's cache was last built
+    /// against. Compared in [`RendererPool::checkout`] to detect a call to
+    /// [`RendererPool::invalidate`] made (possibly on another thread) since.
+    static RENDERER_CACHE_GENERATION: std::cell::Cell<u64> = const { std::cell::Cell::new(0) };
 }
 
+/// Opaque, `Copy`-friendly identifier derived from a [`RendererProfile`]'s configuration.
+///
+/// Using a hash instead of the profile itself keeps the thread-local cache map and the
+/// `RendererLease` cheap to carry around, while still giving distinct configurations
+/// (different static bundles, different JSX pragmas) their own LRU bucket.
 #[derive(Clone, Copy, Hash, Eq, PartialEq)]
-enum RendererKey {
-    Engine,
+struct RendererKey(u64);
+
+impl RendererKey {
+    /// The raw hash backing this key, exposed for [`PoolKeyStats`] reporting.
+    fn raw(self) -> u64 {
+        self.0
+    }
 }
 
-/// Profiles describe the runtime flavor required for a given render request.
-#[derive(Clone, Copy)]
-pub enum RendererProfile {
-    /// Standard engine renderer used for HTML and JavaScript outputs.
-    Engine,
+/// A fixed set of components baked into a [`RendererProfile`]'s V8 startup snapshot -
+/// see [`RendererProfile::with_components`].
+///
+/// Only `fingerprint` participates in the owning profile's `Hash`/`Eq` (and therefore
+/// its [`RendererKey`]): two profiles built from component maps with the same names,
+/// registration names, and source code hash identically and share a snapshot and LRU
+/// bucket, regardless of `HashMap` iteration order or which allocation holds the data.
+#[derive(Clone, Debug)]
+struct ComponentSet {
+    components: Arc<HashMap<String, ComponentDefinition>>,
+    mode: RegistrationMode,
+    fingerprint: u64,
+}
+
+impl ComponentSet {
+    fn new(components: HashMap<String, ComponentDefinition>, mode: RegistrationMode) -> Self {
+        let fingerprint = Self::compute_fingerprint(&components, mode);
+        Self {
+            components: Arc::new(components),
+            mode,
+            fingerprint,
+        }
+    }
+
+    /// Hashes each component's map key, registration name, and source - sorted by map
+    /// key so the result doesn't depend on `HashMap` iteration order - plus the
+    /// registration mode, since it changes the shape of the baked-in script.
+    fn compute_fingerprint(
+        components: &HashMap<String, ComponentDefinition>,
+        mode: RegistrationMode,
+    ) -> u64 {
+        let mut entries: Vec<(&str, &ComponentDefinition)> =
+            components.iter().map(|(key, def)| (key.as_str(), def)).collect();
+        entries.sort_unstable_by_key(|(key, _)| *key);
+
+        let mut hasher = DefaultHasher::new();
+        matches!(mode, RegistrationMode::Concatenated).hash(&mut hasher);
+        for (key, def) in entries {
+            key.hash(&mut hasher);
+            def.name.hash(&mut hasher);
+            def.code.hash(&mut hasher);
+        }
+        hasher.finish()
+    }
+}
+
+/// Describes the runtime flavor required for a given render request: which static JS
+/// bundle to load, which JSX pragma/fragment function names the engine expects, and
+/// optionally which fixed set of components (see [`RendererProfile::with_components`])
+/// should be pre-registered when this profile's V8 startup snapshot is built.
+///
+/// Two profiles with equal configuration hash to the same [`RendererKey`] and therefore
+/// share an LRU bucket and a cached V8 startup snapshot; profiles that differ in static
+/// directory, pragma settings, or baked-in component set pool independently.
+#[derive(Clone, Debug)]
+pub struct RendererProfile {
+    static_dir: PathBuf,
+    jsx_pragma: String,
+    jsx_pragma_frag: String,
+    components: Option<ComponentSet>,
+}
+
+impl PartialEq for RendererProfile {
+    fn eq(&self, other: &Self) -> bool {
+        self.static_dir == other.static_dir
+            && self.jsx_pragma == other.jsx_pragma
+            && self.jsx_pragma_frag == other.jsx_pragma_frag
+            && self.components.as_ref().map(|c| c.fingerprint)
+                == other.components.as_ref().map(|c| c.fingerprint)
+    }
+}
+
+impl Eq for RendererProfile {}
+
+impl Hash for RendererProfile {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.static_dir.hash(state);
+        self.jsx_pragma.hash(state);
+        self.jsx_pragma_frag.hash(state);
+        self.components.as_ref().map(|c| c.fingerprint).hash(state);
+    }
 }
 
 impl RendererProfile {
-    fn key(self) -> RendererKey {
-        match self {
-            RendererProfile::Engine => RendererKey::Engine,
+    /// Builds a profile for an arbitrary static bundle / JSX pragma combination.
+    pub fn new(
+        static_dir: impl Into<PathBuf>,
+        jsx_pragma: impl Into<String>,
+        jsx_pragma_frag: impl Into<String>,
+    ) -> Self {
+        Self {
+            static_dir: static_dir.into(),
+            jsx_pragma: jsx_pragma.into(),
+            jsx_pragma_frag: jsx_pragma_frag.into(),
+            components: None,
+        }
+    }
+
+    /// The standard engine profile (`engine.h` / `engine.Fragment`) used for HTML,
+    /// JavaScript, Schema, and Json output.
+    pub fn engine(static_dir: impl Into<PathBuf>) -> Self {
+        Self::new(static_dir, "engine.h", "engine.Fragment")
+    }
+
+    /// Builds a profile that also bakes `components` into its V8 startup snapshot, so
+    /// a renderer checked out for it boots with every component already resolved and
+    /// assigned to `globalThis` - see [`JsRenderer::render_preregistered_component`].
+    /// [`RendererPool::warm`] triggers building (and caching) that snapshot the same
+    /// way it does for the plain engine profile; [`RendererPool::checkout`] falls back
+    /// to registering `components` in a cold renderer if snapshotting fails.
+    ///
+    /// `mode` controls whether the baked-in registration wraps each component in its
+    /// own IIFE or concatenates them into one shared scope - see [`RegistrationMode`].
+    pub fn with_components(
+        static_dir: impl Into<PathBuf>,
+        jsx_pragma: impl Into<String>,
+        jsx_pragma_frag: impl Into<String>,
+        components: HashMap<String, ComponentDefinition>,
+        mode: RegistrationMode,
+    ) -> Self {
+        Self {
+            static_dir: static_dir.into(),
+            jsx_pragma: jsx_pragma.into(),
+            jsx_pragma_frag: jsx_pragma_frag.into(),
+            components: Some(ComponentSet::new(components, mode)),
         }
     }
+
+    /// [`RendererProfile::with_components`] for the standard engine pragma.
+    pub fn engine_with_components(
+        static_dir: impl Into<PathBuf>,
+        components: HashMap<String, ComponentDefinition>,
+        mode: RegistrationMode,
+    ) -> Self {
+        Self::with_components(static_dir, "engine.h", "engine.Fragment", components, mode)
+    }
+
+    /// Directory containing the static JavaScript files this profile loads.
+    pub(crate) fn static_dir(&self) -> &Path {
+        &self.static_dir
+    }
+
+    fn key(&self) -> RendererKey {
+        let mut hasher = DefaultHasher::new();
+        self.hash(&mut hasher);
+        RendererKey(hasher.finish())
+    }
 }
 
 /// Lease that returns the renderer to the cache when dropped.
@@ -136,6 +392,21 @@ impl<'pool> RendererLease<'pool> {
             .as_ref()
             .ok_or_else(|| MdxError::tsx_transform("Renderer already returned to pool"))
     }
+
+    /// Returns the current output size estimate for this lease's profile, for
+    /// pre-reserving a [`super::Buffer`] before rendering.
+    ///
+    /// Returns `0` until at least one output has been recorded via
+    /// [`RendererLease::record_output_size`].
+    pub fn size_hint(&self) -> usize {
+        RendererPool::size_hint_for(self.key)
+    }
+
+    /// Folds an observed output length into this lease's profile's size hint, so
+    /// future renders of the same profile get a better `reserve` estimate.
+    pub fn record_output_size(&self, actual_len: usize) {
+        RendererPool::record_output_size_for(self.key, actual_len);
+    }
 }
 
 impl<'pool> Drop for RendererLease<'pool> {
@@ -146,6 +417,68 @@ impl<'pool> Drop for RendererLease<'pool> {
     }
 }
 
+/// Observable statistics for a single profile's cache on the calling thread.
+#[derive(Debug, Clone, Copy, serde::Serialize)]
+pub struct PoolKeyStats {
+    /// Opaque hash identifying the [`RendererProfile`] these stats belong to.
+    pub key: u64,
+    /// Number of renderers currently cached for this profile.
+    pub cached: usize,
+    /// Total number of `checkout` calls for this profile.
+    pub checkouts: u64,
+    /// Number of checkouts served from the cache.
+    pub hits: u64,
+    /// Number of checkouts that required creating a new renderer.
+    pub misses: u64,
+    /// Number of renderers evicted to stay within `max_cached_per_key`.
+    pub count_evictions: u64,
+    /// Number of renderers evicted by `reap` for being idle too long.
+    pub idle_evictions: u64,
+}
+
+/// Observable statistics for the calling thread's renderer cache, broken down by
+/// profile and summed across all profiles.
+///
+/// Gives operators the numbers needed to tell whether `max_cached_per_key` and
+/// `warm_count` are tuned well: a high miss rate suggests warming more or raising
+/// the cache size; a high idle-eviction rate after calling `reap` suggests the
+/// thread is over-provisioned for its traffic.
+#[derive(Debug, Clone, Default, serde::Serialize)]
+pub struct PoolStats {
+    /// Total renderers currently cached, summed across all profiles.
+    pub total_cached: usize,
+    /// Total `checkout` calls, summed across all profiles.
+    pub total_checkouts: u64,
+    /// Total cache hits, summed across all profiles.
+    pub total_hits: u64,
+    /// Total cache misses, summed across all profiles.
+    pub total_misses: u64,
+    /// Total count-based evictions, summed across all profiles.
+    pub total_count_evictions: u64,
+    /// Total idle (TTL) evictions, summed across all profiles.
+    pub total_idle_evictions: u64,
+    /// Per-profile breakdown.
+    pub per_key: Vec<PoolKeyStats>,
+}
+
+impl PoolStats {
+    fn from_per_key(per_key: Vec<PoolKeyStats>) -> Self {
+        let mut totals = Self {
+            per_key,
+            ..Self::default()
+        };
+        for key_stats in &totals.per_key {
+            totals.total_cached += key_stats.cached;
+            totals.total_checkouts += key_stats.checkouts;
+            totals.total_hits += key_stats.hits;
+            totals.total_misses += key_stats.misses;
+            totals.total_count_evictions += key_stats.count_evictions;
+            totals.total_idle_evictions += key_stats.idle_evictions;
+        }
+        totals
+    }
+}
+
 /// Thread-local cache of initialized JavaScript runtimes.
 ///
 /// This pool uses LRU (Least Recently Used) eviction to manage cached renderers.
@@ -156,49 +489,132 @@ impl<'pool> Drop for RendererLease<'pool> {
 /// maximum cache size is reached for a given profile.
 #[derive(Clone)]
 pub struct RendererPool {
-    static_dir: PathBuf,
-    max_cached_per_key: usize,
+    /// Shared so [`Self::set_max_cached_per_key`] takes effect for every clone of
+    /// this pool (one per `RenderService` clone, one per Actix worker) without
+    /// needing to recreate the pool - see [`crate::service::RenderService::reconfigure`].
+    max_cached_per_key: Arc<AtomicUsize>,
+    /// V8 startup snapshots with the engine globals pre-evaluated, keyed by profile and
+    /// built lazily the first time a given profile is warmed or checked out. Shared
+    /// across threads via `Arc<Mutex<_>>` since snapshot bytes (unlike `JsRuntime`) are
+    /// `Send + Sync`. A missing entry for a profile that failed to snapshot means
+    /// renderers for it fall back to the cold `JsRenderer::new` path.
+    snapshots: Arc<Mutex<HashMap<RendererKey, Arc<[u8]>>>>,
+    /// When set, a directory [`Self::snapshot_for`] persists each profile's built
+    /// snapshot blob to (and reloads it from on a later process's first use) - see
+    /// [`Self::with_snapshot_cache_dir`].
+    snapshot_cache_dir: Option<PathBuf>,
+    /// When `false`, [`Self::snapshot_for`] never builds or looks up a snapshot, so
+    /// every [`Self::create_renderer`] takes the cold `JsRenderer::new` path - see
+    /// [`Self::set_snapshot_enabled`]. Defaults to `true`.
+    snapshot_enabled: Arc<AtomicBool>,
+    /// Bumped by [`Self::invalidate`] to tell every thread's cache it's stale. Shared
+    /// (unlike [`RENDERER_CACHE`], which is thread-local because `JsRuntime` isn't
+    /// `Send`) so one call from a watcher thread is enough to reach them all: each
+    /// thread compares it against [`RENDERER_CACHE_GENERATION`] at the top of
+    /// [`Self::checkout`] and drops its stale cache lazily, on its own next request,
+    /// rather than needing to be signalled directly.
+    generation: Arc<AtomicU64>,
 }
 
 impl RendererPool {
     /// Creates a new renderer pool.
     ///
     /// # Arguments
-    /// * `static_dir` - Directory containing static JavaScript files
     /// * `max_cached_per_key` - Maximum number of cached renderers per profile
     ///
     /// # Returns
     /// A new `RendererPool` instance
-    pub fn new(static_dir: impl Into<PathBuf>, max_cached_per_key: usize) -> Self {
+    pub fn new(max_cached_per_key: usize) -> Self {
         Self {
-            static_dir: static_dir.into(),
-            max_cached_per_key,
+            max_cached_per_key: Arc::new(AtomicUsize::new(max_cached_per_key)),
+            snapshots: Arc::new(Mutex::new(HashMap::new())),
+            snapshot_cache_dir: None,
+            snapshot_enabled: Arc::new(AtomicBool::new(true)),
+            generation: Arc::new(AtomicU64::new(0)),
         }
     }
 
-    /// Warms up the pool by pre-creating renderers for common profiles.
+    /// Changes the maximum number of cached renderers per profile, effective for
+    /// every thread's next eviction check - see [`Self::max_cached_per_key`]'s field
+    /// doc. Lowering it doesn't immediately evict anything; each return of a renderer
+    /// to a profile's cache at or over the new limit evicts one least recently used
+    /// renderer, so a thread's cache shrinks to the limit one checkout/return at a time.
+    pub fn set_max_cached_per_key(&self, max_cached_per_key: usize) {
+        self.max_cached_per_key.store(max_cached_per_key, Ordering::Relaxed);
+    }
+
+    /// Enables or disables V8 startup snapshot use, effective for every clone of this
+    /// pool's next [`Self::checkout`]. Disabling doesn't evict renderers already
+    /// built from a snapshot, it just stops [`Self::snapshot_for`] from building or
+    /// returning one for any profile not yet cached in-process.
+    pub fn set_snapshot_enabled(&self, enabled: bool) {
+        self.snapshot_enabled.store(enabled, Ordering::Relaxed);
+    }
+
+    /// Invalidates every thread's renderer cache, forcing the next [`Self::checkout`]
+    /// on each thread to rebuild its renderers from scratch instead of reusing ones
+    /// that loaded now-stale `static_dir` engine or component files.
+    ///
+    /// Cheap and safe to call from any thread, including one other than the workers
+    /// doing the checkouts - see [`Self::generation`]. Also clears the shared snapshot
+    /// cache, since a stale snapshot would just re-bake the old files into every
+    /// renderer rebuilt from it.
+    pub fn invalidate(&self) {
+        self.generation.fetch_add(1, Ordering::Relaxed);
+        self.snapshots.lock().unwrap().clear();
+    }
+
+    /// Returns this pool configured to persist each profile's built V8 startup
+    /// snapshot under `dir`, keyed by the profile's [`RendererKey`] plus a content
+    /// hash of its static engine files (see
+    /// [`super::engine::hash_static_files`]) - so a fresh process (which starts with
+    /// an empty in-memory [`Self::snapshots`] cache) can load yesterday's blob
+    /// straight off disk instead of re-running `load_static_files_internal`.
+    ///
+    /// A cached blob whose key no longer matches (static files changed, or this is a
+    /// profile never seen before) is treated exactly like an in-memory cache miss:
+    /// [`Self::snapshot_for`] falls through to building a fresh snapshot in-process,
+    /// and - best-effort - writes it back out to `dir` for next time.
+    #[must_use]
+    pub fn with_snapshot_cache_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.snapshot_cache_dir = Some(dir.into());
+        self
+    }
+
+    /// Returns this pool with snapshot use enabled or disabled from construction,
+    /// equivalent to calling [`Self::set_snapshot_enabled`] right after [`Self::new`]
+    /// - see that method's doc.
+    #[must_use]
+    pub fn with_snapshot_enabled(self, enabled: bool) -> Self {
+        self.set_snapshot_enabled(enabled);
+        self
+    }
+
+    /// Warms up the pool by pre-creating renderers for the given profiles.
     ///
-    /// This reduces first-request latency by initializing renderers ahead of time.
-    /// Errors during warming are logged but don't prevent pool creation.
+    /// This reduces first-request latency by initializing renderers (and their V8
+    /// startup snapshots) ahead of time. Errors during warming are logged but don't
+    /// prevent pool creation.
     ///
     /// # Arguments
-    /// * `warm_count` - Number of renderers to pre-create per profile (defaults to 1)
-    pub fn warm(&self, warm_count: usize) {
+    /// * `profiles` - The renderer profiles to warm
+    /// * `warm_count` - Number of renderers to pre-create per profile
+    pub fn warm(&self, profiles: &[RendererProfile], warm_count: usize) {
         if warm_count == 0 {
             return;
         }
 
-        // Warm up common profiles
-        let profiles = [RendererProfile::Engine];
-
-        for profile in profiles.iter() {
-            for _ in 0..warm_count.min(self.max_cached_per_key) {
-                if let Ok(renderer) = self.create_renderer(*profile) {
-                    let key = profile.key();
-                    self.return_renderer(key, renderer);
+        let max_cached_per_key = self.max_cached_per_key.load(Ordering::Relaxed);
+        for profile in profiles {
+            for _ in 0..warm_count.min(max_cached_per_key) {
+                if let Ok(renderer) = self.create_renderer(profile) {
+                    self.return_renderer(profile.key(), renderer);
                 } else {
                     // Log but continue - warming is best-effort
-                    eprintln!("Warning: Failed to warm renderer for profile Engine");
+                    eprintln!(
+                        "Warning: Failed to warm renderer for profile {:?}",
+                        profile.static_dir()
+                    );
                 }
             }
         }
@@ -209,17 +625,29 @@ impl RendererPool {
     /// The renderer is returned to the pool when the `RendererLease` is dropped.
     ///
     /// # Arguments
-    /// * `profile` - The renderer profile (Engine)
+    /// * `profile` - The renderer profile describing the desired configuration
     ///
     /// # Returns
     /// A `RendererLease` containing the renderer, or an error if creation fails
     pub fn checkout<'pool>(
         &'pool self,
-        profile: RendererProfile,
+        profile: &RendererProfile,
     ) -> AnyhowResult<RendererLease<'pool>> {
+        self.evict_if_stale();
+
         let key = profile.key();
-        let renderer =
-            Self::take_cached_renderer(key).map_or_else(|| self.create_renderer(profile), Ok)?;
+        let cached = Self::take_cached_renderer(key);
+
+        Self::record_stat(key, |stats| {
+            stats.checkouts += 1;
+            if cached.is_some() {
+                stats.hits += 1;
+            } else {
+                stats.misses += 1;
+            }
+        });
+
+        let renderer = cached.map_or_else(|| self.create_renderer(profile), Ok)?;
 
         Ok(RendererLease {
             renderer: Some(renderer),
@@ -228,9 +656,208 @@ impl RendererPool {
         })
     }
 
-    fn create_renderer(&self, profile: RendererProfile) -> AnyhowResult<JsRenderer> {
-        match profile {
-            RendererProfile::Engine => JsRenderer::new(&self.static_dir),
+    /// Drops renderers that have been idle (not checked out) longer than `max_idle`,
+    /// across every profile cached on the calling thread.
+    ///
+    /// Because `JsRuntime` is thread-local, this only reaps the calling thread's
+    /// cache. Call it periodically (e.g. from a maintenance task run on each worker
+    /// thread) to bound memory on long-lived threads that had a burst of traffic and
+    /// then went quiet.
+    ///
+    /// # Returns
+    /// The total number of renderers evicted.
+    pub fn reap(&self, max_idle: Duration) -> usize {
+        RENDERER_CACHE.with(|cache| {
+            let mut cache = cache.borrow_mut();
+            let mut total_evicted = 0;
+            cache.retain(|key, entry| {
+                let evicted = entry.reap(max_idle);
+                if evicted > 0 {
+                    total_evicted += evicted;
+                    Self::record_stat(*key, |stats| stats.idle_evictions += evicted as u64);
+                }
+                entry.len() > 0
+            });
+            total_evicted
+        })
+    }
+
+    /// Returns observable statistics for the calling thread's renderer cache.
+    ///
+    /// See [`PoolStats`] for the fields reported. As with [`RendererPool::reap`],
+    /// this only reflects the thread it's called from, since renderers are cached
+    /// per-thread.
+    pub fn stats(&self) -> PoolStats {
+        let cached_per_key: HashMap<RendererKey, usize> =
+            RENDERER_CACHE.with(|cache| cache.borrow().iter().map(|(k, v)| (*k, v.len())).collect());
+
+        let per_key = RENDERER_STATS.with(|stats| {
+            stats
+                .borrow()
+                .iter()
+                .map(|(key, stats)| PoolKeyStats {
+                    key: key.raw(),
+                    cached: cached_per_key.get(key).copied().unwrap_or(0),
+                    checkouts: stats.checkouts,
+                    hits: stats.hits,
+                    misses: stats.misses,
+                    count_evictions: stats.count_evictions,
+                    idle_evictions: stats.idle_evictions,
+                })
+                .collect::<Vec<_>>()
+        });
+
+        PoolStats::from_per_key(per_key)
+    }
+
+    /// Drops the calling thread's entire renderer cache if [`Self::invalidate`] has
+    /// bumped [`Self::generation`] since this thread last rebuilt it. A no-op on
+    /// every checkout in between, so the common (non-`--watch`) path pays only an
+    /// `Ordering::Relaxed` load.
+    fn evict_if_stale(&self) {
+        let current = self.generation.load(Ordering::Relaxed);
+        RENDERER_CACHE_GENERATION.with(|seen| {
+            if seen.get() != current {
+                seen.set(current);
+                RENDERER_CACHE.with(|cache| cache.borrow_mut().clear());
+            }
+        });
+    }
+
+    fn record_stat(key: RendererKey, update: impl FnOnce(&mut KeyStats)) {
+        RENDERER_STATS.with(|stats| {
+            let mut stats = stats.borrow_mut();
+            update(stats.entry(key).or_default());
+        });
+    }
+
+    fn size_hint_for(key: RendererKey) -> usize {
+        RENDERER_SIZE_HINTS.with(|hints| {
+            hints
+                .borrow()
+                .get(&key)
+                .map(SizeHint::estimate)
+                .unwrap_or(0)
+        })
+    }
+
+    fn record_output_size_for(key: RendererKey, actual_len: usize) {
+        RENDERER_SIZE_HINTS.with(|hints| {
+            hints
+                .borrow_mut()
+                .entry(key)
+                .or_insert_with(SizeHint::new)
+                .record(actual_len);
+        });
+    }
+
+    fn create_renderer(&self, profile: &RendererProfile) -> AnyhowResult<JsRenderer> {
+        let key = profile.key();
+        let snapshot = self.snapshot_for(key, profile);
+
+        match snapshot {
+            Some(snapshot) => JsRenderer::create_from_snapshot(&snapshot, profile.static_dir()),
+            None => {
+                // Snapshotting failed (or hasn't been attempted successfully yet) -
+                // fall back to the cold path, registering any baked-in component set
+                // the normal per-render way so the profile's contract still holds.
+                let renderer = JsRenderer::new(profile.static_dir())?;
+                if let Some(set) = &profile.components {
+                    renderer
+                        .register_components(&set.components, set.mode)
+                        .map_err(anyhow::Error::from)?;
+                }
+                Ok(renderer)
+            }
+        }
+    }
+
+    /// Returns the cached snapshot for `key`, building and caching it on first use.
+    ///
+    /// For a plain engine profile this builds the engine-only snapshot; for a profile
+    /// built via [`RendererProfile::with_components`] it builds one with that
+    /// component set's registration also baked in - either way, the same per-key cache
+    /// keeps it from being rebuilt on every miss for the same profile. When
+    /// [`Self::with_snapshot_cache_dir`] was set, a disk-cached blob is tried before
+    /// building, and a freshly-built one is written back out for next time. Returns
+    /// `None` unconditionally when [`Self::set_snapshot_enabled`] has disabled
+    /// snapshot use, without touching the in-memory or disk cache either way.
+    fn snapshot_for(&self, key: RendererKey, profile: &RendererProfile) -> Option<Arc<[u8]>> {
+        if !self.snapshot_enabled.load(Ordering::Relaxed) {
+            return None;
+        }
+
+        let mut snapshots = self.snapshots.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+
+        if let Some(existing) = snapshots.get(&key) {
+            return Some(Arc::clone(existing));
+        }
+
+        if let Some(bytes) = self.read_disk_snapshot(key, profile) {
+            let snapshot: Arc<[u8]> = Arc::from(bytes);
+            snapshots.insert(key, Arc::clone(&snapshot));
+            return Some(snapshot);
+        }
+
+        let built = match &profile.components {
+            Some(set) => build_component_snapshot(profile.static_dir(), &set.components, set.mode),
+            None => build_engine_snapshot(profile.static_dir()),
+        };
+
+        match built {
+            Ok(bytes) => {
+                let snapshot: Arc<[u8]> = Arc::from(bytes);
+                self.write_disk_snapshot(key, profile, &snapshot);
+                snapshots.insert(key, Arc::clone(&snapshot));
+                Some(snapshot)
+            }
+            Err(err) => {
+                // Best-effort: fall back to the cold creation path rather than
+                // failing renderer creation outright.
+                eprintln!(
+                    "Warning: Failed to build V8 startup snapshot for profile {:?}, \
+                     renderers will be created without it: {err}",
+                    profile.static_dir()
+                );
+                None
+            }
+        }
+    }
+
+    /// The path a disk-cached snapshot blob for `key`/`profile` would live at under
+    /// [`Self::snapshot_cache_dir`], or `None` if no cache dir is configured or the
+    /// profile's static files can't be hashed (e.g. missing/unreadable) - in which
+    /// case disk caching is simply skipped for this profile.
+    fn disk_snapshot_path(&self, key: RendererKey, profile: &RendererProfile) -> Option<PathBuf> {
+        let cache_dir = self.snapshot_cache_dir.as_ref()?;
+        let content_hash = super::engine::hash_static_files(profile.static_dir()).ok()?;
+        Some(cache_dir.join(format!("{:016x}-{content_hash}.snapshot", key.raw())))
+    }
+
+    /// Reads a disk-cached snapshot blob for `key`/`profile`, if disk caching is
+    /// configured and a matching file exists. Any I/O error (missing directory, no
+    /// matching file, permissions) is treated as a plain cache miss.
+    fn read_disk_snapshot(&self, key: RendererKey, profile: &RendererProfile) -> Option<Box<[u8]>> {
+        let path = self.disk_snapshot_path(key, profile)?;
+        std::fs::read(path).ok().map(Vec::into_boxed_slice)
+    }
+
+    /// Best-effort write of a freshly-built snapshot blob to disk, so the next
+    /// process to start can load it instead of rebuilding. Failures (cache dir
+    /// doesn't exist, read-only filesystem) are logged but otherwise ignored - the
+    /// in-memory cache this process just populated still works for its own lifetime.
+    fn write_disk_snapshot(&self, key: RendererKey, profile: &RendererProfile, bytes: &[u8]) {
+        let Some(path) = self.disk_snapshot_path(key, profile) else {
+            return;
+        };
+        if let Some(parent) = path.parent() {
+            if let Err(err) = std::fs::create_dir_all(parent) {
+                eprintln!("Warning: Failed to create snapshot cache dir {}: {err}", parent.display());
+                return;
+            }
+        }
+        if let Err(err) = std::fs::write(&path, bytes) {
+            eprintln!("Warning: Failed to write snapshot cache file {}: {err}", path.display());
         }
     }
 
@@ -248,11 +875,14 @@ impl RendererPool {
     }
 
     fn return_renderer(&self, key: RendererKey, renderer: JsRenderer) {
-        RENDERER_CACHE.with(|cache| {
+        let evicted = RENDERER_CACHE.with(|cache| {
             let mut cache = cache.borrow_mut();
             let entry = cache.entry(key).or_insert_with(CacheEntry::new);
             // Use LRU eviction: remove oldest if at capacity
-            entry.push_with_limit(renderer, self.max_cached_per_key);
+            entry.push_with_limit(renderer, self.max_cached_per_key.load(Ordering::Relaxed))
         });
+        if evicted {
+            Self::record_stat(key, |stats| stats.count_evictions += 1);
+        }
     }
 }
@@ -0,0 +1,84 @@
+//! Thread-local cache of built component registration scripts.
+//!
+//! `build_component_registration_script` previously re-ran [`crate::transform::transform_component_code`]
+//! and re-built the registration wrapper for every component on every batch render. The
+//! transform step is already memoized process-wide by [`crate::transform_cache`], but the
+//! registration snippet built around it (the `globalThis[name] = resolved` wrapper) was not.
+//! This module caches that finished snippet, keyed by a fast hash of the component's source
+//! plus the registration name and emission mode (both of which are baked into the snippet),
+//! so batches that repeatedly render the same components skip rebuilding the wrapper too.
+//!
+//! This cache lives alongside the renderer pool's cache (see [`super::pool`]): thread-local,
+//! because it is only ever consulted from the same thread that renders with it. Unlike the
+//! pool's per-profile LRU, it is bounded by a fixed FIFO eviction cap rather than per-entry
+//! recency tracking.
+
+use crate::error::MdxError;
+use std::cell::RefCell;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, VecDeque};
+use std::hash::{Hash, Hasher};
+use std::rc::Rc;
+
+/// Maximum number of distinct registration snippets retained per thread before the
+/// oldest is evicted. Half the process-wide [`crate::transform_cache`] bound, since
+/// registration snippets are only reused within a single thread's batch of requests.
+const MAX_CACHED_REGISTRATIONS: usize = 256;
+
+thread_local! {
+    static REGISTRATION_CACHE: RefCell<RegistrationCacheState> =
+        RefCell::new(RegistrationCacheState::new());
+}
+
+struct RegistrationCacheState {
+    map: HashMap<u64, Rc<str>>,
+    /// Insertion order, oldest first, used for FIFO eviction.
+    order: VecDeque<u64>,
+}
+
+impl RegistrationCacheState {
+    fn new() -> Self {
+        Self {
+            map: HashMap::new(),
+            order: VecDeque::new(),
+        }
+    }
+
+    fn insert(&mut self, key: u64, snippet: Rc<str>) {
+        if self.map.contains_key(&key) {
+            return;
+        }
+        if self.order.len() >= MAX_CACHED_REGISTRATIONS {
+            if let Some(oldest) = self.order.pop_front() {
+                self.map.remove(&oldest);
+            }
+        }
+        self.order.push_back(key);
+        self.map.insert(key, snippet);
+    }
+}
+
+/// Computes a stable cache key from a component's source code, its registration name,
+/// and the emission mode - the three inputs that determine the built snippet's contents.
+pub(super) fn cache_key(code: &str, registration_name: &str, mode_tag: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    code.hash(&mut hasher);
+    registration_name.hash(&mut hasher);
+    mode_tag.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Returns the cached registration snippet for `key`, building and storing it via
+/// `build` on a cache miss. Scoped to the current thread.
+pub(super) fn get_or_build(
+    key: u64,
+    build: impl FnOnce() -> Result<String, MdxError>,
+) -> Result<Rc<str>, MdxError> {
+    if let Some(cached) = REGISTRATION_CACHE.with(|cache| cache.borrow().map.get(&key).cloned()) {
+        return Ok(cached);
+    }
+
+    let snippet: Rc<str> = Rc::from(build()?);
+    REGISTRATION_CACHE.with(|cache| cache.borrow_mut().insert(key, snippet.clone()));
+    Ok(snippet)
+}
@@ -2,13 +2,15 @@
 //!
 //! This module handles JsRuntime lifecycle, cleanup, and value extraction.
 
-use crate::error::MdxError;
+use crate::error::{MdxError, ParseError, SourceLocation};
 use anyhow::Result as AnyhowResult;
-use deno_core::JsRuntime;
+use deno_core::{JsRuntime, ModuleSpecifier};
 use std::cell::RefCell;
 use std::rc::Rc;
 
 use super::constants::script_tags;
+use super::scripts::PERMISSION_DENIED_PREFIX;
+use super::source_map;
 
 /// Estimated overhead for context setup script (wrapper code, try-catch, etc.)
 /// This is used to pre-allocate string capacity for better performance.
@@ -100,6 +102,17 @@ pub(super) fn with_runtime<R>(
 }
 
 /// Cleans up the JavaScript runtime by removing registered components and globals
+///
+/// This only undoes what the classic script-injection render paths
+/// (`JsRenderer::render_component`/`render_transformed_component` and friends)
+/// install directly on `globalThis`. Module-based renders
+/// (`JsRenderer::render_module_component_async`) don't need anything cleaned up here:
+/// `deno_core` has no stable public API to evict entries from a `JsRuntime`'s module
+/// map, so rather than fight that, `module_loader::ComponentModuleLoader` instead
+/// gives every render's entry/component modules a fresh, never-before-seen specifier
+/// (see its generation counter) - the stale modules from earlier renders are simply
+/// never looked up again, left to be reclaimed whenever this renderer itself is
+/// reaped by `pool::RendererPool`.
 pub(super) fn cleanup_runtime(runtime: &mut JsRuntime) -> Result<(), MdxError> {
     const CLEANUP_SCRIPT: &str = r#"
         try {
@@ -123,6 +136,7 @@ pub(super) fn cleanup_runtime(runtime: &mut JsRuntime) -> Result<(), MdxError> {
             }
 
             delete globalThis.context;
+            delete globalThis.__dinja_render_target;
         } catch (cleanupError) {
             console.warn('Renderer cleanup failed', cleanupError);
         }
@@ -180,23 +194,260 @@ pub(super) fn setup_context(runtime: &mut JsRuntime, props_json: &str) -> Result
     Ok(())
 }
 
-/// Extracts a string value from a V8 result handle
-pub(super) fn extract_string_from_v8(
+/// Translates an `execute_script` failure into an [`MdxError`], resolving the V8 error
+/// position against `source_map` (the render script's source map, as returned alongside
+/// it by [`super::scripts::component_render_script`]/[`super::scripts::schema_render_script`])
+/// when one is present.
+///
+/// Takes the error's `{:?}` formatting rather than the error itself, since
+/// `execute_script`'s error type varies by Deno Core version and every other error site
+/// in this module already just formats it this way.
+///
+/// When a map is present and a position can be resolved, the returned error is a
+/// `TsxTransform` carrying a [`SourceLocation`] that points at the author's original
+/// TSX rather than the generated render script - the whole point of
+/// [`crate::models::TsxTransformConfig::with_source_maps`]. When the map also
+/// embeds `sourcesContent` for that position, the offending line (with a `^` caret
+/// under the column) is attached as the [`ParseError`]'s help text. Falls back to a
+/// plain message (matching every other `execute_script` error site in this module)
+/// when there's no map, or the V8 error doesn't carry a position we can parse.
+pub(super) fn translate_execution_error(
+    error_debug: &str,
+    source_map: Option<&str>,
+    context: &str,
+) -> MdxError {
+    // A capability trap installed by `JsRenderer::apply_permissions` throws an error
+    // prefixed with `PERMISSION_DENIED_PREFIX` - surface it as its own `MdxError`
+    // variant instead of a generic `TsxTransform`, so callers can tell a denied
+    // capability apart from an ordinary component bug.
+    if let Some(idx) = error_debug.find(PERMISSION_DENIED_PREFIX) {
+        let capability = error_debug[idx + PERMISSION_DENIED_PREFIX.len()..]
+            .lines()
+            .next()
+            .unwrap_or_default()
+            .trim();
+        return MdxError::PermissionDenied(capability.to_string());
+    }
+
+    if let Some(map_json) = source_map {
+        if let Some((line, column)) = extract_v8_position(error_debug, script_tags::RENDER) {
+            if let Some(pos) = source_map::lookup(map_json, line, column) {
+                let location = SourceLocation::new(pos.line, pos.column, 0, 0);
+                let message = format!(
+                    "{context} (originally {}:{}:{}): {error_debug}",
+                    pos.source,
+                    pos.line + 1,
+                    pos.column + 1
+                );
+                let mut error = ParseError::with_location(message, location);
+                if let Some(snippet) = pos.snippet {
+                    error = error.with_help(snippet);
+                }
+                return MdxError::TsxTransform(vec![error]);
+            }
+        }
+    }
+
+    MdxError::tsx_transform(format!("{context}: {error_debug}"))
+}
+
+/// Parses a V8 stack frame position of the form `<specifier>:<line>:<column>` out of a
+/// formatted error, returning 0-indexed `(line, column)`. V8 reports 1-indexed
+/// positions, so this converts on the way out.
+fn extract_v8_position(debug: &str, specifier: &str) -> Option<(u32, u32)> {
+    let marker = format!("{specifier}:");
+    let idx = debug.rfind(&marker)?;
+    let rest = &debug[idx + marker.len()..];
+
+    let line_end = rest.find(|c: char| !c.is_ascii_digit())?;
+    if line_end == 0 || rest.as_bytes().get(line_end) != Some(&b':') {
+        return None;
+    }
+    let line: u32 = rest[..line_end].parse().ok()?;
+
+    let after = &rest[line_end + 1..];
+    let col_end = after
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(after.len());
+    if col_end == 0 {
+        return None;
+    }
+    let column: u32 = after[..col_end].parse().ok()?;
+
+    Some((line.saturating_sub(1), column.saturating_sub(1)))
+}
+
+/// Async counterpart to the execute-then-extract sequence every synchronous render
+/// method runs through [`with_runtime`]: sets up `context`, executes the dynamic
+/// resolution `script`, then `finish_script` - then, unlike the synchronous path,
+/// drives the event loop to completion and resolves the result before extracting it.
+/// A component that returns a Promise (e.g. one using top-level `await` to fetch data
+/// during SSR) finishes and yields its resolved value this way, instead of the
+/// synchronous path's unresolved `[object Promise]`.
+///
+/// Must be awaited on a single-threaded executor (e.g. a per-thread
+/// `tokio::runtime::Builder::new_current_thread`, mirroring the thread-local
+/// [`super::pool::RendererPool`]): `JsRuntime` is `!Send`, so this future can't be
+/// polled from a multi-threaded executor that might resume it on a different thread
+/// between polls.
+///
+/// As with [`with_runtime`], cleanup happens via [`RuntimeCleanupGuard`] on drop.
+///
+/// `max_render_time_ms` (typically
+/// [`crate::models::ResourceLimits::max_render_time_ms`]), if set, bounds how long the
+/// event loop may run before this returns [`MdxError::RenderTimeout`] - guarding
+/// against a component awaiting a promise that never settles.
+pub(super) async fn execute_and_extract_async(
+    runtime: Rc<RefCell<JsRuntime>>,
+    props_json: &str,
+    script_tag: &'static str,
+    script: String,
+    finish_script: &'static str,
+    source_map: Option<&str>,
+    context: &str,
+    max_render_time_ms: Option<u64>,
+) -> AnyhowResult<String> {
+    debug_assert!(
+        finish_script.is_ascii(),
+        "finish script must be pure ASCII to be handed to V8 as an external one-byte string"
+    );
+
+    let mut rt = runtime.try_borrow_mut().map_err(|e| {
+        anyhow::anyhow!(
+            "Failed to borrow runtime mutably: {e}. This may indicate concurrent access, \
+             cleanup in progress, or recursive runtime operations within the same thread."
+        )
+    })?;
+
+    let _cleanup = RuntimeCleanupGuard::new(Rc::clone(&runtime));
+
+    setup_context(&mut rt, props_json).map_err(anyhow::Error::from)?;
+
+    // Resolution script: the dynamic component bootstrap/code/props wrapper built by
+    // `scripts::build_render_script_wrapper`. Just resolves the component to render and
+    // publishes it to `globalThis` - no value worth resolving yet.
+    rt.execute_script(script_tag, script).map_err(|e| {
+        anyhow::Error::from(translate_execution_error(&format!("{e:?}"), source_map, context))
+    })?;
+
+    // Finish script: static across every render (see `scripts::RENDER_COMPONENT_FINISH_SCRIPT`/
+    // `scripts::RENDER_SCHEMA_FINISH_SCRIPT`) - calls the published component and
+    // renders it. Kept as a separate `execute_script` call, rather than folded into the
+    // resolution script, so it can be handed to V8 as a genuine `&'static str`.
+    let global = rt
+        .execute_script(script_tags::RENDER_FINISH, finish_script)
+        .map_err(|e| {
+            anyhow::Error::from(translate_execution_error(&format!("{e:?}"), source_map, context))
+        })?;
+
+    match max_render_time_ms {
+        Some(ms) => tokio::time::timeout(
+            std::time::Duration::from_millis(ms),
+            rt.run_event_loop(false),
+        )
+        .await
+        .map_err(|_elapsed| anyhow::Error::from(MdxError::RenderTimeout(ms)))?
+        .map_err(|e| {
+            anyhow::Error::from(translate_execution_error(&format!("{e:?}"), source_map, context))
+        })?,
+        None => rt.run_event_loop(false).await.map_err(|e| {
+            anyhow::Error::from(translate_execution_error(&format!("{e:?}"), source_map, context))
+        })?,
+    }
+
+    let resolved = rt.resolve_value(global).await.map_err(|e| {
+        anyhow::Error::from(translate_execution_error(&format!("{e:?}"), source_map, context))
+    })?;
+
+    extract_string_from_v8(resolved, &mut rt, "Failed to convert result to string")
+        .map_err(anyhow::Error::from)
+}
+
+/// Async module-loading counterpart to [`execute_and_extract_async`]: instead of
+/// executing a classic script, loads and evaluates `entry_specifier` as an ES module
+/// (via the runtime's installed
+/// [`super::module_loader::ComponentModuleLoader`]), driving the event loop to
+/// resolve the evaluation, then reads the render result back out with
+/// `result_script` - a small classic script that reads the `globalThis` property the
+/// evaluated module assigned its output to (see
+/// [`super::scripts::module_entry_script`]), since a module's own top-level scope
+/// isn't otherwise observable from Rust without walking its namespace object.
+///
+/// See [`execute_and_extract_async`] for the single-threaded executor requirement this
+/// comes with, and for what `max_render_time_ms` bounds.
+pub(super) async fn execute_module_and_extract_async(
+    runtime: Rc<RefCell<JsRuntime>>,
+    props_json: &str,
+    entry_specifier: &ModuleSpecifier,
+    result_script: &'static str,
+    context: &str,
+    max_render_time_ms: Option<u64>,
+) -> AnyhowResult<String> {
+    let mut rt = runtime.try_borrow_mut().map_err(|e| {
+        anyhow::anyhow!(
+            "Failed to borrow runtime mutably: {e}. This may indicate concurrent access, \
+             cleanup in progress, or recursive runtime operations within the same thread."
+        )
+    })?;
+
+    let _cleanup = RuntimeCleanupGuard::new(Rc::clone(&runtime));
+
+    setup_context(&mut rt, props_json).map_err(anyhow::Error::from)?;
+
+    let module_id = rt
+        .load_main_module(entry_specifier, None)
+        .await
+        .map_err(|e| anyhow::anyhow!("{context}: failed to load module {entry_specifier}: {e:?}"))?;
+
+    let evaluation = rt.mod_evaluate(module_id);
+    match max_render_time_ms {
+        Some(ms) => tokio::time::timeout(
+            std::time::Duration::from_millis(ms),
+            rt.run_event_loop(false),
+        )
+        .await
+        .map_err(|_elapsed| anyhow::Error::from(MdxError::RenderTimeout(ms)))?
+        .map_err(|e| anyhow::anyhow!("{context}: {e:?}"))?,
+        None => rt.run_event_loop(false).await.map_err(|e| anyhow::anyhow!("{context}: {e:?}"))?,
+    }
+    evaluation.await.map_err(|e| anyhow::anyhow!("{context}: {e:?}"))?;
+
+    let result = rt
+        .execute_script(script_tags::RENDER, result_script)
+        .map_err(|e| anyhow::anyhow!("{context}: failed to read render result: {e:?}"))?;
+
+    extract_string_from_v8(result, &mut rt, "Failed to convert result to string")
+        .map_err(anyhow::Error::from)
+}
+
+/// Deserializes a V8 result handle directly into `T` via `serde_v8`, instead of the
+/// `JSON.stringify`-on-the-JS-side-then-`serde_json::from_str`-on-the-Rust-side round
+/// trip callers used to need for anything beyond a plain string. Lets the Schema
+/// output path (and anything else reading structured data back out of an isolate,
+/// e.g. [`super::JsRenderer::collect_coverage`]) deserialize straight into a real
+/// `struct`/`HashMap`/etc., or into a [`serde_json::Value`] for the untyped case.
+///
+/// `error_msg` is prefixed onto whatever `serde_v8` reports on a type mismatch - for
+/// a struct target, that includes the name of the field deserialization failed on.
+pub(super) fn extract_value_from_v8<T: serde::de::DeserializeOwned>(
     result: deno_core::v8::Global<deno_core::v8::Value>,
     runtime: &mut JsRuntime,
     error_msg: &str,
-) -> Result<String, MdxError> {
+) -> Result<T, MdxError> {
     let scope = &mut runtime.handle_scope();
     let local = deno_core::v8::Local::new(scope, result);
 
-    if local.is_string() {
-        local
-            .to_string(scope)
-            .map(|s| s.to_rust_string_lossy(scope))
-            .ok_or_else(|| MdxError::TsxTransform(error_msg.to_string()))
-    } else {
-        Err(MdxError::TsxTransform(format!(
-            "{error_msg}: result is not a string"
-        )))
-    }
+    deno_core::serde_v8::from_v8(scope, local)
+        .map_err(|e| MdxError::TsxTransform(format!("{error_msg}: {e}")))
+}
+
+/// Extracts a string value from a V8 result handle - a thin wrapper over
+/// [`extract_value_from_v8`] for the common case of a render result that's already a
+/// plain string.
+pub(super) fn extract_string_from_v8(
+    result: deno_core::v8::Global<deno_core::v8::Value>,
+    runtime: &mut JsRuntime,
+    error_msg: &str,
+) -> Result<String, MdxError> {
+    extract_value_from_v8(result, runtime, error_msg)
 }
@@ -1,55 +1,127 @@
 //! Script generation for component rendering
 //!
 //! This module handles the generation of JavaScript code for rendering components,
-//! component registration, and script wrappers.
+//! component registration, and script wrappers. Built registration snippets are
+//! memoized per-thread in [`super::registration_cache`], and components can be
+//! registered either in their own IIFE or concatenated into one shared scope - see
+//! [`RegistrationMode`].
+//!
+//! ## Source Maps
+//!
+//! When [`crate::models::TsxTransformConfig::with_source_maps`] is enabled, a
+//! transformed fragment (a component's code, or a whole transformed TSX file) carries
+//! an inline `//# sourceMappingURL=...` comment at its end. Every function here that
+//! splices such a fragment into a larger script extracts that map
+//! ([`crate::transform::extract_inline_source_map`]), counts how many lines of wrapper
+//! text precede the fragment, shifts the map by that many lines
+//! ([`crate::transform::shift_source_map_lines`]), and re-embeds it at the end of its
+//! own output - so a map produced by the original Oxc transform stays correctly
+//! targeted no matter how many wrapper layers it passes through before reaching the
+//! script actually handed to V8.
 
+use super::registration_cache;
 use crate::error::MdxError;
 use crate::models::ComponentDefinition;
+use crate::transform::{extract_inline_source_map, inline_source_map_comment, shift_source_map_lines};
 use std::collections::HashMap;
+use std::sync::OnceLock;
 
-/// Generates JavaScript code to resolve a component from various export patterns
-///
-/// # Arguments
-/// * `var_name` - Name of the variable to assign the resolved component to
-/// * `throw_on_not_found` - If true, throws an error if component is not found
-///
-/// # Returns
-/// JavaScript code that resolves View, Component (for backwards compatibility), module.exports.default, or module.exports
-/// Generates JavaScript code to resolve the main View component for rendering
+/// Number of `\n` bytes in `s`, used to compute how many lines a fragment of wrapper
+/// text adds in front of the next thing spliced into the script.
+fn count_lines(s: &str) -> u32 {
+    s.matches('\n').count() as u32
+}
+
+/// Generates JavaScript code to resolve the main View component for rendering.
 /// Throws an error if component is not found.
-pub(super) fn component_resolution_code() -> String {
-    format!(
-        r#"
+///
+/// Pure ASCII with no interpolation, so unlike the dynamic fragments this is a plain
+/// `&'static str` rather than a `format!`-produced `String` - it never needs to be
+/// reallocated or re-copied, and is cheap to hand to V8 as-is on every render.
+pub(super) const fn component_resolution_code() -> &'static str {
+    r#"
             let ComponentToRender = typeof View !== 'undefined' ? View : (typeof Component !== 'undefined' ? Component : null);
-            if (!ComponentToRender && module && module.exports) {{
+            if (!ComponentToRender && module && module.exports) {
                 ComponentToRender = module.exports.default || module.exports;
-            }}
-            if (!ComponentToRender && exports) {{
+            }
+            if (!ComponentToRender && exports) {
                 ComponentToRender = exports.default || exports;
-            }}
-            if (!ComponentToRender) {{
+            }
+            if (!ComponentToRender) {
                 throw new Error('Component not found. Expected View, Component or default export.');
-            }}
+            }
     "#
-    )
 }
 
 /// Helper function to resolve a component being registered (NOT the main View)
 /// Returns JavaScript code that resolves Component, module.exports.default, or module.exports
 /// Does NOT look for View - View is the MDX content being rendered, not a component to register
 /// Does not throw an error if component is not found (caller should check).
-pub(super) fn resolve_component_code() -> String {
-    format!(
-        r#"
+///
+/// Pure ASCII with no interpolation - see [`component_resolution_code`] for why this is
+/// a `&'static str` rather than a `format!`-produced `String`.
+pub(super) const fn resolve_component_code() -> &'static str {
+    r#"
             let resolved = typeof Component !== 'undefined' ? Component : null;
-            if (!resolved && module && module.exports) {{
+            if (!resolved && module && module.exports) {
                 resolved = module.exports.default || module.exports;
-            }}
-            if (!resolved && exports) {{
+            }
+            if (!resolved && exports) {
                 resolved = exports.default || exports;
-            }}
+            }
     "#
-    )
+}
+
+/// `globalThis` property the resolution script built by [`build_render_script_wrapper`]
+/// publishes the resolved component under, for [`RENDER_COMPONENT_FINISH_SCRIPT`]/
+/// [`RENDER_SCHEMA_FINISH_SCRIPT`] to read back once it runs as a separate
+/// `execute_script` call.
+const RENDER_TARGET_GLOBAL: &str = "__dinja_render_target";
+
+/// A render script split into its constant and per-render parts.
+///
+/// `static_prefix` and `static_suffix` are the same bytes on every render (they never
+/// contain interpolated content - rendering itself has moved out into a separate static
+/// finish script, see [`RENDER_COMPONENT_FINISH_SCRIPT`]/[`RENDER_SCHEMA_FINISH_SCRIPT`]),
+/// so a caller that wants to avoid re-copying them into V8 on every render can hand them
+/// to V8 as external one-byte strings (the technique Deno itself uses for its own ASCII
+/// bootstrap scripts) and copy only `dynamic` - the component bootstrap, component code,
+/// and props - which genuinely differs per render. [`RenderScriptFragments::into_parts`]
+/// is provided for callers that want the fused script (plus its source map, if any).
+pub(super) struct RenderScriptFragments {
+    /// Constant wrapper prologue: the opening IIFE.
+    pub(super) static_prefix: &'static str,
+    /// Per-render content: component bootstrap, component code, and props.
+    pub(super) dynamic: String,
+    /// Constant epilogue: component resolution, publishing the resolved component to
+    /// [`RENDER_TARGET_GLOBAL`], and the closing IIFE.
+    pub(super) static_suffix: &'static str,
+    /// The component code's source map, already shifted to this script's line numbers,
+    /// if source maps are enabled for this render. This is the map that should be
+    /// consulted to translate a V8 error position back to the author's original TSX.
+    pub(super) source_map: Option<String>,
+}
+
+impl RenderScriptFragments {
+    /// Concatenates the fragments into a single script (with the source map, if any,
+    /// appended as the final inline comment), and returns the map separately so callers
+    /// can translate a later execution error without re-parsing the script.
+    pub(super) fn into_parts(self) -> (String, Option<String>) {
+        let mut script = String::with_capacity(
+            self.static_prefix.len()
+                + self.dynamic.len()
+                + self.static_suffix.len()
+                + self.source_map.as_ref().map_or(0, |m| m.len() + 64),
+        );
+        script.push_str(self.static_prefix);
+        script.push_str(&self.dynamic);
+        script.push_str(self.static_suffix);
+        if let Some(map) = &self.source_map {
+            script.push('\n');
+            script.push_str(&inline_source_map_comment(map));
+        }
+        (script, self.source_map)
+    }
 }
 
 /// Builds a render script wrapper with common component resolution logic
@@ -60,11 +132,14 @@ pub(super) fn resolve_component_code() -> String {
 /// - **Pre-allocation**: Estimates total capacity and pre-allocates to avoid multiple reallocations
 /// - **`write!` macro**: More efficient than `format!` for building strings incrementally
 /// - **Capacity estimation**: Adds ~200 bytes overhead for wrapper code (function declaration, etc.)
+/// - **Static/dynamic split**: The constant prologue/epilogue are returned as
+///   `&'static str`s rather than folded into the allocated `dynamic` string - see
+///   [`RenderScriptFragments`].
 ///
 /// ## String Allocation Strategy
 ///
-/// We pre-allocate with `String::with_capacity()` based on the sum of input lengths plus overhead.
-/// This strategy works well because:
+/// We pre-allocate `dynamic` with `String::with_capacity()` based on the sum of input
+/// lengths plus overhead. This strategy works well because:
 /// - Input sizes are known at call time
 /// - The final string size is predictable (inputs + fixed wrapper)
 /// - Avoids multiple reallocations during string building
@@ -73,31 +148,34 @@ pub(super) fn build_render_script_wrapper(
     component_bootstrap: &str,
     component_code: &str,
     props_json: &str,
-    render_body: &str,
-) -> Result<String, MdxError> {
+) -> Result<RenderScriptFragments, MdxError> {
+    const STATIC_PREFIX: &str = "\n        (function() {\n";
+
+    let (component_code, component_map) = extract_inline_source_map(component_code);
+
     // Pre-allocate with estimated capacity for better performance
     // Strategy: Sum all input lengths + fixed overhead to avoid reallocations
-    let estimated_capacity = component_bootstrap.len()
-        + component_code.len()
-        + props_json.len()
-        + render_body.len()
-        + 200; // Base script overhead (function wrapper, etc.)
-    let mut script = String::with_capacity(estimated_capacity);
+    let estimated_capacity =
+        component_bootstrap.len() + component_code.len() + props_json.len() + 150;
+    let mut dynamic = String::with_capacity(estimated_capacity);
 
     // Use write! macro for better performance in hot path
     use std::fmt::Write;
-    let component_resolution = component_resolution_code();
     write!(
-        script,
+        dynamic,
+        "\n            {component_bootstrap}\n\n            // Execute the component code\n            ",
+    )
+    .map_err(|e| MdxError::TsxTransform(format!("Failed to write script wrapper: {e}")))?;
+
+    // `component_code` is spliced in right here, so this is how many lines of the
+    // final script precede it - what its (if any) source map needs to be shifted by.
+    let component_code_line_offset = count_lines(STATIC_PREFIX) + count_lines(&dynamic);
+    dynamic.push_str(component_code);
+
+    write!(
+        dynamic,
         r#"
-        (function() {{
-            {component_bootstrap}
 
-            // Execute the component code
-            {component_code}
-            
-            {component_resolution}
-            
             // Context originates from trusted serde_json serialization
             // Create context function using reducer pattern for dotted path access
             const contextData = {props_json};
@@ -109,67 +187,118 @@ pub(super) fn build_render_script_wrapper(
                     }}, options);
                 }};
             }})(contextData);
-            
-            {render_body}
-        }})()
         "#,
-        component_bootstrap = component_bootstrap,
-        component_code = component_code,
-        component_resolution = component_resolution,
-        props_json = props_json,
-        render_body = render_body
     )
     .map_err(|e| MdxError::TsxTransform(format!("Failed to write script wrapper: {e}")))?;
 
-    Ok(script)
+    let source_map =
+        component_map.and_then(|map| shift_source_map_lines(&map, component_code_line_offset));
+
+    Ok(RenderScriptFragments {
+        static_prefix: STATIC_PREFIX,
+        dynamic,
+        static_suffix: render_wrapper_static_suffix(),
+        source_map,
+    })
+}
+
+/// The constant epilogue appended after the dynamic component code in every render
+/// script: resolves the component to render (see [`component_resolution_code`]),
+/// publishes it to `globalThis.__dinja_render_target` for the static finish script
+/// (see [`RENDER_COMPONENT_FINISH_SCRIPT`]/[`RENDER_SCHEMA_FINISH_SCRIPT`]) to pick up,
+/// then closes the wrapping IIFE. Identical for every render kind now that rendering
+/// itself has moved into a separate finish script, so this is built once and cached
+/// rather than reconstructed (even though it contains no per-render interpolation, it's
+/// still assembled from [`component_resolution_code`] via `format!`, so caching avoids
+/// redoing that on every render).
+fn render_wrapper_static_suffix() -> &'static str {
+    static SUFFIX: OnceLock<String> = OnceLock::new();
+    SUFFIX.get_or_init(|| {
+        format!(
+            "\n            {}\n\n            globalThis.{RENDER_TARGET_GLOBAL} = ComponentToRender;\n        }})()\n        ",
+            component_resolution_code()
+        )
+    })
 }
 
-/// Generates a render script for standard engine components
+/// Generates a render script for standard engine components.
+///
+/// Returns the script alongside its (already line-shifted) source map, if source maps
+/// are enabled - callers keep the map around to translate a later V8 execution error
+/// without re-parsing the script to pull it back out.
 pub(super) fn component_render_script(
     component_code: &str,
     props_json: &str,
-) -> Result<String, MdxError> {
-    const RENDER_BODY: &str = r#"
-            // Render using engine-render-to-string
-            if (typeof engine_to_string !== 'undefined' && engine_to_string) {
-                return engine_to_string(ComponentToRender(context));
-            } else if (typeof engine_to_string !== 'undefined' && engine_to_string.renderToString) {
-                return engine_to_string.renderToString(ComponentToRender(context));
-            } else {
-                throw new Error('engine_to_string not available');
-            }
-    "#;
-
-    build_render_script_wrapper("", component_code, props_json, RENDER_BODY)
+) -> Result<(String, Option<String>), MdxError> {
+    build_render_script_wrapper("", component_code, props_json).map(RenderScriptFragments::into_parts)
 }
 
-/// Generates a render script for schema output using core.js engine
+/// Static finish script for [`component_render_script`]: reads the component published
+/// to `globalThis.__dinja_render_target` by the resolution script, along with the
+/// `context` already set up by [`super::runtime::setup_context`], and renders it to an
+/// HTML string.
+///
+/// Never changes across renders, so unlike the resolution script it's handed to
+/// `execute_script` as a genuine `&'static str` rather than a freshly built `String` -
+/// letting V8 wrap it as an external one-byte string instead of copying it in on every
+/// render. Must stay pure ASCII (the external one-byte representation is invalid for
+/// non-ASCII text, hence the `debug_assert!` at its call sites).
+pub(super) const RENDER_COMPONENT_FINISH_SCRIPT: &str = r#"
+(function() {
+    const ComponentToRender = globalThis.__dinja_render_target;
+    // Render using engine-render-to-string
+    if (typeof engine_to_string !== 'undefined' && engine_to_string) {
+        return engine_to_string(ComponentToRender(context));
+    } else if (typeof engine_to_string !== 'undefined' && engine_to_string.renderToString) {
+        return engine_to_string.renderToString(ComponentToRender(context));
+    } else {
+        throw new Error('engine_to_string not available');
+    }
+})()
+"#;
+
+/// Generates a render script for schema output using core.js engine.
+///
+/// See [`component_render_script`] for why this returns the source map alongside the
+/// script.
 pub(super) fn schema_render_script(
     component_code: &str,
     props_json: &str,
-) -> Result<String, MdxError> {
-    const RENDER_BODY: &str = r#"
-            // Render using core.js engine.render() which returns JSON string
-            // Use coreEngine (saved from core.js) for schema rendering
-            if (typeof coreEngine !== 'undefined' && coreEngine && typeof coreEngine.render === 'function') {
-                return coreEngine.render(ComponentToRender, context);
-            } else if (typeof engine !== 'undefined' && engine && typeof engine.render === 'function') {
-                // Fallback to engine if coreEngine not available
-                return engine.render(ComponentToRender, context);
-            } else {
-                throw new Error('core.js engine not available. Expected coreEngine or engine with render method.');
-            }
-    "#;
-
-    build_render_script_wrapper("", component_code, props_json, RENDER_BODY)
+) -> Result<(String, Option<String>), MdxError> {
+    build_render_script_wrapper("", component_code, props_json).map(RenderScriptFragments::into_parts)
 }
 
-/// Wraps transformed component code with bootstrap
+/// Static finish script for [`schema_render_script`] - see
+/// [`RENDER_COMPONENT_FINISH_SCRIPT`] for why this is a genuine `&'static str` rather
+/// than a built `String`.
+pub(super) const RENDER_SCHEMA_FINISH_SCRIPT: &str = r#"
+(function() {
+    const ComponentToRender = globalThis.__dinja_render_target;
+    // Render using core.js engine.render() which returns JSON string
+    // Use coreEngine (saved from core.js) for schema rendering
+    if (typeof coreEngine !== 'undefined' && coreEngine && typeof coreEngine.render === 'function') {
+        return coreEngine.render(ComponentToRender, context);
+    } else if (typeof engine !== 'undefined' && engine && typeof engine.render === 'function') {
+        // Fallback to engine if coreEngine not available
+        return engine.render(ComponentToRender, context);
+    } else {
+        throw new Error('core.js engine not available. Expected coreEngine or engine with render method.');
+    }
+})()
+"#;
+
+/// Wraps transformed component code with bootstrap.
+///
+/// If `transformed_js` carries an inline source map (see the module docs), it is
+/// re-shifted to account for the wrapper text added here and re-embedded at the end
+/// of the returned script.
 pub(super) fn wrap_transformed_component(
     component_bootstrap: &str,
     transformed_js: &str,
     component_names: &[String],
 ) -> String {
+    let (transformed_js, transformed_map) = extract_inline_source_map(transformed_js);
+
     // Generate variable declarations for components
     let mut component_vars = String::new();
     for name in component_names {
@@ -184,26 +313,68 @@ pub(super) fn wrap_transformed_component(
         String::new()
     };
 
-    format!(
-        r#"
-        {component_bootstrap}
-
-        // Make registered components available as variables
-{component_vars}
-        // Transformed component code
-        {transformed_js}
-        "#,
-        component_bootstrap = wrapped_bootstrap,
-        component_vars = component_vars,
-        transformed_js = transformed_js
+    let mut script = String::with_capacity(
+        wrapped_bootstrap.len() + component_vars.len() + transformed_js.len() + 100,
+    );
+    use std::fmt::Write;
+    write!(
+        script,
+        "\n        {wrapped_bootstrap}\n\n        // Make registered components available as variables\n{component_vars}\n        // Transformed component code\n        ",
     )
+    .expect("writing to a String cannot fail");
+
+    // `transformed_js` is spliced in right here.
+    let transformed_js_line_offset = count_lines(&script);
+    script.push_str(transformed_js);
+    script.push_str("\n        ");
+
+    if let Some(map) = transformed_map.and_then(|map| shift_source_map_lines(&map, transformed_js_line_offset)) {
+        script.push('\n');
+        script.push_str(&inline_source_map_comment(&map));
+    }
+
+    script
 }
 
-/// Builds the registration script for a single component
-pub(super) fn build_single_component_registration(
+/// Selects how a batch of components is wired into the registration script.
+///
+/// Public (re-exported as [`crate::renderer::RegistrationMode`]) because
+/// [`crate::renderer::pool::RendererProfile::with_components`] takes it directly, so
+/// a caller that wants a V8 startup snapshot with components baked in can pick the
+/// same mode a cold render would have used.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RegistrationMode {
+    /// Each component is resolved inside its own IIFE (one V8 closure per component).
+    Separate,
+    /// All components are resolved inside a single shared IIFE, each in its own block
+    /// scope rather than its own closure - analogous to bundler module concatenation,
+    /// trading per-component isolation for less V8 parse/scope overhead.
+    Concatenated,
+}
+
+impl RegistrationMode {
+    /// Stable tag used as part of the registration cache key, since the mode changes
+    /// the shape of the built snippet (its own IIFE vs. a bare block).
+    fn cache_tag(self) -> &'static str {
+        match self {
+            RegistrationMode::Separate => "separate",
+            RegistrationMode::Concatenated => "concatenated",
+        }
+    }
+}
+
+/// Builds the resolution body for one component: runs its transformed code in a
+/// `module`/`exports` shim, resolves the export, and assigns it to `globalThis`.
+///
+/// This is the part shared by both [`RegistrationMode`]s; callers wrap it in either
+/// its own IIFE ([`RegistrationMode::Separate`]) or a bare block scope sharing one
+/// IIFE with other components ([`RegistrationMode::Concatenated`]).
+fn component_registration_body(
     registration_name: &str,
     component_js: &str,
 ) -> Result<String, MdxError> {
+    let (component_js, component_map) = extract_inline_source_map(component_js);
+
     let name_literal = serde_json::to_string(registration_name).map_err(|e| {
         MdxError::TsxTransform(format!(
             "Failed to serialize component name {registration_name}: {e}"
@@ -212,15 +383,25 @@ pub(super) fn build_single_component_registration(
 
     // Use write! for better performance in hot path
     use std::fmt::Write;
-    let mut script = String::with_capacity(200 + component_js.len());
+    let mut body = String::with_capacity(200 + component_js.len());
+    write!(
+        body,
+        "\n            {{\n                const module = {{ exports: {{}} }};\n                const exports = module.exports;\n                ",
+    )
+    .map_err(|e| {
+        MdxError::TsxTransform(format!(
+            "Failed to build component registration script for {registration_name}: {e}"
+        ))
+    })?;
+
+    // `component_js` is spliced in right here.
+    let component_js_line_offset = count_lines(&body);
+    body.push_str(component_js);
+
     let resolve_component = resolve_component_code();
     write!(
-        script,
+        body,
         r#"
-            (function() {{
-                const module = {{ exports: {{}} }};
-                const exports = module.exports;
-                {component_js}
 
                 {resolve_component}
 
@@ -232,7 +413,7 @@ pub(super) fn build_single_component_registration(
                 if (Array.isArray(globalThis.__registered_component_names)) {{
                     globalThis.__registered_component_names.push({name_literal});
                 }}
-            }})();
+            }}
             "#,
         resolve_component = resolve_component
     )
@@ -242,12 +423,66 @@ pub(super) fn build_single_component_registration(
         ))
     })?;
 
+    if let Some(map) = component_map.and_then(|map| shift_source_map_lines(&map, component_js_line_offset)) {
+        body.push('\n');
+        body.push_str(&inline_source_map_comment(&map));
+    }
+
+    Ok(body)
+}
+
+/// Builds the registration script for a single component, wrapped in its own IIFE.
+pub(super) fn build_single_component_registration(
+    registration_name: &str,
+    component_js: &str,
+) -> Result<String, MdxError> {
+    let body = component_registration_body(registration_name, component_js)?;
+    let (body, map) = extract_inline_source_map(&body);
+    let mut script = format!("(function() {{\n{body}\n}})();");
+    if let Some(map) = map.and_then(|map| shift_source_map_lines(&map, 1)) {
+        script.push('\n');
+        script.push_str(&inline_source_map_comment(&map));
+    }
     Ok(script)
 }
 
-/// Builds the component registration script for multiple components
+/// Builds (or reuses from cache) the registration snippet for one component in the
+/// given mode, keyed by a hash of its transformed source, name, and mode.
+fn cached_component_registration(
+    registration_name: &str,
+    component_js: &str,
+    mode: RegistrationMode,
+) -> Result<std::rc::Rc<str>, MdxError> {
+    let key = registration_cache::cache_key(component_js, registration_name, mode.cache_tag());
+    registration_cache::get_or_build(key, || match mode {
+        RegistrationMode::Separate => {
+            build_single_component_registration(registration_name, component_js)
+        }
+        RegistrationMode::Concatenated => {
+            component_registration_body(registration_name, component_js)
+        }
+    })
+}
+
+/// Builds the component registration script for multiple components.
+///
+/// Components are registered in [`crate::transform::component_dependency_order`]
+/// rather than `components`' own (unordered) `HashMap` iteration, so a component that
+/// includes another one as a JSX tag (e.g. a `<Card>` whose code contains `<Footer>`)
+/// always has its dependency already registered first, regardless of map ordering.
+/// Fails with [`MdxError::ComponentCycle`] if any component (transitively) includes
+/// itself.
+///
+/// Only one inline source map can apply to a script, so when registering more than one
+/// component in the same bootstrap script, each component's own map (built by
+/// [`cached_component_registration`]) is stripped here rather than kept - there's no
+/// single correct choice of which component's map should "win" the shared comment slot.
+/// The map that matters for translating a render error is the one on the component
+/// actually being rendered (see [`build_render_script_wrapper`]), not on a registered
+/// helper component.
 pub(super) fn build_component_registration_script(
     components: &HashMap<String, ComponentDefinition>,
+    mode: RegistrationMode,
 ) -> Result<String, MdxError> {
     if components.is_empty() {
         return Ok(String::new());
@@ -267,30 +502,369 @@ pub(super) fn build_component_registration_script(
         "#,
     );
 
-    for (map_key, comp_def) in components {
+    let order = crate::transform::component_dependency_order(components)?;
+
+    let mut bodies = String::new();
+    for map_key in &order {
+        let comp_def = components
+            .get(map_key)
+            .expect("component_dependency_order only returns names present in `components`");
         let registration_name = comp_def.name.as_deref().unwrap_or(map_key.as_str());
+        // The Oxc transform itself is already memoized process-wide by
+        // `crate::transform_cache`; this cache covers the registration wrapper built
+        // around that output.
         let component_js =
-            crate::transform::transform_component_code(&comp_def.code).map_err(|e| {
-                MdxError::TsxTransform(format!(
-                    "Failed to transform component {registration_name} code: {e:?}"
-                ))
-            })?;
-
-        let component_registration =
-            build_single_component_registration(registration_name, &component_js)?;
-        script.push_str(&component_registration);
+            crate::transform::transform_component_code_with_options(&comp_def.code, true)
+                .map_err(|e| {
+                    let diagnostics =
+                        crate::transform::component_error_diagnostics(&comp_def.code, e)
+                            .into_iter()
+                            .map(|d| d.prefixed(format!("component '{registration_name}'")))
+                            .collect();
+                    MdxError::TsxTransform(diagnostics)
+                })?;
+
+        let snippet = cached_component_registration(registration_name, &component_js, mode)?;
+        let (snippet, _dropped_map) = extract_inline_source_map(&snippet);
+        match mode {
+            RegistrationMode::Separate => script.push_str(snippet),
+            RegistrationMode::Concatenated => bodies.push_str(snippet),
+        }
+    }
+
+    if mode == RegistrationMode::Concatenated && !bodies.is_empty() {
+        script.push_str("(function() {\n");
+        script.push_str(&bodies);
+        script.push_str("\n})();");
     }
 
     Ok(script)
 }
 
+/// Prefix of the reserved, per-render specifier the synthetic "driver" module built
+/// by [`module_entry_script`] is loaded under - see
+/// [`super::module_loader::ComponentModuleLoader::begin_render`] for how a render's
+/// generation number is appended to this to get the actual specifier.
+pub(super) const MODULE_ENTRY_SPECIFIER_PREFIX: &str = "dinja:entry/";
+
+/// `globalThis` property [`module_entry_script`]'s driver module assigns its
+/// rendered HTML to.
+const MODULE_RENDER_RESULT_GLOBAL: &str = "__dinja_module_render_result";
+
+/// Classic script that reads back the value a driver module built by
+/// [`module_entry_script`] assigned to [`MODULE_RENDER_RESULT_GLOBAL`], once that
+/// module has finished evaluating.
+pub(super) const MODULE_RENDER_RESULT_SCRIPT: &str = "globalThis.__dinja_module_render_result";
+
+/// Builds the source of the synthetic "driver" module
+/// [`super::JsRenderer::render_module_component_async`] loads as its main module: it
+/// imports the entry component by its synthetic specifier and `engine_to_string` from
+/// [`super::module_loader::PREACT_ENGINE_SPECIFIER`] (rather than reading it off
+/// `globalThis`, as the classic script-injection path has to), calls the component
+/// with the global `context` (set up the same way as for a classic-script render, see
+/// [`super::runtime::setup_context`]), and renders the result to HTML exactly as
+/// [`RENDER_COMPONENT_FINISH_SCRIPT`] does - assigning the HTML to a `globalThis`
+/// property rather than returning it directly, since a module's top-level value isn't
+/// otherwise observable from Rust without walking its namespace object.
+pub(super) fn module_entry_script(entry_specifier: &str) -> Result<String, MdxError> {
+    let specifier_literal = serde_json::to_string(entry_specifier).map_err(|e| {
+        MdxError::TsxTransform(format!("Failed to serialize module specifier: {e}"))
+    })?;
+
+    Ok(format!(
+        r#"
+        import EntryComponent from {specifier_literal};
+        import {{ engine_to_string }} from "{preact_engine_specifier}";
+        globalThis.{MODULE_RENDER_RESULT_GLOBAL} = (function() {{
+            if (engine_to_string) {{
+                return engine_to_string(EntryComponent(context));
+            }} else if (engine_to_string && engine_to_string.renderToString) {{
+                return engine_to_string.renderToString(EntryComponent(context));
+            }} else {{
+                throw new Error('engine_to_string not available');
+            }}
+        }})();
+        "#,
+        preact_engine_specifier = super::module_loader::PREACT_ENGINE_SPECIFIER,
+    ))
+}
+
+/// Environment variable that switches component registration to
+/// [`RegistrationMode::Concatenated`]. Unset (the default) keeps each component in its
+/// own IIFE, which is the safer choice when component code relies on closure isolation.
+const ENV_CONCATENATED_REGISTRATION: &str = "RUST_CMS_CONCATENATED_COMPONENT_REGISTRATION";
+
 /// Generates bootstrap script for components
 pub(super) fn component_bootstrap_script(
     components: Option<&HashMap<String, ComponentDefinition>>,
 ) -> Result<String, MdxError> {
-    components
+    let mode = if std::env::var(ENV_CONCATENATED_REGISTRATION).is_ok() {
+        RegistrationMode::Concatenated
+    } else {
+        RegistrationMode::Separate
+    };
+
+    let mut script = components
         .filter(|map| !map.is_empty())
-        .map(build_component_registration_script)
-        .transpose()
-        .map(|maybe| maybe.unwrap_or_default())
+        .map(|map| build_component_registration_script(map, mode))
+        .transpose()?
+        .unwrap_or_default();
+
+    // Per-request components (unlike ones baked into the renderer's profile at
+    // checkout) are re-registered by this bootstrap on every single render, which
+    // would otherwise overwrite a counting wrapper `JsRenderer::start_coverage`
+    // installed earlier in the batch - so re-apply it to whatever this bootstrap just
+    // (re-)registered. A no-op, cheap `typeof` check when coverage isn't running.
+    if !script.is_empty() {
+        script.push_str(REWRAP_COVERAGE_IF_RUNNING);
+    }
+
+    Ok(script)
+}
+
+/// Appended after every component (re-)registration to keep
+/// [`crate::models::RenderSettings::coverage`] instrumentation intact - see
+/// [`coverage_init_script`] for where `__dinjaRewrapCoverage` comes from.
+const REWRAP_COVERAGE_IF_RUNNING: &str = r#"
+        if (typeof globalThis.__dinjaRewrapCoverage === "function") {
+            globalThis.__dinjaRewrapCoverage();
+        }
+"#;
+
+/// Prefix every error thrown by a trapped global this script installs starts with -
+/// [`crate::renderer::JsRenderer::apply_permissions`]'s caller greps a thrown
+/// message for it to tell a capability violation apart from any other runtime error,
+/// and reports the capability name that follows it.
+pub const PERMISSION_DENIED_PREFIX: &str = "Permission denied: ";
+
+/// Builds the capability-trap script [`crate::renderer::JsRenderer::apply_permissions`]
+/// runs before a component renders, per [`crate::models::ComponentPermissions`].
+///
+/// The embedded `deno_core::JsRuntime` this crate drives registers no network,
+/// filesystem, or environment ops to begin with - the only extension it loads is a
+/// custom timers one (`crate::renderer::timers`) - so most of these traps guard
+/// globals that were never reachable in the first place. They're installed anyway so
+/// a future extension that *does* expose one of these globals is deny-by-default from
+/// day one, and so `permissions` behaves identically regardless of what this runtime
+/// happens to expose today. `eval`/`Function` are the one trap that guards something
+/// genuinely reachable right now.
+///
+/// Denied capabilities are traps that throw [`PERMISSION_DENIED_PREFIX`] followed by
+/// the capability name; a granted one is left untouched rather than reset to its
+/// original value, since nothing upstream of this script ever removes a global - so
+/// "untouched" and "restored" are the same thing.
+pub(super) fn build_permissions_script(
+    permissions: &crate::models::ComponentPermissions,
+) -> String {
+    let mut script = String::from(
+        r#"
+        (function() {
+            function dinjaDenyCapability(name) {
+                return function() {
+                    throw new Error("#,
+    );
+    script.push_str(&serde_json::to_string(PERMISSION_DENIED_PREFIX).unwrap_or_default());
+    script.push_str(
+        r#" + name);
+                };
+            }
+            function dinjaDenyNamespace(name) {
+                return new Proxy({}, {
+                    get() {
+                        throw new Error("#,
+    );
+    script.push_str(&serde_json::to_string(PERMISSION_DENIED_PREFIX).unwrap_or_default());
+    script.push_str(
+        r#" + name);
+                    },
+                });
+            }
+"#,
+    );
+
+    if !permissions.eval {
+        script.push_str(
+            r#"
+            globalThis.eval = dinjaDenyCapability("eval");
+            globalThis.Function = dinjaDenyCapability("eval");
+            [
+                Function.prototype,
+                (function* () {}).constructor.prototype,
+                (async function () {}).constructor.prototype,
+                (async function* () {}).constructor.prototype,
+            ].forEach(function (prototype) {
+                Object.defineProperty(prototype, "constructor", {
+                    value: dinjaDenyCapability("eval"),
+                    writable: false,
+                    configurable: false,
+                });
+            });
+"#,
+        );
+    }
+    if !permissions.network {
+        script.push_str(
+            r#"
+            globalThis.fetch = dinjaDenyCapability("network");
+            globalThis.XMLHttpRequest = dinjaDenyCapability("network");
+            globalThis.WebSocket = dinjaDenyCapability("network");
+"#,
+        );
+    }
+    if !permissions.filesystem {
+        script.push_str(
+            r#"
+            globalThis.Deno = dinjaDenyNamespace("filesystem");
+"#,
+        );
+    }
+    if !permissions.environment {
+        script.push_str(
+            r#"
+            globalThis.process = { env: dinjaDenyNamespace("environment") };
+"#,
+        );
+    }
+
+    script.push_str(
+        r#"
+        })();
+        "#,
+    );
+
+    script
+}
+
+/// `globalThis` property [`coverage_init_script`] stores per-component invocation
+/// counts on, and [`COVERAGE_COLLECT_SCRIPT`] reads back.
+const COVERAGE_COUNTS_GLOBAL: &str = "__dinja_coverage_counts";
+
+/// Builds the coverage-instrumentation script
+/// [`crate::renderer::JsRenderer::start_coverage`] runs once per batch, before any of
+/// its files render.
+///
+/// This crate doesn't hook V8's own precise-coverage API (`deno_core` doesn't expose
+/// it, and it's function-granularity, not component-granularity, once a render script
+/// wraps every component call the same way regardless of which MDX file invoked it) -
+/// instead it resets [`COVERAGE_COUNTS_GLOBAL`] to empty and defines
+/// `globalThis.__dinjaRewrapCoverage`, a function that walks
+/// `globalThis.__registered_component_names` (populated by
+/// [`build_component_registration_script`]) and replaces each registered component
+/// with a thin counting wrapper, then calls it once immediately to cover whatever's
+/// already registered (typically a renderer profile's baked-in component set - see
+/// [`crate::renderer::pool::RendererPool`]).
+///
+/// Per-request components (passed as [`crate::models::NamedMdxBatchInput::components`]
+/// rather than baked into the profile) are registered fresh on every file's render -
+/// see [`component_bootstrap_script`] - which would silently overwrite this wrapper
+/// with the raw, uncounted component function. [`component_bootstrap_script`] calls
+/// `__dinjaRewrapCoverage` again right after each such registration for exactly this
+/// reason; a component already wrapped (its function has `__dinjaCoverageWrapped` set)
+/// is left alone so re-registering it mid-batch doesn't reset its count back to `0`.
+pub(super) fn coverage_init_script() -> String {
+    format!(
+        r#"
+        (function() {{
+            globalThis.{counts} = {{}};
+            globalThis.__dinjaRewrapCoverage = function() {{
+                var names = Array.isArray(globalThis.__registered_component_names)
+                    ? globalThis.__registered_component_names
+                    : [];
+                for (var i = 0; i < names.length; i++) {{
+                    (function(name) {{
+                        var original = globalThis[name];
+                        if (typeof original !== "function" || original.__dinjaCoverageWrapped) {{
+                            return;
+                        }}
+                        if (!(name in globalThis.{counts})) {{
+                            globalThis.{counts}[name] = 0;
+                        }}
+                        var wrapped = function() {{
+                            globalThis.{counts}[name]++;
+                            return original.apply(this, arguments);
+                        }};
+                        wrapped.__dinjaCoverageWrapped = true;
+                        globalThis[name] = wrapped;
+                    }})(names[i]);
+                }}
+            }};
+            globalThis.__dinjaRewrapCoverage();
+        }})();
+        "#,
+        counts = COVERAGE_COUNTS_GLOBAL
+    )
+}
+
+/// Static readback script [`crate::renderer::JsRenderer::collect_coverage`] executes
+/// once a batch is done, returning the counts object [`coverage_init_script`] collected
+/// for Rust to deserialize directly (see `runtime::extract_value_from_v8`).
+pub(super) const COVERAGE_COLLECT_SCRIPT: &str =
+    "globalThis.__dinja_coverage_counts || {}";
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use deno_core::{JsRuntime, RuntimeOptions};
+
+    /// Every way V8 exposes a callable `Function` constructor: the global itself,
+    /// `Function.prototype.constructor`, and the same for the three function
+    /// subtypes (`GeneratorFunction`, `AsyncFunction`, `AsyncGeneratorFunction`) -
+    /// each of which has its own `.prototype`, distinct from `Function.prototype`,
+    /// so trapping only the latter leaves the other three reachable.
+    const EVAL_BYPASS_VECTORS: &[&str] = &[
+        "globalThis.eval('1')",
+        "globalThis.Function('return 1')()",
+        "(function(){}).constructor('return 1')()",
+        "(function*(){}).constructor('return 1')()",
+        "(async function(){}).constructor('return 1')()",
+        "(async function*(){}).constructor('return 1')()",
+    ];
+
+    fn deny_eval_runtime() -> JsRuntime {
+        let mut runtime = JsRuntime::new(RuntimeOptions {
+            extensions: vec![super::super::timers::dinja_timers::init_ops()],
+            ..Default::default()
+        });
+        let permissions = crate::models::ComponentPermissions { eval: false, ..Default::default() };
+        runtime
+            .execute_script("permissions.js", build_permissions_script(&permissions))
+            .expect("permission trap script should install cleanly");
+        runtime
+    }
+
+    #[test]
+    fn eval_permission_denies_every_function_constructor_vector() {
+        for vector in EVAL_BYPASS_VECTORS {
+            let mut runtime = deny_eval_runtime();
+            let err = runtime
+                .execute_script("bypass-attempt.js", vector.to_string())
+                .expect_err(&format!("{vector} should have been denied"));
+            // `execute_script`'s error type varies by Deno Core version - every other
+            // call site in this module formats it with `{:?}` rather than `Display`,
+            // see `runtime::translate_execution_error`'s doc comment.
+            let message = format!("{err:?}");
+            assert!(
+                message.contains(PERMISSION_DENIED_PREFIX),
+                "{vector} should throw the permission-denied error, got: {message}"
+            );
+        }
+    }
+
+    #[test]
+    fn granted_eval_leaves_every_function_constructor_vector_working() {
+        let mut runtime = JsRuntime::new(RuntimeOptions {
+            extensions: vec![super::super::timers::dinja_timers::init_ops()],
+            ..Default::default()
+        });
+        let permissions = crate::models::ComponentPermissions { eval: true, ..Default::default() };
+        runtime
+            .execute_script("permissions.js", build_permissions_script(&permissions))
+            .expect("permission script should install cleanly");
+
+        for vector in EVAL_BYPASS_VECTORS {
+            runtime
+                .execute_script("granted-attempt.js", vector.to_string())
+                .unwrap_or_else(|e| panic!("{vector} should be allowed when eval is granted: {e:?}"));
+        }
+    }
 }
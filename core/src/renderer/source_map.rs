@@ -0,0 +1,146 @@
+//! Minimal V3 source map consumer.
+//!
+//! This exists solely to support [`super::runtime::translate_execution_error`]: given a
+//! generated `(line, column)` from a V8 stack trace, find the original position it came
+//! from - and, when the map embeds `sourcesContent` for it, a snippet of the
+//! offending line. It is not a general-purpose source map library - it decodes
+//! exactly the `sources`/`mappings`/`sourcesContent` fields a codegen might produce
+//! and nothing else.
+
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+struct RawSourceMap {
+    sources: Vec<String>,
+    mappings: String,
+    /// Original source text, one entry per `sources` index, `null` where the
+    /// generator didn't embed it. Not every source map carries this - when it's
+    /// missing, [`lookup`] still resolves a position, just without a code snippet.
+    #[serde(default, rename = "sourcesContent")]
+    sources_content: Vec<Option<String>>,
+}
+
+/// An original source position resolved from a generated position.
+pub(super) struct OriginalPosition {
+    /// Path of the original source file, taken from the map's `sources` array.
+    pub(super) source: String,
+    /// 0-indexed original line number.
+    pub(super) line: u32,
+    /// 0-indexed original column number.
+    pub(super) column: u32,
+    /// The original source line the position falls on, with a `^` caret under the
+    /// offending column, if the map embedded `sourcesContent` for this source.
+    pub(super) snippet: Option<String>,
+}
+
+/// Builds a two-line "<code>\n<caret>" snippet for `line`/`column` out of `content`,
+/// or `None` if `content` doesn't have that many lines.
+fn build_snippet(content: &str, line: u32, column: u32) -> Option<String> {
+    let code_line = content.lines().nth(line as usize)?;
+    let caret_offset = code_line
+        .char_indices()
+        .nth(column as usize)
+        .map_or(code_line.len(), |(byte_idx, _)| byte_idx);
+    let mut caret = " ".repeat(caret_offset);
+    caret.push('^');
+    Some(format!("{code_line}\n{caret}"))
+}
+
+/// Looks up the original position for a 0-indexed `(generated_line, generated_column)`
+/// in a V3 source map. Returns `None` if the map is malformed or has no mapping that
+/// covers the requested position.
+pub(super) fn lookup(
+    map_json: &str,
+    generated_line: u32,
+    generated_column: u32,
+) -> Option<OriginalPosition> {
+    let map: RawSourceMap = serde_json::from_str(map_json).ok()?;
+
+    // Mapping fields are cumulative deltas across the *entire* mappings string; only
+    // the generated column resets at the start of each line.
+    let mut cur_source = 0i64;
+    let mut cur_orig_line = 0i64;
+    let mut cur_orig_col = 0i64;
+    let mut best: Option<(i64, i64, i64)> = None; // (source, orig_line, orig_col)
+
+    for (line_idx, line) in map.mappings.split(';').enumerate() {
+        if line_idx as u32 > generated_line {
+            break;
+        }
+        let mut cur_gen_col = 0i64;
+        for segment in line.split(',') {
+            if segment.is_empty() {
+                continue;
+            }
+            let fields = decode_vlq_segment(segment)?;
+            if fields.is_empty() {
+                continue;
+            }
+            cur_gen_col += fields[0];
+            if fields.len() >= 4 {
+                cur_source += fields[1];
+                cur_orig_line += fields[2];
+                cur_orig_col += fields[3];
+            }
+
+            if line_idx as u32 == generated_line && cur_gen_col as u32 <= generated_column {
+                best = Some((cur_source, cur_orig_line, cur_orig_col));
+            }
+        }
+    }
+
+    let (source_idx, orig_line, orig_col) = best?;
+    let source_idx = source_idx.max(0) as usize;
+    let source = map.sources.get(source_idx)?.clone();
+    let line = orig_line.max(0) as u32;
+    let column = orig_col.max(0) as u32;
+
+    let snippet = map
+        .sources_content
+        .get(source_idx)
+        .and_then(|content| content.as_deref())
+        .and_then(|content| build_snippet(content, line, column));
+
+    Some(OriginalPosition {
+        source,
+        line,
+        column,
+        snippet,
+    })
+}
+
+/// Decodes one comma-separated VLQ segment into its raw (not yet cumulative) field
+/// deltas: `[generatedColumn, sourceIndex, originalLine, originalColumn, nameIndex?]`.
+fn decode_vlq_segment(segment: &str) -> Option<Vec<i64>> {
+    let mut fields = Vec::with_capacity(4);
+    let mut chars = segment.chars().peekable();
+    while chars.peek().is_some() {
+        let mut result: i64 = 0;
+        let mut shift = 0u32;
+        loop {
+            let c = chars.next()?;
+            let digit = base64_digit(c)?;
+            let continuation = digit & 0x20 != 0;
+            result += i64::from(digit & 0x1f) << shift;
+            shift += 5;
+            if !continuation {
+                break;
+            }
+        }
+        let negate = result & 1 == 1;
+        result >>= 1;
+        fields.push(if negate { -result } else { result });
+    }
+    Some(fields)
+}
+
+fn base64_digit(c: char) -> Option<u8> {
+    match c {
+        'A'..='Z' => Some(c as u8 - b'A'),
+        'a'..='z' => Some(c as u8 - b'a' + 26),
+        '0'..='9' => Some(c as u8 - b'0' + 52),
+        '+' => Some(62),
+        '/' => Some(63),
+        _ => None,
+    }
+}
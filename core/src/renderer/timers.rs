@@ -0,0 +1,25 @@
+//! Real `setTimeout`/`clearTimeout` support for async SSR.
+//!
+//! [`engine::setup_globals`](super::engine) used to stub `setTimeout` to fire
+//! synchronously only when `delay` is `0`/unset, so a component that `await`s a
+//! library built on a real delay (a debounce helper, a hand-rolled `sleep(ms)`) would
+//! hang forever instead of resolving. [`op_dinja_set_timeout`] is a small async op
+//! backing a real timer queue: `setup_globals`'s JS shim calls it and chains the
+//! callback off the returned promise, so the delay is driven to completion by the
+//! same `rt.run_event_loop(...).await` that already resolves a render's other pending
+//! promises (see `runtime::execute_and_extract_async`/`execute_module_and_extract_async`).
+//!
+//! `setInterval`/`requestAnimationFrame` stay permanent no-ops (see `setup_globals`):
+//! a recurring timer has no natural end for a render that produces one HTML string and
+//! stops, so there's nothing for it to usefully drive.
+
+use deno_core::op2;
+
+/// Resolves after `delay_ms` milliseconds, backing `globalThis.setTimeout`'s shim
+/// installed by [`super::engine::setup_globals`].
+#[op2(async)]
+pub(super) async fn op_dinja_set_timeout(delay_ms: u32) {
+    tokio::time::sleep(std::time::Duration::from_millis(u64::from(delay_ms))).await;
+}
+
+deno_core::extension!(dinja_timers, ops = [op_dinja_set_timeout]);
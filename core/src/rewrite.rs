@@ -0,0 +1,627 @@
+//! Extension point for structural search-and-replace rules over the rendered JSON
+//! document tree (the same `{type, attributes, children}` shape
+//! [`crate::mdx::render_with_engine_pipeline`]'s schema/JSON output hands to
+//! `extract_schema_from_json`/`traverse_json_tree`), in the spirit of
+//! [comby](https://comby.dev)/semgrep structural rewriting.
+//!
+//! A rule is written as `<pattern> ==> <template>`, e.g.:
+//!
+//! ```text
+//! <Callout type=$t>$body</Callout> ==> <aside class={$t}>$body</aside>
+//! ```
+//!
+//! The left side parses into a pattern tree where a `$name` token is a metavariable
+//! that binds to whatever subtree, single child, or attribute value appears in its
+//! place; the right side parses into a template tree that reuses those bindings. A
+//! metavariable referenced more than once in a pattern must bind equal subtrees every
+//! time - e.g. `<Box w=$n h=$n>` only matches a square - and every metavariable the
+//! template uses must appear somewhere in the pattern, so a rule can't reference a
+//! binding that was never captured.
+//!
+//! [`apply_rewrites`] walks the document bottom-up - a node's children are rewritten
+//! before the node itself is matched, so an outer rule sees its children already
+//! desugared - trying each registered rule in registration order and stopping at the
+//! first match per node. A matched node is replaced wholesale by its template
+//! instantiation and isn't re-visited at that position, so a rule can't loop forever
+//! rewriting its own output; [`MAX_REWRITES_PER_DOCUMENT`] additionally bounds the total
+//! number of rewrites applied to one document.
+
+use crate::error::MdxError;
+use serde_json::{Map, Value};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+
+/// Hard cap on the number of node replacements [`apply_rewrites`] will make in a single
+/// document, regardless of how many rules are registered or how deeply they chain -
+/// bounds the cost of a pathological rule set (e.g. one whose template reintroduces a
+/// structure another rule keeps matching) instead of rewriting without limit.
+const MAX_REWRITES_PER_DOCUMENT: usize = 10_000;
+
+/// One bound metavariable's value: the whole JSON subtree (object, string, array, ...)
+/// it matched. A var bound in attribute position always holds a scalar; a var bound in
+/// child position may hold anything, including an array of several children.
+type Bindings = HashMap<String, Value>;
+
+/// An attribute value in a parsed pattern or template: either a literal JSON value the
+/// target attribute must equal (pattern) or is set to (template), or a `$name`
+/// metavariable.
+#[derive(Debug, Clone, PartialEq)]
+enum AttrNode {
+    Literal(Value),
+    Var(String),
+}
+
+/// One parsed node of a pattern or template tree - see [`crate::rewrite`].
+#[derive(Debug, Clone, PartialEq)]
+enum Node {
+    /// A bare `$name` metavariable, standing in for a whole node (any JSON value).
+    Var(String),
+    /// Literal text content between tags.
+    Text(String),
+    /// `<Tag attr=... ...>children</Tag>` or its self-closing form.
+    Element {
+        tag: String,
+        attrs: BTreeMap<String, AttrNode>,
+        children: Vec<Node>,
+    },
+}
+
+/// One parsed `<pattern> ==> <template>` rewrite rule (see [`crate::rewrite`]).
+#[derive(Debug, Clone)]
+pub struct RewriteRule {
+    /// Original rule source, kept only for [`std::fmt::Debug`]/diagnostics.
+    source: String,
+    pattern: Node,
+    template: Node,
+}
+
+/// Registry of [`RewriteRule`]s, tried against every node of the rendered JSON document
+/// tree in registration order - see [`crate::rewrite`]. Cheap to clone - rules are held
+/// behind an [`Arc`], so cloning a [`crate::service::RenderService`] doesn't copy them.
+#[derive(Clone, Debug, Default)]
+pub struct RewriteRegistry {
+    rules: Arc<Vec<RewriteRule>>,
+}
+
+impl RewriteRegistry {
+    /// Creates an empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Parses `source` as a `<pattern> ==> <template>` rule and appends it to this
+    /// registry - rules are tried in the order they were registered, first match per
+    /// node wins.
+    ///
+    /// # Errors
+    /// Returns [`MdxError::RewriteRuleParse`] if `source` isn't valid `pattern ==>
+    /// template` syntax, or if the template references a metavariable the pattern never
+    /// binds.
+    pub fn register(&mut self, source: impl Into<String>) -> Result<&mut Self, MdxError> {
+        let rule = parse_rule(source.into())?;
+        Arc::make_mut(&mut self.rules).push(rule);
+        Ok(self)
+    }
+
+    /// Returns true if no rules are registered.
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// Number of registered rules.
+    pub fn len(&self) -> usize {
+        self.rules.len()
+    }
+}
+
+/// Parses a `<pattern> ==> <template>` rule source into a [`RewriteRule`].
+fn parse_rule(source: String) -> Result<RewriteRule, MdxError> {
+    let Some((pattern_src, template_src)) = source.split_once("==>") else {
+        return Err(MdxError::RewriteRuleParse(format!(
+            "rewrite rule '{source}' is missing its '==>' separator"
+        )));
+    };
+
+    let pattern = parse_node(pattern_src.trim()).map_err(|e| {
+        MdxError::RewriteRuleParse(format!("rewrite rule '{source}' has an invalid pattern: {e}"))
+    })?;
+    let template = parse_node(template_src.trim()).map_err(|e| {
+        MdxError::RewriteRuleParse(format!("rewrite rule '{source}' has an invalid template: {e}"))
+    })?;
+
+    let mut pattern_vars = std::collections::HashSet::new();
+    collect_vars(&pattern, &mut pattern_vars);
+    let mut template_vars = std::collections::HashSet::new();
+    collect_vars(&template, &mut template_vars);
+    if let Some(unbound) = template_vars.difference(&pattern_vars).next() {
+        return Err(MdxError::RewriteRuleParse(format!(
+            "rewrite rule '{source}' template references '${unbound}', which its pattern never binds"
+        )));
+    }
+
+    Ok(RewriteRule { source, pattern, template })
+}
+
+/// Collects every metavariable name appearing anywhere in `node` (as a node, a child,
+/// or an attribute value) into `vars`.
+fn collect_vars(node: &Node, vars: &mut std::collections::HashSet<String>) {
+    match node {
+        Node::Var(name) => {
+            vars.insert(name.clone());
+        }
+        Node::Text(_) => {}
+        Node::Element { attrs, children, .. } => {
+            for attr in attrs.values() {
+                if let AttrNode::Var(name) = attr {
+                    vars.insert(name.clone());
+                }
+            }
+            for child in children {
+                collect_vars(child, vars);
+            }
+        }
+    }
+}
+
+/// Parses one `<Tag attr=... ...>children</Tag>` element (or a bare `$name`) from the
+/// entire span of `source`, failing if anything is left over afterwards.
+fn parse_node(source: &str) -> Result<Node, String> {
+    let mut parser = NodeParser { src: source, input: source.as_bytes(), pos: 0 };
+    let node = parser.parse_node()?;
+    parser.skip_whitespace();
+    if parser.pos != parser.input.len() {
+        return Err(format!(
+            "unexpected trailing content '{}'",
+            &source[parser.pos..]
+        ));
+    }
+    Ok(node)
+}
+
+struct NodeParser<'a> {
+    src: &'a str,
+    input: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> NodeParser<'a> {
+    fn skip_whitespace(&mut self) {
+        while self.pos < self.input.len() && self.input[self.pos].is_ascii_whitespace() {
+            self.pos += 1;
+        }
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.input.get(self.pos).copied()
+    }
+
+    fn expect(&mut self, c: u8) -> Result<(), String> {
+        if self.peek() == Some(c) {
+            self.pos += 1;
+            Ok(())
+        } else {
+            Err(format!("expected '{}' at byte {}", c as char, self.pos))
+        }
+    }
+
+    fn rest(&self) -> &str {
+        std::str::from_utf8(&self.input[self.pos..]).unwrap_or("")
+    }
+
+    fn parse_node(&mut self) -> Result<Node, String> {
+        self.skip_whitespace();
+        match self.peek() {
+            Some(b'<') => self.parse_element(),
+            Some(b'$') => Ok(Node::Var(self.parse_var_name()?)),
+            _ => Err(format!("expected '<' or '$' at '{}'", self.rest())),
+        }
+    }
+
+    /// Parses a `$name` token, returning `name` without the leading `$`.
+    fn parse_var_name(&mut self) -> Result<String, String> {
+        self.expect(b'$')?;
+        let start = self.pos;
+        while matches!(self.peek(), Some(c) if c.is_ascii_alphanumeric() || c == b'_') {
+            self.pos += 1;
+        }
+        if self.pos == start {
+            return Err("'$' must be followed by a metavariable name".to_string());
+        }
+        Ok(self.src[start..self.pos].to_string())
+    }
+
+    fn parse_ident(&mut self) -> Result<String, String> {
+        let start = self.pos;
+        while matches!(self.peek(), Some(c) if c.is_ascii_alphanumeric() || c == b'_' || c == b'-' || c == b':') {
+            self.pos += 1;
+        }
+        if self.pos == start {
+            return Err(format!("expected an identifier at '{}'", self.rest()));
+        }
+        Ok(self.src[start..self.pos].to_string())
+    }
+
+    fn parse_element(&mut self) -> Result<Node, String> {
+        self.expect(b'<')?;
+        let tag = self.parse_ident()?;
+
+        let mut attrs = BTreeMap::new();
+        loop {
+            self.skip_whitespace();
+            match self.peek() {
+                Some(b'/') | Some(b'>') => break,
+                Some(_) => {
+                    let name = self.parse_ident()?;
+                    self.skip_whitespace();
+                    self.expect(b'=')?;
+                    self.skip_whitespace();
+                    let value = self.parse_attr_value()?;
+                    attrs.insert(name, value);
+                }
+                None => return Err("unterminated opening tag".to_string()),
+            }
+        }
+
+        self.skip_whitespace();
+        if self.peek() == Some(b'/') {
+            self.pos += 1;
+            self.expect(b'>')?;
+            return Ok(Node::Element { tag, attrs, children: Vec::new() });
+        }
+        self.expect(b'>')?;
+
+        let children = self.parse_children()?;
+
+        self.expect(b'<')?;
+        self.expect(b'/')?;
+        let close_tag = self.parse_ident()?;
+        if close_tag != tag {
+            return Err(format!("closing tag '</{close_tag}>' doesn't match opening tag '<{tag}>'"));
+        }
+        self.skip_whitespace();
+        self.expect(b'>')?;
+
+        Ok(Node::Element { tag, attrs, children })
+    }
+
+    fn parse_attr_value(&mut self) -> Result<AttrNode, String> {
+        match self.peek() {
+            Some(b'$') => Ok(AttrNode::Var(self.parse_var_name()?)),
+            Some(b'"') => Ok(AttrNode::Literal(Value::String(self.parse_quoted()?))),
+            Some(b'{') => {
+                self.pos += 1;
+                self.skip_whitespace();
+                let inner = if self.peek() == Some(b'$') {
+                    AttrNode::Var(self.parse_var_name()?)
+                } else {
+                    let start = self.pos;
+                    while self.peek().is_some() && self.peek() != Some(b'}') {
+                        self.pos += 1;
+                    }
+                    let raw = self.src[start..self.pos].trim();
+                    AttrNode::Literal(
+                        serde_json::from_str(raw).unwrap_or_else(|_| Value::String(raw.to_string())),
+                    )
+                };
+                self.skip_whitespace();
+                self.expect(b'}')?;
+                Ok(inner)
+            }
+            _ => Err(format!("expected an attribute value at '{}'", self.rest())),
+        }
+    }
+
+    fn parse_quoted(&mut self) -> Result<String, String> {
+        self.expect(b'"')?;
+        let start = self.pos;
+        while self.peek().is_some() && self.peek() != Some(b'"') {
+            self.pos += 1;
+        }
+        if self.peek() != Some(b'"') {
+            return Err("unterminated quoted string".to_string());
+        }
+        let value = self.src[start..self.pos].to_string();
+        self.pos += 1;
+        Ok(value)
+    }
+
+    /// Parses the sequence of child nodes up to (not including) the matching `</tag>`.
+    /// A text run that's pure whitespace between two tags/metavariables is dropped,
+    /// since the rendered tree doesn't carry insignificant whitespace text nodes
+    /// either.
+    fn parse_children(&mut self) -> Result<Vec<Node>, String> {
+        let mut children = Vec::new();
+        loop {
+            match self.peek() {
+                Some(b'<') if self.input.get(self.pos + 1) == Some(&b'/') => break,
+                Some(b'<') => children.push(self.parse_element()?),
+                Some(b'$') => children.push(Node::Var(self.parse_var_name()?)),
+                Some(_) => {
+                    let start = self.pos;
+                    while self.peek().is_some()
+                        && self.peek() != Some(b'<')
+                        && self.peek() != Some(b'$')
+                    {
+                        self.pos += 1;
+                    }
+                    let text = &self.src[start..self.pos];
+                    if !text.trim().is_empty() {
+                        children.push(Node::Text(text.to_string()));
+                    }
+                }
+                None => return Err("unterminated element, missing closing tag".to_string()),
+            }
+        }
+        Ok(children)
+    }
+}
+
+/// Binds `name` to `value` in `bindings`, failing if it's already bound to a different
+/// value - a metavariable appearing twice in a pattern must match equal subtrees.
+fn bind(bindings: &mut Bindings, name: &str, value: Value) -> bool {
+    match bindings.get(name) {
+        Some(existing) => *existing == value,
+        None => {
+            bindings.insert(name.to_string(), value);
+            true
+        }
+    }
+}
+
+/// Normalizes a node's `children` field into a list for elementwise matching: absent ->
+/// empty, an array -> itself, any other single value -> a one-element list.
+fn children_as_list(children: Option<&Value>) -> Vec<Value> {
+    match children {
+        None => Vec::new(),
+        Some(Value::Array(items)) => items.clone(),
+        Some(other) => vec![other.clone()],
+    }
+}
+
+/// Attempts to unify `pattern` against `value`, extending `bindings` with any
+/// metavariables it captures. Returns false (leaving `bindings` partially populated, by
+/// design - callers discard it on failure) if `pattern` doesn't match.
+fn unify(pattern: &Node, value: &Value, bindings: &mut Bindings) -> bool {
+    match pattern {
+        Node::Var(name) => bind(bindings, name, value.clone()),
+        Node::Text(literal) => matches!(value, Value::String(s) if s == literal),
+        Node::Element { tag, attrs, children } => {
+            let Value::Object(obj) = value else { return false };
+            if obj.get("type").and_then(Value::as_str) != Some(tag.as_str()) {
+                return false;
+            }
+
+            let empty = Map::new();
+            let actual_attrs = match obj.get("attributes") {
+                Some(Value::Object(map)) => map,
+                _ => &empty,
+            };
+            for (key, attr_pattern) in attrs {
+                let Some(actual_value) = actual_attrs.get(key) else { return false };
+                let matched = match attr_pattern {
+                    AttrNode::Literal(expected) => actual_value == expected,
+                    AttrNode::Var(name) => bind(bindings, name, actual_value.clone()),
+                };
+                if !matched {
+                    return false;
+                }
+            }
+
+            unify_children(children, obj.get("children"), bindings)
+        }
+    }
+}
+
+/// Unifies an element pattern's child list against the target node's raw `children`
+/// value. A pattern whose sole child is a metavariable binds it to the whole `children`
+/// value as-is (any shape); otherwise the two are matched elementwise, requiring equal
+/// length.
+fn unify_children(pattern_children: &[Node], actual: Option<&Value>, bindings: &mut Bindings) -> bool {
+    if let [Node::Var(name)] = pattern_children {
+        let whole = actual.cloned().unwrap_or_else(|| Value::Array(Vec::new()));
+        return bind(bindings, name, whole);
+    }
+
+    let actual_list = children_as_list(actual);
+    if actual_list.len() != pattern_children.len() {
+        return false;
+    }
+    pattern_children
+        .iter()
+        .zip(actual_list.iter())
+        .all(|(pattern, value)| unify(pattern, value, bindings))
+}
+
+/// Clones `template`, substituting every metavariable against `bindings` - the inverse
+/// of [`unify`]. Only called after a successful match, so every variable `template`
+/// references is guaranteed present in `bindings` (checked once at parse time in
+/// [`parse_rule`]).
+fn instantiate(template: &Node, bindings: &Bindings) -> Value {
+    match template {
+        Node::Var(name) => bindings.get(name).cloned().unwrap_or(Value::Null),
+        Node::Text(literal) => Value::String(literal.clone()),
+        Node::Element { tag, attrs, children } => {
+            let mut attr_map = Map::new();
+            for (key, attr) in attrs {
+                let value = match attr {
+                    AttrNode::Literal(v) => v.clone(),
+                    AttrNode::Var(name) => bindings.get(name).cloned().unwrap_or(Value::Null),
+                };
+                attr_map.insert(key.clone(), value);
+            }
+
+            let mut node = Map::new();
+            node.insert("type".to_string(), Value::String(tag.clone()));
+            node.insert("attributes".to_string(), Value::Object(attr_map));
+            node.insert("children".to_string(), instantiate_children(children, bindings));
+            Value::Object(node)
+        }
+    }
+}
+
+/// Instantiates an element template's child list - the inverse of [`unify_children`]: a
+/// template whose sole child is a metavariable splices in whatever shape that binding
+/// holds, rather than wrapping it in a single-element array.
+fn instantiate_children(children: &[Node], bindings: &Bindings) -> Value {
+    if let [Node::Var(name)] = children {
+        return bindings.get(name).cloned().unwrap_or_else(|| Value::Array(Vec::new()));
+    }
+    Value::Array(children.iter().map(|child| instantiate(child, bindings)).collect())
+}
+
+/// Walks `value` bottom-up, trying `rules` against every object node in registration
+/// order and replacing the first match with its template instantiation. A node's
+/// children are rewritten before the node itself is matched, and a replacement isn't
+/// re-visited at its own position, so a rule can never loop on the output it just
+/// produced. Stops applying further rewrites once `budget` reaches zero.
+fn rewrite_value(value: &mut Value, rules: &[RewriteRule], budget: &mut usize) {
+    match value {
+        Value::Object(obj) => {
+            if let Some(children) = obj.get_mut("children") {
+                rewrite_value(children, rules, budget);
+            }
+
+            if *budget == 0 {
+                return;
+            }
+            for rule in rules {
+                let mut bindings = Bindings::new();
+                if unify(&rule.pattern, value, &mut bindings) {
+                    *value = instantiate(&rule.template, &bindings);
+                    *budget -= 1;
+                    break;
+                }
+            }
+        }
+        Value::Array(items) => {
+            for item in items {
+                rewrite_value(item, rules, budget);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Parses `json_tree` (a rendered document tree, as produced for
+/// [`crate::models::OutputFormat::Schema`]/[`crate::models::OutputFormat::Json`]/
+/// [`crate::models::OutputFormat::Ast`] output), applies every rule in `registry`
+/// against it via [`rewrite_value`], and re-serializes the result. A no-op, returning
+/// `json_tree` unchanged, if `registry` has no rules registered.
+///
+/// # Errors
+/// Returns [`MdxError::FrontmatterParse`] if `json_tree` isn't valid JSON (the render
+/// engine is expected to always produce valid JSON, so this would indicate an engine
+/// bug, not a user error).
+pub(crate) fn apply_rewrites(json_tree: &str, registry: &RewriteRegistry) -> Result<String, MdxError> {
+    if registry.is_empty() {
+        return Ok(json_tree.to_string());
+    }
+
+    let mut tree: Value = serde_json::from_str(json_tree)
+        .map_err(|e| MdxError::FrontmatterParse(format!("Failed to parse JSON tree: {e}")))?;
+
+    let mut budget = MAX_REWRITES_PER_DOCUMENT;
+    rewrite_value(&mut tree, &registry.rules, &mut budget);
+
+    serde_json::to_string(&tree)
+        .map_err(|e| MdxError::FrontmatterParse(format!("Failed to serialize rewritten JSON tree: {e}")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn node(tag: &str, attrs: &[(&str, &str)], children: Value) -> Value {
+        let mut attr_map = Map::new();
+        for (k, v) in attrs {
+            attr_map.insert(k.to_string(), Value::String(v.to_string()));
+        }
+        serde_json::json!({ "type": tag, "attributes": Value::Object(attr_map), "children": children })
+    }
+
+    #[test]
+    fn test_register_rejects_missing_separator() {
+        let mut registry = RewriteRegistry::new();
+        let err = registry.register("<Callout>$body</Callout>").unwrap_err();
+        assert!(matches!(err, MdxError::RewriteRuleParse(_)));
+    }
+
+    #[test]
+    fn test_register_rejects_unbound_template_var() {
+        let mut registry = RewriteRegistry::new();
+        let err = registry
+            .register("<Callout>$body</Callout> ==> <aside>$other</aside>")
+            .unwrap_err();
+        assert!(matches!(err, MdxError::RewriteRuleParse(_)));
+    }
+
+    #[test]
+    fn test_simple_rewrite_with_attribute_and_children_vars() {
+        let mut registry = RewriteRegistry::new();
+        registry
+            .register(r#"<Callout type=$t>$body</Callout> ==> <aside class={$t}>$body</aside>"#)
+            .unwrap();
+
+        let tree = node("Callout", &[("type", "warning")], Value::String("careful".to_string()));
+        let json = serde_json::to_string(&tree).unwrap();
+
+        let rewritten = apply_rewrites(&json, &registry).unwrap();
+        let value: Value = serde_json::from_str(&rewritten).unwrap();
+
+        assert_eq!(value["type"], "aside");
+        assert_eq!(value["attributes"]["class"], "warning");
+        assert_eq!(value["children"], "careful");
+    }
+
+    #[test]
+    fn test_rewrite_is_noop_without_matching_rules() {
+        let mut registry = RewriteRegistry::new();
+        registry.register("<Foo>$body</Foo> ==> <Bar>$body</Bar>").unwrap();
+
+        let tree = node("Callout", &[], Value::Array(Vec::new()));
+        let json = serde_json::to_string(&tree).unwrap();
+
+        let rewritten = apply_rewrites(&json, &registry).unwrap();
+        assert_eq!(rewritten, json);
+    }
+
+    #[test]
+    fn test_rewrite_applies_bottom_up_to_nested_children() {
+        let mut registry = RewriteRegistry::new();
+        registry.register("<Foo>$body</Foo> ==> <Bar>$body</Bar>").unwrap();
+
+        let inner = node("Foo", &[], Value::String("hi".to_string()));
+        let outer = node("Outer", &[], Value::Array(vec![inner]));
+        let json = serde_json::to_string(&outer).unwrap();
+
+        let rewritten = apply_rewrites(&json, &registry).unwrap();
+        let value: Value = serde_json::from_str(&rewritten).unwrap();
+
+        assert_eq!(value["children"][0]["type"], "Bar");
+    }
+
+    #[test]
+    fn test_repeated_metavariable_requires_equal_subtrees() {
+        let mut registry = RewriteRegistry::new();
+        registry
+            .register("<Box w=$n h=$n>$body</Box> ==> <Square size={$n}>$body</Square>")
+            .unwrap();
+
+        let square = node("Box", &[("w", "4"), ("h", "4")], Value::String("x".to_string()));
+        let rewritten: Value =
+            serde_json::from_str(&apply_rewrites(&serde_json::to_string(&square).unwrap(), &registry).unwrap())
+                .unwrap();
+        assert_eq!(rewritten["type"], "Square");
+
+        let rect = node("Box", &[("w", "4"), ("h", "2")], Value::String("x".to_string()));
+        let unchanged = apply_rewrites(&serde_json::to_string(&rect).unwrap(), &registry).unwrap();
+        assert_eq!(unchanged, serde_json::to_string(&rect).unwrap());
+    }
+
+    #[test]
+    fn test_registry_is_empty_by_default() {
+        let registry = RewriteRegistry::new();
+        assert!(registry.is_empty());
+        assert_eq!(registry.len(), 0);
+    }
+}
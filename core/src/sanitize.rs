@@ -0,0 +1,168 @@
+//! Opt-in HTML sanitization pass for rendering MDX from untrusted authors, per
+//! [`crate::models::RenderSettings::sanitize`] and the security note on
+//! [`crate::mdx::markdown_options`]'s `allow_dangerous_html`: that comment's own advice
+//! ("sanitize the output HTML after rendering") is what this module does.
+//!
+//! This is the same lightweight, regex-driven approach the rest of this crate already
+//! uses for HTML post-processing (see [`crate::links`], [`crate::highlight`]) rather
+//! than a full HTML parser/tree sanitizer - adequate for MDX's fairly constrained
+//! output shape, not a substitute for a dedicated sanitizer (e.g. `ammonia`) when the
+//! threat model demands one.
+//!
+//! Three independent defenses, applied in order:
+//! 1. `<script>`/`<style>`/`<iframe>`/`<object>`/`<embed>` elements are dropped
+//!    entirely, tag and content both - these can execute code or load arbitrary
+//!    content regardless of any attribute filtering below.
+//! 2. Every other tag not in [`SanitizeSettings::tag_allowlist`] has its open/close
+//!    tags stripped (its inner content is kept - unwrapped, not removed), and every
+//!    tag that *is* allowed has its `on*` event-handler attributes dropped and any
+//!    `href`/`src` whose value starts with a `javascript:`/`data:` URL scheme removed.
+//! 3. When [`SanitizeSettings::neutralize_images`] is set, every `<img>`'s `src` is
+//!    renamed to `data-src`, so the image doesn't load until a caller's own script
+//!    opts it back in.
+
+use crate::models::SanitizeSettings;
+use regex::Regex;
+use std::sync::LazyLock;
+
+/// Tag names whose content (not just the tag itself) is dropped, regardless of
+/// [`SanitizeSettings::tag_allowlist`] - these can run code or load content even with
+/// every attribute stripped.
+const DANGEROUS_CONTENT_TAGS: [&str; 5] = ["script", "style", "iframe", "object", "embed"];
+
+/// One `<tag ...>...</tag>` removal pattern per [`DANGEROUS_CONTENT_TAGS`] entry, built
+/// once at process start rather than compiled per call.
+static DANGEROUS_TAG_PATTERNS: LazyLock<Vec<Regex>> = LazyLock::new(|| {
+    DANGEROUS_CONTENT_TAGS
+        .iter()
+        .map(|tag| {
+            Regex::new(&format!(r"(?is)<{tag}\b[^>]*>.*?</{tag}\s*>"))
+                .expect("tag name is a hardcoded ASCII identifier")
+        })
+        .collect()
+});
+
+/// Matches a single opening, closing, or self-closing tag, capturing the leading `/`
+/// (closing tags), the tag name, its raw attribute text, and a trailing `/`
+/// (self-closing tags).
+///
+/// # Safety
+/// Pattern is compile-time constant and known to be valid.
+static ANY_TAG: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"<(/?)([a-zA-Z][a-zA-Z0-9-]*)((?:\s+[^<>]*)?)\s*(/?)>")
+        .expect("hardcoded regex pattern is valid")
+});
+
+/// Matches an `on*` event-handler attribute (`onclick="..."`, `onerror='...'`, or an
+/// unquoted value).
+static EVENT_HANDLER_ATTR: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"(?i)\s+on[a-z]+\s*=\s*("[^"]*"|'[^']*'|[^\s"'>]+)"#)
+        .expect("hardcoded regex pattern is valid")
+});
+
+/// Matches an `href`/`src` attribute whose value is a `javascript:` or `data:` URL -
+/// the two schemes that turn a plain link or image into a script sink.
+static DANGEROUS_URL_ATTR: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"(?i)\s+(href|src)\s*=\s*("(?:javascript|data):[^"]*"|'(?:javascript|data):[^']*')"#)
+        .expect("hardcoded regex pattern is valid")
+});
+
+/// Matches an `<img ...>` tag's `src` attribute name, for
+/// [`SanitizeSettings::neutralize_images`].
+static IMG_SRC_ATTR: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"(?i)\bsrc(\s*=)"#).expect("hardcoded regex pattern is valid"));
+
+/// Sanitizes `html` per `settings` - see the module docs for the three defenses applied.
+pub(crate) fn sanitize_html(html: &str, settings: &SanitizeSettings) -> String {
+    let mut out = html.to_string();
+    for pattern in DANGEROUS_TAG_PATTERNS.iter() {
+        out = pattern.replace_all(&out, "").into_owned();
+    }
+
+    out = ANY_TAG
+        .replace_all(&out, |caps: &regex::Captures| {
+            rewrite_tag(&caps[1], &caps[2], &caps[3], &caps[4], settings)
+        })
+        .into_owned();
+
+    out
+}
+
+/// Rewrites one already-matched tag: dropped entirely (content kept, tag removed) if
+/// its name isn't in `settings.tag_allowlist`; otherwise stripped of dangerous
+/// attributes and, for `<img>` when opted into, `src`-neutralized.
+fn rewrite_tag(slash: &str, name: &str, attrs: &str, self_close: &str, settings: &SanitizeSettings) -> String {
+    let lower_name = name.to_lowercase();
+    if !settings.tag_allowlist.iter().any(|allowed| allowed.eq_ignore_ascii_case(&lower_name)) {
+        return String::new();
+    }
+
+    // Closing tags carry no attributes to filter.
+    if !slash.is_empty() {
+        return format!("</{name}>");
+    }
+
+    let mut attrs = EVENT_HANDLER_ATTR.replace_all(attrs, "").into_owned();
+    attrs = DANGEROUS_URL_ATTR.replace_all(&attrs, "").into_owned();
+    if settings.neutralize_images && lower_name == "img" {
+        attrs = IMG_SRC_ATTR.replace_all(&attrs, "data-src$1").into_owned();
+    }
+
+    format!("<{name}{attrs}{}>", if self_close.is_empty() { "" } else { " /" })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn settings() -> SanitizeSettings {
+        SanitizeSettings::default()
+    }
+
+    #[test]
+    fn test_drops_script_tag_and_content() {
+        let html = r#"<p>hi</p><script>alert(1)</script><p>bye</p>"#;
+        assert_eq!(sanitize_html(html, &settings()), "<p>hi</p><p>bye</p>");
+    }
+
+    #[test]
+    fn test_unwraps_disallowed_tag_but_keeps_content() {
+        let html = r#"<marquee>wheee</marquee>"#;
+        assert_eq!(sanitize_html(html, &settings()), "wheee");
+    }
+
+    #[test]
+    fn test_strips_event_handler_attributes() {
+        let html = r#"<button onclick="doEvil()">click</button>"#;
+        assert_eq!(sanitize_html(html, &settings()), "<button>click</button>");
+    }
+
+    #[test]
+    fn test_strips_javascript_url_href() {
+        let html = r#"<a href="javascript:alert(1)">x</a>"#;
+        assert_eq!(sanitize_html(html, &settings()), "<a>x</a>");
+    }
+
+    #[test]
+    fn test_keeps_safe_href() {
+        let html = r#"<a href="https://example.com">x</a>"#;
+        assert_eq!(sanitize_html(html, &settings()), html);
+    }
+
+    #[test]
+    fn test_neutralizes_image_src_when_opted_in() {
+        let mut opts = settings();
+        opts.neutralize_images = true;
+        let html = r#"<img src="https://example.com/cat.png">"#;
+        assert_eq!(
+            sanitize_html(html, &opts),
+            r#"<img data-src="https://example.com/cat.png">"#
+        );
+    }
+
+    #[test]
+    fn test_leaves_image_src_when_not_opted_in() {
+        let html = r#"<img src="https://example.com/cat.png">"#;
+        assert_eq!(sanitize_html(html, &settings()), html);
+    }
+}
@@ -0,0 +1,338 @@
+//! Extension point for Lua-scripted container directives and template utilities.
+//!
+//! Like [`crate::decorators`], this turns a piece of the render pipeline from a closed
+//! set into something a host registers entries into - but backed by `mlua` instead of
+//! native Rust closures, for a host that wants to ship the scripts themselves as data
+//! (config, a database row, a file on disk) rather than compiled Rust. Two registries:
+//!
+//! - [`LuaDirectiveRegistry`]: container directives - a markdown `:::name ... :::`
+//!   block maps to a registered Lua function receiving the block's attributes and
+//!   rendered inner HTML, returning replacement HTML. Resolved by
+//!   [`expand_directives`] before the surrounding markdown is rendered.
+//! - [`LuaUtilsRegistry`]: named helpers callable from an inline `{name(arg, ...)}`
+//!   expression in the markdown body (distinct from a JSX `{expr}` - only an
+//!   expression whose head is a *registered* util name is touched, so ordinary JSX
+//!   expressions pass through untouched). Resolved by [`expand_utils`].
+//!
+//! Each call gets a fresh [`Lua`] state - nothing leaks between directives, utils, or
+//! files in a batch - built with [`new_sandboxed_lua`]'s restricted standard library
+//! (no `os`, no `io`: a script can't shell out or touch the filesystem regardless of
+//! how low-trust its source is) and wall-clock execution capped at
+//! [`LUA_EXECUTION_TIMEOUT`], so a slow or infinite-looping script can't hang a
+//! render. The timeout is checked at VM instruction boundaries, not preemptively, so
+//! it bounds a runaway *script loop*, not a single blocking native call - which is
+//! exactly why the stdlib restriction matters instead of relying on the timeout
+//! alone. A script failure becomes [`MdxError::LuaScript`] and surfaces through the
+//! same per-file error channel as any other render failure, rather than aborting the
+//! whole batch.
+
+use crate::error::MdxError;
+use mlua::{Function, Lua, LuaOptions, StdLib, Variadic, VmState};
+use regex::Regex;
+use std::collections::HashMap;
+use std::sync::{Arc, LazyLock};
+use std::time::{Duration, Instant};
+
+/// Maximum wall-clock time a single Lua callback invocation may run before being
+/// aborted - generous enough for real template logic, small enough that a runaway
+/// script can't stall a batch render for long.
+const LUA_EXECUTION_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// One registered Lua callback's source: a chunk that evaluates to a function, e.g.
+/// `"function(attrs, html) return '<div class=\"note\">' .. html .. '</div>' end"`.
+/// Held as source text rather than a compiled [`mlua::Function`] because [`Lua`] isn't
+/// `Send`/`Sync` and can't be shared across the threads a batch render may fan out
+/// across - each call compiles fresh from this source in its own short-lived [`Lua`]
+/// state instead.
+type LuaSource = Arc<str>;
+
+/// Registry of named Lua container-directive handlers (see [`crate::scripting`]).
+/// Cheap to clone - entries are held behind an [`Arc`], so cloning a
+/// [`crate::service::RenderService`] doesn't copy them.
+#[derive(Clone, Debug, Default)]
+pub struct LuaDirectiveRegistry {
+    handlers: Arc<HashMap<String, LuaSource>>,
+}
+
+impl LuaDirectiveRegistry {
+    /// Creates an empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers a Lua function under `name` (referenced in markdown as a
+    /// `:::name ... :::` container block), replacing any existing handler of that
+    /// name. `source` must evaluate to a function of two arguments, `(attrs, html)`,
+    /// returning the block's replacement HTML.
+    pub fn register(&mut self, name: impl Into<String>, source: impl Into<Arc<str>>) -> &mut Self {
+        Arc::make_mut(&mut self.handlers).insert(name.into(), source.into());
+        self
+    }
+
+    /// Returns true if no directive handlers are registered.
+    pub fn is_empty(&self) -> bool {
+        self.handlers.is_empty()
+    }
+
+    /// Number of registered directive handlers.
+    pub fn len(&self) -> usize {
+        self.handlers.len()
+    }
+}
+
+/// Registry of named Lua template-utility functions (see [`crate::scripting`]). Cheap
+/// to clone - entries are held behind an [`Arc`], so cloning a
+/// [`crate::service::RenderService`] doesn't copy them.
+#[derive(Clone, Debug, Default)]
+pub struct LuaUtilsRegistry {
+    functions: Arc<HashMap<String, LuaSource>>,
+}
+
+impl LuaUtilsRegistry {
+    /// Creates an empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers a Lua function under `name` (callable in markdown body text as
+    /// `{name(arg, ...)}`), replacing any existing util of that name. `source` must
+    /// evaluate to a function taking the call's arguments as strings and returning a
+    /// string.
+    pub fn register(&mut self, name: impl Into<String>, source: impl Into<Arc<str>>) -> &mut Self {
+        Arc::make_mut(&mut self.functions).insert(name.into(), source.into());
+        self
+    }
+
+    /// Returns true if no utility functions are registered.
+    pub fn is_empty(&self) -> bool {
+        self.functions.is_empty()
+    }
+
+    /// Number of registered utility functions.
+    pub fn len(&self) -> usize {
+        self.functions.len()
+    }
+}
+
+/// Standard library subset loaded into [`new_sandboxed_lua`]'s `Lua` state: just
+/// enough for the expression/template logic a directive or util body needs (values,
+/// strings, tables, math), deliberately excluding `os` and `io` - both of which
+/// `Lua::new()`'s default stdlib includes and which would otherwise hand a script
+/// `os.execute`, `os.remove`, `io.open`/`io.popen`, and the like. A host registering
+/// scripts as data (config, a database row, a file on disk - see the module docs)
+/// must be able to treat that source as lower-trust than compiled Rust; a state that
+/// still exposes process and filesystem access isn't actually sandboxed regardless of
+/// what it's called.
+fn sandboxed_stdlib() -> StdLib {
+    StdLib::BASE | StdLib::STRING | StdLib::TABLE | StdLib::MATH
+}
+
+/// Creates a fresh `Lua` state restricted to [`sandboxed_stdlib`] (no `os`/`io`
+/// access), with a wall-clock execution cap: once [`LUA_EXECUTION_TIMEOUT`] has
+/// elapsed since this state was created, the next VM instruction boundary aborts the
+/// running script with an error instead of continuing. The instruction-boundary check
+/// means a single blocking native call still isn't preempted mid-call - the stdlib
+/// restriction is what keeps a directive/util body from reaching one in the first
+/// place, rather than relying on the timeout to bound it.
+fn new_sandboxed_lua() -> Lua {
+    let lua = Lua::new_with(sandboxed_stdlib(), LuaOptions::new())
+        .expect("sandboxed_stdlib() is a subset of Lua::new()'s default libs and always loads");
+    let start = Instant::now();
+    lua.set_interrupt(move |_| {
+        if start.elapsed() > LUA_EXECUTION_TIMEOUT {
+            Err(mlua::Error::RuntimeError(
+                "Lua script exceeded its execution time limit".to_string(),
+            ))
+        } else {
+            Ok(VmState::Continue)
+        }
+    });
+    lua
+}
+
+/// Compiles `source` in `lua` and evaluates it to a callable [`Function`], wrapping any
+/// failure as [`MdxError::LuaScript`] with `label` (the directive or util name)
+/// identifying which script failed.
+fn load_function(lua: &Lua, label: &str, source: &str) -> Result<Function, MdxError> {
+    let func: Function = lua
+        .load(source)
+        .eval()
+        .map_err(|e| MdxError::LuaScript(format!("Lua script '{label}' failed to load: {e}")))?;
+    Ok(func)
+}
+
+/// Runs the Lua directive handler registered under `name` against `attrs` and
+/// `inner_html`, returning its replacement HTML.
+///
+/// # Errors
+/// Returns [`MdxError::LuaScript`] if no handler of that name is registered, the
+/// script fails to compile, exceeds its execution time limit, or errors at runtime.
+pub(crate) fn run_directive(
+    registry: &LuaDirectiveRegistry,
+    name: &str,
+    attrs: &HashMap<String, String>,
+    inner_html: &str,
+) -> Result<String, MdxError> {
+    let source = registry.handlers.get(name).ok_or_else(|| {
+        MdxError::LuaScript(format!(
+            "Unknown Lua directive ':::{name}' - no handler registered"
+        ))
+    })?;
+
+    let lua = new_sandboxed_lua();
+    let func = load_function(&lua, name, source)?;
+
+    let attrs_table = lua
+        .create_table()
+        .map_err(|e| MdxError::LuaScript(format!("Lua directive '{name}' setup failed: {e}")))?;
+    for (key, value) in attrs {
+        attrs_table
+            .set(key.as_str(), value.as_str())
+            .map_err(|e| MdxError::LuaScript(format!("Lua directive '{name}' setup failed: {e}")))?;
+    }
+
+    let result: String = func
+        .call((attrs_table, inner_html))
+        .map_err(|e| MdxError::LuaScript(format!("Lua directive ':::{name}' failed: {e}")))?;
+    Ok(result)
+}
+
+/// Runs the Lua utility function registered under `name` with `args`, returning its
+/// string result.
+///
+/// # Errors
+/// Returns [`MdxError::LuaScript`] if no utility of that name is registered, the
+/// script fails to compile, exceeds its execution time limit, or errors at runtime.
+pub(crate) fn run_util(
+    registry: &LuaUtilsRegistry,
+    name: &str,
+    args: &[String],
+) -> Result<String, MdxError> {
+    let source = registry.functions.get(name).ok_or_else(|| {
+        MdxError::LuaScript(format!("Unknown Lua utility '{name}' - no function registered"))
+    })?;
+
+    let lua = new_sandboxed_lua();
+    let func = load_function(&lua, name, source)?;
+
+    let lua_args: Variadic<String> = args.iter().cloned().collect();
+    let result: String = func
+        .call(lua_args)
+        .map_err(|e| MdxError::LuaScript(format!("Lua utility '{name}' failed: {e}")))?;
+    Ok(result)
+}
+
+/// Matches a container directive block: `:::name` (optionally followed by
+/// `key="value"` attribute pairs on the same line), a body, and a closing `:::` on its
+/// own line.
+static DIRECTIVE_BLOCK: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?m)^:::([A-Za-z][\w-]*)([^\n]*)\n([\s\S]*?)\n:::[ \t]*$")
+        .expect("hardcoded regex pattern is valid")
+});
+
+/// Matches a `key="value"` attribute pair on a directive's opening line.
+static ATTR_PAIR: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"([A-Za-z_][\w-]*)\s*=\s*"([^"]*)""#).expect("hardcoded regex pattern is valid")
+});
+
+/// Matches an inline utility call: `{name(arg, ...)}`. Only fired when `name` is a
+/// registered util - see [`expand_utils`].
+static UTIL_CALL: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"\{([A-Za-z_][\w-]*)\(([^{}]*)\)\}").expect("hardcoded regex pattern is valid")
+});
+
+/// Expands every `:::name ... :::` container directive block in raw MDX `content`
+/// against `registry`, before the surrounding document is rendered as markdown. Each
+/// block's body is first rendered to HTML via [`crate::mdx::render_markdown`] (so the
+/// Lua handler sees real HTML, not raw markdown source), then passed to the handler
+/// registered under `name` along with the block's attributes; the block is replaced
+/// with whatever HTML the handler returns. Content with no directive blocks passes
+/// through unchanged, even if `registry` is empty.
+///
+/// # Errors
+/// Returns [`MdxError::LuaScript`] on the first directive that fails (unknown name or
+/// script error), or [`MdxError::MarkdownRender`] if a block's body fails to render.
+pub(crate) fn expand_directives(content: &str, registry: &LuaDirectiveRegistry) -> Result<String, MdxError> {
+    if registry.is_empty() || !content.contains(":::") {
+        return Ok(content.to_string());
+    }
+
+    let mut out = String::with_capacity(content.len());
+    let mut last = 0;
+    for caps in DIRECTIVE_BLOCK.captures_iter(content) {
+        let whole = caps.get(0).expect("capture group 0 always matches");
+        out.push_str(&content[last..whole.start()]);
+
+        let name = &caps[1];
+        let attrs_source = &caps[2];
+        let inner = &caps[3];
+
+        let mut attrs = HashMap::new();
+        for attr in ATTR_PAIR.captures_iter(attrs_source) {
+            attrs.insert(attr[1].to_string(), attr[2].to_string());
+        }
+
+        let inner_html = crate::mdx::render_markdown(inner)?;
+        let replacement = run_directive(registry, name, &attrs, &inner_html)?;
+
+        out.push('\n');
+        out.push_str(&replacement);
+        out.push('\n');
+        last = whole.end();
+    }
+    out.push_str(&content[last..]);
+    Ok(out)
+}
+
+/// Expands every `{name(arg, ...)}` inline utility call in raw MDX `content` whose
+/// `name` is registered in `registry`, before the surrounding document is rendered as
+/// markdown - an expression whose head isn't a registered util name (including
+/// ordinary JSX `{expr}` interpolation) passes through untouched. Arguments are split
+/// on commas and unquoted like a [`crate::decorators`] expression's parameters.
+///
+/// # Errors
+/// Returns [`MdxError::LuaScript`] on the first call that fails (script error or
+/// execution timeout).
+pub(crate) fn expand_utils(content: &str, registry: &LuaUtilsRegistry) -> Result<String, MdxError> {
+    if registry.is_empty() || !content.contains('{') {
+        return Ok(content.to_string());
+    }
+
+    let mut out = String::with_capacity(content.len());
+    let mut last = 0;
+    for caps in UTIL_CALL.captures_iter(content) {
+        let whole = caps.get(0).expect("capture group 0 always matches");
+        let name = &caps[1];
+        if !registry.functions.contains_key(name) {
+            continue;
+        }
+
+        out.push_str(&content[last..whole.start()]);
+
+        let args_source = &caps[2];
+        let args: Vec<String> = if args_source.trim().is_empty() {
+            Vec::new()
+        } else {
+            args_source.split(',').map(|arg| unquote(arg.trim())).collect()
+        };
+
+        out.push_str(&run_util(registry, name, &args)?);
+        last = whole.end();
+    }
+    out.push_str(&content[last..]);
+    Ok(out)
+}
+
+/// Strips one layer of matching `"`/`'` quotes from `arg`, if present - same
+/// convention as [`crate::decorators`]'s argument parsing.
+fn unquote(arg: &str) -> String {
+    let bytes = arg.as_bytes();
+    if bytes.len() >= 2 {
+        let first = bytes[0];
+        let last = bytes[bytes.len() - 1];
+        if (first == b'"' && last == b'"') || (first == b'\'' && last == b'\'') {
+            return arg[1..arg.len() - 1].to_string();
+        }
+    }
+    arg.to_string()
+}
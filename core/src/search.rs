@@ -0,0 +1,185 @@
+//! Full-text search index generation for a rendered batch (see
+//! [`crate::models::RenderSettings::build_search_index`]), in the mdbook/rustdoc
+//! mould: a client can ship [`SearchIndex`] alongside a static site and rank query
+//! matches in the browser without a server round-trip.
+//!
+//! Each rendered file's HTML is stripped to plain text and tokenized on word
+//! boundaries; each token is recorded against the slug of the nearest preceding
+//! heading (reusing [`crate::toc::inject_heading_ids`]'s slugging, so a query result
+//! can deep-link straight to `#slug`) along with how many times it occurred there.
+//! [`SearchIndex::postings`] is a [`BTreeMap`], so two builds of the same batch
+//! serialize identically regardless of file iteration order.
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::LazyLock;
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+/// Matches a heading's opening tag after [`crate::toc::inject_heading_ids`] has
+/// injected an `id=` slug into it, capturing that slug.
+static HEADING_ID_TAG: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"<h[1-6][^>]*\sid="([^"]+)"[^>]*>"#).expect("hardcoded regex pattern is valid")
+});
+
+/// An inverted index over every word in a rendered batch, suitable for client-side
+/// query-time ranking (see [`crate::models::RenderSettings::build_search_index`]).
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct SearchIndex {
+    /// Lowercased term to every document position it occurs in, sorted by file then
+    /// heading slug so the same batch always serializes the same way.
+    pub postings: BTreeMap<String, Vec<SearchPosting>>,
+}
+
+/// One term's occurrence within a single file, scoped to the heading section it fell
+/// under (see [`SearchIndex`]).
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct SearchPosting {
+    /// Name of the file (the same key used in [`crate::service::BatchRenderOutcome::files`]).
+    pub file: String,
+    /// Slug of the nearest preceding heading (see [`crate::toc::inject_heading_ids`]),
+    /// or `None` if the term occurred before the file's first heading.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub heading_slug: Option<String>,
+    /// Number of times the term occurred in this file's heading section.
+    pub term_frequency: u32,
+}
+
+/// Builds a [`SearchIndex`] from `files` - each entry a rendered file's name paired
+/// with its rendered HTML output. A file whose output isn't present (e.g. a failed
+/// render) is simply absent from `files` and contributes nothing.
+pub(crate) fn build_search_index<'a, I>(files: I) -> SearchIndex
+where
+    I: IntoIterator<Item = (&'a str, &'a str)>,
+{
+    let mut postings: BTreeMap<String, Vec<SearchPosting>> = BTreeMap::new();
+
+    for (file, html) in files {
+        let (html_with_ids, _) = crate::toc::inject_heading_ids(html, 0);
+
+        for (heading_slug, section_html) in heading_sections(&html_with_ids) {
+            let text = crate::toc::strip_tags_and_unescape(section_html);
+
+            let mut term_frequencies: HashMap<String, u32> = HashMap::new();
+            for term in tokenize(&text) {
+                *term_frequencies.entry(term).or_insert(0) += 1;
+            }
+
+            for (term, term_frequency) in term_frequencies {
+                postings.entry(term).or_default().push(SearchPosting {
+                    file: file.to_string(),
+                    heading_slug: heading_slug.clone(),
+                    term_frequency,
+                });
+            }
+        }
+    }
+
+    for posting_list in postings.values_mut() {
+        posting_list.sort_by(|a, b| a.file.cmp(&b.file).then(a.heading_slug.cmp(&b.heading_slug)));
+    }
+
+    SearchIndex { postings }
+}
+
+/// Splits `html` (already heading-id-injected) into `(heading_slug, section_html)`
+/// spans, one per heading plus a leading `None`-keyed span for any content before the
+/// first heading - mirroring how [`crate::toc::inject_heading_ids`] walks heading tags
+/// in document order.
+fn heading_sections(html: &str) -> Vec<(Option<String>, &str)> {
+    let mut boundaries: Vec<(usize, Option<String>)> = vec![(0, None)];
+    for caps in HEADING_ID_TAG.captures_iter(html) {
+        let whole = caps.get(0).expect("group 0 always matches");
+        boundaries.push((whole.start(), Some(caps[1].to_string())));
+    }
+
+    let mut sections = Vec::with_capacity(boundaries.len());
+    for (i, (start, slug)) in boundaries.iter().enumerate() {
+        let end = boundaries.get(i + 1).map_or(html.len(), |(next, _)| *next);
+        if end > *start {
+            sections.push((slug.clone(), &html[*start..end]));
+        }
+    }
+    sections
+}
+
+/// A single document's search index (see
+/// [`crate::models::OutputFormat::SearchIndex`]): the document split into
+/// heading-bounded sections plus an inverted term -> postings map over them - the
+/// single-file analogue of [`SearchIndex`].
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct DocumentSearchIndex {
+    /// One entry per heading-bounded section, in document order (a leading section
+    /// with no preceding heading comes first, if the document has one).
+    pub sections: Vec<DocumentSearchSection>,
+    /// Lowercased term to every section it occurs in, sorted for stable serialization.
+    pub postings: BTreeMap<String, Vec<DocumentSearchPosting>>,
+}
+
+/// One heading-bounded section of a [`DocumentSearchIndex`].
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct DocumentSearchSection {
+    /// Slug of the section's heading (see [`crate::toc::inject_heading_ids`]), or
+    /// `None` for the leading section before the document's first heading.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub heading_slug: Option<String>,
+    /// The section heading's text, or `None` for the leading section.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    /// Plain-text body of the section (tags stripped, entities unescaped).
+    pub body: String,
+}
+
+/// One term's occurrence within a single [`DocumentSearchIndex`] section.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct DocumentSearchPosting {
+    /// Slug of the section the term occurred in - see [`DocumentSearchSection::heading_slug`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub heading_slug: Option<String>,
+    /// Number of times the term occurred in this section.
+    pub term_frequency: u32,
+}
+
+/// Builds a [`DocumentSearchIndex`] over a single rendered document's HTML - see
+/// [`crate::models::OutputFormat::SearchIndex`].
+pub(crate) fn build_document_index(html: &str) -> DocumentSearchIndex {
+    let (html_with_ids, headings) = crate::toc::inject_heading_ids(html, 0);
+    let titles: HashMap<String, String> =
+        headings.into_iter().map(|entry| (entry.slug, entry.text)).collect();
+
+    let mut sections = Vec::new();
+    let mut postings: BTreeMap<String, Vec<DocumentSearchPosting>> = BTreeMap::new();
+
+    for (heading_slug, section_html) in heading_sections(&html_with_ids) {
+        let body = crate::toc::strip_tags_and_unescape(section_html);
+        let title = heading_slug.as_ref().and_then(|slug| titles.get(slug).cloned());
+
+        let mut term_frequencies: HashMap<String, u32> = HashMap::new();
+        for term in tokenize(&body) {
+            *term_frequencies.entry(term).or_insert(0) += 1;
+        }
+        for (term, term_frequency) in term_frequencies {
+            postings
+                .entry(term)
+                .or_default()
+                .push(DocumentSearchPosting { heading_slug: heading_slug.clone(), term_frequency });
+        }
+
+        sections.push(DocumentSearchSection { heading_slug, title, body });
+    }
+
+    for posting_list in postings.values_mut() {
+        posting_list.sort_by(|a, b| a.heading_slug.cmp(&b.heading_slug));
+    }
+
+    DocumentSearchIndex { sections, postings }
+}
+
+/// Splits `text` on non-alphanumeric boundaries and lowercases each resulting token,
+/// dropping empty tokens produced by runs of punctuation/whitespace - the same
+/// alphanumeric-boundary convention `crate::toc`'s slug generation uses.
+fn tokenize(text: &str) -> impl Iterator<Item = String> + '_ {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|term| !term.is_empty())
+        .map(str::to_lowercase)
+}
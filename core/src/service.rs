@@ -6,7 +6,7 @@
 //!
 //! ## Module Size Note
 //!
-//! This module is currently ~593 lines. While slightly over the ~500 line guideline,
+//! This module is currently ~2900 lines. While over the ~500 line guideline,
 //! the code is well-organized into cohesive sections (configuration, service, errors, outcomes).
 //! Consider splitting into submodules if it grows beyond ~700 lines or if new major features
 //! are added that don't fit the current structure.
@@ -18,6 +18,26 @@
 //! - **Resource Limits**: Prevents memory exhaustion from large batches or content
 //! - **Batch Processing**: Renders multiple MDX files in a single operation
 //!
+//! [`RenderService::render_batch`] only returns the final [`BatchRenderOutcome`];
+//! [`RenderService::render_batch_streaming`] additionally emits a [`RenderEvent`] per
+//! file as the batch proceeds, for a host that wants progress feedback on a large
+//! batch. `render_batch` is implemented in terms of it, draining the stream with no
+//! receiver attached.
+//!
+//! A failed file's [`FileRenderOutcome`] carries both the existing flat
+//! [`FileRenderOutcome::error`] string and a structured [`Diagnostic`] list with a
+//! category code and, where available, a source [`DiagnosticSpan`] - so a caller can
+//! locate and filter failures (a JSX parse error, a component-naming-convention
+//! violation, an unresolved import) without substring-matching the flat message.
+//!
+//! [`RenderService::render_file_to`] and [`RenderService::render_batch_to`] write
+//! rendered content directly into a caller-provided `impl std::fmt::Write` sink
+//! instead of returning it as an owned `String` - for a server handler that wants to
+//! stream a render straight into its response body rather than buffer it twice.
+//! [`RenderService::render_batch`] and [`mdx_to_html_with_frontmatter`] remain the
+//! `String`-returning entry points, implemented as thin wrappers over the same
+//! writer-based rendering underneath.
+//!
 //! ## Thread Safety
 //!
 //! `RenderService` is `Clone` and can be shared across threads. However, the underlying
@@ -47,42 +67,134 @@
 //!     settings: RenderSettings {
 //!         output: OutputFormat::Html,
 //!         minify: true,
+//!         compiler_options: None,
+//!         decorators: None,
+//!         highlight: None,
+//!         headings: false,
+//!         diagnostics: Default::default(),
+//!         directives: None,
+//!         lua_directives: None,
+//!         lua_utils: None,
+//!         rewrite_rules: None,
+//!         parallelism: None,
+//!         build_search_index: false,
+//!         fence_attributes: false,
+//!         smart_punctuation: false,
+//!         external_links_target_blank: false,
+//!         external_links_nofollow: false,
+//!         external_links_noreferrer: false,
+//!         external_links_site_host: None,
+//!         parser_hooks: None,
+//!         summary_length: None,
+//!         render_emoji: false,
+//!         render_cache: false,
+//!         ..Default::default()
 //!     },
 //!     mdx: mdx_files,
 //!     components: None,
+//!     partials: None,
 //! };
 //!
 //! let outcome = service.render_batch(&input)?;
 //! # Ok(())
 //! # }
 //! ```
-use crate::mdx::{create_error_response, mdx_to_html_with_frontmatter};
+use crate::decorators::DecoratorRegistry;
+use crate::error::{DiagnosticStyle, FailureCategory, MdxError, Severity, SourceLocation};
+use crate::mdx::{
+    create_error_response, mdx_to_html_with_frontmatter, mdx_to_writer_with_frontmatter,
+};
+use crate::parser_hooks::{ParseSignal, ParserHookRegistry};
+use crate::rewrite::RewriteRegistry;
+use crate::scripting::{LuaDirectiveRegistry, LuaUtilsRegistry};
 use crate::models::{
-    ComponentDefinition, NamedMdxBatchInput, OutputFormat, RenderedMdx,
+    ComponentDefinition, NamedMdxBatchInput, OutputFormat, RenderSettings, RenderedMdx,
     ResourceLimits,
 };
 use crate::renderer::pool::{RendererPool, RendererProfile};
+use crate::renderer::JsRenderer;
 use anyhow::Error as AnyhowError;
 use serde::{Deserialize, Serialize};
+use serde_json::json;
 use std::collections::HashMap;
 use std::env;
-#[cfg(feature = "http")]
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::mpsc::{Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime};
 use std::fs;
-#[cfg(feature = "http")]
+use std::panic::{self, AssertUnwindSafe};
 use std::path::Path;
 use std::path::PathBuf;
 
 const ENV_STATIC_DIR: &str = "RUST_CMS_STATIC_DIR";
+const ENV_MAX_BATCH_CONCURRENCY: &str = "RUST_CMS_MAX_BATCH_CONCURRENCY";
+const ENV_SNAPSHOT_CACHE_DIR: &str = "RUST_CMS_SNAPSHOT_CACHE_DIR";
+const ENV_WORKER_STACK_SIZE_BYTES: &str = "RUST_CMS_WORKER_STACK_SIZE_BYTES";
+const ENV_WORKER_THREADS: &str = "RUST_CMS_WORKER_THREADS";
+/// Smallest stack size [`RenderServiceConfig::worker_stack_size_bytes`] accepts - large
+/// enough for a batch worker thread to check out a renderer and run the TSX
+/// transform/render pipeline without immediately overflowing.
+const MIN_WORKER_STACK_SIZE_BYTES: usize = 64 * 1024;
 
 /// Configuration for the rendering service.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize)]
 pub struct RenderServiceConfig {
     /// Directory containing static JavaScript files (e.g., engine.min.js)
     pub static_dir: PathBuf,
     /// Maximum number of cached renderers per profile
     pub max_cached_renderers: usize,
+    /// Maximum number of files [`RenderService::render_batch_streaming`] (and
+    /// [`RenderService::render_batch`], built on it) renders concurrently within a
+    /// single batch. Each worker thread checks out its own renderer from the
+    /// thread-local pool, so raising this trades CPU/memory for wall-clock time on
+    /// large batches. `1` (the default) keeps the original strictly-sequential,
+    /// single-thread behavior.
+    pub max_batch_concurrency: usize,
     /// Resource limits for preventing resource exhaustion
     pub resource_limits: ResourceLimits,
+    /// Which `Accept-Encoding` codings [`crate::handlers::handle_render_result`] is
+    /// allowed to negotiate for its response body, and the size below which it skips
+    /// compression entirely - see [`crate::compression::CompressionConfig`].
+    pub compression: crate::compression::CompressionConfig,
+    /// Per-file and total size limits a `multipart/form-data` upload is held to - see
+    /// [`crate::upload::UploadConfig`].
+    pub upload: crate::upload::UploadConfig,
+    /// When set, the directory [`RenderService::new`] persists each renderer
+    /// profile's built V8 startup snapshot to (and reloads it from on a later process
+    /// start) - see [`crate::renderer::pool::RendererPool::with_snapshot_cache_dir`].
+    /// `None` (the default) keeps snapshots in-memory only, rebuilt once per process.
+    pub snapshot_cache_dir: Option<PathBuf>,
+    /// When `false`, every renderer is created cold (`JsRenderer::new`) instead of
+    /// deserialized from a cached V8 startup snapshot - see
+    /// [`crate::renderer::pool::RendererPool::set_snapshot_enabled`]. `true` (the
+    /// default) is almost always the right choice; this mostly exists to isolate
+    /// snapshot-related issues or to measure cold-start cost, e.g. in
+    /// `core/benches/render_benchmark.rs`.
+    pub snapshot_enabled: bool,
+    /// When `true`, [`RenderService::render_batch_streaming`] (and
+    /// [`RenderService::render_batch`], built on it) times pool checkout and
+    /// each file's render and attaches a [`RenderProfile`] to the outcome. `false` (the
+    /// default) skips every clock read on the instrumented path, so a deployment that
+    /// never asks for profiling pays nothing for it.
+    pub enable_profiling: bool,
+    /// Stack size, in bytes, given to each persistent worker thread
+    /// [`RenderService::new`] spawns (see `worker_threads`) - the threads
+    /// [`RenderService::render_batch_streaming`] renders on when `max_batch_concurrency`
+    /// (or a per-call `parallelism` override) is greater than `1`. `None` (the
+    /// default) uses the platform's default thread stack size. Raise this if a batch's
+    /// components recurse deeply enough through the TSX transform/render pipeline to
+    /// overflow the default stack - the `concurrency == 1` path reuses the calling
+    /// thread and is unaffected by this setting.
+    pub worker_stack_size_bytes: Option<usize>,
+    /// Number of persistent threads [`RenderService::new`] spawns to back
+    /// [`RenderService::render_batch_streaming`]'s concurrent path - see
+    /// [`crate::batch_worker_pool::BatchWorkerPool`]. Rather than being spawned fresh
+    /// per batch call, these threads live for the lifetime of the service, so setting this has no
+    /// per-call thread-spawn cost. `None` (the default) derives the count from
+    /// `max_cached_renderers`, since that's already a reasonable guess at how many
+    /// renderers are expected to be warm at once.
+    pub worker_threads: Option<usize>,
 }
 
 impl Default for RenderServiceConfig {
@@ -90,7 +202,15 @@ impl Default for RenderServiceConfig {
         Self {
             static_dir: PathBuf::from("static"),
             max_cached_renderers: 4,
+            max_batch_concurrency: 1,
             resource_limits: ResourceLimits::default(),
+            compression: crate::compression::CompressionConfig::default(),
+            upload: crate::upload::UploadConfig::default(),
+            snapshot_cache_dir: None,
+            snapshot_enabled: true,
+            enable_profiling: false,
+            worker_stack_size_bytes: None,
+            worker_threads: None,
         }
     }
 }
@@ -101,7 +221,15 @@ impl Default for RenderServiceConfig {
 struct TomlConfig {
     static_dir: Option<String>,
     max_cached_renderers: Option<usize>,
+    max_batch_concurrency: Option<usize>,
     resource_limits: Option<TomlResourceLimits>,
+    compression: Option<TomlCompressionConfig>,
+    upload: Option<TomlUploadConfig>,
+    snapshot_cache_dir: Option<String>,
+    snapshot_enabled: Option<bool>,
+    enable_profiling: Option<bool>,
+    worker_stack_size_bytes: Option<usize>,
+    worker_threads: Option<usize>,
 }
 
 #[cfg(feature = "http")]
@@ -110,6 +238,23 @@ struct TomlResourceLimits {
     max_batch_size: Option<usize>,
     max_mdx_content_size: Option<usize>,
     max_component_code_size: Option<usize>,
+    max_render_time_ms: Option<u64>,
+}
+
+#[cfg(feature = "http")]
+#[derive(Deserialize, Debug)]
+struct TomlCompressionConfig {
+    gzip: Option<bool>,
+    brotli: Option<bool>,
+    deflate: Option<bool>,
+    min_size_bytes: Option<usize>,
+}
+
+#[cfg(feature = "http")]
+#[derive(Deserialize, Debug)]
+struct TomlUploadConfig {
+    max_file_size_bytes: Option<usize>,
+    max_total_size_bytes: Option<usize>,
 }
 
 impl RenderServiceConfig {
@@ -119,6 +264,24 @@ impl RenderServiceConfig {
         if let Ok(path) = env::var(ENV_STATIC_DIR) {
             config.static_dir = PathBuf::from(path);
         }
+        if let Ok(concurrency) = env::var(ENV_MAX_BATCH_CONCURRENCY) {
+            if let Ok(concurrency) = concurrency.parse() {
+                config.max_batch_concurrency = concurrency;
+            }
+        }
+        if let Ok(path) = env::var(ENV_SNAPSHOT_CACHE_DIR) {
+            config.snapshot_cache_dir = Some(PathBuf::from(path));
+        }
+        if let Ok(stack_size) = env::var(ENV_WORKER_STACK_SIZE_BYTES) {
+            if let Ok(stack_size) = stack_size.parse() {
+                config.worker_stack_size_bytes = Some(stack_size);
+            }
+        }
+        if let Ok(worker_threads) = env::var(ENV_WORKER_THREADS) {
+            if let Ok(worker_threads) = worker_threads.parse() {
+                config.worker_threads = Some(worker_threads);
+            }
+        }
         config
     }
 
@@ -162,6 +325,10 @@ impl RenderServiceConfig {
             config.max_cached_renderers = max_cached;
         }
 
+        if let Some(max_batch_concurrency) = toml_config.max_batch_concurrency {
+            config.max_batch_concurrency = max_batch_concurrency;
+        }
+
         if let Some(limits) = toml_config.resource_limits {
             if let Some(max_batch_size) = limits.max_batch_size {
                 config.resource_limits.max_batch_size = max_batch_size;
@@ -172,6 +339,53 @@ impl RenderServiceConfig {
             if let Some(max_component_code_size) = limits.max_component_code_size {
                 config.resource_limits.max_component_code_size = max_component_code_size;
             }
+            if let Some(max_render_time_ms) = limits.max_render_time_ms {
+                config.resource_limits.max_render_time_ms = max_render_time_ms;
+            }
+        }
+
+        if let Some(compression) = toml_config.compression {
+            if let Some(gzip) = compression.gzip {
+                config.compression.gzip = gzip;
+            }
+            if let Some(brotli) = compression.brotli {
+                config.compression.brotli = brotli;
+            }
+            if let Some(deflate) = compression.deflate {
+                config.compression.deflate = deflate;
+            }
+            if let Some(min_size_bytes) = compression.min_size_bytes {
+                config.compression.min_size_bytes = min_size_bytes;
+            }
+        }
+
+        if let Some(upload) = toml_config.upload {
+            if let Some(max_file_size_bytes) = upload.max_file_size_bytes {
+                config.upload.max_file_size_bytes = max_file_size_bytes;
+            }
+            if let Some(max_total_size_bytes) = upload.max_total_size_bytes {
+                config.upload.max_total_size_bytes = max_total_size_bytes;
+            }
+        }
+
+        if let Some(snapshot_cache_dir) = toml_config.snapshot_cache_dir {
+            config.snapshot_cache_dir = Some(PathBuf::from(snapshot_cache_dir));
+        }
+
+        if let Some(snapshot_enabled) = toml_config.snapshot_enabled {
+            config.snapshot_enabled = snapshot_enabled;
+        }
+
+        if let Some(enable_profiling) = toml_config.enable_profiling {
+            config.enable_profiling = enable_profiling;
+        }
+
+        if let Some(worker_stack_size_bytes) = toml_config.worker_stack_size_bytes {
+            config.worker_stack_size_bytes = Some(worker_stack_size_bytes);
+        }
+
+        if let Some(worker_threads) = toml_config.worker_threads {
+            config.worker_threads = Some(worker_threads);
         }
 
         Ok(config)
@@ -199,6 +413,24 @@ impl RenderServiceConfig {
         if let Ok(path) = env::var(ENV_STATIC_DIR) {
             config.static_dir = PathBuf::from(path);
         }
+        if let Ok(concurrency) = env::var(ENV_MAX_BATCH_CONCURRENCY) {
+            if let Ok(concurrency) = concurrency.parse() {
+                config.max_batch_concurrency = concurrency;
+            }
+        }
+        if let Ok(path) = env::var(ENV_SNAPSHOT_CACHE_DIR) {
+            config.snapshot_cache_dir = Some(PathBuf::from(path));
+        }
+        if let Ok(stack_size) = env::var(ENV_WORKER_STACK_SIZE_BYTES) {
+            if let Ok(stack_size) = stack_size.parse() {
+                config.worker_stack_size_bytes = Some(stack_size);
+            }
+        }
+        if let Ok(worker_threads) = env::var(ENV_WORKER_THREADS) {
+            if let Ok(worker_threads) = worker_threads.parse() {
+                config.worker_threads = Some(worker_threads);
+            }
+        }
 
         Ok(config)
     }
@@ -233,6 +465,38 @@ impl RenderServiceConfig {
             ));
         }
 
+        // Validate max_batch_concurrency is reasonable
+        if self.max_batch_concurrency == 0 {
+            return Err("max_batch_concurrency must be greater than 0".to_string());
+        }
+        if self.max_batch_concurrency > 256 {
+            return Err(format!(
+                "max_batch_concurrency ({}) is unreasonably large, maximum recommended is 256",
+                self.max_batch_concurrency
+            ));
+        }
+
+        // Validate worker stack size is reasonable
+        if let Some(worker_stack_size_bytes) = self.worker_stack_size_bytes {
+            if worker_stack_size_bytes < MIN_WORKER_STACK_SIZE_BYTES {
+                return Err(format!(
+                    "worker_stack_size_bytes ({worker_stack_size_bytes}) is too small, minimum is {MIN_WORKER_STACK_SIZE_BYTES}"
+                ));
+            }
+        }
+
+        // Validate worker_threads is reasonable
+        if let Some(worker_threads) = self.worker_threads {
+            if worker_threads == 0 {
+                return Err("worker_threads must be greater than 0".to_string());
+            }
+            if worker_threads > 256 {
+                return Err(format!(
+                    "worker_threads ({worker_threads}) is unreasonably large, maximum recommended is 256"
+                ));
+            }
+        }
+
         // Validate resource limits
         self.resource_limits.validate()?;
 
@@ -247,8 +511,164 @@ impl RenderServiceConfig {
 /// renderer lifecycle management.
 #[derive(Clone)]
 pub struct RenderService {
-    config: RenderServiceConfig,
+    /// Shared so [`Self::reconfigure`] is visible to every clone of this service (one
+    /// per Actix worker) without tearing anything down - see [`Self::describe`].
+    config: Arc<Mutex<RenderServiceConfig>>,
     pool: RendererPool,
+    /// Persistent worker threads backing [`Self::render_batch_streaming`]'s
+    /// concurrent path - see [`RenderServiceConfig::worker_threads`]. Shared (not
+    /// rebuilt) across every clone of this service, same as `pool`.
+    worker_pool: Arc<crate::batch_worker_pool::BatchWorkerPool>,
+    decorators: DecoratorRegistry,
+    lua_directives: LuaDirectiveRegistry,
+    lua_utils: LuaUtilsRegistry,
+    parser_hooks: ParserHookRegistry,
+    rewrite_rules: RewriteRegistry,
+    /// Opt-in whole-file render cache (see [`Self::with_cache`]). `None` unless
+    /// explicitly enabled, so a caller that never opts in pays no extra hashing cost
+    /// per file.
+    cache: Option<Arc<crate::batch_cache::BatchCache>>,
+    /// Cumulative file counts across every [`Self::render_batch`]/
+    /// [`Self::render_batch_streaming`]/[`Self::render_batch_to`] call since this
+    /// service was created - see [`Self::describe`]. Shared like `config` so every
+    /// clone reports the same running total.
+    counters: Arc<ServiceCounters>,
+}
+
+/// Cumulative success/failure counts backing [`ServiceStatus::total_succeeded`] and
+/// [`ServiceStatus::total_failed`].
+#[derive(Default)]
+struct ServiceCounters {
+    succeeded: AtomicUsize,
+    failed: AtomicUsize,
+}
+
+/// A point-in-time snapshot of a [`RenderService`]'s configuration and runtime
+/// state, returned by [`RenderService::describe`].
+#[derive(Debug, Clone, Serialize)]
+pub struct ServiceStatus {
+    /// The service's current configuration.
+    pub config: RenderServiceConfig,
+    /// The calling thread's renderer pool statistics.
+    pub pool: crate::renderer::pool::PoolStats,
+    /// Total files rendered successfully since this service was created.
+    pub total_succeeded: u64,
+    /// Total files that failed to render since this service was created.
+    pub total_failed: u64,
+}
+
+/// A partial configuration update accepted by [`RenderService::reconfigure`]. Every
+/// field is optional; an absent field leaves that part of the current configuration
+/// untouched.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ServiceReconfigure {
+    /// New maximum cached renderers per profile, if changing it.
+    pub max_cached_renderers: Option<usize>,
+    /// New resource limits, if changing them.
+    pub resource_limits: Option<ResourceLimits>,
+}
+
+/// Tuning for [`RenderService::watch_config`].
+#[cfg(feature = "http")]
+#[derive(Debug, Clone)]
+pub struct ConfigWatchOptions {
+    /// How often to check the watched file for changes.
+    pub poll_interval: std::time::Duration,
+    /// Config files larger than this are skipped rather than parsed, guarding against
+    /// a pathological read (e.g. the path accidentally pointing at a huge file) -
+    /// override via [`Self::allow_oversized`].
+    pub max_file_size_bytes: u64,
+    /// Opt-in to parse a file over [`Self::max_file_size_bytes`] anyway, instead of
+    /// skipping that reload and logging a warning.
+    pub allow_oversized: bool,
+}
+
+#[cfg(feature = "http")]
+impl Default for ConfigWatchOptions {
+    fn default() -> Self {
+        Self {
+            poll_interval: std::time::Duration::from_secs(2),
+            max_file_size_bytes: 1024 * 1024,
+            allow_oversized: false,
+        }
+    }
+}
+
+/// Tuning for [`RenderService::watch`].
+#[derive(Debug, Clone)]
+pub struct MdxWatchOptions {
+    /// How often to poll `directory` for `.mdx` changes.
+    pub poll_interval: Duration,
+    /// How long `directory` must stay unchanged after a detected change before it's
+    /// rendered, coalescing a burst of saves (e.g. a format-on-save editor rewriting
+    /// several files at once) into a single render pass instead of one per file.
+    pub debounce: Duration,
+}
+
+impl Default for MdxWatchOptions {
+    fn default() -> Self {
+        Self {
+            poll_interval: Duration::from_millis(300),
+            debounce: Duration::from_millis(150),
+        }
+    }
+}
+
+/// A running [`RenderService::watch`]. Dropping it (or calling [`Self::stop`])
+/// signals the background polling thread to stop and blocks until it has exited, so
+/// a caller that tears down its service can be sure no render is still in flight
+/// against it afterward.
+pub struct WatchHandle {
+    stop: Arc<AtomicBool>,
+    thread: Option<std::thread::JoinHandle<()>>,
+}
+
+impl WatchHandle {
+    /// Stops the watch and blocks until its background thread has exited.
+    pub fn stop(mut self) {
+        self.stop_and_join();
+    }
+
+    fn stop_and_join(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+impl Drop for WatchHandle {
+    fn drop(&mut self) {
+        self.stop_and_join();
+    }
+}
+
+/// A handle a caller can use to ask [`RenderService::render_batch_cancellable`] to
+/// stop starting new files, without needing a callback or an `async` executor - just
+/// an `Arc<AtomicBool>` flipped from whatever thread decides to cancel (a request
+/// handler reacting to a dropped connection, a CLI signal handler, a test timeout).
+/// `Clone` is shallow - every clone shares the same underlying flag.
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl CancellationToken {
+    /// Creates a token that starts out not cancelled.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Flags this token (and every clone of it) as cancelled.
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    /// Returns `true` once [`Self::cancel`] has been called on this token or any of
+    /// its clones.
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
 }
 
 impl RenderService {
@@ -263,13 +683,163 @@ impl RenderService {
     /// `Ok(RenderService)` if configuration is valid, `Err` with validation error if invalid
     pub fn new(config: RenderServiceConfig) -> Result<Self, String> {
         config.validate()?;
-        let pool = RendererPool::new(config.static_dir.clone(), config.max_cached_renderers);
+        let mut pool = RendererPool::new(config.max_cached_renderers).with_snapshot_enabled(config.snapshot_enabled);
+        if let Some(dir) = &config.snapshot_cache_dir {
+            pool = pool.with_snapshot_cache_dir(dir.clone());
+        }
         // Warm up the pool with one renderer per common profile to reduce first-request latency
         // Skip warming when RUST_CMS_SKIP_POOL_WARMING is set (useful for tests)
         if env::var("RUST_CMS_SKIP_POOL_WARMING").is_err() {
-            pool.warm(1);
+            pool.warm(&[RendererProfile::engine(config.static_dir.clone())], 1);
         }
-        Ok(Self { config, pool })
+        let worker_threads = config.worker_threads.unwrap_or(config.max_cached_renderers);
+        let worker_pool = Arc::new(crate::batch_worker_pool::BatchWorkerPool::new(
+            worker_threads,
+            config.worker_stack_size_bytes,
+        ));
+        Ok(Self {
+            config: Arc::new(Mutex::new(config)),
+            pool,
+            worker_pool,
+            decorators: DecoratorRegistry::new(),
+            lua_directives: LuaDirectiveRegistry::new(),
+            lua_utils: LuaUtilsRegistry::new(),
+            parser_hooks: ParserHookRegistry::new(),
+            rewrite_rules: RewriteRegistry::new(),
+            cache: None,
+            counters: Arc::new(ServiceCounters::default()),
+        })
+    }
+
+    /// Creates a new render service like [`Self::new`], additionally enabling a
+    /// whole-file render cache bounded at `capacity` entries: a subsequent
+    /// [`Self::render_batch`]/[`Self::render_batch_streaming`] call skips re-rendering
+    /// any file whose MDX content, referenced [`ComponentDefinition`]s, and
+    /// [`RenderSettings`] all match a still-cached entry's (see
+    /// [`crate::batch_cache`]), reusing its cached HTML/TOC instead - useful for a
+    /// watch loop that re-renders a mostly-unchanged document set on every save.
+    ///
+    /// # Arguments
+    /// * `config` - Service configuration including static directory and resource limits
+    /// * `capacity` - Maximum number of distinct render results retained before the
+    ///   oldest is evicted (clamped to at least one)
+    ///
+    /// # Returns
+    /// `Ok(RenderService)` if configuration is valid, `Err` with validation error if invalid
+    pub fn with_cache(config: RenderServiceConfig, capacity: usize) -> Result<Self, String> {
+        let mut service = Self::new(config)?;
+        service.cache = Some(Arc::new(crate::batch_cache::BatchCache::new(capacity)));
+        Ok(service)
+    }
+
+    /// Discards every entry in this service's render cache. A no-op if caching wasn't
+    /// enabled via [`Self::with_cache`].
+    pub fn clear_cache(&self) {
+        if let Some(cache) = &self.cache {
+            cache.clear();
+        }
+    }
+
+    /// Number of entries currently held in this service's render cache, or `0` if
+    /// caching wasn't enabled via [`Self::with_cache`].
+    pub fn cached_entries(&self) -> usize {
+        self.cache.as_ref().map(|cache| cache.len()).unwrap_or(0)
+    }
+
+    /// Registers `decorator` under `name` on this service's
+    /// [`DecoratorRegistry`](crate::decorators::DecoratorRegistry), so every
+    /// subsequent [`Self::render_batch`] call can resolve a frontmatter `@name` (or
+    /// `@name(arg, ...)`) decorator expression against it instead of failing with
+    /// [`crate::error::MdxError::UnknownDecorator`] - see
+    /// [`crate::decorators::apply_to_frontmatter`]. Replaces any existing decorator
+    /// already registered under `name`.
+    pub fn register_decorator(
+        &mut self,
+        name: impl Into<String>,
+        decorator: impl Fn(&str, &[String], &serde_json::Value) -> Result<String, MdxError>
+            + Send
+            + Sync
+            + 'static,
+    ) -> &mut Self {
+        self.decorators.register(name, decorator);
+        self
+    }
+
+    /// Registers a Lua-scripted container-directive handler under `name` on this
+    /// service's [`LuaDirectiveRegistry`](crate::scripting::LuaDirectiveRegistry), so a
+    /// markdown `:::name ... :::` block resolves against it on every subsequent
+    /// [`Self::render_batch`] call instead of failing with
+    /// [`crate::error::MdxError::LuaScript`] - see
+    /// [`crate::scripting::expand_directives`]. `source` must evaluate to a Lua
+    /// function of two arguments, `(attrs, html)`, returning the block's replacement
+    /// HTML. Replaces any existing handler already registered under `name`.
+    pub fn register_lua_directive(
+        &mut self,
+        name: impl Into<String>,
+        source: impl Into<std::sync::Arc<str>>,
+    ) -> &mut Self {
+        self.lua_directives.register(name, source);
+        self
+    }
+
+    /// Registers a Lua-scripted template utility under `name` on this service's
+    /// [`LuaUtilsRegistry`](crate::scripting::LuaUtilsRegistry), so an inline
+    /// `{name(arg, ...)}` call in a document's markdown body resolves against it on
+    /// every subsequent [`Self::render_batch`] call - see
+    /// [`crate::scripting::expand_utils`]. `source` must evaluate to a Lua function
+    /// taking the call's arguments as strings and returning a string. Replaces any
+    /// existing utility already registered under `name`.
+    pub fn register_lua_util(
+        &mut self,
+        name: impl Into<String>,
+        source: impl Into<std::sync::Arc<str>>,
+    ) -> &mut Self {
+        self.lua_utils.register(name, source);
+        self
+    }
+
+    /// Registers `hook` as this service's `{...}` expression parser on its
+    /// [`ParserHookRegistry`](crate::parser_hooks::ParserHookRegistry), so every
+    /// subsequent [`Self::render_batch`] call validates each document's curly-brace
+    /// expressions against it, failing with [`crate::error::MdxError::JsExprParse`] on
+    /// the first one `hook` rejects outright - see [`crate::parser_hooks`]. Replaces
+    /// any expression parser already registered.
+    pub fn register_expression_parser(
+        &mut self,
+        hook: impl Fn(&str, usize) -> ParseSignal + Send + Sync + 'static,
+    ) -> &mut Self {
+        self.parser_hooks.set_expression_parser(hook);
+        self
+    }
+
+    /// Registers `hook` as this service's ESM `import`/`export` block parser on its
+    /// [`ParserHookRegistry`](crate::parser_hooks::ParserHookRegistry), so every
+    /// subsequent [`Self::render_batch`] call validates each document's ESM blocks
+    /// against it - see [`crate::parser_hooks`]. Replaces any ESM parser already
+    /// registered.
+    pub fn register_esm_parser(
+        &mut self,
+        hook: impl Fn(&str, usize) -> ParseSignal + Send + Sync + 'static,
+    ) -> &mut Self {
+        self.parser_hooks.set_esm_parser(hook);
+        self
+    }
+
+    /// Parses `source` as a `<Pattern> ==> <Template>` structural rewrite rule and adds
+    /// it to this service's [`RewriteRegistry`](crate::rewrite::RewriteRegistry), so
+    /// every subsequent [`Self::render_batch`] call's
+    /// [`OutputFormat::Schema`](crate::models::OutputFormat::Schema)/`Json`/`Ast` output
+    /// has it applied against the rendered JSON document tree - see
+    /// [`crate::rewrite`]. Rules are tried in registration order, first match per node
+    /// wins.
+    ///
+    /// # Errors
+    /// Returns [`crate::error::MdxError::RewriteRuleParse`] if `source` isn't valid
+    /// `pattern ==> template` syntax, or its template references a metavariable the
+    /// pattern never binds.
+    pub fn register_rewrite_rule(&mut self, source: impl Into<String>) -> Result<&mut Self, MdxError> {
+        self.rewrite_rules.register(source)?;
+        Ok(self)
     }
 
     /// Creates a new render service with configuration validation.
@@ -286,9 +856,256 @@ impl RenderService {
         Self::new(config)
     }
 
-    /// Returns a reference to the service configuration.
-    pub fn config(&self) -> &RenderServiceConfig {
-        &self.config
+    /// Returns a clone of the service's current configuration. An owned value rather
+    /// than a reference since [`Self::reconfigure`] can change it underneath any
+    /// clone of this service at any time - see `config`'s field doc.
+    pub fn config(&self) -> RenderServiceConfig {
+        self.config.lock().unwrap().clone()
+    }
+
+    /// Returns a point-in-time snapshot of this service's configuration and runtime
+    /// state - modeled on a versioned daemon's `/status` endpoint, so the `http`
+    /// feature can trivially mount it as a `GET` JSON response.
+    ///
+    /// [`ServiceStatus::pool`] reflects only the calling thread's renderer cache
+    /// (see [`RendererPool::stats`]'s doc comment) since renderers are thread-local;
+    /// [`ServiceStatus::total_succeeded`]/[`ServiceStatus::total_failed`] are
+    /// process-wide, summed across every thread and every
+    /// [`Self::render_batch`]/[`Self::render_batch_streaming`]/[`Self::render_batch_to`]
+    /// call since this service was created.
+    pub fn describe(&self) -> ServiceStatus {
+        ServiceStatus {
+            config: self.config(),
+            pool: self.pool.stats(),
+            total_succeeded: self.counters.succeeded.load(Ordering::Relaxed) as u64,
+            total_failed: self.counters.failed.load(Ordering::Relaxed) as u64,
+        }
+    }
+
+    /// Atomically applies `partial`'s overrides to this service's configuration,
+    /// without tearing down the service, dropping in-flight batches, or requiring a
+    /// process restart - the `http` feature can trivially mount this as a `PUT` JSON
+    /// endpoint alongside [`Self::describe`].
+    ///
+    /// The candidate configuration (this service's current one with `partial`'s
+    /// fields overlaid) is validated via [`RenderServiceConfig::validate`] before
+    /// anything is committed; on failure, nothing changes. On success, the renderer
+    /// pool's cap is updated in place (see
+    /// [`crate::renderer::pool::RendererPool::set_max_cached_per_key`]) and the
+    /// previous configuration is returned so the caller can roll back by calling
+    /// this again with it.
+    ///
+    /// # Errors
+    /// Returns the [`RenderServiceConfig::validate`] error message if the candidate
+    /// configuration is invalid.
+    pub fn reconfigure(&self, partial: ServiceReconfigure) -> Result<RenderServiceConfig, String> {
+        let mut guard = self.config.lock().unwrap();
+
+        let mut candidate = guard.clone();
+        if let Some(max_cached_renderers) = partial.max_cached_renderers {
+            candidate.max_cached_renderers = max_cached_renderers;
+        }
+        if let Some(resource_limits) = partial.resource_limits {
+            candidate.resource_limits = resource_limits;
+        }
+        candidate.validate()?;
+        let max_cached_renderers = candidate.max_cached_renderers;
+
+        let previous = std::mem::replace(&mut *guard, candidate);
+        drop(guard);
+
+        self.pool.set_max_cached_per_key(max_cached_renderers);
+        Ok(previous)
+    }
+
+    /// Spawns a background thread that polls `path` for changes every
+    /// `options.poll_interval` and, on a change, re-reads it via
+    /// [`RenderServiceConfig::from_file`] and applies its `max_cached_renderers`/
+    /// `resource_limits` to this service through [`Self::reconfigure`] - letting a
+    /// deployment tune limits by editing the config file, without a restart.
+    ///
+    /// Environment variable overrides (see [`RenderServiceConfig::from_file_and_env`])
+    /// remain authoritative: this only re-applies the two fields [`Self::reconfigure`]
+    /// accepts, so a value pinned by an env var at process start is never touched by a
+    /// later file edit.
+    ///
+    /// A reload that fails for any reason - the file grew past
+    /// `options.max_file_size_bytes`, got deleted, failed to parse, or produced an
+    /// invalid configuration - is logged and ignored, leaving the service on its last
+    /// good configuration rather than taking it down.
+    #[cfg(feature = "http")]
+    pub fn watch_config(&self, path: impl Into<PathBuf>, options: ConfigWatchOptions) {
+        let service = self.clone();
+        let path = path.into();
+        std::thread::spawn(move || {
+            let mut last_modified = fs::metadata(&path).and_then(|m| m.modified()).ok();
+            loop {
+                std::thread::sleep(options.poll_interval);
+
+                let modified = match fs::metadata(&path).and_then(|m| m.modified()) {
+                    Ok(modified) => modified,
+                    Err(e) => {
+                        eprintln!(
+                            "⚠️  Failed to stat watched config file {}: {}",
+                            path.display(),
+                            e
+                        );
+                        continue;
+                    }
+                };
+                if Some(modified) == last_modified {
+                    continue;
+                }
+                last_modified = Some(modified);
+
+                if let Err(e) = service.reload_config_from(&path, &options) {
+                    eprintln!(
+                        "⚠️  Ignoring invalid config reload from {}: {}",
+                        path.display(),
+                        e
+                    );
+                }
+            }
+        });
+    }
+
+    /// One pass of [`Self::watch_config`]'s reload: size-guard, parse, and apply
+    /// `path`'s `max_cached_renderers`/`resource_limits` to this service via
+    /// [`Self::reconfigure`].
+    #[cfg(feature = "http")]
+    fn reload_config_from(&self, path: &Path, options: &ConfigWatchOptions) -> Result<(), String> {
+        let size = fs::metadata(path)
+            .map_err(|e| format!("failed to stat {}: {e}", path.display()))?
+            .len();
+        if size > options.max_file_size_bytes && !options.allow_oversized {
+            return Err(format!(
+                "{size} bytes exceeds the {}-byte limit (see `ConfigWatchOptions::allow_oversized`)",
+                options.max_file_size_bytes
+            ));
+        }
+
+        let reloaded = RenderServiceConfig::from_file(path)?;
+        self.reconfigure(ServiceReconfigure {
+            max_cached_renderers: Some(reloaded.max_cached_renderers),
+            resource_limits: Some(reloaded.resource_limits),
+        })?;
+        Ok(())
+    }
+
+    /// Watches `directory` for changes to `.mdx` files and re-renders each changed
+    /// one through this already-warmed service, calling `on_change` with its name
+    /// (relative to `directory`) and [`FileRenderOutcome`] once that pass's batch
+    /// render has finished -
+    /// the same "reuse one service and isolate pool across rebuilds" pattern
+    /// `python-bindings`' rapid-iteration tests validate, applied to a live edit loop
+    /// instead of a test harness.
+    ///
+    /// Polls rather than using a filesystem-event watcher, mirroring
+    /// [`crate::dev_watch::watch_static_dir`]'s reasoning - dependency-free and more
+    /// than responsive enough for a developer saving a file. `options.debounce`
+    /// coalesces a burst of saves (to the same file or several) into a single render
+    /// pass instead of one per file.
+    ///
+    /// Returns a [`WatchHandle`]; dropping it (or calling [`WatchHandle::stop`])
+    /// stops the background thread.
+    pub fn watch(
+        &self,
+        directory: impl Into<PathBuf>,
+        settings: RenderSettings,
+        options: MdxWatchOptions,
+        mut on_change: impl FnMut(String, FileRenderOutcome) + Send + 'static,
+    ) -> WatchHandle {
+        let service = self.clone();
+        let directory = directory.into();
+        let stop = Arc::new(AtomicBool::new(false));
+        let thread_stop = stop.clone();
+
+        let thread = std::thread::spawn(move || {
+            let mut last_snapshot = snapshot_mdx_mtimes(&directory);
+            while !thread_stop.load(Ordering::Relaxed) {
+                std::thread::sleep(options.poll_interval);
+                if thread_stop.load(Ordering::Relaxed) {
+                    break;
+                }
+
+                let mut snapshot = snapshot_mdx_mtimes(&directory);
+                if snapshot == last_snapshot {
+                    continue;
+                }
+
+                // Debounce: keep polling until the tree stops changing for one full
+                // window, so a burst of saves becomes one render pass.
+                loop {
+                    std::thread::sleep(options.debounce);
+                    let next = snapshot_mdx_mtimes(&directory);
+                    if next == snapshot {
+                        break;
+                    }
+                    snapshot = next;
+                }
+
+                let changed: Vec<&PathBuf> = snapshot
+                    .iter()
+                    .filter(|(path, modified)| last_snapshot.get(*path) != Some(*modified))
+                    .map(|(path, _)| path)
+                    .collect();
+
+                let mut mdx = HashMap::with_capacity(changed.len());
+                for path in changed {
+                    let name = path
+                        .strip_prefix(&directory)
+                        .unwrap_or(path)
+                        .to_string_lossy()
+                        .into_owned();
+                    match fs::read_to_string(path) {
+                        Ok(contents) => {
+                            mdx.insert(name, contents);
+                        }
+                        Err(e) => {
+                            eprintln!(
+                                "⚠️  Failed to read changed file {}: {}",
+                                path.display(),
+                                e
+                            );
+                        }
+                    }
+                }
+                last_snapshot = snapshot;
+
+                if mdx.is_empty() {
+                    continue;
+                }
+
+                let input = NamedMdxBatchInput {
+                    settings: settings.clone(),
+                    mdx,
+                    components: None,
+                    partials: None,
+                };
+                match service.render_batch(&input) {
+                    Ok(mut outcome) => {
+                        for (name, file_outcome) in outcome.files.drain() {
+                            on_change(name, file_outcome);
+                        }
+                    }
+                    Err(e) => {
+                        eprintln!("⚠️  Watch render failed: {}", e);
+                    }
+                }
+            }
+        });
+
+        WatchHandle {
+            stop,
+            thread: Some(thread),
+        }
+    }
+
+    /// Adds `succeeded`/`failed` to this service's cumulative counters - see
+    /// [`ServiceStatus::total_succeeded`]/[`ServiceStatus::total_failed`].
+    fn record_outcome(&self, succeeded: usize, failed: usize) {
+        self.counters.succeeded.fetch_add(succeeded, Ordering::Relaxed);
+        self.counters.failed.fetch_add(failed, Ordering::Relaxed);
     }
 
     /// Returns a reference to the renderer pool.
@@ -327,78 +1144,744 @@ impl RenderService {
     pub fn render_batch(
         &self,
         input: &NamedMdxBatchInput,
+    ) -> Result<BatchRenderOutcome, RenderBatchError> {
+        // Drain a streaming run into the final outcome; nothing is listening on the
+        // receiver, but `Sender::send` never blocks on that, so this is just
+        // `render_batch_streaming` minus the progress events.
+        let (sink, _events) = std::sync::mpsc::channel();
+        self.render_batch_streaming(input, &sink)
+    }
+
+    /// Renders a batch like [`Self::render_batch`], but forces this call's
+    /// concurrency to `jobs` regardless of
+    /// [`RenderServiceConfig::max_batch_concurrency`] - equivalent to setting
+    /// [`crate::models::RenderSettings::parallelism`] to `Some(jobs)` before calling
+    /// [`Self::render_batch`], just without needing a `&mut` on `input`. This is what
+    /// backs `dinja`'s Python `Renderer.render_parallel(jobs=...)`, for a caller that
+    /// wants to pick concurrency per call rather than configure it once for the whole
+    /// service.
+    pub fn render_batch_parallel(
+        &self,
+        mut input: NamedMdxBatchInput,
+        jobs: usize,
+    ) -> Result<BatchRenderOutcome, RenderBatchError> {
+        input.settings.parallelism = Some(jobs);
+        self.render_batch(&input)
+    }
+
+    /// Renders a batch like [`Self::render_batch`], but additionally emits a
+    /// [`RenderEvent`] per file lifecycle transition over `sink` as the batch
+    /// proceeds - a `Plan` event up front with the total file count, a `Wait` when
+    /// each file starts, a `Result` when it finishes (with status and elapsed time),
+    /// and a terminal `Summary` - mirroring a test runner's event stream so a host can
+    /// forward these to a progress bar or websocket instead of waiting for the final
+    /// `BatchRenderOutcome`. A send failing (no receiver left) is ignored - the batch
+    /// still completes and its outcome is still returned.
+    pub fn render_batch_streaming(
+        &self,
+        input: &NamedMdxBatchInput,
+        sink: &Sender<RenderEvent>,
     ) -> Result<BatchRenderOutcome, RenderBatchError> {
         // Use components from input directly
         let resolved_components = input.components.as_ref();
+        let resolved_partials = input.partials.as_ref();
 
         // Validate resource limits
         self.validate_resource_limits(input, resolved_components)?;
 
         let profile = self.profile_for_request(&input.settings.output)?;
+        let effective_settings = self.settings_with_decorators(&input.settings);
+        let profiling_enabled = self.config.lock().unwrap().enable_profiling;
+        let batch_started = profiling_enabled.then(Instant::now);
+
+        let _ = sink.send(RenderEvent::Plan {
+            pending: input.mdx.len(),
+            filtered: 0,
+        });
 
         if input.mdx.is_empty() {
+            let _ = sink.send(RenderEvent::Summary {
+                total: 0,
+                succeeded: 0,
+                failed: 0,
+            });
             return Ok(BatchRenderOutcome::empty());
         }
 
-        let renderer = self
-            .pool
-            .checkout(profile)
-            .map_err(RenderBatchError::Internal)?;
-
-        let mut files = HashMap::with_capacity(input.mdx.len());
         // Pre-allocate errors Vec with estimated capacity (assume ~10% failure rate)
         // This denominator represents the expected success rate: 1/10 = 10% failure rate
         // Pre-allocating prevents multiple reallocations during batch processing
         const ESTIMATED_ERROR_RATE_DENOMINATOR: usize = 10;
-        let mut errors = Vec::with_capacity(input.mdx.len() / ESTIMATED_ERROR_RATE_DENOMINATOR);
+
+        // `max_batch_concurrency` files in flight at once, unless this request
+        // overrides it via `RenderSettings.parallelism` - see its doc comment. `1`
+        // (the default) keeps the original strictly-sequential, single-renderer-lease
+        // code path below. Capped at the file count so a large configured concurrency
+        // doesn't spin up threads with nothing to do.
+        let concurrency = input
+            .settings
+            .parallelism
+            .unwrap_or(self.config.lock().unwrap().max_batch_concurrency)
+            .clamp(1, input.mdx.len());
+
+        let (files, errors, succeeded, failed, pool_checkout_ms, coverage) = if concurrency == 1 {
+            let checkout_started = profiling_enabled.then(Instant::now);
+            let renderer = self
+                .pool
+                .checkout(&profile)
+                .map_err(RenderBatchError::Internal)?;
+            let pool_checkout_ms =
+                checkout_started.map(|started| started.elapsed().as_millis() as u64);
+
+            let renderer_ref = renderer
+                .renderer()
+                .map_err(|e| RenderBatchError::Internal(anyhow::Error::from(e)))?;
+            renderer_ref
+                .apply_permissions(&effective_settings.permissions)
+                .map_err(RenderBatchError::Internal)?;
+            if input.settings.coverage {
+                renderer_ref
+                    .start_coverage()
+                    .map_err(RenderBatchError::Internal)?;
+            }
+
+            let mut files = HashMap::with_capacity(input.mdx.len());
+            let mut errors =
+                Vec::with_capacity(input.mdx.len() / ESTIMATED_ERROR_RATE_DENOMINATOR);
+            let mut succeeded = 0usize;
+            let mut failed = 0usize;
+
+            // HOT PATH: Batch processing loop - processes multiple MDX files sequentially
+            // Error recovery: Individual file failures don't stop the batch; errors are
+            // collected and returned in the outcome. This allows partial success scenarios -
+            // except a `FailureCategory::Forbidden` failure (a component tripping a
+            // `ComponentPermissions` trap), which aborts the whole batch immediately: a
+            // capability violation is a policy breach, not ordinary bad content, and
+            // should fail loud rather than let the rest of the batch quietly proceed.
+            for (name, mdx_source) in &input.mdx {
+                let _ = sink.send(RenderEvent::Wait { name: name.clone() });
+                let renderer_ref = renderer
+                    .renderer()
+                    .map_err(|e| RenderBatchError::Internal(anyhow::Error::from(e)))?;
+                let (file_outcome, batch_error) = render_one_file_catching_panics(
+                    name,
+                    mdx_source,
+                    renderer_ref,
+                    resolved_components,
+                    resolved_partials,
+                    &effective_settings,
+                    self.cache.as_deref(),
+                );
+                if let Some(batch_error) = &batch_error {
+                    if batch_error.category == FailureCategory::Forbidden {
+                        return Err(RenderBatchError::Forbidden(batch_error.message.clone()));
+                    }
+                }
+                if batch_error.is_some() {
+                    failed += 1;
+                } else {
+                    succeeded += 1;
+                }
+                let _ = sink.send(RenderEvent::Result {
+                    name: name.clone(),
+                    duration_ms: file_outcome.duration_ms,
+                    status: file_outcome.status.clone(),
+                    output: file_outcome.result.as_ref().and_then(|r| r.output.clone()),
+                    error: file_outcome.error.clone(),
+                });
+                if let Some(batch_error) = batch_error {
+                    errors.push(batch_error);
+                }
+                files.insert(name.clone(), file_outcome);
+            }
+
+            let coverage = if input.settings.coverage {
+                Some(
+                    renderer
+                        .renderer()
+                        .map_err(|e| RenderBatchError::Internal(anyhow::Error::from(e)))?
+                        .collect_coverage()
+                        .map_err(RenderBatchError::Internal)?,
+                )
+            } else {
+                None
+            };
+
+            (files, errors, succeeded, failed, pool_checkout_ms, coverage)
+        } else {
+            // Drive the batch through this service's persistent worker pool (see
+            // `crate::batch_worker_pool`) instead of spawning threads fresh for this
+            // call - each of its long-lived threads still checks out its own renderer
+            // from the thread-local pool per job, same as before. `concurrency` keeps
+            // bounding how many of *this* batch's files are in flight at once,
+            // regardless of how many threads the pool actually has, via a sliding
+            // window: `concurrency` jobs are enqueued up front, and each reply
+            // immediately enqueues the next not-yet-dispatched file, if any.
+            let entries: Vec<(&String, &String)> = input.mdx.iter().collect();
+            let components = Arc::new(input.components.clone());
+            let partials = Arc::new(input.partials.clone());
+            let settings = Arc::new(effective_settings.clone());
+
+            let dispatch = self.worker_pool.begin_batch(
+                self.pool.clone(),
+                profile.clone(),
+                components,
+                partials,
+                settings,
+                self.cache.clone(),
+                sink.clone(),
+            );
+
+            let mut next = 0usize;
+            let mut in_flight = 0usize;
+            while next < concurrency && next < entries.len() {
+                let (name, mdx_source) = entries[next];
+                dispatch.enqueue(name, mdx_source);
+                next += 1;
+                in_flight += 1;
+            }
+
+            let mut files = HashMap::with_capacity(entries.len());
+            let mut errors = Vec::with_capacity(entries.len() / ESTIMATED_ERROR_RATE_DENOMINATOR);
+            let mut succeeded = 0usize;
+            let mut failed = 0usize;
+            let mut forbidden: Option<String> = None;
+            let mut internal_error: Option<MdxError> = None;
+            // Merged counts from every job's own renderer - see `concurrency == 1`
+            // path above for what a single renderer's coverage collection looks like.
+            let mut merged_coverage: Option<HashMap<String, u32>> =
+                input.settings.coverage.then(HashMap::new);
+            let mut aborted = false;
+
+            while in_flight > 0 {
+                let outcome = dispatch.recv();
+                in_flight -= 1;
+
+                match outcome {
+                    crate::batch_worker_pool::JobOutcome::Skipped => {}
+                    crate::batch_worker_pool::JobOutcome::Failed(err) => {
+                        if internal_error.is_none() {
+                            internal_error = Some(err);
+                        }
+                        aborted = true;
+                        dispatch.abort();
+                    }
+                    crate::batch_worker_pool::JobOutcome::Rendered {
+                        name,
+                        file_outcome,
+                        batch_error,
+                        coverage,
+                    } => {
+                        if let Some(counts) = coverage {
+                            if let Some(merged) = merged_coverage.as_mut() {
+                                for (component, count) in counts {
+                                    *merged.entry(component).or_insert(0) += count;
+                                }
+                            }
+                        }
+                        let is_forbidden = batch_error
+                            .as_ref()
+                            .is_some_and(|e| e.category == FailureCategory::Forbidden);
+                        if is_forbidden {
+                            let batch_error = batch_error.expect("checked above");
+                            if forbidden.is_none() {
+                                forbidden = Some(batch_error.message.clone());
+                            }
+                            aborted = true;
+                            dispatch.abort();
+                        } else if let Some(batch_error) = batch_error {
+                            failed += 1;
+                            errors.push(batch_error);
+                            files.insert(name, file_outcome);
+                        } else {
+                            succeeded += 1;
+                            files.insert(name, file_outcome);
+                        }
+                    }
+                }
+
+                if !aborted && next < entries.len() {
+                    let (name, mdx_source) = entries[next];
+                    dispatch.enqueue(name, mdx_source);
+                    next += 1;
+                    in_flight += 1;
+                }
+            }
+
+            if let Some(message) = forbidden {
+                return Err(RenderBatchError::Forbidden(message));
+            }
+            if let Some(err) = internal_error {
+                return Err(RenderBatchError::Internal(anyhow::Error::from(err)));
+            }
+
+            (files, errors, succeeded, failed, None, merged_coverage)
+        };
+
+        let _ = sink.send(RenderEvent::Summary {
+            total: succeeded + failed,
+            succeeded,
+            failed,
+        });
+        self.record_outcome(succeeded, failed);
+
+        let mut outcome = BatchRenderOutcome::new(files, errors, succeeded, failed);
+        if input.settings.build_search_index {
+            let indexable = outcome.files.iter().filter_map(|(name, file_outcome)| {
+                let html = file_outcome.result.as_ref()?.output.as_deref()?;
+                Some((name.as_str(), html))
+            });
+            outcome.search_index = Some(crate::search::build_search_index(indexable));
+        }
+        if let (Some(batch_started), Some(pool_checkout_ms)) = (batch_started, pool_checkout_ms) {
+            outcome.profile =
+                Some(render_profile_for(&outcome.files, pool_checkout_ms, batch_started));
+        }
+        outcome.coverage = coverage.map(CoverageReport::from_counts);
+
+        Ok(outcome)
+    }
+
+    /// Renders a batch like [`Self::render_batch_streaming`] with `parallelism`/
+    /// `max_batch_concurrency` ignored - always the single-renderer-lease sequential
+    /// path - but checks `cancellation` before starting each file, so a caller with a
+    /// handle to the same token can stop the batch early (e.g. when a client
+    /// disconnects). A file already in progress always finishes; cancellation only
+    /// skips files that haven't started yet, and the checked-out renderer is dropped
+    /// normally afterward, returning it to the pool exactly as it would on a completed
+    /// batch.
+    ///
+    /// The returned outcome's `cancelled` is `true` if cancellation was observed
+    /// before every file was attempted, and `skipped` names the files that were never
+    /// started - resubmit just those to resume.
+    ///
+    /// # Errors
+    /// Returns `RenderBatchError` under the same conditions as [`Self::render_batch`].
+    pub fn render_batch_cancellable(
+        &self,
+        input: &NamedMdxBatchInput,
+        sink: &Sender<RenderEvent>,
+        cancellation: &CancellationToken,
+    ) -> Result<BatchRenderOutcome, RenderBatchError> {
+        let resolved_components = input.components.as_ref();
+        let resolved_partials = input.partials.as_ref();
+
+        self.validate_resource_limits(input, resolved_components)?;
+
+        let profile = self.profile_for_request(&input.settings.output)?;
+        let effective_settings = self.settings_with_decorators(&input.settings);
+
+        let _ = sink.send(RenderEvent::Plan {
+            pending: input.mdx.len(),
+            filtered: 0,
+        });
+
+        if input.mdx.is_empty() {
+            let _ = sink.send(RenderEvent::Summary {
+                total: 0,
+                succeeded: 0,
+                failed: 0,
+            });
+            return Ok(BatchRenderOutcome::empty());
+        }
+
+        let renderer = self.pool.checkout(&profile).map_err(RenderBatchError::Internal)?;
+        let renderer_ref = renderer
+            .renderer()
+            .map_err(|e| RenderBatchError::Internal(anyhow::Error::from(e)))?;
+        renderer_ref
+            .apply_permissions(&effective_settings.permissions)
+            .map_err(RenderBatchError::Internal)?;
+        if input.settings.coverage {
+            renderer_ref.start_coverage().map_err(RenderBatchError::Internal)?;
+        }
+
+        let mut files = HashMap::with_capacity(input.mdx.len());
+        let mut errors = Vec::new();
         let mut succeeded = 0usize;
         let mut failed = 0usize;
+        let mut skipped = Vec::new();
+        let mut cancelled = false;
 
-        // HOT PATH: Batch processing loop - processes multiple MDX files sequentially
-        // Error recovery: Individual file failures don't stop the batch; errors are collected
-        // and returned in the outcome. This allows partial success scenarios.
         for (name, mdx_source) in &input.mdx {
+            if cancellation.is_cancelled() {
+                cancelled = true;
+                skipped.push(name.clone());
+                continue;
+            }
+
+            let _ = sink.send(RenderEvent::Wait { name: name.clone() });
             let renderer_ref = renderer
                 .renderer()
                 .map_err(|e| RenderBatchError::Internal(anyhow::Error::from(e)))?;
-            match mdx_to_html_with_frontmatter(
+            let (file_outcome, batch_error) = render_one_file_catching_panics(
+                name,
+                mdx_source,
+                renderer_ref,
+                resolved_components,
+                resolved_partials,
+                &effective_settings,
+                self.cache.as_deref(),
+            );
+            if let Some(batch_error) = &batch_error {
+                if batch_error.category == FailureCategory::Forbidden {
+                    drop(renderer);
+                    return Err(RenderBatchError::Forbidden(batch_error.message.clone()));
+                }
+            }
+            if batch_error.is_some() {
+                failed += 1;
+            } else {
+                succeeded += 1;
+            }
+            let _ = sink.send(RenderEvent::Result {
+                name: name.clone(),
+                duration_ms: file_outcome.duration_ms,
+                status: file_outcome.status.clone(),
+                output: file_outcome.result.as_ref().and_then(|r| r.output.clone()),
+                error: file_outcome.error.clone(),
+            });
+            if let Some(batch_error) = batch_error {
+                errors.push(batch_error);
+            }
+            files.insert(name.clone(), file_outcome);
+        }
+        let coverage = if input.settings.coverage {
+            Some(
+                renderer
+                    .renderer()
+                    .map_err(|e| RenderBatchError::Internal(anyhow::Error::from(e)))?
+                    .collect_coverage()
+                    .map_err(RenderBatchError::Internal)?,
+            )
+        } else {
+            None
+        };
+        drop(renderer);
+
+        let _ = sink.send(RenderEvent::Summary {
+            total: succeeded + failed,
+            succeeded,
+            failed,
+        });
+        self.record_outcome(succeeded, failed);
+
+        let mut outcome = BatchRenderOutcome::new(files, errors, succeeded, failed);
+        outcome.cancelled = cancelled;
+        outcome.skipped = skipped;
+        outcome.coverage = coverage.map(CoverageReport::from_counts);
+        if input.settings.build_search_index {
+            let indexable = outcome.files.iter().filter_map(|(name, file_outcome)| {
+                let html = file_outcome.result.as_ref()?.output.as_deref()?;
+                Some((name.as_str(), html))
+            });
+            outcome.search_index = Some(crate::search::build_search_index(indexable));
+        }
+
+        Ok(outcome)
+    }
+
+    /// Renders a batch like [`Self::render_batch_streaming`], but returns a channel of
+    /// already-NDJSON-encoded lines instead of [`RenderEvent`]s - one line per file as
+    /// it finishes (`{"name", "status", "output"|"error"}`), followed by a final
+    /// summary line (`{"summary": true, "total", "succeeded", "failed", "status"}`)
+    /// once every file has been processed. Meant for a handler that wants to write a
+    /// chunked response body as each item completes instead of buffering the whole
+    /// `BatchRenderOutcome` in memory until the slowest file finishes.
+    ///
+    /// Resource-limit and engine-availability validation happens synchronously before
+    /// this function returns, exactly as in [`Self::render_batch`] - so a caller can
+    /// still reject an invalid batch with its usual status code before committing to a
+    /// streaming `200` response. Only once validation passes does the actual rendering
+    /// move onto a background thread, with lines drained from the returned receiver as
+    /// they arrive.
+    ///
+    /// # Errors
+    /// Returns `RenderBatchError` under the same conditions as [`Self::render_batch`],
+    /// before any rendering or streaming begins.
+    pub fn render_batch_ndjson(
+        &self,
+        input: NamedMdxBatchInput,
+    ) -> Result<Receiver<Vec<u8>>, RenderBatchError> {
+        self.validate_resource_limits(&input, input.components.as_ref())?;
+        self.profile_for_request(&input.settings.output)?;
+
+        let (lines_tx, lines_rx) = std::sync::mpsc::channel::<Vec<u8>>();
+        let service = self.clone();
+        std::thread::spawn(move || {
+            let (events_tx, events_rx) = std::sync::mpsc::channel();
+            let render_thread =
+                std::thread::spawn(move || service.render_batch_streaming(&input, &events_tx));
+
+            for event in events_rx {
+                let line = match event {
+                    RenderEvent::Result { name, status, output, error, .. } => {
+                        json!({ "name": name, "status": status, "output": output, "error": error })
+                    }
+                    RenderEvent::Summary {
+                        total,
+                        succeeded,
+                        failed,
+                    } => {
+                        let status = if failed == 0 {
+                            "success"
+                        } else if succeeded == 0 {
+                            "failure"
+                        } else {
+                            "partial"
+                        };
+                        json!({
+                            "summary": true,
+                            "total": total,
+                            "succeeded": succeeded,
+                            "failed": failed,
+                            "status": status,
+                        })
+                    }
+                    RenderEvent::Plan { .. } | RenderEvent::Wait { .. } => continue,
+                };
+                let Ok(mut bytes) = serde_json::to_vec(&line) else {
+                    continue;
+                };
+                bytes.push(b'\n');
+                if lines_tx.send(bytes).is_err() {
+                    break;
+                }
+            }
+
+            // Propagate an internal error (e.g. renderer checkout failure) as a final
+            // error line rather than silently closing the stream - the HTTP status was
+            // already committed to 200, so this is the only way left to surface it.
+            if let Err(err) = render_thread.join().unwrap_or_else(|_| {
+                Err(RenderBatchError::Internal(anyhow::anyhow!(
+                    "render worker thread panicked"
+                )))
+            }) {
+                let line =
+                    json!({ "summary": true, "status": "failure", "error": err.to_string() });
+                if let Ok(mut bytes) = serde_json::to_vec(&line) {
+                    bytes.push(b'\n');
+                    let _ = lines_tx.send(bytes);
+                }
+            }
+        });
+
+        Ok(lines_rx)
+    }
+
+    /// Renders a single named file and writes its content directly into `out`,
+    /// instead of returning it as an owned `String` a caller (e.g. an HTTP handler)
+    /// then has to copy into its own response buffer. Returns the file's parsed
+    /// frontmatter metadata; the rendered content itself is only available through
+    /// `out`.
+    ///
+    /// The JS engine's render step still produces its result as one owned `String`
+    /// internally - there is no way to stream partial output out of a single engine
+    /// evaluation - but this skips the copies the batch path adds on top of that: no
+    /// `RenderedMdx`, `FileRenderOutcome`, or batch `HashMap` is ever allocated for the
+    /// result, so a server handler can stream straight into its response body.
+    ///
+    /// # Errors
+    /// Returns `RenderBatchError` under the same conditions as [`Self::render_batch`],
+    /// plus if writing to `out` fails.
+    pub fn render_file_to<W: std::fmt::Write>(
+        &self,
+        name: &str,
+        mdx: &str,
+        components: Option<&HashMap<String, ComponentDefinition>>,
+        partials: Option<&HashMap<String, String>>,
+        settings: &RenderSettings,
+        out: &mut W,
+    ) -> Result<crate::mdx::FrontmatterResult, RenderBatchError> {
+        let mut mdx_files = HashMap::with_capacity(1);
+        mdx_files.insert(name.to_string(), mdx.to_string());
+        let validation_input = NamedMdxBatchInput {
+            settings: settings.clone(),
+            mdx: mdx_files,
+            components: components.cloned(),
+            partials: partials.cloned(),
+        };
+        self.validate_resource_limits(&validation_input, components)?;
+
+        let profile = self.profile_for_request(&settings.output)?;
+        let effective_settings = self.settings_with_decorators(settings);
+        let renderer = self
+            .pool
+            .checkout(&profile)
+            .map_err(RenderBatchError::Internal)?;
+        let renderer_ref = renderer
+            .renderer()
+            .map_err(|e| RenderBatchError::Internal(AnyhowError::from(e)))?;
+        renderer_ref
+            .apply_permissions(&effective_settings.permissions)
+            .map_err(RenderBatchError::Internal)?;
+
+        mdx_to_writer_with_frontmatter(
+            mdx,
+            renderer_ref,
+            components,
+            partials,
+            &effective_settings,
+            out,
+        )
+        .map_err(RenderBatchError::from)
+    }
+
+    /// Batch variant of [`Self::render_file_to`]: renders every file in `input`,
+    /// writing each one's content into the sink `sink_for` returns for its name
+    /// instead of buffering every file's output in the returned outcome. A file
+    /// `sink_for` returns `None` for is skipped - its render outcome is never
+    /// produced and it is excluded from the returned counts.
+    ///
+    /// The returned [`BatchRenderOutcome`] carries the same per-file status and
+    /// [`FileRenderOutcome::diagnostics`] [`Self::render_batch`] does; each
+    /// [`FileRenderOutcome::result`]'s `output` is `None` since the content was
+    /// written to its sink instead.
+    ///
+    /// # Errors
+    /// Returns `RenderBatchError` under the same conditions as [`Self::render_batch`].
+    pub fn render_batch_to<W: std::fmt::Write>(
+        &self,
+        input: &NamedMdxBatchInput,
+        mut sink_for: impl FnMut(&str) -> Option<W>,
+    ) -> Result<BatchRenderOutcome, RenderBatchError> {
+        let resolved_components = input.components.as_ref();
+        let resolved_partials = input.partials.as_ref();
+        self.validate_resource_limits(input, resolved_components)?;
+
+        if input.mdx.is_empty() {
+            return Ok(BatchRenderOutcome::empty());
+        }
+
+        let profile = self.profile_for_request(&input.settings.output)?;
+        let effective_settings = self.settings_with_decorators(&input.settings);
+        let renderer = self
+            .pool
+            .checkout(&profile)
+            .map_err(RenderBatchError::Internal)?;
+        renderer
+            .renderer()
+            .map_err(|e| RenderBatchError::Internal(AnyhowError::from(e)))?
+            .apply_permissions(&effective_settings.permissions)
+            .map_err(RenderBatchError::Internal)?;
+
+        let mut files = HashMap::with_capacity(input.mdx.len());
+        let mut errors = Vec::new();
+        let mut succeeded = 0usize;
+        let mut failed = 0usize;
+
+        for (name, mdx_source) in &input.mdx {
+            let Some(mut sink) = sink_for(name) else {
+                continue;
+            };
+            let started = Instant::now();
+            let renderer_ref = renderer
+                .renderer()
+                .map_err(|e| RenderBatchError::Internal(AnyhowError::from(e)))?;
+            let components: Vec<String> = resolved_components
+                .map(|components| {
+                    crate::transform::referenced_component_names(mdx_source, components)
+                        .into_iter()
+                        .map(String::from)
+                        .collect()
+                })
+                .unwrap_or_default();
+
+            let file_outcome = match mdx_to_writer_with_frontmatter(
                 mdx_source,
                 renderer_ref,
                 resolved_components,
-                &input.settings,
+                resolved_partials,
+                &effective_settings,
+                &mut sink,
             ) {
-                Ok(rendered) => {
+                Ok(result) => {
                     succeeded += 1;
-                    files.insert(name.clone(), FileRenderOutcome::success(rendered));
+                    FileRenderOutcome::success(
+                        RenderedMdx {
+                            metadata: result.metadata,
+                            output: None,
+                            toc: result.toc,
+                            summary: result.summary,
+                            doctests: result.doctests,
+                        },
+                        started.elapsed().as_millis() as u64,
+                        components,
+                    )
                 }
                 Err(err) => {
                     failed += 1;
-                    // Convert MdxError to anyhow::Error for error response creation
-                    // Using `anyhow::Error::from()` preserves the error chain automatically
-                    // since MdxError implements std::error::Error via thiserror
-                    let anyhow_err = anyhow::Error::from(err);
-                    // Preserve full error context including chain using {:#} format
-                    // This includes all underlying causes in the error chain
+                    let diagnostics = diagnostics_for_error(
+                        name,
+                        mdx_source,
+                        &err,
+                        effective_settings.diagnostics,
+                    );
+                    let category = err.kind().category();
+                    let anyhow_err = AnyhowError::from(err);
                     let message = format!("{:#}", anyhow_err);
+                    if category == FailureCategory::Forbidden {
+                        return Err(RenderBatchError::Forbidden(message));
+                    }
                     let fallback = create_error_response(&anyhow_err);
                     errors.push(BatchError {
                         file: name.clone(),
                         message: message.clone(),
+                        category,
                     });
-                    files.insert(name.clone(), FileRenderOutcome::failure(message, fallback));
+                    FileRenderOutcome::failure(
+                        message,
+                        fallback,
+                        diagnostics,
+                        started.elapsed().as_millis() as u64,
+                        components,
+                        category,
+                    )
                 }
-            }
+            };
+
+            files.insert(name.clone(), file_outcome);
         }
 
+        self.record_outcome(succeeded, failed);
         Ok(BatchRenderOutcome::new(files, errors, succeeded, failed))
     }
 
+    /// Returns `settings` cloned with this service's [`DecoratorRegistry`], Lua
+    /// scripting registries, [`ParserHookRegistry`](crate::parser_hooks::ParserHookRegistry),
+    /// and [`RewriteRegistry`] attached, for whichever of them have entries registered -
+    /// see [`Self::register_decorator`], [`Self::register_lua_directive`],
+    /// [`Self::register_lua_util`], [`Self::register_expression_parser`],
+    /// [`Self::register_esm_parser`], and [`Self::register_rewrite_rule`]. Called once
+    /// per batch (not once per file), so the clone this requires is amortized over
+    /// every file in it.
+    fn settings_with_decorators(&self, settings: &RenderSettings) -> RenderSettings {
+        let mut settings = settings.clone();
+        if !self.decorators.is_empty() {
+            settings.decorators = Some(self.decorators.clone());
+        }
+        if !self.lua_directives.is_empty() {
+            settings.lua_directives = Some(self.lua_directives.clone());
+        }
+        if !self.lua_utils.is_empty() {
+            settings.lua_utils = Some(self.lua_utils.clone());
+        }
+        if !self.parser_hooks.is_empty() {
+            settings.parser_hooks = Some(self.parser_hooks.clone());
+        }
+        if !self.rewrite_rules.is_empty() {
+            settings.rewrite_rules = Some(self.rewrite_rules.clone());
+        }
+        settings.external_html_root = Some(self.config.lock().unwrap().static_dir.clone());
+        settings
+    }
+
     fn validate_resource_limits(
         &self,
         input: &NamedMdxBatchInput,
         components: Option<&HashMap<String, ComponentDefinition>>,
     ) -> Result<(), RenderBatchError> {
-        let limits = &self.config.resource_limits;
+        let limits = self.config.lock().unwrap().resource_limits.clone();
 
         // Check batch size
         if input.mdx.len() > limits.max_batch_size {
@@ -443,8 +1926,15 @@ impl RenderService {
         format: &OutputFormat,
     ) -> Result<RendererProfile, RenderBatchError> {
         match format {
-            OutputFormat::Html | OutputFormat::Javascript | OutputFormat::Schema | OutputFormat::Json => {
-                Ok(RendererProfile::Engine)
+            OutputFormat::Html
+            | OutputFormat::Javascript
+            | OutputFormat::Schema
+            | OutputFormat::Json
+            | OutputFormat::Ast
+            | OutputFormat::EsModule
+            | OutputFormat::Toc
+            | OutputFormat::SearchIndex => {
+                Ok(RendererProfile::engine(self.config.lock().unwrap().static_dir.clone()))
             }
         }
     }
@@ -489,6 +1979,9 @@ impl From<anyhow::Error> for RenderBatchError {
 
 impl From<crate::error::MdxError> for RenderBatchError {
     fn from(err: crate::error::MdxError) -> Self {
+        if err.kind().category() == FailureCategory::Forbidden {
+            return RenderBatchError::Forbidden(err.to_string());
+        }
         RenderBatchError::Internal(anyhow::Error::from(err))
     }
 }
@@ -508,6 +2001,134 @@ pub struct BatchRenderOutcome {
     /// Map of file names to their rendering outcomes
     #[serde(default)]
     pub files: HashMap<String, FileRenderOutcome>,
+    /// Inverted full-text search index over every successfully rendered HTML file in
+    /// the batch (see [`crate::search`]), present only when
+    /// [`crate::models::RenderSettings::build_search_index`] was set on the request.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub search_index: Option<crate::search::SearchIndex>,
+    /// `true` if [`RenderService::render_batch_cancellable`]'s token was observed
+    /// cancelled before every file was attempted. `false` for every other render
+    /// path, which can't be cancelled.
+    #[serde(default)]
+    pub cancelled: bool,
+    /// Names of files [`RenderService::render_batch_cancellable`] never attempted
+    /// because `cancelled` became true first - resubmit just these to resume.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub skipped: Vec<String>,
+    /// Per-phase timing for this batch, present only when
+    /// [`RenderServiceConfig::enable_profiling`] is set. Only populated for a
+    /// strictly-sequential batch (`max_batch_concurrency`/`RenderSettings::parallelism`
+    /// of `1`) - a concurrent batch's per-thread checkout times don't collapse into a
+    /// single meaningful number, so it's left `None` there.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub profile: Option<RenderProfile>,
+    /// Per-component invocation counts for this batch, present only when
+    /// [`crate::models::RenderSettings::coverage`] was set on the request.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub coverage: Option<CoverageReport>,
+}
+
+/// Which registered components this batch actually instantiated, built from
+/// [`crate::renderer::JsRenderer::collect_coverage`] when
+/// [`crate::models::RenderSettings::coverage`] is set - see
+/// [`BatchRenderOutcome::coverage`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CoverageReport {
+    /// Invocation count for every registered component, keyed by name - `0` for one
+    /// that was registered but never rendered.
+    pub components: HashMap<String, u32>,
+    /// Names of components with a `0` count in `components`, for convenience over
+    /// filtering it client-side.
+    pub unused_components: Vec<String>,
+}
+
+impl CoverageReport {
+    fn from_counts(components: HashMap<String, u32>) -> Self {
+        let unused_components = components
+            .iter()
+            .filter(|(_, &count)| count == 0)
+            .map(|(name, _)| name.clone())
+            .collect();
+        Self {
+            components,
+            unused_components,
+        }
+    }
+}
+
+/// Aggregated timing for one batch, built by [`render_profile_for`] from each file's
+/// [`FileRenderOutcome::duration_ms`] plus the time spent checking out a renderer from
+/// the pool up front - see [`RenderServiceConfig::enable_profiling`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RenderProfile {
+    /// Wall-clock time for the whole batch, in milliseconds.
+    pub total_ms: u64,
+    /// Time spent checking out a renderer from the pool before rendering started.
+    pub pool_checkout_ms: u64,
+    /// Sum of every file's render time - the renderer's actual busy time, as opposed
+    /// to `total_ms`, which also includes checkout and per-event bookkeeping.
+    pub renderer_busy_ms: u64,
+    /// Fastest file's render time.
+    pub file_ms_min: u64,
+    /// Median file render time.
+    pub file_ms_median: u64,
+    /// 95th-percentile file render time.
+    pub file_ms_p95: u64,
+    /// Slowest file's render time.
+    pub file_ms_max: u64,
+    /// The slowest files in the batch, slowest first, capped at
+    /// [`PROFILE_SLOWEST_FILES_LIMIT`].
+    pub slowest_files: Vec<SlowFile>,
+}
+
+/// One entry in [`RenderProfile::slowest_files`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SlowFile {
+    /// File name.
+    pub name: String,
+    /// How long it took to render, in milliseconds.
+    pub duration_ms: u64,
+}
+
+/// Maximum number of files [`render_profile_for`] lists in
+/// [`RenderProfile::slowest_files`].
+const PROFILE_SLOWEST_FILES_LIMIT: usize = 5;
+
+/// Builds a [`RenderProfile`] from a completed batch's per-file outcomes, the time it
+/// took to check out a renderer, and the batch's start time.
+fn render_profile_for(
+    files: &HashMap<String, FileRenderOutcome>,
+    pool_checkout_ms: u64,
+    batch_started: Instant,
+) -> RenderProfile {
+    let mut durations: Vec<u64> = files.values().map(|f| f.duration_ms).collect();
+    durations.sort_unstable();
+
+    let percentile = |p: f64| -> u64 {
+        if durations.is_empty() {
+            return 0;
+        }
+        let idx = ((durations.len() - 1) as f64 * p).round() as usize;
+        durations[idx]
+    };
+
+    let mut slowest: Vec<SlowFile> = files
+        .iter()
+        .map(|(name, outcome)| SlowFile { name: name.clone(), duration_ms: outcome.duration_ms })
+        .collect();
+    slowest.sort_by(|a, b| b.duration_ms.cmp(&a.duration_ms));
+    slowest.truncate(PROFILE_SLOWEST_FILES_LIMIT);
+
+    RenderProfile {
+        total_ms: batch_started.elapsed().as_millis() as u64,
+        pool_checkout_ms,
+        renderer_busy_ms: durations.iter().sum(),
+        file_ms_min: durations.first().copied().unwrap_or(0),
+        file_ms_median: percentile(0.5),
+        file_ms_p95: percentile(0.95),
+        file_ms_max: durations.last().copied().unwrap_or(0),
+        slowest_files: slowest,
+    }
 }
 
 impl BatchRenderOutcome {
@@ -531,6 +2152,11 @@ impl BatchRenderOutcome {
             failed,
             errors,
             files,
+            search_index: None,
+            cancelled: false,
+            skipped: Vec::new(),
+            profile: None,
+            coverage: None,
         }
     }
 
@@ -542,6 +2168,11 @@ impl BatchRenderOutcome {
             failed: 0,
             errors: Vec::new(),
             files: HashMap::new(),
+            search_index: None,
+            cancelled: false,
+            skipped: Vec::new(),
+            profile: None,
+            coverage: None,
         }
     }
 
@@ -554,6 +2185,209 @@ impl BatchRenderOutcome {
     pub fn is_complete_failure(&self) -> bool {
         self.total > 0 && self.succeeded == 0
     }
+
+    /// Serializes this outcome as a JUnit `<testsuites>` XML report, for CI systems
+    /// (Buildkite, Jenkins, GitHub Actions) that render JUnit results natively - so
+    /// `render_batch` can be dropped straight into a content-linting CI step. Each file
+    /// becomes its own `<testsuite>`, containing a `<testcase>` for the file itself plus
+    /// one more per component instantiated within it (see [`FileRenderOutcome::components`]),
+    /// all with `classname` set to the file path - real `<testcase>` elements rather than
+    /// `<property>` tags, so CI UIs that only understand cases still show the structure. A
+    /// failed file's own `<testcase>` carries a `<failure>` built from its
+    /// [`FileRenderOutcome::diagnostics`] (or its flat [`FileRenderOutcome::error`] if it
+    /// has none), with each diagnostic's line, column, help and source frame folded into
+    /// the failure body as code context. Writes directly into one pre-sized `String`
+    /// rather than building an intermediate document, so a large batch's report is never
+    /// buffered twice.
+    pub fn to_junit_xml(&self) -> String {
+        use std::fmt::Write as _;
+
+        let total_time_ms: u64 = self.files.values().map(|outcome| outcome.duration_ms).sum();
+        let total_cases: usize = self
+            .files
+            .values()
+            .map(|outcome| 1 + outcome.components.len())
+            .sum();
+
+        let mut xml = String::with_capacity(256 + total_cases * 160);
+        let _ = writeln!(xml, r#"<?xml version="1.0" encoding="UTF-8"?>"#);
+        let _ = writeln!(
+            xml,
+            r#"<testsuites name="dinja" tests="{}" failures="{}" time="{:.3}">"#,
+            total_cases,
+            self.failed,
+            total_time_ms as f64 / 1000.0,
+        );
+
+        let mut names: Vec<&String> = self.files.keys().collect();
+        names.sort();
+
+        for name in names {
+            let outcome = &self.files[name];
+            let failed = matches!(outcome.status, FileRenderStatus::Failed);
+            let suite_tests = 1 + outcome.components.len();
+
+            let _ = writeln!(
+                xml,
+                r#"  <testsuite name="{}" tests="{}" failures="{}" time="{:.3}">"#,
+                xml_escape(name),
+                suite_tests,
+                usize::from(failed),
+                outcome.duration_ms as f64 / 1000.0,
+            );
+
+            let _ = write!(
+                xml,
+                r#"    <testcase name="{}" classname="{}" time="{:.3}">"#,
+                xml_escape(name),
+                xml_escape(name),
+                outcome.duration_ms as f64 / 1000.0,
+            );
+
+            if failed {
+                let message = junit_failure_message(outcome);
+                let context = junit_failure_context(outcome);
+                let _ = write!(
+                    xml,
+                    "\n      <failure message=\"{}\"><![CDATA[{}]]></failure>\n    ",
+                    xml_escape(&message),
+                    escape_cdata(&context),
+                );
+            }
+
+            xml.push_str("</testcase>\n");
+
+            for component in &outcome.components {
+                let _ = writeln!(
+                    xml,
+                    r#"    <testcase name="{}" classname="{}" time="0"/>"#,
+                    xml_escape(component),
+                    xml_escape(name),
+                );
+            }
+
+            xml.push_str("  </testsuite>\n");
+        }
+
+        xml.push_str("</testsuites>\n");
+        xml
+    }
+}
+
+/// The message a failed file's `<failure>` element's `message` attribute is built from:
+/// its diagnostics joined one per line, or its flat error string if it has none.
+fn junit_failure_message(outcome: &FileRenderOutcome) -> String {
+    if outcome.diagnostics.is_empty() {
+        outcome.error.clone().unwrap_or_default()
+    } else {
+        outcome
+            .diagnostics
+            .iter()
+            .map(|diagnostic| diagnostic.message.as_str())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+/// The CDATA body of a failed file's `<failure>` element: each diagnostic's message
+/// followed by its line/column (1-indexed for display), help text and source frame when
+/// present, so a CI log shows the same code context the error type already carries
+/// instead of just a flat message.
+fn junit_failure_context(outcome: &FileRenderOutcome) -> String {
+    if outcome.diagnostics.is_empty() {
+        return outcome.error.clone().unwrap_or_default();
+    }
+
+    let mut context = String::new();
+    for diagnostic in &outcome.diagnostics {
+        if !context.is_empty() {
+            context.push('\n');
+        }
+        context.push_str(&diagnostic.message);
+        if let Some(span) = &diagnostic.span {
+            context.push_str(&format!(" (line {}, column {})", span.line + 1, span.column + 1));
+        }
+        if let Some(frame) = &diagnostic.frame {
+            context.push('\n');
+            context.push_str(frame);
+        }
+        if let Some(help) = &diagnostic.help {
+            context.push_str("\n= help: ");
+            context.push_str(help);
+        }
+    }
+    context
+}
+
+/// Escapes `&`, `<`, `>`, `"` and `'` for use in an XML attribute value or text node.
+fn xml_escape(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            other => escaped.push(other),
+        }
+    }
+    escaped
+}
+
+/// Escapes a `]]>` terminator so `value` is safe to embed in a `<![CDATA[ ]]>` section,
+/// by closing and reopening the section around each occurrence.
+fn escape_cdata(value: &str) -> String {
+    value.replace("]]>", "]]]]><![CDATA[>")
+}
+
+/// A per-file lifecycle event emitted by [`RenderService::render_batch_streaming`] as
+/// a batch proceeds, mirroring a test runner's plan/wait/result/summary event stream
+/// so a host can forward these to a progress bar or websocket instead of waiting for
+/// the final [`BatchRenderOutcome`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", content = "data", rename_all = "snake_case")]
+pub enum RenderEvent {
+    /// Emitted once, before any file starts rendering.
+    Plan {
+        /// Number of files queued for this batch
+        pending: usize,
+        /// Number of files excluded from the batch up front (always 0 today; mirrors
+        /// the test-runner event this is modeled on, which distinguishes a filtered
+        /// count from the pending count)
+        filtered: usize,
+    },
+    /// Emitted just before a file starts rendering.
+    Wait {
+        /// File about to be rendered
+        name: String,
+    },
+    /// Emitted once a file finishes rendering, successfully or not.
+    Result {
+        /// File that finished
+        name: String,
+        /// Wall-clock time spent rendering this file, in milliseconds
+        duration_ms: u64,
+        /// Whether the file succeeded or failed
+        status: FileRenderStatus,
+        /// Rendered output, present on success - carried alongside `status` so a
+        /// consumer (e.g. [`RenderService::render_batch_ndjson`]) can forward a
+        /// complete per-file result without waiting on the final `BatchRenderOutcome`.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        output: Option<String>,
+        /// Error message, present on failure.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        error: Option<String>,
+    },
+    /// Emitted once, after every file in the batch has been processed.
+    Summary {
+        /// Total files processed
+        total: usize,
+        /// Files that rendered successfully
+        succeeded: usize,
+        /// Files that failed to render
+        failed: usize,
+    },
 }
 
 /// Error information for a single file in a batch
@@ -563,10 +2397,464 @@ pub struct BatchError {
     pub file: String,
     /// Error message describing the failure
     pub message: String,
+    /// Coarse, stable classification of this failure (see [`FailureCategory`]), for
+    /// filtering - e.g. alerting only on [`FailureCategory::Internal`] rather than
+    /// every error a batch produced.
+    pub category: FailureCategory,
+}
+
+/// A single source-span-aware diagnostic produced while rendering a file - a JSX parse
+/// error, a component-naming-convention violation, an unresolved component reference,
+/// or a TypeScript transform error. Unlike [`FileRenderOutcome::error`]'s flat string,
+/// each diagnostic keeps its own category [`Self::code`] and, when the underlying error
+/// carried one, a [`Self::span`], so a caller can filter or locate a failure without
+/// substring-matching the message.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Diagnostic {
+    /// How seriously this diagnostic should be treated.
+    pub severity: Severity,
+    /// Stable category code (see [`crate::error::diagnostic_codes`] and
+    /// [`crate::error::MdxErrorKind::diagnostic_code`]), for tooling that wants to
+    /// filter by kind rather than pattern-match [`Self::message`].
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub code: Option<String>,
+    /// Human-readable description of the problem.
+    pub message: String,
+    /// Location of the offending source, when the underlying error carried one.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub span: Option<DiagnosticSpan>,
+    /// Name of the file this diagnostic was raised against.
+    pub file: String,
+    /// Suggested fix or explanation, carried through from [`crate::error::ParseError::help`]
+    /// when the underlying error set one.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub help: Option<String>,
+    /// Rendered source code frame (source line plus a caret underline), carried
+    /// through from [`crate::error::ParseError::frame`] - used as the code context in
+    /// [`BatchRenderOutcome::to_junit_xml`]'s `<failure>` body.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub frame: Option<String>,
+    /// An `ariadne`-rendered report string pointing at this diagnostic's byte range in
+    /// the original source, populated only when [`crate::models::RenderSettings::diagnostics`]
+    /// is [`DiagnosticStyle::Pretty`] and this diagnostic has a [`Self::span`]. `None`
+    /// under [`DiagnosticStyle::Plain`] (where [`Self::render_diagnostic`]'s frame
+    /// already covers the same need without the extra dependency) or when there's no
+    /// span to annotate.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub report: Option<String>,
+}
+
+/// A [`Diagnostic`]'s location within its source file: a 0-indexed line and column plus
+/// a span length, mirroring [`crate::error::SourceLocation`] without the raw byte
+/// offset a diagnostics consumer (an editor, a CLI) has no use for.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct DiagnosticSpan {
+    /// 0-indexed line number
+    pub line: u32,
+    /// 0-indexed column number
+    pub column: u32,
+    /// Length of the span in bytes
+    pub length: u32,
+}
+
+/// Number of source lines shown before and after the offending line in
+/// [`Diagnostic::render_diagnostic`]'s code frame.
+const DIAGNOSTIC_CONTEXT_LINES: usize = 2;
+
+impl Diagnostic {
+    /// Renders this diagnostic as an IDE-quality code frame against `source` (the
+    /// original content of [`Self::file`]), in the style of Deno's `Diagnostic`
+    /// presentation: a `file:line:column: message` header, the offending line plus a
+    /// couple of lines of surrounding context, a caret (`^`) underline spanning
+    /// [`DiagnosticSpan::length`] under the column, and [`Self::help`] below. Set `ansi`
+    /// to wrap the header and caret in ANSI color codes for terminal output; pass
+    /// `false` when writing to a log file or web UI.
+    ///
+    /// Falls back to a bare `file: message` line when this diagnostic has no
+    /// [`Self::span`] (some [`crate::error::MdxError`] variants aren't tied to a single
+    /// source location).
+    pub fn render_diagnostic(&self, source: &str, ansi: bool) -> String {
+        use std::fmt::Write as _;
+
+        let (bold, red, dim, reset) = if ansi {
+            ("\x1b[1m", "\x1b[31m", "\x1b[2m", "\x1b[0m")
+        } else {
+            ("", "", "", "")
+        };
+
+        let Some(span) = self.span else {
+            return format!("{bold}{}{reset}: {}", self.file, self.message);
+        };
+
+        let lines: Vec<&str> = source.lines().collect();
+        let line_idx = (span.line as usize).min(lines.len().saturating_sub(1));
+        let start = line_idx.saturating_sub(DIAGNOSTIC_CONTEXT_LINES);
+        let end = (line_idx + DIAGNOSTIC_CONTEXT_LINES + 1).min(lines.len());
+        let gutter_width = end.to_string().len();
+
+        let mut rendered = format!(
+            "{bold}{}:{}:{}{reset}: {red}{}{reset}\n",
+            self.file,
+            span.line + 1,
+            span.column + 1,
+            self.message,
+        );
+
+        for (offset, line_text) in lines[start..end].iter().enumerate() {
+            let n = start + offset;
+            let _ = write!(
+                &mut rendered,
+                "{:>gutter_width$} | {line_text}\n",
+                n + 1,
+            );
+            if n == line_idx {
+                let column = span.column as usize;
+                let remaining = line_text.chars().count().saturating_sub(column).max(1);
+                let caret_len = (span.length.max(1) as usize).min(remaining);
+                let _ = write!(
+                    &mut rendered,
+                    "{} | {dim}{}{reset}{red}{}{reset}\n",
+                    " ".repeat(gutter_width),
+                    " ".repeat(column),
+                    "^".repeat(caret_len),
+                );
+            }
+        }
+
+        if let Some(help) = &self.help {
+            let _ = write!(&mut rendered, "{dim}= help: {help}{reset}\n");
+        }
+
+        rendered
+    }
+}
+
+/// Renders one file and builds its [`FileRenderOutcome`], plus the [`BatchError`] to
+/// record for it if it failed - the part of
+/// [`RenderService::render_batch_streaming`]'s per-file work shared by its sequential
+/// and [`crate::batch_worker_pool`] code paths.
+fn render_one_file(
+    name: &str,
+    mdx_source: &str,
+    renderer_ref: &JsRenderer,
+    resolved_components: Option<&HashMap<String, ComponentDefinition>>,
+    resolved_partials: Option<&HashMap<String, String>>,
+    effective_settings: &RenderSettings,
+) -> (FileRenderOutcome, Option<BatchError>) {
+    let started = Instant::now();
+    let components: Vec<String> = resolved_components
+        .map(|components| {
+            crate::transform::referenced_component_names(mdx_source, components)
+                .into_iter()
+                .map(String::from)
+                .collect()
+        })
+        .unwrap_or_default();
+
+    match mdx_to_html_with_frontmatter(
+        mdx_source,
+        renderer_ref,
+        resolved_components,
+        resolved_partials,
+        effective_settings,
+    ) {
+        Ok(rendered) => (
+            FileRenderOutcome::success(rendered, started.elapsed().as_millis() as u64, components),
+            None,
+        ),
+        Err(err) => {
+            let diagnostics =
+                diagnostics_for_error(name, mdx_source, &err, effective_settings.diagnostics);
+            let category = err.kind().category();
+            // Convert MdxError to anyhow::Error for error response creation
+            // Using `anyhow::Error::from()` preserves the error chain automatically
+            // since MdxError implements std::error::Error via thiserror
+            let anyhow_err = anyhow::Error::from(err);
+            // Preserve full error context including chain using {:#} format
+            // This includes all underlying causes in the error chain
+            let message = format!("{:#}", anyhow_err);
+            let fallback = create_error_response(&anyhow_err);
+            let batch_error = BatchError {
+                file: name.to_string(),
+                message: message.clone(),
+                category,
+            };
+            let outcome = FileRenderOutcome::failure(
+                message,
+                fallback,
+                diagnostics,
+                started.elapsed().as_millis() as u64,
+                components,
+                category,
+            );
+            (outcome, Some(batch_error))
+        }
+    }
+}
+
+/// Renders one file like [`render_one_file`], but first consults `cache` (see
+/// [`RenderService::with_cache`]) for a result keyed on this file's content,
+/// referenced components, partials, and settings - skipping the render entirely on a
+/// hit, and storing a freshly-rendered success into it on a miss. Identical to
+/// [`render_one_file`] when `cache` is `None`.
+#[allow(clippy::too_many_arguments)]
+fn render_one_file_cached(
+    name: &str,
+    mdx_source: &str,
+    renderer_ref: &JsRenderer,
+    resolved_components: Option<&HashMap<String, ComponentDefinition>>,
+    resolved_partials: Option<&HashMap<String, String>>,
+    effective_settings: &RenderSettings,
+    cache: Option<&crate::batch_cache::BatchCache>,
+) -> (FileRenderOutcome, Option<BatchError>) {
+    let Some(cache) = cache else {
+        return render_one_file(
+            name,
+            mdx_source,
+            renderer_ref,
+            resolved_components,
+            resolved_partials,
+            effective_settings,
+        );
+    };
+
+    let referenced: Vec<(&str, &ComponentDefinition)> = resolved_components
+        .map(|components| {
+            crate::transform::referenced_component_names(mdx_source, components)
+                .into_iter()
+                .filter_map(|referenced_name| {
+                    components
+                        .get(referenced_name)
+                        .map(|component| (referenced_name, component))
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    let digest = crate::batch_cache::digest_for(
+        mdx_source,
+        &referenced,
+        resolved_partials,
+        effective_settings,
+    );
+
+    if let Some(cached) = cache.get(&digest) {
+        let components = referenced
+            .iter()
+            .map(|(name, _)| name.to_string())
+            .collect();
+        return (FileRenderOutcome::success(cached, 0, components), None);
+    }
+
+    let (outcome, batch_error) = render_one_file(
+        name,
+        mdx_source,
+        renderer_ref,
+        resolved_components,
+        resolved_partials,
+        effective_settings,
+    );
+    if batch_error.is_none() {
+        if let Some(result) = &outcome.result {
+            cache.insert(digest, result.clone());
+        }
+    }
+    (outcome, batch_error)
+}
+
+/// Renders one file like [`render_one_file_cached`], but additionally catches a panic
+/// raised while doing so, recording it as an ordinary failed [`FileRenderOutcome`]/
+/// [`BatchError`] for this file instead of letting it unwind past the batch and take
+/// every other file's result with it - rustdoc's `catch_with_exit_code` panic-isolation
+/// strategy, applied per file rather than per whole run. The per-file worker boundary
+/// each of [`RenderService::render_batch_streaming`]'s two code paths and
+/// [`RenderService::render_batch_cancellable`] call this at is exactly the
+/// `AssertUnwindSafe` this needs: a panic here can only have interrupted this one file's
+/// render, so the renderer, components, and settings it borrowed are still in a usable
+/// state for the next file.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn render_one_file_catching_panics(
+    name: &str,
+    mdx_source: &str,
+    renderer_ref: &JsRenderer,
+    resolved_components: Option<&HashMap<String, ComponentDefinition>>,
+    resolved_partials: Option<&HashMap<String, String>>,
+    effective_settings: &RenderSettings,
+    cache: Option<&crate::batch_cache::BatchCache>,
+) -> (FileRenderOutcome, Option<BatchError>) {
+    let started = Instant::now();
+    panic::catch_unwind(AssertUnwindSafe(|| {
+        render_one_file_cached(
+            name,
+            mdx_source,
+            renderer_ref,
+            resolved_components,
+            resolved_partials,
+            effective_settings,
+            cache,
+        )
+    }))
+    .unwrap_or_else(|payload| {
+        let anyhow_err = anyhow::anyhow!(
+            "panic while rendering {name}: {}",
+            panic_payload_message(payload)
+        );
+        let message = format!("{:#}", anyhow_err);
+        let fallback = create_error_response(&anyhow_err);
+        let batch_error = BatchError {
+            file: name.to_string(),
+            message: message.clone(),
+            category: FailureCategory::Internal,
+        };
+        let outcome = FileRenderOutcome::failure(
+            message,
+            fallback,
+            Vec::new(),
+            started.elapsed().as_millis() as u64,
+            Vec::new(),
+            FailureCategory::Internal,
+        );
+        (outcome, Some(batch_error))
+    })
+}
+
+/// Extracts a human-readable message from a caught panic payload - `&str`/`String`
+/// payloads (the overwhelming majority, from `panic!`/`.unwrap()`/`.expect()`) are used
+/// directly; anything else falls back to a generic placeholder.
+fn panic_payload_message(payload: Box<dyn std::any::Any + Send>) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "non-string panic payload".to_string()
+    }
+}
+
+/// Converts `err` (raised while rendering `file`, whose original content is `source`)
+/// into one [`Diagnostic`] per [`crate::error::ParseError`] it carries (see
+/// [`MdxError::errors`]), or a single span-less diagnostic for every other variant.
+/// When `style` is [`DiagnosticStyle::Pretty`], each diagnostic with a location also
+/// gets its [`Diagnostic::report`] populated via `ariadne`.
+fn diagnostics_for_error(
+    file: &str,
+    source: &str,
+    err: &MdxError,
+    style: DiagnosticStyle,
+) -> Vec<Diagnostic> {
+    match err.errors() {
+        Some(parse_errors) => parse_errors
+            .iter()
+            .map(|parse_error| Diagnostic {
+                severity: parse_error.severity,
+                code: parse_error
+                    .code
+                    .clone()
+                    .or_else(|| Some(err.kind().diagnostic_code().to_string())),
+                message: parse_error.message.clone(),
+                span: parse_error
+                    .location
+                    .as_ref()
+                    .map(|location| DiagnosticSpan {
+                        line: location.line,
+                        column: location.column,
+                        length: location.length,
+                    }),
+                file: file.to_string(),
+                help: parse_error.help.clone(),
+                frame: parse_error.frame.clone(),
+                report: match (style, parse_error.location.as_ref()) {
+                    (DiagnosticStyle::Pretty, Some(location)) => Some(render_ariadne_report(
+                        file,
+                        &parse_error.message,
+                        location,
+                        parse_error.help.as_deref(),
+                        source,
+                    )),
+                    _ => None,
+                },
+            })
+            .collect(),
+        None => vec![Diagnostic {
+            severity: Severity::Error,
+            code: Some(err.kind().diagnostic_code().to_string()),
+            message: err.to_string(),
+            span: None,
+            file: file.to_string(),
+            help: None,
+            frame: None,
+            report: None,
+        }],
+    }
+}
+
+/// Builds an `ariadne`-rendered report string for a single diagnostic at `location`
+/// within `source`, underlining its byte range and attaching `message` (plus `help`,
+/// if any) - the `Pretty` counterpart to [`Diagnostic::render_diagnostic`]'s hand-rolled
+/// frame.
+fn render_ariadne_report(
+    file: &str,
+    message: &str,
+    location: &SourceLocation,
+    help: Option<&str>,
+    source: &str,
+) -> String {
+    use ariadne::{Color, Label, Report, ReportKind, Source};
+
+    let start = location.offset as usize;
+    let end = start + (location.length.max(1) as usize);
+
+    let mut builder = Report::build(ReportKind::Error, file, start)
+        .with_message(message)
+        .with_label(
+            Label::new((file, start..end))
+                .with_message(message)
+                .with_color(Color::Red),
+        );
+    if let Some(help) = help {
+        builder = builder.with_help(help);
+    }
+
+    let mut rendered = Vec::new();
+    if builder
+        .finish()
+        .write((file, Source::from(source)), &mut rendered)
+        .is_err()
+    {
+        return message.to_string();
+    }
+    String::from_utf8(rendered).unwrap_or_else(|_| message.to_string())
+}
+
+/// Maps every `.mdx` file under `dir` (recursively) to its last-modified time, used
+/// by [`RenderService::watch`] to detect a change between two polls. Missing or
+/// unreadable entries are skipped rather than failing the whole scan, since a file
+/// can legitimately disappear mid-save (editors often write via a temp file and
+/// rename) - mirrors [`crate::dev_watch`]'s own `snapshot_mtimes`, just filtered to
+/// `.mdx` files instead of every file.
+fn snapshot_mdx_mtimes(dir: &Path) -> HashMap<PathBuf, SystemTime> {
+    let mut snapshot = HashMap::new();
+    collect_mdx_mtimes(dir, &mut snapshot);
+    snapshot
+}
+
+fn collect_mdx_mtimes(dir: &Path, snapshot: &mut HashMap<PathBuf, SystemTime>) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            collect_mdx_mtimes(&path, snapshot);
+        } else if path.extension().is_some_and(|ext| ext == "mdx") {
+            if let Ok(modified) = entry.metadata().and_then(|metadata| metadata.modified()) {
+                snapshot.insert(path, modified);
+            }
+        }
+    }
 }
 
 /// Status of a single file render operation
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum FileRenderStatus {
     /// File rendered successfully
@@ -586,22 +2874,57 @@ pub struct FileRenderOutcome {
     /// Error message (only present on failure)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub error: Option<String>,
+    /// Structured, span-aware diagnostics for this file (see [`Diagnostic`]). Empty on
+    /// success. [`Self::error`] remains the full error-chain string for callers that
+    /// don't need per-diagnostic detail; this list is its structured counterpart.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub diagnostics: Vec<Diagnostic>,
+    /// Wall-clock time spent rendering this file, in milliseconds (see
+    /// [`BatchRenderOutcome::to_junit_xml`], which reports it per `<testcase>`).
+    #[serde(default)]
+    pub duration_ms: u64,
+    /// Names of the components instantiated within this file (see
+    /// [`crate::transform::referenced_component_names`]), in no particular order. Empty
+    /// if the file used no components. [`BatchRenderOutcome::to_junit_xml`] reports one
+    /// `<testcase>` per entry alongside the file's own.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub components: Vec<String>,
+    /// Coarse, stable classification of this file's failure (see [`FailureCategory`]),
+    /// `None` on success. Lets a monitor filter on e.g.
+    /// [`FailureCategory::Internal`] without string-matching [`Self::error`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub category: Option<FailureCategory>,
 }
 
 impl FileRenderOutcome {
-    fn success(result: RenderedMdx) -> Self {
+    fn success(result: RenderedMdx, duration_ms: u64, components: Vec<String>) -> Self {
         Self {
             status: FileRenderStatus::Success,
             result: Some(result),
             error: None,
+            diagnostics: Vec::new(),
+            duration_ms,
+            components,
+            category: None,
         }
     }
 
-    fn failure(message: String, fallback: RenderedMdx) -> Self {
+    fn failure(
+        message: String,
+        fallback: RenderedMdx,
+        diagnostics: Vec<Diagnostic>,
+        duration_ms: u64,
+        components: Vec<String>,
+        category: FailureCategory,
+    ) -> Self {
         Self {
             status: FileRenderStatus::Failed,
             result: Some(fallback),
             error: Some(message),
+            diagnostics,
+            duration_ms,
+            components,
+            category: Some(category),
         }
     }
 }
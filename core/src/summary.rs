@@ -0,0 +1,47 @@
+//! Plain-text summary/excerpt generation, over rendered HTML.
+//!
+//! Mirrors rustdoc's `plain_text_summary`: drop `<pre>` code blocks entirely (their
+//! content isn't prose), strip every remaining HTML/JSX tag, collapse whitespace runs
+//! to a single space (cf. mdbook's `collapse_whitespace`), and truncate to a character
+//! budget on a word boundary with a trailing ellipsis - a short plain-text excerpt
+//! suitable for `<meta name="description">` or a list-page teaser, without a second
+//! pass over the source MDX.
+
+use crate::toc::strip_tags_and_unescape;
+use regex::Regex;
+use std::sync::LazyLock;
+
+/// Matches a `<pre>...</pre>` element (with or without attributes), including its
+/// contents.
+///
+/// # Safety
+/// Pattern is compile-time constant and known to be valid.
+static PRE_BLOCK: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?s)<pre\b[^>]*>.*?</pre>").expect("hardcoded regex pattern is valid")
+});
+
+/// Matches a run of whitespace (including newlines).
+static WHITESPACE_RUN: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\s+").expect("hardcoded regex pattern is valid"));
+
+/// Builds a plain-text summary of rendered `html`, truncated to at most `max_chars`
+/// characters - see [`crate::models::RenderSettings::summary_length`].
+pub(crate) fn plain_text_summary(html: &str, max_chars: usize) -> String {
+    let without_code = PRE_BLOCK.replace_all(html, "");
+    let text = strip_tags_and_unescape(&without_code);
+    let collapsed = WHITESPACE_RUN.replace_all(text.trim(), " ");
+    truncate_at_word_boundary(&collapsed, max_chars)
+}
+
+/// Truncates `text` to at most `max_chars` characters, backing up to the previous word
+/// boundary and appending `"..."` if it had to cut the document short. Returns `text`
+/// unchanged (as an owned `String`) if it already fits.
+fn truncate_at_word_boundary(text: &str, max_chars: usize) -> String {
+    if text.chars().count() <= max_chars {
+        return text.to_string();
+    }
+
+    let truncated: String = text.chars().take(max_chars).collect();
+    let boundary = truncated.rfind(' ').unwrap_or(truncated.len());
+    format!("{}...", truncated[..boundary].trim_end())
+}
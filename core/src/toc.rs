@@ -0,0 +1,228 @@
+//! Heading-anchor generation and table-of-contents extraction.
+//!
+//! [`crate::mdx::render_markdown`] already emits plain `<h1>..<h6>` tags for every
+//! markdown heading; this module's job is to find them, inject a GitHub-style `id=`
+//! slug into each one, and collect the same information into a [`TocEntry`] list (see
+//! [`crate::models::RenderSettings::headings`]) - the same `IdMap` technique rustdoc
+//! uses for its own heading anchors: a per-document collision counter that appends
+//! `-1`, `-2`, ... to a repeated slug rather than letting two headings collide on the
+//! same `id`.
+
+use crate::models::{TocEntry, TocNode};
+use regex::Regex;
+use std::collections::HashMap;
+use std::sync::LazyLock;
+
+/// Matches an opening heading tag, capturing its level and any existing attributes.
+///
+/// # Safety
+/// Pattern is compile-time constant and known to be valid.
+static HEADING_OPEN_TAG: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"<h([1-6])((?:\s[^>]*)?)>").expect("hardcoded regex pattern is valid")
+});
+
+/// Strips HTML tags from a heading's inner markup, to recover its plain text.
+///
+/// # Safety
+/// Pattern is compile-time constant and known to be valid.
+static INNER_TAG: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"<[^>]+>").expect("hardcoded regex pattern is valid"));
+
+/// Injects a unique `id=` slug into every `<h1>..<h6>` tag in `html`, returning the
+/// rewritten HTML alongside one [`TocEntry`] per heading, in document order.
+///
+/// `offset` shifts each heading's level down by that many levels before it's written
+/// back out and recorded in the returned [`TocEntry`]s (see
+/// [`crate::models::RenderSettings::heading_offset`]), clamped to `<h6>` rather than
+/// overflowing past it.
+///
+/// A heading tag with no matching closing tag (malformed input) is left untouched and
+/// excluded from the returned TOC, since there's no reliable span to rewrite.
+pub(crate) fn inject_heading_ids(html: &str, offset: u8) -> (String, Vec<TocEntry>) {
+    let mut toc = Vec::new();
+    let mut seen_slugs: HashMap<String, usize> = HashMap::new();
+    let mut out = String::with_capacity(html.len());
+    let mut last_copied = 0;
+
+    for caps in HEADING_OPEN_TAG.captures_iter(html) {
+        let whole = caps.get(0).expect("group 0 always matches");
+        if whole.start() < last_copied {
+            // Nested inside a heading already rewritten below - not a real heading.
+            continue;
+        }
+
+        let source_level: u8 = caps[1].parse().expect("regex only captures digits 1-6");
+        let level = source_level.saturating_add(offset).min(6);
+        let attrs = &caps[2];
+        let closing_tag = format!("</h{source_level}>");
+        let Some(relative_close) = html[whole.end()..].find(&closing_tag) else {
+            continue;
+        };
+        let close_start = whole.end() + relative_close;
+        let close_end = close_start + closing_tag.len();
+
+        let inner_html = &html[whole.end()..close_start];
+        let text = strip_tags_and_unescape(inner_html);
+        let slug = unique_slug(&text, &mut seen_slugs);
+
+        out.push_str(&html[last_copied..whole.start()]);
+        out.push_str(&format!("<h{level}{attrs} id=\"{slug}\">"));
+        out.push_str(inner_html);
+        out.push_str(&format!("</h{level}>"));
+        last_copied = close_end;
+
+        toc.push(TocEntry { level, text, slug });
+    }
+    out.push_str(&html[last_copied..]);
+
+    (out, toc)
+}
+
+/// Computes a GitHub-style slug for `text` (lowercase, punctuation stripped, runs of
+/// whitespace/hyphens collapsed to a single `-`), then deduplicates it against every
+/// slug already seen in this document by appending `-1`, `-2`, ... on collision.
+fn unique_slug(text: &str, seen_slugs: &mut HashMap<String, usize>) -> String {
+    let base = slugify(text);
+    let mut count = seen_slugs.get(&base).copied().unwrap_or(0);
+    let mut slug = if count == 0 {
+        base.clone()
+    } else {
+        format!("{base}-{count}")
+    };
+    // A suffixed slug can equal an earlier heading's own text slug - skip past it.
+    while count > 0 && seen_slugs.contains_key(&slug) {
+        count += 1;
+        slug = format!("{base}-{count}");
+    }
+    seen_slugs.insert(base, count + 1);
+    seen_slugs.entry(slug.clone()).or_insert(1);
+    slug
+}
+
+/// Lowercases `text`, keeps alphanumerics and `_` verbatim (the rustdoc/mdbook
+/// `IdMap`/`normalize_id` convention), and collapses any run of whitespace or `-`
+/// into a single `-` (dropping all other punctuation entirely).
+fn slugify(text: &str) -> String {
+    let mut slug = String::with_capacity(text.len());
+    let mut last_was_separator = true; // avoids ever emitting a leading `-`
+    for ch in text.chars().flat_map(char::to_lowercase) {
+        if ch.is_alphanumeric() || ch == '_' {
+            slug.push(ch);
+            last_was_separator = false;
+        } else if (ch.is_whitespace() || ch == '-') && !last_was_separator {
+            slug.push('-');
+            last_was_separator = true;
+        }
+    }
+    slug.trim_end_matches('-').to_string()
+}
+
+/// Nests a flat, document-order [`TocEntry`] list (as [`inject_heading_ids`] returns)
+/// into a [`TocNode`] tree: each entry becomes a child of the nearest preceding entry
+/// with a shallower level, and an entry with no shallower predecessor becomes a root.
+/// A document that skips levels (an `<h1>` followed directly by an `<h3>`) nests the
+/// `<h3>` under the `<h1>` anyway, the same as mdbook's TOC builder - there's no
+/// missing `<h2>` to blame the gap on.
+pub(crate) fn build_toc_tree(entries: &[TocEntry]) -> Vec<TocNode> {
+    let mut iter = entries.iter().peekable();
+    build_toc_children(&mut iter, 0)
+}
+
+/// Consumes every upcoming entry deeper than `parent_level` from `iter`, recursing one
+/// level deeper per entry to collect its own children before returning to pick up its
+/// siblings - see [`build_toc_tree`].
+fn build_toc_children(
+    iter: &mut std::iter::Peekable<std::slice::Iter<'_, TocEntry>>,
+    parent_level: u8,
+) -> Vec<TocNode> {
+    let mut nodes = Vec::new();
+    while let Some(entry) = iter.peek() {
+        if entry.level <= parent_level {
+            break;
+        }
+        let entry = iter.next().expect("just peeked");
+        let children = build_toc_children(iter, entry.level);
+        nodes.push(TocNode {
+            level: entry.level,
+            text: entry.text.clone(),
+            slug: entry.slug.clone(),
+            children,
+        });
+    }
+    nodes
+}
+
+/// Strips inline HTML tags (e.g. `<code>`, `<em>`) from a heading's inner markup and
+/// reverses the HTML-entity escaping markdown applied to its text.
+pub(crate) fn strip_tags_and_unescape(inner_html: &str) -> String {
+    let without_tags = INNER_TAG.replace_all(inner_html, "");
+    without_tags
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&amp;", "&")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(level: u8, text: &str, slug: &str) -> TocEntry {
+        TocEntry { level, text: text.to_string(), slug: slug.to_string() }
+    }
+
+    #[test]
+    fn test_build_toc_tree_nests_by_level() {
+        let entries = vec![
+            entry(1, "Intro", "intro"),
+            entry(2, "Setup", "setup"),
+            entry(2, "Usage", "usage"),
+            entry(3, "Advanced", "advanced"),
+            entry(1, "Appendix", "appendix"),
+        ];
+        let tree = build_toc_tree(&entries);
+
+        assert_eq!(tree.len(), 2);
+        assert_eq!(tree[0].slug, "intro");
+        assert_eq!(tree[0].children.len(), 2);
+        assert_eq!(tree[0].children[1].slug, "usage");
+        assert_eq!(tree[0].children[1].children[0].slug, "advanced");
+        assert_eq!(tree[1].slug, "appendix");
+        assert!(tree[1].children.is_empty());
+    }
+
+    #[test]
+    fn test_build_toc_tree_nests_across_skipped_levels() {
+        let entries = vec![entry(1, "Title", "title"), entry(3, "Deep", "deep")];
+        let tree = build_toc_tree(&entries);
+
+        assert_eq!(tree.len(), 1);
+        assert_eq!(tree[0].children.len(), 1);
+        assert_eq!(tree[0].children[0].slug, "deep");
+    }
+
+    #[test]
+    fn test_build_toc_tree_empty() {
+        assert!(build_toc_tree(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_inject_heading_ids_applies_offset() {
+        let html = "<h1>Intro</h1><h2>Setup</h2>";
+        let (with_ids, toc) = inject_heading_ids(html, 1);
+
+        assert_eq!(with_ids, "<h2 id=\"intro\">Intro</h2><h3 id=\"setup\">Setup</h3>");
+        assert_eq!(toc[0].level, 2);
+        assert_eq!(toc[1].level, 3);
+    }
+
+    #[test]
+    fn test_inject_heading_ids_offset_clamps_at_h6() {
+        let html = "<h5>Deep</h5>";
+        let (with_ids, toc) = inject_heading_ids(html, 3);
+
+        assert_eq!(with_ids, "<h6 id=\"deep\">Deep</h6>");
+        assert_eq!(toc[0].level, 6);
+    }
+}
@@ -0,0 +1,202 @@
+//! `tower::Service` adapter that coalesces concurrent single-file render requests.
+//!
+//! [`RenderService::render_batch`][crate::service::RenderService::render_batch] already
+//! amortizes renderer checkout and JS context setup across every file in a batch, but a
+//! caller that only ever has one file at a time (e.g. an HTTP handler rendering one
+//! request) never benefits from that. [`BatchingService`] sits in front of a
+//! [`RenderService`] and buffers concurrently-submitted [`SingleMdxRequest`]s, flushing
+//! them into one `render_batch` call once either a configured count or a short timer
+//! elapses - turning many small renders under load into fewer, larger ones.
+
+use crate::models::{ComponentDefinition, NamedMdxBatchInput, RenderSettings};
+use crate::service::{FileRenderOutcome, RenderBatchError, RenderService};
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::Duration;
+use tokio::sync::{mpsc, oneshot};
+use tower::Service;
+
+/// One file submitted to a [`BatchingService`] - the unit [`Service::call`] accepts,
+/// coalesced with concurrently-submitted requests into a single
+/// [`RenderService::render_batch`] call.
+#[derive(Debug, Clone)]
+pub struct SingleMdxRequest {
+    /// File name - also the key its [`FileRenderOutcome`] is returned under.
+    pub name: String,
+    /// MDX source to render.
+    pub mdx: String,
+    /// Rendering settings for this file.
+    pub settings: RenderSettings,
+    /// Component definitions available to this file, if any.
+    pub components: Option<HashMap<String, ComponentDefinition>>,
+    /// Partials available to this file, if any.
+    pub partials: Option<HashMap<String, String>>,
+}
+
+/// Tuning for [`BatchingService::new`].
+#[derive(Debug, Clone)]
+pub struct BatchingConfig {
+    /// Flush the pending queue once it reaches this many requests.
+    pub max_batch_size: usize,
+    /// Flush the pending queue this long after its first request arrived, even if
+    /// `max_batch_size` hasn't been reached.
+    pub max_wait: Duration,
+}
+
+impl Default for BatchingConfig {
+    fn default() -> Self {
+        Self {
+            max_batch_size: 32,
+            max_wait: Duration::from_millis(5),
+        }
+    }
+}
+
+/// A request waiting in [`BatchingService`]'s queue, paired with the `oneshot` used to
+/// deliver its result back to the caller that submitted it.
+struct PendingRequest {
+    request: SingleMdxRequest,
+    responder: oneshot::Sender<Result<FileRenderOutcome, RenderBatchError>>,
+}
+
+/// A [`Service<SingleMdxRequest>`] that coalesces concurrently-submitted single-file
+/// requests into batches against an underlying [`RenderService`].
+///
+/// The error type is the concrete [`RenderBatchError`] rather than a generic
+/// parameter - a generic here would need a `Clone` bound (the same batch error is
+/// fanned out to every request it failed), which in turn forces every caller to pick a
+/// concrete, `Clone`-able error type and fight inference at the call site for little
+/// benefit, since there's only ever one real error type in this crate anyway.
+///
+/// Cloning a `BatchingService` is cheap and shares the same background worker and
+/// underlying [`RenderService`] - it's just a handle to the queue.
+#[derive(Clone)]
+pub struct BatchingService {
+    sender: mpsc::UnboundedSender<PendingRequest>,
+}
+
+impl BatchingService {
+    /// Spawns the background worker that drains requests into batches against
+    /// `service`, sized and timed per `config`.
+    pub fn new(service: RenderService, config: BatchingConfig) -> Self {
+        let (sender, receiver) = mpsc::unbounded_channel();
+        tokio::spawn(run_worker(service, config, receiver));
+        Self { sender }
+    }
+}
+
+impl Service<SingleMdxRequest> for BatchingService {
+    type Response = FileRenderOutcome;
+    type Error = RenderBatchError;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        // The queue is unbounded and the worker never exits except when every sender
+        // (this one included) is dropped, so there's no backpressure to report here.
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, request: SingleMdxRequest) -> Self::Future {
+        let (responder, receiver) = oneshot::channel();
+        let queued = self.sender.send(PendingRequest { request, responder });
+        Box::pin(async move {
+            queued.map_err(|_| {
+                RenderBatchError::Internal(anyhow::anyhow!("batching worker is no longer running"))
+            })?;
+            receiver.await.map_err(|_| {
+                RenderBatchError::Internal(anyhow::anyhow!(
+                    "batching worker dropped the request before responding"
+                ))
+            })?
+        })
+    }
+}
+
+/// Drains `receiver` into batches, flushing each once `config.max_batch_size` requests
+/// have accumulated or `config.max_wait` has elapsed since the first request in the
+/// current batch arrived - whichever comes first. Runs until `receiver` closes, which
+/// happens once every [`BatchingService`] handle for this worker has been dropped.
+async fn run_worker(
+    service: RenderService,
+    config: BatchingConfig,
+    mut receiver: mpsc::UnboundedReceiver<PendingRequest>,
+) {
+    loop {
+        let Some(first) = receiver.recv().await else {
+            break;
+        };
+
+        let mut pending = Vec::with_capacity(config.max_batch_size);
+        pending.push(first);
+
+        let deadline = tokio::time::sleep(config.max_wait);
+        tokio::pin!(deadline);
+        while pending.len() < config.max_batch_size {
+            tokio::select! {
+                _ = &mut deadline => break,
+                next = receiver.recv() => match next {
+                    Some(request) => pending.push(request),
+                    None => break,
+                },
+            }
+        }
+
+        flush(&service, pending);
+    }
+}
+
+/// Renders `batch` as one [`RenderService::render_batch`] call and fans each file's
+/// [`FileRenderOutcome`] back to its waiting responder, keyed by
+/// [`SingleMdxRequest::name`].
+fn flush(service: &RenderService, batch: Vec<PendingRequest>) {
+    // `NamedMdxBatchInput` carries one shared settings/components/partials set for the
+    // whole batch, while `SingleMdxRequest` carries its own per file - use the first
+    // request's, matching how a caller submitting several files in one call already
+    // shares them.
+    let Some(first) = batch.first() else { return };
+    let settings = first.request.settings.clone();
+    let components = first.request.components.clone();
+    let partials = first.request.partials.clone();
+
+    let mdx = batch
+        .iter()
+        .map(|pending| (pending.request.name.clone(), pending.request.mdx.clone()))
+        .collect();
+
+    let input = NamedMdxBatchInput { settings, mdx, components, partials };
+
+    match service.render_batch(&input) {
+        Ok(mut outcome) => {
+            for pending in batch {
+                let result = outcome.files.remove(&pending.request.name).ok_or_else(|| {
+                    RenderBatchError::Internal(anyhow::anyhow!(
+                        "batch outcome had no result for file '{}'",
+                        pending.request.name
+                    ))
+                });
+                let _ = pending.responder.send(result);
+            }
+        }
+        Err(err) => {
+            // `RenderBatchError` isn't `Clone` (its `Internal` variant wraps an
+            // `anyhow::Error`), so rebuild an equivalent error per responder from its
+            // message rather than sharing one.
+            let rebuild = |err: &RenderBatchError| match err {
+                RenderBatchError::Forbidden(message) => {
+                    RenderBatchError::Forbidden(message.clone())
+                }
+                RenderBatchError::InvalidRequest(message) => {
+                    RenderBatchError::InvalidRequest(message.clone())
+                }
+                RenderBatchError::Internal(source) => {
+                    RenderBatchError::Internal(anyhow::anyhow!("{:#}", source))
+                }
+            };
+            for pending in batch {
+                let _ = pending.responder.send(Err(rebuild(&err)));
+            }
+        }
+    }
+}
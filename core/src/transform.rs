@@ -2,6 +2,8 @@
 //!
 //! This module handles the transformation of TSX/JSX syntax to JavaScript using the Oxc compiler.
 //! It supports various JSX pragmas (engine, React-compatible) and handles component wrapping.
+//! JSX can be compiled with either the classic factory-call runtime or the automatic
+//! (`_jsx`/`_jsxs`) runtime, selected via `TsxTransformConfig::jsx_runtime`.
 //!
 //! ## Transformation Process
 //!
@@ -16,24 +18,237 @@
 //!
 //! All transformation errors use `MdxError` for domain-specific error reporting.
 //! Errors include source location information when available from OXC.
-
-use crate::error::{byte_offset_to_line_col, MdxError, ParseError, SourceLocation};
-use crate::models::TsxTransformConfig;
+//!
+//! ## Caching
+//!
+//! Transform results are memoized in the process-wide [`crate::transform_cache`],
+//! keyed by source content and the relevant [`TsxTransformConfig`] fields, so
+//! repeated component code and unchanged MDX content skip the pipeline entirely.
+//!
+//! ## Source Maps
+//!
+//! When [`TsxTransformConfig::with_source_maps`] is set, the generated code carries an
+//! inline `//# sourceMappingURL=data:...` comment (see [`inline_source_map_comment`])
+//! so that a later V8 runtime error can be translated back to the original TSX - see
+//! [`crate::renderer`]'s script-assembly functions, which extract, shift, and re-embed
+//! this comment as the code is spliced into larger wrapper scripts.
+//!
+//! ## Imports
+//!
+//! A component's static `import`s are collected from the AST before cleanup strips
+//! them (see [`transform_tsx_to_js_with_imports`]). With [`TsxTransformConfig::import_map`]
+//! set, each specifier is resolved to a `const` binding against its mapped global
+//! instead of being dropped; an unmapped specifier fails with
+//! [`crate::error::MdxError::UnresolvedImport`].
+//!
+//! ## Component Source Maps
+//!
+//! [`transform_component_code_with_map`] pairs generated code with a Source Map v3
+//! JSON naming the original component file, re-serialized through the `sourcemap`
+//! crate so a browser or debugger can map a compiled MDX component's runtime errors
+//! back to its original `.mdx`/TSX lines.
+//!
+//! ## Prop Metadata
+//!
+//! [`extract_component_props`] reads the TypeScript type annotation on a component's
+//! single parameter - an inline object type, or a reference to a top-level `interface`
+//! or `type` alias - and reports each member as a [`crate::models::PropInfo`],
+//! react-docgen-style, for downstream tooling that builds prop tables or validates
+//! usage without re-parsing the component itself.
+//!
+//! ## Diagnostics
+//!
+//! [`transform_component_code`] and friends stop at the first error. For an editor or
+//! CLI that wants every issue in one pass, [`diagnose_component_code`] instead collects
+//! every syntax error Oxc's error-recovering parser reports plus the `export default`
+//! validation error (if any) into one `Vec<ParseError>`, each with a byte span,
+//! line/column, and a short rendered code frame. Since a single file can produce many
+//! diagnostics, [`extract_parse_errors`] builds one [`crate::error::LineIndex`] per
+//! call and reuses it for every diagnostic rather than re-scanning the source from the
+//! start for each one.
+//!
+//! Not every diagnostic needs to be fatal: [`crate::error::ParseError::severity`]
+//! defaults to [`crate::error::Severity::Error`], but
+//! [`transform_component_code_with_lints`] accumulates
+//! [`crate::error::Severity::Warning`]-level issues (e.g. an anonymous default export
+//! that gets implicitly named) and returns them alongside a successful result instead
+//! of failing, so callers can surface lints without failing the build.
+//!
+//! ## Decorators
+//!
+//! [`TsxTransformConfig::decorators_legacy`] and
+//! [`TsxTransformConfig::emit_decorator_metadata`] configure how class/method
+//! decorators lower - see [`create_transform_options`].
+//! [`transform_component_code_with_config`] threads a full config through the
+//! component-level entry points for this.
+//!
+//! ## Type Declarations
+//!
+//! [`generate_component_declarations`] produces an isolated-declarations-style
+//! `.d.ts` for a component module's default and named exports, reusing the existing
+//! Oxc parse rather than running whole-program type inference - an exported binding
+//! with no explicit (or literal-inferable) type fails with a pointed `MdxError`
+//! instead of being guessed at.
+//!
+//! ## Import Analysis
+//!
+//! [`analyze_imports`] reports a component's import graph - every `import` and
+//! re-export-from specifier, with duplicates flagged - similar to an import-linting
+//! tool. [`transform_component_code_with_import_allow_list`] additionally rejects a
+//! bare specifier absent from a caller-supplied allow-list, for a sandboxed host that
+//! only wants to allow a fixed set of globals/components to be imported.
+//!
+//! ## Batch Processing
+//!
+//! [`process_component_batch`] transforms many named components at once, recovering
+//! from a per-file failure (including non-UTF-8 input, via
+//! [`crate::error::validate_utf8`]) instead of aborting the whole batch, mirroring
+//! [`crate::service::RenderService::render_batch`]'s per-file recovery one layer down,
+//! at the component-transform level rather than whole-MDX-document level.
+
+use crate::error::{
+    byte_offset_to_line_col, diagnostic_codes, validate_utf8, LineIndex, MdxError, ParseError,
+    Severity, SourceLocation,
+};
+use crate::models::{
+    ComponentDefinition, ImportDescriptor, ImportReport, ImportedName, JsxRuntimeMode, PropInfo,
+    TsxTransformConfig,
+};
+use crate::transform_cache;
 use oxc_allocator::Allocator;
+use oxc_ast::ast::{
+    Argument, BindingPatternKind, CallExpression, ClassElement, Declaration,
+    ExportDefaultDeclaration, ExportDefaultDeclarationKind, Expression, Function,
+    ImportDeclarationSpecifier, ImportOrExportKind, MethodDefinitionKind, ModuleExportName,
+    Program, PropertyKey, Statement, StringLiteral, TSSignature, TSType, TSTypeName,
+    VariableDeclarator,
+};
+use oxc_ast::visit::{walk_mut, VisitMut};
 use oxc_codegen::{Codegen, CodegenOptions};
 use oxc_diagnostics::OxcDiagnostic;
 use oxc_parser::Parser;
 use oxc_semantic::SemanticBuilder;
-use oxc_span::SourceType;
+use oxc_span::{Atom, GetSpan, SourceType, Span, SPAN};
 use oxc_transformer::{DecoratorOptions, JsxRuntime, TransformOptions, Transformer};
+use regex::Regex;
 use std::borrow::Cow;
-use std::cmp::Reverse;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::path::Path;
+use std::sync::LazyLock;
 
 /// Base overhead for component wrapper (function declaration, JSX wrapper, etc.)
 const COMPONENT_WRAPPER_OVERHEAD: usize = 100;
 
+/// Marker prefix for an inline source map comment, used both to build and to detect one.
+pub(crate) const SOURCE_MAPPING_URL_PREFIX: &str =
+    "//# sourceMappingURL=data:application/json;base64,";
+
+/// Builds a `//# sourceMappingURL=data:...` comment embedding `map_json` as base64.
+///
+/// Embedding inline (rather than returning the map alongside the code) keeps every
+/// transform entry point's return type a plain `String`, so the map travels with the
+/// code through [`transform_cache`] and through however callers assemble it into a
+/// larger script, instead of needing to be threaded through as a second value.
+pub(crate) fn inline_source_map_comment(map_json: &str) -> String {
+    format!("{SOURCE_MAPPING_URL_PREFIX}{}", base64_encode(map_json.as_bytes()))
+}
+
+/// Strips a trailing inline source map comment (if present) from `code`, returning the
+/// code without it and the decoded map JSON.
+///
+/// Used by the renderer when it re-assembles transformed fragments into a larger
+/// script: each assembly stage extracts the map left by the stage before it, shifts it
+/// by however many lines it adds in front of that fragment (see
+/// [`shift_source_map_lines`]), and re-embeds the result - so a map produced by the
+/// original Oxc transform ends up correctly shifted no matter how many wrapper layers
+/// it passes through before reaching the script actually handed to V8.
+pub(crate) fn extract_inline_source_map(code: &str) -> (&str, Option<String>) {
+    let Some(idx) = code.rfind(SOURCE_MAPPING_URL_PREFIX) else {
+        return (code, None);
+    };
+    let before = code[..idx].trim_end_matches(['\n', '\r']);
+    let encoded = code[idx + SOURCE_MAPPING_URL_PREFIX.len()..].trim_end();
+    match base64_decode(encoded) {
+        Some(json) => (before, Some(json)),
+        None => (code, None),
+    }
+}
+
+/// Shifts a V3 source map's `mappings` down by `line_offset` whole generated lines.
+///
+/// Each `;` in the `mappings` VLQ string advances the generated line by one with no
+/// column delta, so prepending `line_offset` of them is a correct, allocation-cheap way
+/// to re-target a map that was generated assuming its code started at line 0, onto code
+/// that has since been prefixed with `line_offset` lines of wrapper text. A zero
+/// `line_offset` returns `map_json` unchanged without inspecting it; otherwise returns
+/// `None` if `map_json` isn't a JSON object with a string `mappings` field.
+pub(crate) fn shift_source_map_lines(map_json: &str, line_offset: u32) -> Option<String> {
+    if line_offset == 0 {
+        return Some(map_json.to_string());
+    }
+    let mut value: serde_json::Value = serde_json::from_str(map_json).ok()?;
+    let mappings = value.get("mappings")?.as_str()?.to_string();
+    let shifted = format!("{}{mappings}", ";".repeat(line_offset as usize));
+    value["mappings"] = serde_json::Value::String(shifted);
+    serde_json::to_string(&value).ok()
+}
+
+/// Minimal, dependency-free base64 encoder (standard alphabet, with `=` padding).
+///
+/// Source maps are produced and consumed entirely in-process, so there's no need to
+/// pull in a dedicated base64 crate for this one call site.
+fn base64_encode(data: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] =
+        b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut out = String::with_capacity(data.len().div_ceil(3) * 4);
+    for chunk in data.chunks(3) {
+        let b0 = chunk[0];
+        let b1 = chunk.get(1).copied();
+        let b2 = chunk.get(2).copied();
+
+        out.push(ALPHABET[(b0 >> 2) as usize] as char);
+        out.push(ALPHABET[(((b0 & 0x03) << 4) | (b1.unwrap_or(0) >> 4)) as usize] as char);
+        out.push(match b1 {
+            Some(b1) => ALPHABET[(((b1 & 0x0f) << 2) | (b2.unwrap_or(0) >> 6)) as usize] as char,
+            None => '=',
+        });
+        out.push(match b2 {
+            Some(b2) => ALPHABET[(b2 & 0x3f) as usize] as char,
+            None => '=',
+        });
+    }
+    out
+}
+
+/// Inverse of [`base64_encode`]. Returns `None` on malformed input rather than panicking,
+/// since it runs on data extracted from a generated script rather than trusted input.
+fn base64_decode(encoded: &str) -> Option<String> {
+    fn digit(c: u8) -> Option<u8> {
+        match c {
+            b'A'..=b'Z' => Some(c - b'A'),
+            b'a'..=b'z' => Some(c - b'a' + 26),
+            b'0'..=b'9' => Some(c - b'0' + 52),
+            b'+' => Some(62),
+            b'/' => Some(63),
+            _ => None,
+        }
+    }
+
+    let bytes: Vec<u8> = encoded.bytes().filter(|&b| b != b'=').collect();
+    let mut out = Vec::with_capacity(bytes.len() / 4 * 3 + 3);
+    for chunk in bytes.chunks(4) {
+        let digits: Vec<u8> = chunk.iter().map(|&b| digit(b)).collect::<Option<_>>()?;
+        out.push((digits[0] << 2) | (digits.get(1).copied().unwrap_or(0) >> 4));
+        if digits.len() > 2 {
+            out.push((digits[1] << 4) | (digits[2] >> 2));
+        }
+        if digits.len() > 3 {
+            out.push((digits[2] << 6) | digits[3]);
+        }
+    }
+    String::from_utf8(out).ok()
+}
+
 /// Wraps TSX content in a React component structure
 ///
 /// This function wraps raw TSX/HTML content in a function component that returns
@@ -79,20 +294,35 @@ pub fn create_transform_options(config: &TsxTransformConfig) -> TransformOptions
     // JSX configuration
     options.jsx.pragma = Some(config.jsx_pragma.clone());
     options.jsx.pragma_frag = Some(config.jsx_pragma_frag.clone());
-    options.jsx.runtime = JsxRuntime::Classic;
-    options.jsx.development = false;
-    options.jsx.refresh = None;
-
-    // Enable TypeScript legacy decorators (experimentalDecorators + emitDecoratorMetadata).
-    // This supports the decorator syntax used by Angular, NestJS, TypeORM, MobX, etc.
+    options.jsx.runtime = match config.jsx_runtime {
+        JsxRuntimeMode::Classic => JsxRuntime::Classic,
+        JsxRuntimeMode::Automatic => JsxRuntime::Automatic,
+    };
+    if config.jsx_runtime == JsxRuntimeMode::Automatic {
+        options.jsx.import_source = Some(config.jsx_import_source.clone());
+    }
+    // Development mode injects `__source`/`__self` debug props (and switches to the
+    // `jsxDEV` factory under the automatic runtime) and registers Fast Refresh
+    // boundaries - both no-ops we don't want paying for on the production render path.
+    options.jsx.development = config.development;
+    options.jsx.refresh = config
+        .development
+        .then(|| config.refresh.clone().unwrap_or_default());
+
+    // Decorator semantics are configurable (legacy stage-1/`experimentalDecorators` vs
+    // TC39), matching the two distinct lowerings SWC exposes; `emit_decorator_metadata`
+    // additionally controls TypeScript's `Reflect.metadata` emission for DI-style
+    // helper classes (Angular, NestJS, TypeORM, MobX, etc.).
     //
     // NOTE: TC39 Stage 3 decorators (2023 standard) are NOT yet supported by OXC transformer.
     // See: https://github.com/oxc-project/oxc/issues/9170
-    // The parser can parse TC39 syntax, but transformation is not implemented.
-    // Most frameworks still use legacy decorators, so this should cover common use cases.
+    // The parser can parse TC39 syntax, but transformation is not implemented, so setting
+    // `decorators_legacy` to `false` currently leaves TC39 decorator syntax untouched rather
+    // than producing a TC39-semantics lowering. Most frameworks still use legacy decorators,
+    // so this should cover common use cases.
     options.decorator = DecoratorOptions {
-        legacy: true,
-        emit_decorator_metadata: true,
+        legacy: config.decorators_legacy,
+        emit_decorator_metadata: config.decorators_legacy && config.emit_decorator_metadata,
     };
 
     options
@@ -142,11 +372,105 @@ pub fn transform_tsx_to_js_for_output(tsx_content: &str, minify: bool) -> Result
     transform_tsx_to_js_with_config(tsx_content, TsxTransformConfig::for_output(minify))
 }
 
+/// Transforms TSX content to JavaScript for a development build: development-mode JSX
+/// (`__source`/`__self` debug props) with Fast Refresh boundaries registered, so a dev
+/// server gets better error locations and component-level hot reloading instead of
+/// only the production transform.
+///
+/// # Arguments
+/// * `tsx_content` - TSX source code to transform
+///
+/// # Returns
+/// Generated JavaScript code or an error
+pub fn transform_tsx_to_js_dev(tsx_content: &str) -> Result<String, MdxError> {
+    transform_tsx_to_js_with_config(tsx_content, TsxTransformConfig::for_development(false, None))
+}
+
+/// Result of [`transform_tsx_to_js_with_map`]: generated code and its source map as
+/// separate values, for callers that want to persist or inspect the map directly
+/// instead of relying on the inline `//# sourceMappingURL=data:...` comment convention
+/// every other transform entry point embeds in its returned code (see
+/// [`inline_source_map_comment`]).
+pub struct TransformOutput {
+    /// Generated JavaScript code, with no inline source map comment.
+    pub code: String,
+    /// The V3 source map JSON, if `config.with_source_maps` was set. Its generated-side
+    /// lines are shifted past any shim lines prepended ahead of the Oxc output, and
+    /// `cleanup_generated_code` keeps the line count when maps are on. Its source-side
+    /// positions refer to the code Oxc parsed, i.e. `tsx_content` as wrapped by
+    /// [`wrap_in_component`]; they are not shifted back onto the original `tsx_content`.
+    pub map: Option<String>,
+}
+
+/// Like [`transform_tsx_to_js_with_config`], but returns the source map as a separate
+/// [`TransformOutput::map`] instead of embedding it as an inline comment in the code.
+///
+/// # Arguments
+/// * `tsx_content` - TSX source code to transform
+/// * `config` - Transformation configuration; only meaningful with
+///   `config.with_source_maps` set, otherwise `map` is always `None`
+pub fn transform_tsx_to_js_with_map(
+    tsx_content: &str,
+    config: TsxTransformConfig,
+) -> Result<TransformOutput, MdxError> {
+    transform_with_map(tsx_content, config, true)
+}
+
+/// Shared implementation behind [`transform_tsx_to_js_with_map`] and
+/// [`transform_component_code_with_map`]: runs the transform, then splits the inline
+/// source map comment back out into [`TransformOutput::map`].
+fn transform_with_map(
+    tsx_content: &str,
+    config: TsxTransformConfig,
+    wrap_content: bool,
+) -> Result<TransformOutput, MdxError> {
+    let combined = transform_tsx_internal(tsx_content, &config, wrap_content)?;
+    let (code, map) = extract_inline_source_map(&combined);
+    Ok(TransformOutput {
+        code: code.to_string(),
+        map,
+    })
+}
+
+/// Result of [`transform_tsx_to_js_with_imports`]: generated code alongside every
+/// static import the source declared - analogous to swc/deno's `analyze_dependencies`
+/// - instead of callers having no way to find out what `cleanup_generated_code`
+/// stripped or [`resolve_imports_shim`] resolved away.
+pub struct ImportAnalysis {
+    /// Generated JavaScript code. Imports are resolved to `const` bindings against
+    /// `config.import_map` when set, otherwise stripped exactly as
+    /// [`transform_tsx_to_js_with_config`] strips them.
+    pub code: String,
+    /// Every static import collected from `tsx_content`, in source order.
+    pub imports: Vec<ImportDescriptor>,
+}
+
+/// Like [`transform_tsx_to_js_with_config`], but also returns the static imports the
+/// component declared (see [`ImportAnalysis`]). Set `config.import_map` to resolve
+/// each import specifier to a global reference instead of merely collecting it - an
+/// unmapped specifier fails the transform with [`MdxError::UnresolvedImport`].
+///
+/// Bypasses [`transform_cache`], since the cache only stores generated code and
+/// component imports aren't on the hot rendering path the way repeated MDX content is.
+///
+/// # Arguments
+/// * `tsx_content` - TSX source code to transform
+/// * `config` - Transformation configuration
+pub fn transform_tsx_to_js_with_imports(
+    tsx_content: &str,
+    config: TsxTransformConfig,
+) -> Result<ImportAnalysis, MdxError> {
+    transform_tsx_uncached_with_imports(tsx_content, &config, true)
+}
+
 /// Extracts parse errors from OXC diagnostics with location info
 ///
 /// This function converts OXC's `OxcDiagnostic` errors into our `ParseError` type,
 /// extracting source location information when available from the diagnostic labels.
 fn extract_parse_errors(diagnostics: &[OxcDiagnostic], source: &str) -> Vec<ParseError> {
+    // Built once and reused for every diagnostic in this file, so a file with many
+    // errors resolves offsets in `O(log n)` each rather than `O(n)` (see `LineIndex`).
+    let line_index = LineIndex::new(source);
     diagnostics
         .iter()
         .map(|diag| {
@@ -159,23 +483,38 @@ fn extract_parse_errors(diagnostics: &[OxcDiagnostic], source: &str) -> Vec<Pars
                 labels.first().map(|label| {
                     let offset = label.offset() as u32;
                     let length = label.len() as u32;
-                    let (line, column) = byte_offset_to_line_col(source, offset);
+                    let (line, column) = line_index.line_col(offset);
                     SourceLocation::new(line, column, offset, length)
                 })
             });
 
             // Try to get help text from the public help field
             let help = diag.help.as_ref().map(|h| h.to_string());
+            let frame = location.as_ref().map(|loc| render_code_frame(source, loc));
 
             ParseError {
                 message,
                 location,
                 help,
+                code: None,
+                frame,
+                severity: Severity::Error,
             }
         })
         .collect()
 }
 
+/// Renders a short code frame for `location` in `source`: the offending line, plus a
+/// caret underline spanning the error's column range. Single-line only - enough to
+/// orient a reader without reimplementing a full multi-line frame renderer.
+fn render_code_frame(source: &str, location: &SourceLocation) -> String {
+    let line_text = source.lines().nth(location.line as usize).unwrap_or("");
+    let gutter = location.display_line().to_string();
+    let underline = "^".repeat(location.length.max(1) as usize);
+    let pad = " ".repeat(gutter.len() + 3 + location.column as usize);
+    format!("{gutter} | {line_text}\n{pad}{underline}")
+}
+
 /// Validates and parses TSX content, returning an error if parsing fails
 fn validate_parse_result(
     parser_return: &oxc_parser::ParserReturn,
@@ -200,49 +539,168 @@ fn validate_transform_result(
     Ok(())
 }
 
-/// Transform that converts component function references to string names in AST
-///
-/// This uses a simple post-processing approach on the generated code since
-/// AST traversal with Oxc requires more complex setup. The string replacement
-/// is safe because we only replace known component names in specific patterns.
-fn convert_component_refs_in_ast(code: &str, component_names: &HashSet<&str>) -> String {
-    if component_names.is_empty() {
-        return code.to_string();
+/// Whether `callee` is the classic JSX factory - the bare `h` identifier or the
+/// `engine.h` member expression - the two shapes [`create_transform_options`] can
+/// configure `jsx_pragma` to compile calls against.
+fn is_jsx_factory_callee(callee: &Expression<'_>) -> bool {
+    match callee {
+        Expression::Identifier(ident) => ident.name == "h",
+        Expression::StaticMemberExpression(member) => {
+            member.property.name == "h"
+                && matches!(&member.object, Expression::Identifier(obj) if obj.name == "engine")
+        }
+        _ => false,
     }
+}
 
-    let mut result = code.to_string();
-
-    // Sort by length (longest first) to avoid partial matches
-    let mut sorted_names: Vec<&str> = component_names.iter().copied().collect();
-    sorted_names.sort_by_key(|name| Reverse(name.len()));
+/// AST pass that rewrites a JSX factory call's component-reference first argument from
+/// an identifier to a string literal, e.g. `h(Button, ...)` -> `h('Button', ...)` - used
+/// for schema output, where components are referenced by name rather than by the
+/// function itself.
+///
+/// Runs on the already-transformed `Program`, right after
+/// [`oxc_transformer::Transformer`] and before [`Codegen`]. Unlike the previous
+/// approach (a `String::replace` pass over the generated code, matching on fixed
+/// `h(Name` / `engine.h(Name` patterns), this can't be thrown off by whitespace
+/// variations, minified single-line output, nested calls, or a local variable that
+/// happens to share a component's name - it only ever touches the exact AST node it's
+/// looking for.
+struct ComponentRefRewriter<'a, 'n> {
+    allocator: &'a Allocator,
+    component_names: &'n HashSet<&'n str>,
+}
 
-    for component_name in sorted_names {
-        // Pattern 1: h(ComponentName, -> h('ComponentName',
-        let pattern1 = format!("h({},", component_name);
-        let replacement1 = format!("h('{}',", component_name);
-        result = result.replace(&pattern1, &replacement1);
+impl<'a, 'n> VisitMut<'a> for ComponentRefRewriter<'a, 'n> {
+    fn visit_call_expression(&mut self, call: &mut CallExpression<'a>) {
+        if is_jsx_factory_callee(&call.callee) {
+            let rewrite = matches!(
+                call.arguments.first(),
+                Some(Argument::Identifier(ident)) if self.component_names.contains(ident.name.as_str())
+            );
+            if rewrite {
+                let Some(Argument::Identifier(ident)) = call.arguments.first() else {
+                    unreachable!("checked above");
+                };
+                let literal = StringLiteral {
+                    span: SPAN,
+                    value: Atom::from_in(ident.name.as_str(), self.allocator),
+                    raw: None,
+                };
+                call.arguments[0] =
+                    Argument::StringLiteral(oxc_allocator::Box::new_in(literal, self.allocator));
+            }
+        }
 
-        // Pattern 2: h(ComponentName) -> h('ComponentName')
-        let pattern2 = format!("h({})", component_name);
-        let replacement2 = format!("h('{}')", component_name);
-        result = result.replace(&pattern2, &replacement2);
+        walk_mut::walk_call_expression(self, call);
+    }
+}
 
-        // Pattern 3: engine.h(ComponentName, -> engine.h('ComponentName',
-        let pattern3 = format!("engine.h({},", component_name);
-        let replacement3 = format!("engine.h('{}',", component_name);
-        result = result.replace(&pattern3, &replacement3);
+/// Walks `program`'s top-level statements and collects every static `import` -
+/// specifier, every binding it introduces, and its byte span - analogous to
+/// swc/deno's `analyze_dependencies`. Runs right after parsing, before
+/// [`cleanup_generated_code`] strips those statements, so this information isn't lost
+/// to callers that want to know what a component depends on.
+fn collect_import_descriptors(program: &Program<'_>) -> Vec<ImportDescriptor> {
+    program
+        .body
+        .iter()
+        .filter_map(|stmt| {
+            let Statement::ImportDeclaration(decl) = stmt else {
+                return None;
+            };
+            let imported_names = decl
+                .specifiers
+                .as_ref()
+                .map(|specifiers| {
+                    specifiers
+                        .iter()
+                        .map(|specifier| match specifier {
+                            ImportDeclarationSpecifier::ImportDefaultSpecifier(s) => {
+                                ImportedName::Default(s.local.name.to_string())
+                            }
+                            ImportDeclarationSpecifier::ImportNamespaceSpecifier(s) => {
+                                ImportedName::Namespace(s.local.name.to_string())
+                            }
+                            ImportDeclarationSpecifier::ImportSpecifier(s) => ImportedName::Named {
+                                imported: module_export_name(&s.imported),
+                                local: s.local.name.to_string(),
+                            },
+                        })
+                        .collect()
+                })
+                .unwrap_or_default();
+
+            Some(ImportDescriptor {
+                specifier: decl.source.value.to_string(),
+                imported_names,
+                type_only: matches!(decl.import_kind, ImportOrExportKind::Type),
+                start: decl.span.start,
+                end: decl.span.end,
+            })
+        })
+        .collect()
+}
 
-        // Pattern 4: engine.h(ComponentName) -> engine.h('ComponentName')
-        let pattern4 = format!("engine.h({})", component_name);
-        let replacement4 = format!("engine.h('{}')", component_name);
-        result = result.replace(&pattern4, &replacement4);
+/// Extracts the plain name out of a `ModuleExportName`, covering every shape the
+/// `imported` side of an `import { imported as local }` specifier can take.
+fn module_export_name(name: &ModuleExportName<'_>) -> String {
+    match name {
+        ModuleExportName::IdentifierName(id) => id.name.to_string(),
+        ModuleExportName::IdentifierReference(id) => id.name.to_string(),
+        ModuleExportName::StringLiteral(lit) => lit.value.to_string(),
     }
+}
 
-    result
+/// Builds `const local = resolved[.imported];` bindings for every import in `imports`,
+/// resolving each specifier through `import_map` - this is what turns the previous
+/// "imports are illegal" behavior into real module linking, letting a component's
+/// imported identifiers keep working against whatever global the host resolves them
+/// to instead of vanishing when [`cleanup_generated_code`] strips the `import` line.
+///
+/// # Errors
+/// Returns [`MdxError::UnresolvedImport`] for the first specifier with no entry in
+/// `import_map`.
+fn resolve_imports_shim(
+    imports: &[ImportDescriptor],
+    import_map: &HashMap<String, String>,
+) -> Result<String, MdxError> {
+    let mut shim = String::new();
+    for import in imports {
+        if import.type_only {
+            // Erased at runtime by `cleanup_generated_code`'s import-stripping, same as
+            // any other `import` statement - nothing to resolve against `import_map`.
+            continue;
+        }
+        let Some(resolved) = import_map.get(&import.specifier) else {
+            return Err(MdxError::UnresolvedImport(import.specifier.clone()));
+        };
+        for name in &import.imported_names {
+            match name {
+                ImportedName::Default(local) | ImportedName::Namespace(local) => {
+                    shim.push_str(&format!("const {local} = {resolved};\n"));
+                }
+                ImportedName::Named { imported, local } => {
+                    shim.push_str(&format!("const {local} = {resolved}.{imported};\n"));
+                }
+            }
+        }
+    }
+    Ok(shim)
 }
 
 /// Cleans up the generated code by removing pure annotations, ES module imports, and export statements
-fn cleanup_generated_code(code: &str) -> String {
+///
+/// This only ever touches the `/* @__PURE__ */` marker and import/export lines - it
+/// never matches against comment text, so author comments Oxc kept per
+/// `config.keep_comments` (JSDoc, `@license`/`@preserve` banners, ...) pass through
+/// untouched.
+///
+/// When `preserve_line_numbers` is set, stripped import/export-only lines are blanked
+/// out rather than removed, so line numbers in a source map generated against `code`
+/// stay valid against the cleaned output instead of drifting by however many lines were
+/// dropped. Plain annotation/`export default` replacements never change line count, so
+/// they don't need this treatment.
+fn cleanup_generated_code(code: &str, preserve_line_numbers: bool) -> String {
     let mut cleaned = code.to_string();
     // Replace pure annotations with a space
     cleaned = cleaned.replace("/* @__PURE__ */ ", " ");
@@ -255,19 +713,33 @@ fn cleanup_generated_code(code: &str) -> String {
     cleaned = cleaned.replace("export default class ", "class ");
 
     // Remove remaining ES module constructs (import/export) that aren't valid in script context
-    let lines: Vec<&str> = cleaned
+    let lines: Vec<String> = cleaned
         .lines()
-        .filter(|line| {
+        .filter_map(|line| {
             let trimmed = line.trim();
             // Filter out import statements and export-only statements
             // Note: `export default function/class` already converted above
-            !is_import_or_pure_export(trimmed)
+            if is_import_or_pure_export(trimmed) {
+                preserve_line_numbers.then(String::new)
+            } else {
+                Some(line.to_string())
+            }
         })
         .collect();
     cleaned = lines.join("\n");
     cleaned
 }
 
+/// Declares the symbols the automatic JSX runtime compiles calls against.
+///
+/// Oxc's automatic transform emits bare `_jsx`/`_jsxs`/`_Fragment` calls alongside an
+/// ES import of those names from a jsx-runtime module, but `cleanup_generated_code`
+/// strips all imports because the code executes in a non-module script context. This
+/// shim is prepended in their place so the emitted calls resolve against whatever the
+/// engine exposes as `jsxRuntime`, instead of relying on an import that never runs.
+const AUTOMATIC_JSX_RUNTIME_SHIM: &str =
+    "const { jsx: _jsx, jsxs: _jsxs, Fragment: _Fragment } = engine.jsxRuntime;\n";
+
 /// Checks if a line is an import statement or a pure export (not function/class declaration)
 fn is_import_or_pure_export(trimmed: &str) -> bool {
     if trimmed.starts_with("import ") {
@@ -326,11 +798,47 @@ fn is_import_or_pure_export(trimmed: &str) -> bool {
 ///
 /// # Returns
 /// Transformed JavaScript code or an error if parsing/transformation fails
+///
+/// ## Caching
+///
+/// This is the single choke point all public transform entry points funnel through
+/// (except [`transform_tsx_to_js_with_imports`], which calls the uncached pipeline
+/// directly), so it consults the process-wide [`transform_cache`] before running the Oxc
+/// pipeline. Repeated component code and unchanged MDX content - within a batch or
+/// across threads - skip parsing, semantic analysis, and codegen entirely on a
+/// cache hit.
 fn transform_tsx_internal(
     tsx_content: &str,
     config: &TsxTransformConfig,
     wrap_content: bool,
 ) -> Result<String, MdxError> {
+    let key = transform_cache::cache_key(tsx_content, config, wrap_content);
+    transform_cache::get_or_insert_with(key, || {
+        transform_tsx_uncached(tsx_content, config, wrap_content)
+    })
+}
+
+/// Runs the Oxc parse/semantic-analysis/transform/codegen pipeline without
+/// consulting the cache. See [`transform_tsx_internal`] for the cached entry point.
+///
+/// Thin wrapper over [`transform_tsx_uncached_with_imports`] that discards the
+/// collected import list, for callers that only need the generated code.
+fn transform_tsx_uncached(
+    tsx_content: &str,
+    config: &TsxTransformConfig,
+    wrap_content: bool,
+) -> Result<String, MdxError> {
+    transform_tsx_uncached_with_imports(tsx_content, config, wrap_content).map(|output| output.code)
+}
+
+/// Runs the same pipeline as [`transform_tsx_uncached`], additionally returning every
+/// static import collected from the source. See [`transform_tsx_to_js_with_imports`]
+/// for the public, cache-bypassing entry point that surfaces this.
+fn transform_tsx_uncached_with_imports(
+    tsx_content: &str,
+    config: &TsxTransformConfig,
+    wrap_content: bool,
+) -> Result<ImportAnalysis, MdxError> {
     let allocator = Allocator::default();
 
     // Determine source type from file path and configure for module mode with decorators
@@ -356,6 +864,15 @@ fn transform_tsx_internal(
 
     let mut program = parser_return.program;
 
+    // Collect static imports before the transformer/cleanup pass touches them, so
+    // this information survives even though the generated script can't keep the
+    // import statements themselves (see `resolve_imports_shim`/`cleanup_generated_code`).
+    let imports = collect_import_descriptors(&program);
+    let import_shim = match config.import_map.as_ref() {
+        Some(import_map) => resolve_imports_shim(&imports, import_map)?,
+        None => String::new(),
+    };
+
     // Build semantic information for better transformation
     let semantic_return = SemanticBuilder::new()
         .with_excess_capacity(2.0)
@@ -367,30 +884,72 @@ fn transform_tsx_internal(
         .build_with_scoping(semantic_return.semantic.into_scoping(), &mut program);
     validate_transform_result(&transform_return, &content_to_parse)?;
 
+    // Rewrite component references (e.g. `h(Button, ...)` -> `h('Button', ...)`) before
+    // codegen, when component names are provided - used for schema output, where
+    // components are referenced by name rather than by the function itself.
+    if let Some(component_names) = config.component_names.as_ref() {
+        if !component_names.is_empty() {
+            let names_set: HashSet<&str> = component_names.iter().map(|s| s.as_str()).collect();
+            ComponentRefRewriter {
+                allocator: &allocator,
+                component_names: &names_set,
+            }
+            .visit_program(&mut program);
+        }
+    }
+
     // Generate JavaScript code from transformed AST
     let codegen_options = CodegenOptions {
         minify: config.minify,
+        source_map_path: config.with_source_maps.then(|| path.to_path_buf()),
+        comments: config.keep_comments,
         ..Default::default()
     };
 
-    let code = Codegen::new()
-        .with_options(codegen_options)
-        .build(&program)
-        .code;
+    let codegen_return = Codegen::new().with_options(codegen_options).build(&program);
+    let code = codegen_return.code;
+    let source_map = codegen_return.map;
 
-    // Clean up the generated code
-    let mut cleaned = cleanup_generated_code(&code);
+    // Clean up the generated code. When source maps are enabled, stripped lines are
+    // blanked rather than removed so the map (built against `code`) still lines up.
+    let mut cleaned = cleanup_generated_code(&code, config.with_source_maps);
 
-    // Apply component-to-string transformation if component names are provided
-    // This converts h(ComponentName, ...) to h('ComponentName', ...) in the generated code
-    if let Some(component_names) = config.component_names.as_ref() {
-        if !component_names.is_empty() {
-            let names_set: HashSet<&str> = component_names.iter().map(|s| s.as_str()).collect();
-            cleaned = convert_component_refs_in_ast(&cleaned, &names_set);
+    // The automatic runtime's emitted import is stripped above along with every other
+    // ES module construct, so resolve `_jsx`/`_jsxs`/`_Fragment` via a shim instead.
+    // Must happen before the map is embedded below, since prepending a line here shifts
+    // every line of `cleaned` down by one.
+    if config.jsx_runtime == JsxRuntimeMode::Automatic {
+        cleaned = format!("{AUTOMATIC_JSX_RUNTIME_SHIM}{cleaned}");
+    }
+
+    // Likewise, a resolved component import becomes a `const` binding prepended here
+    // rather than surviving as the `import` line `cleanup_generated_code` just stripped.
+    if !import_shim.is_empty() {
+        cleaned = format!("{import_shim}{cleaned}");
+    }
+
+    // Embed the source map as an inline data URI comment so it survives the string
+    // round-trip through caching and travels with the code to wherever it's eventually
+    // assembled into a render script.
+    if let Some(map) = source_map {
+        if let Ok(map_json) = map.to_json_string() {
+            let mut shim_lines = if config.jsx_runtime == JsxRuntimeMode::Automatic {
+                AUTOMATIC_JSX_RUNTIME_SHIM.matches('\n').count() as u32
+            } else {
+                0
+            };
+            shim_lines += import_shim.matches('\n').count() as u32;
+            if let Some(shifted) = shift_source_map_lines(&map_json, shim_lines) {
+                cleaned.push('\n');
+                cleaned.push_str(&inline_source_map_comment(&shifted));
+            }
         }
     }
 
-    Ok(cleaned)
+    Ok(ImportAnalysis {
+        code: cleaned,
+        imports,
+    })
 }
 
 /// Transforms a full component function definition (already wrapped)
@@ -418,7 +977,11 @@ pub fn transform_component_function(component_code: &str) -> Result<String, MdxE
 ///
 /// Note: Arrow functions, async functions, and classes are rejected because
 /// the renderer requires a synchronous function named `Component` that can
-/// be called directly.
+/// be called directly. [`transform_component_code_with_options`] can opt into
+/// normalizing an arrow function, anonymous `function`, or plain (non-`extends`)
+/// class with a `render` method into the required shape instead of rejecting it
+/// here; async exports, and classes the normalizer can't lower (an `extends`
+/// clause, or no `render` method), are still rejected either way.
 fn validate_export_default(rest: &str) -> Result<(), MdxError> {
     let trimmed = rest.trim();
 
@@ -493,15 +1056,35 @@ fn validate_export_default(rest: &str) -> Result<(), MdxError> {
 /// Removes `export default` and `export` from the beginning of component code
 /// to make it compatible with the TSX parser.
 ///
+/// When `normalize_anonymous_default` is set, an `export default` arrow function or
+/// anonymous `function` expression is rewritten into `function Component(...) { ... }`
+/// (see [`normalize_anonymous_default_export`]), and a plain class with a `render`
+/// method is lowered into a `function Component(props) { ... }` that instantiates the
+/// class and invokes `render()` (see [`normalize_class_default_export`]), before
+/// validation, instead of being rejected by [`validate_export_default`].
+///
 /// # Errors
 ///
 /// Returns `MdxError::InvalidExportDefault` if `export default` is followed by
 /// an identifier reference instead of a component definition.
-fn strip_export_statements(code: &str) -> Result<String, MdxError> {
+fn strip_export_statements(code: &str, normalize_anonymous_default: bool) -> Result<String, MdxError> {
     let trimmed = code.trim();
 
     // Handle "export default function" or "export default ..."
     if let Some(rest) = trimmed.strip_prefix("export default ") {
+        if normalize_anonymous_default {
+            let normalized = normalize_anonymous_default_export(trimmed)
+                .or_else(|| normalize_class_default_export(trimmed));
+            if let Some(normalized) = normalized {
+                let rest = normalized
+                    .trim()
+                    .strip_prefix("export default ")
+                    .unwrap_or(&normalized)
+                    .to_string();
+                validate_export_default(&rest)?;
+                return Ok(rest);
+            }
+        }
         // Validate that this is a proper component export
         validate_export_default(rest)?;
         return Ok(rest.to_string());
@@ -515,6 +1098,155 @@ fn strip_export_statements(code: &str) -> Result<String, MdxError> {
     Ok(code.to_string())
 }
 
+/// Rewrites an `export default` arrow function or anonymous `function` expression into
+/// `export default function Component(<params>) { <body> }`, preserving the parameter
+/// list (including TypeScript type annotations) and, for an expression-bodied arrow,
+/// wrapping the expression in a `return` statement.
+///
+/// Mirrors SWC's `function_name` transform, which assigns a stable name to an otherwise
+/// anonymous function expression. Parses `code` for real rather than scanning for `=>`,
+/// since a naive text search can't tell an arrow from a `=>` inside a parameter's type
+/// annotation; the rewrite is then a plain text splice over the declaration's byte span,
+/// which avoids needing to construct new Oxc AST nodes from scratch.
+///
+/// Returns `None` (leaving the caller to fall back to [`validate_export_default`]'s
+/// rejection) when `code` isn't an `export default` arrow/anonymous-function at all, or
+/// when it's async - async default exports stay rejected regardless of this setting,
+/// since the renderer requires a synchronous `Component`.
+fn normalize_anonymous_default_export(code: &str) -> Option<String> {
+    let allocator = Allocator::default();
+    let source_type = SourceType::from_path(Path::new("component.tsx"))
+        .ok()?
+        .with_module(true);
+    let parser_return = Parser::new(&allocator, code, source_type).parse();
+    if parser_return.panicked {
+        return None;
+    }
+    let program = parser_return.program;
+
+    let export_decl = program.body.iter().find_map(|stmt| match stmt {
+        Statement::ExportDefaultDeclaration(decl) => Some(decl),
+        _ => None,
+    })?;
+
+    let (params_span, body_span, wrap_in_return, span) = match &export_decl.declaration {
+        ExportDefaultDeclarationKind::ArrowFunctionExpression(arrow) => {
+            if arrow.r#async {
+                return None;
+            }
+            (arrow.params.span, arrow.body.span, arrow.expression, arrow.span)
+        }
+        ExportDefaultDeclarationKind::FunctionDeclaration(func) if func.id.is_none() => {
+            if func.r#async {
+                return None;
+            }
+            let body = func.body.as_deref()?;
+            (func.params.span, body.span, false, func.span)
+        }
+        _ => return None,
+    };
+
+    // A single-identifier arrow param (`x => ...`) omits the parens Oxc's span then
+    // doesn't include either; a `function` declaration always has them, as does a
+    // multi-param or type-annotated arrow, so only bare params need wrapping here.
+    let params_src = span_text(code, params_span);
+    let params_src = if params_src.trim_start().starts_with('(') {
+        params_src.to_string()
+    } else {
+        format!("({params_src})")
+    };
+    let body_src = span_text(code, body_span);
+    let body = if wrap_in_return {
+        format!("{{ return {body_src}; }}")
+    } else {
+        body_src.to_string()
+    };
+
+    let before = &code[..span.start as usize];
+    let after = &code[span.end as usize..];
+    Some(format!("{before}export default function Component{params_src} {body}{after}"))
+}
+
+/// Rewrites an `export default class { ... render() { ... } }` into
+/// `export default function Component(props) { ... instantiate the class and call
+/// render() ... }`, so a class component can be invoked the same way as every other
+/// component without the engine needing to understand `class`/`new` at all.
+///
+/// Only handles a plain class: one with no `extends` clause (there's no base class
+/// like `React.Component` for it to inherit from here, so a subclass can't be
+/// instantiated correctly) and with a non-static `render` method (otherwise there's
+/// nothing to call to produce output). `props` is threaded in two ways, since a class
+/// component may expect either: as the constructor argument (for a class with its own
+/// constructor), and as an explicit `__instance.props = props` assignment afterward
+/// (for a class that reads `this.props` without ever declaring a constructor).
+///
+/// Returns `None` (leaving the caller to fall back to [`validate_export_default`]'s
+/// rejection) for anything this can't safely lower: not a class default export, a
+/// class with an `extends` clause, or a class without a `render` method.
+fn normalize_class_default_export(code: &str) -> Option<String> {
+    let allocator = Allocator::default();
+    let source_type = SourceType::from_path(Path::new("component.tsx"))
+        .ok()?
+        .with_module(true);
+    let parser_return = Parser::new(&allocator, code, source_type).parse();
+    if parser_return.panicked {
+        return None;
+    }
+    let program = parser_return.program;
+
+    let export_decl = program.body.iter().find_map(|stmt| match stmt {
+        Statement::ExportDefaultDeclaration(decl) => Some(decl),
+        _ => None,
+    })?;
+
+    let class_decl = match &export_decl.declaration {
+        ExportDefaultDeclarationKind::ClassDeclaration(class_decl) => class_decl,
+        _ => return None,
+    };
+
+    // No base class is available in this engine to supply conventional `this.props`
+    // wiring, so a subclass can't be instantiated correctly here.
+    if class_decl.super_class.is_some() {
+        return None;
+    }
+
+    let has_render_method = class_decl.body.body.iter().any(|element| {
+        matches!(
+            element,
+            ClassElement::MethodDefinition(method)
+                if !method.r#static
+                    && method.kind == MethodDefinitionKind::Method
+                    && matches!(&method.key, PropertyKey::StaticIdentifier(id) if id.name == "render")
+        )
+    });
+    if !has_render_method {
+        return None;
+    }
+
+    let class_name = class_decl
+        .id
+        .as_ref()
+        .map(|id| id.name.as_str())
+        .unwrap_or("__ComponentClass");
+    let class_src = span_text(code, class_decl.span);
+    let class_src = if class_decl.id.is_some() {
+        class_src.to_string()
+    } else {
+        class_src.replacen("class", &format!("class {class_name}"), 1)
+    };
+
+    let before = &code[..export_decl.span.start as usize];
+    let after = &code[export_decl.span.end as usize..];
+    Some(format!(
+        "{before}export default function Component(props) {{\n  {class_src}\n  const __instance = new {class_name}(props);\n  __instance.props = props;\n  return __instance.render();\n}}{after}"
+    ))
+}
+
+/// Slices `source` at `span`'s byte offsets.
+fn span_text(source: &str, span: Span) -> &str {
+    &source[span.start as usize..span.end as usize]
+}
+
 /// Intelligently transforms component code (detects if it's raw JSX or a function)
 ///
 /// # Arguments
@@ -529,65 +1261,901 @@ fn strip_export_statements(code: &str) -> Result<String, MdxError> {
 /// `export default` statement (e.g., `export default SomeVariable` instead of
 /// a proper component definition).
 pub fn transform_component_code(code: &str) -> Result<String, MdxError> {
-    // First, strip any export statements (validates export default)
-    let code_without_exports = strip_export_statements(code)?;
-    let trimmed = code_without_exports.trim();
+    transform_component_code_with_options(code, false)
+}
 
-    // Check if it's already a function definition
-    let is_function = trimmed.starts_with("function")
-        || trimmed.starts_with("async function")
-        || trimmed.starts_with("async (")
-        || (trimmed.starts_with('(') && trimmed.contains("=>"))
-        || trimmed.starts_with("const ")
-        || trimmed.starts_with("let ")
-        || trimmed.starts_with("var ")
-        || trimmed.starts_with("class ");
+/// Like [`transform_component_code`], but when `allow_anonymous_default_export` is set,
+/// an `export default` arrow function or anonymous `function` expression is normalized
+/// into `function Component(...) { ... }` (see [`normalize_anonymous_default_export`]),
+/// and a plain class with a `render` method is lowered into an equivalent
+/// `function Component(props) { ... }` (see [`normalize_class_default_export`]),
+/// instead of rejected.
+///
+/// # Errors
+///
+/// Returns `MdxError::InvalidExportDefault` if the code contains an invalid
+/// `export default` statement (e.g., `export default SomeVariable` instead of
+/// a proper component definition).
+pub fn transform_component_code_with_options(
+    code: &str,
+    allow_anonymous_default_export: bool,
+) -> Result<String, MdxError> {
+    transform_component_code_with_config(
+        code,
+        allow_anonymous_default_export,
+        TsxTransformConfig::default(),
+    )
+}
 
-    if is_function {
-        // It's a function, transform without wrapping
-        transform_component_function(&code_without_exports)
-    } else {
-        // It's raw JSX, use the normal transformer that wraps it
-        transform_tsx_to_js(&code_without_exports)
-    }
+/// Like [`transform_component_code_with_options`], but additionally takes a full
+/// [`TsxTransformConfig`] rather than always transforming against the default one -
+/// e.g. to select [`TsxTransformConfig::decorators_legacy`] /
+/// [`TsxTransformConfig::emit_decorator_metadata`] for a component that uses
+/// decorator-driven helper classes.
+///
+/// # Errors
+///
+/// Returns `MdxError::InvalidExportDefault` if the code contains an invalid
+/// `export default` statement (e.g., `export default SomeVariable` instead of
+/// a proper component definition).
+pub fn transform_component_code_with_config(
+    code: &str,
+    allow_anonymous_default_export: bool,
+    config: TsxTransformConfig,
+) -> Result<String, MdxError> {
+    // First, strip any export statements (validates export default)
+    let code_without_exports = strip_export_statements(code, allow_anonymous_default_export)?;
+
+    let wrap_content = !is_function_definition(code_without_exports.trim());
+    transform_tsx_internal(&code_without_exports, &config, wrap_content)
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+/// Diagnostics-collecting counterpart to [`transform_component_code`]: instead of
+/// bailing at the first issue, collects every syntax error Oxc's error-recovering
+/// parser reports in one pass, plus an `export default` validation error (if any),
+/// into a single list - useful for an editor/CLI diagnostics view that wants to show
+/// everything wrong with a component at once rather than one error per fix-and-rerun
+/// cycle. Each diagnostic carries a byte span, 0-indexed line/column, and a short
+/// rendered code frame (see [`render_code_frame`]); validation-layer diagnostics also
+/// carry a stable code from [`crate::error::diagnostic_codes`].
+///
+/// Returns an empty `Vec` when `code` has no detectable issues.
+pub fn diagnose_component_code(code: &str) -> Vec<ParseError> {
+    let allocator = Allocator::default();
+    let Ok(source_type) =
+        SourceType::from_path(Path::new("component.tsx")).map(|t| t.with_module(true))
+    else {
+        return vec![ParseError::new("Failed to determine source type for component.tsx")];
+    };
 
-    // ==================== Valid export default test ====================
-    // Only `export default function Component` is valid
+    let parser_return = Parser::new(&allocator, code, source_type).parse();
+    let mut diagnostics = extract_parse_errors(&parser_return.errors, code);
 
-    #[test]
-    fn test_valid_export_default_function_component() {
-        let code = "export default function Component() { return <button>Click</button>; }";
-        let result = transform_component_code(code);
-        assert!(
-            result.is_ok(),
-            "function Component should be valid, got: {:?}",
-            result.err()
-        );
-        let output = result.unwrap();
-        assert!(
-            output.contains("function Component()"),
-            "Output should contain function Component, got: {output}"
-        );
-        assert!(
-            output.contains("engine.h("),
-            "Output should have transformed JSX to engine.h calls, got: {output}"
-        );
+    if let Some(rest) = code.trim().strip_prefix("export default ") {
+        if let Err(error) = validate_export_default(rest) {
+            diagnostics.push(invalid_export_default_diagnostic(code, rest, &error));
+        }
     }
 
-    #[test]
-    fn test_valid_export_default_function_component_with_props() {
-        let code = "export default function Component(props) { return <div>{props.name}</div>; }";
-        let result = transform_component_code(code);
-        assert!(
-            result.is_ok(),
-            "function Component with props should be valid, got: {:?}",
-            result.err()
-        );
+    diagnostics
+}
+
+/// Builds the [`ParseError`] for an invalid `export default` found while diagnosing
+/// `code`, pointing at `rest` (the text right after `export default `) rather than the
+/// whole statement, since that's the part `validate_export_default` actually judged.
+fn invalid_export_default_diagnostic(code: &str, rest: &str, error: &MdxError) -> ParseError {
+    // `rest` is a subslice of `code.trim()`, itself a subslice of `code` - both share
+    // the same backing buffer, so their pointer difference is a valid byte offset.
+    let offset = (rest.as_ptr() as usize - code.as_ptr() as usize) as u32;
+    let length = rest
+        .find(['\n', '{'])
+        .unwrap_or(rest.len())
+        .max(1) as u32;
+    let (line, column) = byte_offset_to_line_col(code, offset);
+    let location = SourceLocation::new(line, column, offset, length);
+    let frame = render_code_frame(code, &location);
+
+    ParseError::with_code(diagnostic_codes::INVALID_EXPORT_DEFAULT, error.to_string())
+        .located_at(location)
+        .with_frame(frame)
+        .with_help("Use 'export default function Component() { ... }' instead")
+}
+
+/// Like [`transform_component_code`], but non-fatal for an anonymous `export default`
+/// arrow/function: it's still normalized to `Component` (see
+/// [`normalize_anonymous_default_export`]) so the transform succeeds, but a
+/// [`Severity::Warning`]-level [`ParseError`] pointing at it is returned alongside the
+/// output instead of passing silently, mirroring lint tools that report a warning
+/// without failing the build.
+///
+/// Returns an empty warnings `Vec` when `code` has no lint-worthy pattern.
+pub fn transform_component_code_with_lints(code: &str) -> Result<(String, Vec<ParseError>), MdxError> {
+    let mut warnings = Vec::new();
+    if let Some(warning) = anonymous_default_export_warning(code) {
+        warnings.push(warning);
+    }
+    let output = transform_component_code_with_options(code, true)?;
+    Ok((output, warnings))
+}
+
+/// Builds a warning-level [`ParseError`] when `code`'s `export default` is an
+/// anonymous arrow/function that [`normalize_anonymous_default_export`] would rewrite,
+/// so callers can flag the implicit naming instead of it passing unnoticed.
+fn anonymous_default_export_warning(code: &str) -> Option<ParseError> {
+    let trimmed = code.trim();
+    let rest = trimmed.strip_prefix("export default ")?;
+    normalize_anonymous_default_export(trimmed)?;
+
+    let offset = (rest.as_ptr() as usize - code.as_ptr() as usize) as u32;
+    let length = rest.find(['\n', '{', '=']).unwrap_or(rest.len()).max(1) as u32;
+    let (line, column) = byte_offset_to_line_col(code, offset);
+    let location = SourceLocation::new(line, column, offset, length);
+    let frame = render_code_frame(code, &location);
+
+    Some(
+        ParseError::new("Anonymous default export was implicitly named 'Component'")
+            .located_at(location)
+            .with_frame(frame)
+            .with_help(
+                "Name the component explicitly: 'export default function Component() { ... }'",
+            )
+            .with_severity(Severity::Warning),
+    )
+}
+
+/// The result of processing a single input within [`process_component_batch`]: either
+/// its transformed output, or every diagnostic collected while trying to produce one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BatchComponentResult {
+    /// The input transformed successfully.
+    Output(String),
+    /// The input failed - parse errors, a validation error, or (for non-UTF-8 bytes) a
+    /// single [`MdxError::NotUtf8`]-derived diagnostic.
+    Diagnostics(Vec<ParseError>),
+}
+
+/// Outcome of [`process_component_batch`]: every input's result, keyed by the name or
+/// path it was submitted under, so a caller can tell which files in a batch need
+/// attention without the whole batch aborting at the first failure.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BatchDiagnostics {
+    /// Each input's outcome, keyed by its name/path.
+    pub results: HashMap<String, BatchComponentResult>,
+}
+
+/// Transforms every component in `inputs`, recovering from per-file failures instead of
+/// aborting the batch: a transform error is recorded as that file's diagnostics and
+/// processing continues with the next file, mirroring the "does not eject early"
+/// behavior of lint tools run over a project. Bytes that aren't valid UTF-8 are
+/// recorded as a single [`MdxError::NotUtf8`] diagnostic rather than panicking (see
+/// [`crate::error::validate_utf8`]).
+pub fn process_component_batch(inputs: &HashMap<String, Vec<u8>>) -> BatchDiagnostics {
+    let mut results = HashMap::with_capacity(inputs.len());
+    for (name, bytes) in inputs {
+        let outcome = match validate_utf8(name, bytes) {
+            Ok(code) => match transform_component_code(code) {
+                Ok(output) => BatchComponentResult::Output(output),
+                Err(err) => {
+                    BatchComponentResult::Diagnostics(component_error_diagnostics(code, err))
+                }
+            },
+            Err(err) => BatchComponentResult::Diagnostics(mdx_error_to_diagnostics(err)),
+        };
+        results.insert(name.clone(), outcome);
+    }
+    BatchDiagnostics { results }
+}
+
+/// Compiled regex matching a JSX opening tag's component name (e.g. the `Card` in
+/// `<Card>`) - the same capitalized-tag convention [`crate::mdx`]'s
+/// `COMPONENT_NAME_PATTERN` uses to tell a component reference from a plain HTML tag.
+///
+/// # Safety
+/// Pattern is compile-time constant and known to be valid.
+static COMPONENT_TAG_PATTERN: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"<([A-Z][a-zA-Z0-9]*)").expect("hardcoded regex pattern is valid")
+});
+
+/// Names of other entries in `components` referenced as JSX tags within `code`,
+/// deduplicated (order doesn't matter - traversal order is [`visit_component`]'s to
+/// decide).
+pub(crate) fn referenced_component_names<'a>(
+    code: &str,
+    components: &'a HashMap<String, ComponentDefinition>,
+) -> Vec<&'a str> {
+    let tags: HashSet<&str> = COMPONENT_TAG_PATTERN
+        .captures_iter(code)
+        .filter_map(|cap| cap.get(1).map(|m| m.as_str()))
+        .collect();
+    components
+        .keys()
+        .filter(|name| tags.contains(name.as_str()))
+        .map(String::as_str)
+        .collect()
+}
+
+/// A component's place in [`component_dependency_order`]'s depth-first traversal:
+/// `Visiting` while its own dependencies are still being walked (seeing it again in
+/// that state is a cycle), `Done` once its dependencies are fully resolved.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum VisitState {
+    Visiting,
+    Done,
+}
+
+/// Depth-first visit of `name` and its dependencies (other `components` entries its
+/// code references as JSX tags), appending to `order` in dependency-first (post-order)
+/// sequence once `name`'s own dependencies are resolved.
+fn visit_component<'a>(
+    name: &'a str,
+    components: &'a HashMap<String, ComponentDefinition>,
+    state: &mut HashMap<&'a str, VisitState>,
+    stack: &mut Vec<&'a str>,
+    order: &mut Vec<String>,
+) -> Result<(), MdxError> {
+    state.insert(name, VisitState::Visiting);
+    stack.push(name);
+
+    if let Some(def) = components.get(name) {
+        for dep in referenced_component_names(&def.code, components) {
+            match state.get(dep) {
+                Some(VisitState::Done) => {}
+                Some(VisitState::Visiting) => {
+                    let start = stack.iter().position(|s| *s == dep).unwrap_or(0);
+                    let mut chain: Vec<&str> = stack[start..].to_vec();
+                    chain.push(dep);
+                    return Err(MdxError::ComponentCycle(chain.join(" -> ")));
+                }
+                None => visit_component(dep, components, state, stack, order)?,
+            }
+        }
+    }
+
+    stack.pop();
+    state.insert(name, VisitState::Done);
+    order.push(name.to_string());
+    Ok(())
+}
+
+/// Topologically orders `components` so that every component referenced as a JSX tag
+/// (e.g. a `<Card>` whose code includes `<Footer>`) appears before the component that
+/// references it - the "inner-to-outer" render order composition needs so a composing
+/// component's included components are always registered first, regardless of
+/// `HashMap` iteration order. Used by [`crate::renderer`]'s component registration
+/// script builder in place of raw map iteration.
+///
+/// Since every component in a render shares one JS global scope and one `context` (the
+/// file's frontmatter, passed once per render rather than per component - see
+/// [`crate::renderer`]), an included component's `context`/props lookups already see
+/// the same values the composing component's do; no extra plumbing is needed for that
+/// part of composition, only this ordering and cycle check.
+///
+/// # Errors
+/// Returns [`MdxError::ComponentCycle`] naming the offending reference chain (e.g.
+/// `"Card -> Footer -> Card"`) if any component (transitively) references itself -
+/// this would otherwise only surface as a V8 stack overflow once the cyclic
+/// components were actually rendered.
+pub fn component_dependency_order(
+    components: &HashMap<String, ComponentDefinition>,
+) -> Result<Vec<String>, MdxError> {
+    let mut order = Vec::with_capacity(components.len());
+    let mut state: HashMap<&str, VisitState> = HashMap::with_capacity(components.len());
+    let mut stack = Vec::new();
+
+    for name in components.keys() {
+        if !state.contains_key(name.as_str()) {
+            visit_component(name, components, &mut state, &mut stack, &mut order)?;
+        }
+    }
+
+    Ok(order)
+}
+
+/// Flattens any [`MdxError`] into a `Vec<ParseError>`: a `TsxParse`/`TsxTransform`
+/// error's list is used as-is, while every other variant (a single message, with no
+/// per-error structure) becomes a one-element list so [`BatchComponentResult`] has a
+/// uniform shape regardless of which error the failed file produced.
+fn mdx_error_to_diagnostics(err: MdxError) -> Vec<ParseError> {
+    match err.errors() {
+        Some(errors) => errors.to_vec(),
+        None => vec![ParseError::new(err.to_string())],
+    }
+}
+
+/// Like [`mdx_error_to_diagnostics`], but for a [`transform_component_code`] failure
+/// against `code`: an [`MdxError::InvalidExportDefault`] is re-diagnosed via
+/// [`diagnose_component_code`] to recover the byte span and
+/// [`diagnostic_codes::INVALID_EXPORT_DEFAULT`] code that
+/// [`transform_component_code`]'s fail-fast path doesn't itself carry, since
+/// [`mdx_error_to_diagnostics`] alone would fall back to a span-less message for it.
+/// Every other error variant is handled identically to [`mdx_error_to_diagnostics`].
+pub(crate) fn component_error_diagnostics(code: &str, err: MdxError) -> Vec<ParseError> {
+    if matches!(err, MdxError::InvalidExportDefault(_)) {
+        let diagnostics = diagnose_component_code(code);
+        if !diagnostics.is_empty() {
+            return diagnostics;
+        }
+    }
+    mdx_error_to_diagnostics(err)
+}
+
+/// Whether `trimmed` already looks like a function/class/variable definition rather
+/// than raw JSX - used to decide whether [`transform_component_code`] needs to wrap it
+/// in a component function before transforming.
+fn is_function_definition(trimmed: &str) -> bool {
+    trimmed.starts_with("function")
+        || trimmed.starts_with("async function")
+        || trimmed.starts_with("async (")
+        || (trimmed.starts_with('(') && trimmed.contains("=>"))
+        || trimmed.starts_with("const ")
+        || trimmed.starts_with("let ")
+        || trimmed.starts_with("var ")
+        || trimmed.starts_with("class ")
+}
+
+/// Default file name recorded in a source map's `sources` field when
+/// [`transform_component_code_with_map`] isn't given one.
+const DEFAULT_SOURCE_FILE_NAME: &str = "component.tsx";
+
+/// Result of [`transform_component_code_with_map`]: generated code and a standard
+/// Source Map v3 JSON naming the original component source, for browser errors and
+/// debuggers to map a compiled MDX component back to its original `.mdx`/TSX lines.
+pub struct ComponentMapOutput {
+    /// Generated JavaScript code, with no inline source map comment.
+    pub code: String,
+    /// Source Map v3 JSON, re-serialized through the `sourcemap` crate so its
+    /// `sources` field names `source_file_name` (see
+    /// [`transform_component_code_with_map`]) instead of the placeholder path Oxc
+    /// generated the map against.
+    pub map: String,
+}
+
+/// Like [`transform_component_code`], but also returns a Source Map v3 JSON mapping
+/// the generated code back to the code Oxc parsed: `code` with its `export`
+/// statements stripped and, for raw JSX, wrapped by [`wrap_in_component`]. Source
+/// positions are not shifted back onto `code`'s original lines.
+///
+/// # Arguments
+/// * `code` - Component code (either raw JSX or a complete function)
+/// * `source_file_name` - Name recorded in the map's `sources` field, e.g. the
+///   original `.mdx`/TSX file name; defaults to [`DEFAULT_SOURCE_FILE_NAME`] when `None`
+///
+/// # Errors
+///
+/// Returns `MdxError::InvalidExportDefault` for the same invalid `export default`
+/// shapes [`transform_component_code`] rejects, or `MdxError::SourceMap` if Oxc's
+/// generated map fails to re-serialize.
+pub fn transform_component_code_with_map(
+    code: &str,
+    source_file_name: Option<&str>,
+) -> Result<ComponentMapOutput, MdxError> {
+    let code_without_exports = strip_export_statements(code, false)?;
+    let wrap_content = !is_function_definition(code_without_exports.trim());
+
+    let config = TsxTransformConfig {
+        with_source_maps: true,
+        ..TsxTransformConfig::default()
+    };
+    let output = transform_with_map(&code_without_exports, config, wrap_content)?;
+
+    let source_file_name = source_file_name.unwrap_or(DEFAULT_SOURCE_FILE_NAME);
+    let map = rename_source_map_source(output.map.as_deref(), source_file_name)?;
+
+    Ok(ComponentMapOutput {
+        code: output.code,
+        map,
+    })
+}
+
+/// Re-serializes an Oxc-generated Source Map v3 JSON (if any) through the `sourcemap`
+/// crate so its `sources`/`file` fields name `source_file_name` instead of the
+/// placeholder path Oxc generated the map against - the one thing a caller embedding a
+/// named `.mdx`/TSX file actually needs changed, everything else (mappings, names)
+/// passes through untouched. `map_json: None` (Oxc produced no map) yields an empty,
+/// but still valid, map naming `source_file_name`.
+fn rename_source_map_source(
+    map_json: Option<&str>,
+    source_file_name: &str,
+) -> Result<String, MdxError> {
+    let mut builder = sourcemap::SourceMapBuilder::new(Some(source_file_name));
+    let src_id = builder.add_source(source_file_name);
+
+    if let Some(map_json) = map_json {
+        let parsed = sourcemap::SourceMap::from_slice(map_json.as_bytes())
+            .map_err(|e| MdxError::SourceMap(e.to_string()))?;
+        for token in parsed.tokens() {
+            builder.add_raw(
+                token.get_dst_line(),
+                token.get_dst_col(),
+                token.get_src_line(),
+                token.get_src_col(),
+                Some(src_id),
+                token.get_name_id(),
+                token.is_range(),
+            );
+        }
+    }
+
+    let mut buf = Vec::new();
+    builder
+        .into_sourcemap()
+        .to_writer(&mut buf)
+        .map_err(|e| MdxError::SourceMap(e.to_string()))?;
+    String::from_utf8(buf).map_err(|e| MdxError::SourceMap(e.to_string()))
+}
+
+/// Extracts react-docgen-style prop metadata from a valid
+/// `export default function Component(props: {...})`: one [`PropInfo`] per member of
+/// the single parameter's TypeScript type, covering an inline object type literal and a
+/// reference to a top-level `interface` or `type` alias declared alongside the
+/// component. Returns an empty list when the export is valid but carries no type
+/// annotation, or when the parameter's type can't be resolved to an object shape
+/// (e.g. `props: any`).
+///
+/// # Errors
+///
+/// Returns `MdxError::InvalidExportDefault` for the same invalid `export default`
+/// shapes [`transform_component_code`] rejects.
+pub fn extract_component_props(code: &str) -> Result<Vec<PropInfo>, MdxError> {
+    let code_without_exports = strip_export_statements(code, false)?;
+
+    let allocator = Allocator::default();
+    let source_type = SourceType::from_path(Path::new("component.tsx"))
+        .map_err(|e| MdxError::SourceType(e.to_string()))?
+        .with_module(true);
+    let parser_return = Parser::new(&allocator, &code_without_exports, source_type).parse();
+    validate_parse_result(&parser_return, &code_without_exports)?;
+    let program = parser_return.program;
+
+    let Some(func) = program.body.iter().find_map(|stmt| match stmt {
+        Statement::FunctionDeclaration(func) => Some(func.as_ref()),
+        _ => None,
+    }) else {
+        return Ok(Vec::new());
+    };
+
+    let Some(param) = func.params.items.first() else {
+        return Ok(Vec::new());
+    };
+
+    let Some(type_annotation) = param.pattern.type_annotation.as_ref() else {
+        return Ok(Vec::new());
+    };
+
+    let Some(members) = resolve_type_members(&type_annotation.type_annotation, &program) else {
+        return Ok(Vec::new());
+    };
+
+    let defaults = destructured_defaults(&param.pattern.kind, &code_without_exports);
+
+    Ok(members
+        .iter()
+        .filter_map(|member| prop_info_from_signature(member, &code_without_exports, &defaults))
+        .collect())
+}
+
+/// Resolves `ts_type` to the member signatures of an object shape: directly for an
+/// inline `TSTypeLiteral`, or by looking up a top-level `interface`/`type` alias of the
+/// same name in `program` for a `TSTypeReference`. Returns `None` for any other type
+/// (e.g. `any`, a union, a primitive) since there's no member list to report.
+fn resolve_type_members<'a, 'p>(
+    ts_type: &'p TSType<'a>,
+    program: &'p Program<'a>,
+) -> Option<&'p oxc_allocator::Vec<'a, TSSignature<'a>>> {
+    match ts_type {
+        TSType::TSTypeLiteral(literal) => Some(&literal.members),
+        TSType::TSTypeReference(reference) => {
+            let TSTypeName::IdentifierReference(ident) = &reference.type_name else {
+                return None;
+            };
+            program.body.iter().find_map(|stmt| match stmt {
+                Statement::TSInterfaceDeclaration(decl) if decl.id.name == ident.name => {
+                    Some(&decl.body.body)
+                }
+                Statement::TSTypeAliasDeclaration(decl) if decl.id.name == ident.name => {
+                    match &decl.type_annotation {
+                        TSType::TSTypeLiteral(literal) => Some(&literal.members),
+                        _ => None,
+                    }
+                }
+                _ => None,
+            })
+        }
+        _ => None,
+    }
+}
+
+/// Builds a prop-name -> source-text map of destructuring defaults (e.g. `{ count = 0 }`)
+/// off the parameter's binding pattern. Empty for a non-destructured parameter (e.g. a
+/// plain `props` identifier), since there's nowhere for a default to live syntactically.
+fn destructured_defaults(
+    kind: &BindingPatternKind<'_>,
+    source: &str,
+) -> HashMap<String, String> {
+    let BindingPatternKind::ObjectPattern(object) = kind else {
+        return HashMap::new();
+    };
+
+    object
+        .properties
+        .iter()
+        .filter_map(|property| {
+            let BindingPatternKind::AssignmentPattern(assignment) = &property.value.kind else {
+                return None;
+            };
+            let name = property_key_name(&property.key)?;
+            Some((name, span_text(source, assignment.right.span()).to_string()))
+        })
+        .collect()
+}
+
+/// Builds a [`PropInfo`] from a single `interface`/object-type member, looking up its
+/// default value (if any) in `defaults`. Returns `None` for a signature that isn't a
+/// plain property (e.g. a method or index signature) or whose key isn't a plain name.
+fn prop_info_from_signature(
+    signature: &TSSignature<'_>,
+    source: &str,
+    defaults: &HashMap<String, String>,
+) -> Option<PropInfo> {
+    let TSSignature::TSPropertySignature(property) = signature else {
+        return None;
+    };
+    let name = property_key_name(&property.key)?;
+    let type_annotation = property.type_annotation.as_ref();
+    let type_string = type_annotation.map_or_else(
+        || "unknown".to_string(),
+        |annotation| span_text(source, annotation.type_annotation.span()).to_string(),
+    );
+    let optional = property.optional
+        || type_annotation.is_some_and(|annotation| type_includes_undefined(&annotation.type_annotation));
+    let default_value = defaults.get(&name).cloned();
+
+    Some(PropInfo {
+        name,
+        type_string,
+        optional,
+        default_value,
+    })
+}
+
+/// Whether `ts_type` is (or, for a union, includes) the literal `undefined` type -
+/// the other way (besides a `?` modifier) a prop can be optional.
+fn type_includes_undefined(ts_type: &TSType<'_>) -> bool {
+    match ts_type {
+        TSType::TSUndefinedKeyword(_) => true,
+        TSType::TSUnionType(union) => union.types.iter().any(type_includes_undefined),
+        _ => false,
+    }
+}
+
+/// Extracts the plain name out of a `PropertyKey`, covering the shapes a type member's
+/// or a destructured binding's key can take. Returns `None` for a computed key, since
+/// its name isn't known without evaluating an arbitrary expression.
+fn property_key_name(key: &PropertyKey<'_>) -> Option<String> {
+    match key {
+        PropertyKey::StaticIdentifier(id) => Some(id.name.to_string()),
+        PropertyKey::StringLiteral(lit) => Some(lit.value.to_string()),
+        _ => None,
+    }
+}
+
+/// Generates an isolated-declarations-style `.d.ts` for a component module: a
+/// `declare const Component: (props: T) => JSX.Element;` for the default export, and
+/// a `declare const`/`declare function` line for each `export const`/`export function`
+/// named export.
+///
+/// Following the isolated-declarations rule, every exported binding must carry an
+/// explicit type annotation, or (for a `const`) be trivially inferable from a string,
+/// number, or boolean literal initializer - this never runs full-program type
+/// inference, reusing only the existing Oxc parse of `code`.
+///
+/// # Errors
+///
+/// Returns `MdxError::TsxTransform` pointing at the export that needs an explicit
+/// annotation when one can't be inferred, or `MdxError::TsxParse`/`SourceType` if
+/// `code` fails to parse.
+pub fn generate_component_declarations(code: &str) -> Result<String, MdxError> {
+    let allocator = Allocator::default();
+    let source_type = SourceType::from_path(Path::new("component.tsx"))
+        .map_err(|e| MdxError::SourceType(e.to_string()))?
+        .with_module(true);
+    let parser_return = Parser::new(&allocator, code, source_type).parse();
+    validate_parse_result(&parser_return, code)?;
+    let program = parser_return.program;
+
+    let mut declarations = Vec::new();
+    for stmt in &program.body {
+        match stmt {
+            Statement::ExportDefaultDeclaration(decl) => {
+                declarations.push(declare_default_export(code, decl)?);
+            }
+            Statement::ExportNamedDeclaration(decl) => {
+                if let Some(declaration) = &decl.declaration {
+                    declarations.extend(declare_named_export(code, declaration)?);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    Ok(declarations.join("\n"))
+}
+
+/// Builds the `declare const Component: (props: T) => JSX.Element;` line for the
+/// default export. Only `export default function Component(...)` is supported, same
+/// as [`validate_export_default`]'s requirement elsewhere in this module.
+fn declare_default_export(
+    code: &str,
+    decl: &ExportDefaultDeclaration<'_>,
+) -> Result<String, MdxError> {
+    let ExportDefaultDeclarationKind::FunctionDeclaration(func) = &decl.declaration else {
+        return Err(MdxError::tsx_transform(
+            "Cannot generate a .d.ts declaration for a default export that isn't `function Component(...)`",
+        ));
+    };
+    let name = func
+        .id
+        .as_ref()
+        .map_or_else(|| "Component".to_string(), |id| id.name.to_string());
+
+    let Some(param) = func.params.items.first() else {
+        return Ok(format!("declare const {name}: () => JSX.Element;"));
+    };
+    let Some(annotation) = param.pattern.type_annotation.as_ref() else {
+        return Err(missing_annotation_error(&name));
+    };
+    let props_type = span_text(code, annotation.type_annotation.span());
+    Ok(format!("declare const {name}: (props: {props_type}) => JSX.Element;"))
+}
+
+/// Builds the `declare const`/`declare function` line(s) for a single `export const`
+/// or `export function` declaration. Any other exported declaration (class,
+/// interface, type alias) isn't in scope - `.d.ts` output for those would just be the
+/// declaration itself with its body stripped, which isn't what isolated declarations
+/// needs solved here, so they're silently skipped.
+fn declare_named_export(code: &str, declaration: &Declaration<'_>) -> Result<Vec<String>, MdxError> {
+    match declaration {
+        Declaration::VariableDeclaration(var_decl) => var_decl
+            .declarations
+            .iter()
+            .map(|declarator| declare_variable(code, declarator))
+            .collect(),
+        Declaration::FunctionDeclaration(func) => Ok(vec![declare_function(code, func)?]),
+        _ => Ok(Vec::new()),
+    }
+}
+
+/// Builds a `declare const <name>: <type>;` line, using the binding's explicit type
+/// annotation if present, otherwise inferring `string`/`number`/`boolean` from a
+/// literal initializer.
+fn declare_variable(code: &str, declarator: &VariableDeclarator<'_>) -> Result<String, MdxError> {
+    let BindingPatternKind::BindingIdentifier(id) = &declarator.id.kind else {
+        return Err(MdxError::tsx_transform(
+            "Cannot generate a .d.ts declaration for a destructured export binding",
+        ));
+    };
+    let name = id.name.to_string();
+
+    if let Some(annotation) = declarator.id.type_annotation.as_ref() {
+        let type_string = span_text(code, annotation.type_annotation.span());
+        return Ok(format!("declare const {name}: {type_string};"));
+    }
+
+    let inferred = declarator
+        .init
+        .as_ref()
+        .and_then(infer_literal_type)
+        .ok_or_else(|| missing_annotation_error(&name))?;
+    Ok(format!("declare const {name}: {inferred};"))
+}
+
+/// Infers a `.d.ts` type for a trivially-typed literal initializer, the one case
+/// isolated declarations allows without an explicit annotation.
+fn infer_literal_type(expr: &Expression<'_>) -> Option<&'static str> {
+    match expr {
+        Expression::StringLiteral(_) => Some("string"),
+        Expression::NumericLiteral(_) => Some("number"),
+        Expression::BooleanLiteral(_) => Some("boolean"),
+        _ => None,
+    }
+}
+
+/// Builds a `declare function <name>(<params>): <return type>;` line. Requires every
+/// parameter and the return type to carry an explicit annotation - unlike a `const`
+/// initializer, a function body isn't a literal isolated declarations can read a type
+/// off, so there's no inference fallback here.
+fn declare_function(code: &str, func: &Function<'_>) -> Result<String, MdxError> {
+    let Some(id) = func.id.as_ref() else {
+        return Err(MdxError::tsx_transform(
+            "Cannot generate a .d.ts declaration for an anonymous function export",
+        ));
+    };
+    let name = id.name.to_string();
+
+    for param in &func.params.items {
+        if param.pattern.type_annotation.is_none() {
+            return Err(missing_annotation_error(&name));
+        }
+    }
+    let Some(return_type) = func.return_type.as_ref() else {
+        return Err(missing_annotation_error(&name));
+    };
+
+    let params_src = span_text(code, func.params.span);
+    let return_type_src = span_text(code, return_type.type_annotation.span());
+    Ok(format!("declare function {name}{params_src}: {return_type_src};"))
+}
+
+/// Builds the error for an exported binding that needs an explicit type annotation
+/// isolated declarations couldn't infer one for.
+fn missing_annotation_error(export_name: &str) -> MdxError {
+    MdxError::tsx_transform(format!(
+        "Cannot generate a .d.ts declaration for export '{export_name}': add an explicit type annotation (isolated declarations can't infer one)"
+    ))
+}
+
+/// Reports every static `import` and re-export-from (`export ... from`/`export * from`)
+/// specifier in `code`'s import graph, flagging specifiers seen more than once -
+/// similar to what an import-linting tool reports, for a host that wants to inspect or
+/// police a component's dependencies up front.
+///
+/// # Errors
+///
+/// Returns `MdxError::TsxParse`/`SourceType` if `code` fails to parse.
+pub fn analyze_imports(code: &str) -> Result<ImportReport, MdxError> {
+    let allocator = Allocator::default();
+    let source_type = SourceType::from_path(Path::new("component.tsx"))
+        .map_err(|e| MdxError::SourceType(e.to_string()))?
+        .with_module(true);
+    let parser_return = Parser::new(&allocator, code, source_type).parse();
+    validate_parse_result(&parser_return, code)?;
+
+    let mut imports = collect_import_descriptors(&parser_return.program);
+    imports.extend(collect_export_from_descriptors(&parser_return.program));
+
+    let mut seen = HashSet::new();
+    let mut duplicate_specifiers = Vec::new();
+    for import in &imports {
+        if !seen.insert(import.specifier.as_str()) && !duplicate_specifiers.contains(&import.specifier) {
+            duplicate_specifiers.push(import.specifier.clone());
+        }
+    }
+
+    Ok(ImportReport {
+        imports,
+        duplicate_specifiers,
+    })
+}
+
+/// Collects every `export { ... } from "..."` and `export * from "..."` specifier -
+/// the "export ... from" half of [`analyze_imports`]'s import graph, which
+/// [`collect_import_descriptors`] (used on the hot transform path) doesn't need to
+/// know about since a re-export binds nothing into the component's own scope.
+fn collect_export_from_descriptors(program: &Program<'_>) -> Vec<ImportDescriptor> {
+    program
+        .body
+        .iter()
+        .filter_map(|stmt| match stmt {
+            Statement::ExportNamedDeclaration(decl) => {
+                let source = decl.source.as_ref()?;
+                let imported_names = decl
+                    .specifiers
+                    .iter()
+                    .map(|specifier| ImportedName::Named {
+                        imported: module_export_name(&specifier.local),
+                        local: module_export_name(&specifier.exported),
+                    })
+                    .collect();
+                Some(ImportDescriptor {
+                    specifier: source.value.to_string(),
+                    imported_names,
+                    type_only: matches!(decl.export_kind, ImportOrExportKind::Type),
+                    start: decl.span.start,
+                    end: decl.span.end,
+                })
+            }
+            Statement::ExportAllDeclaration(decl) => Some(ImportDescriptor {
+                specifier: decl.source.value.to_string(),
+                imported_names: Vec::new(),
+                type_only: matches!(decl.export_kind, ImportOrExportKind::Type),
+                start: decl.span.start,
+                end: decl.span.end,
+            }),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Rejects the first bare import specifier (not starting with `.` or `/`) absent from
+/// `allow_list`, with a span-carrying diagnostic pointing at the offending `import`.
+/// A relative specifier is never subject to the allow-list, since it names a sibling
+/// component file rather than a host-provided global.
+fn validate_import_allow_list(code: &str, allow_list: &HashSet<String>) -> Result<(), MdxError> {
+    let allocator = Allocator::default();
+    let source_type = SourceType::from_path(Path::new("component.tsx"))
+        .map_err(|e| MdxError::SourceType(e.to_string()))?
+        .with_module(true);
+    let parser_return = Parser::new(&allocator, code, source_type).parse();
+    validate_parse_result(&parser_return, code)?;
+
+    for import in collect_import_descriptors(&parser_return.program) {
+        if import.type_only {
+            continue;
+        }
+        let is_bare = !import.specifier.starts_with('.') && !import.specifier.starts_with('/');
+        if is_bare && !allow_list.contains(&import.specifier) {
+            let (line, column) = byte_offset_to_line_col(code, import.start);
+            let location =
+                SourceLocation::new(line, column, import.start, import.end - import.start);
+            let frame = render_code_frame(code, &location);
+            let diagnostic = ParseError::with_code(
+                diagnostic_codes::DISALLOWED_IMPORT,
+                format!("Import '{}' is not in the allowed import list", import.specifier),
+            )
+            .located_at(location)
+            .with_frame(frame);
+            return Err(MdxError::TsxTransform(vec![diagnostic]));
+        }
+    }
+
+    Ok(())
+}
+
+/// Like [`transform_component_code_with_config`], but rejects any bare import not
+/// present in `allow_list` before transforming - for a sandboxed host that only wants
+/// to allow a fixed set of globals/components to be imported.
+///
+/// # Errors
+///
+/// Returns `MdxError::TsxTransform` carrying a span-located diagnostic for the first
+/// disallowed import, or the same errors [`transform_component_code_with_config`] can
+/// return.
+pub fn transform_component_code_with_import_allow_list(
+    code: &str,
+    allow_anonymous_default_export: bool,
+    config: TsxTransformConfig,
+    allow_list: &HashSet<String>,
+) -> Result<String, MdxError> {
+    validate_import_allow_list(code, allow_list)?;
+    transform_component_code_with_config(code, allow_anonymous_default_export, config)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // ==================== Valid export default test ====================
+    // Only `export default function Component` is valid
+
+    #[test]
+    fn test_valid_export_default_function_component() {
+        let code = "export default function Component() { return <button>Click</button>; }";
+        let result = transform_component_code(code);
+        assert!(
+            result.is_ok(),
+            "function Component should be valid, got: {:?}",
+            result.err()
+        );
+        let output = result.unwrap();
+        assert!(
+            output.contains("function Component()"),
+            "Output should contain function Component, got: {output}"
+        );
+        assert!(
+            output.contains("engine.h("),
+            "Output should have transformed JSX to engine.h calls, got: {output}"
+        );
+    }
+
+    #[test]
+    fn test_valid_export_default_function_component_with_props() {
+        let code = "export default function Component(props) { return <div>{props.name}</div>; }";
+        let result = transform_component_code(code);
+        assert!(
+            result.is_ok(),
+            "function Component with props should be valid, got: {:?}",
+            result.err()
+        );
     }
 
     #[test]
@@ -906,4 +2474,476 @@ export default function Component() {
         let result = transform_component_code(code);
         assert!(result.is_ok(), "export function should be valid");
     }
+
+    // ==================== Anonymous/arrow default export normalization ====================
+
+    #[test]
+    fn test_options_allows_arrow_default_export() {
+        let code = "export default () => <div>Hello</div>;";
+        let result = transform_component_code_with_options(code, true);
+        assert!(result.is_ok(), "arrow default export should be normalized, got: {:?}", result.err());
+    }
+
+    #[test]
+    fn test_without_opt_in_arrow_default_export_still_fails() {
+        let code = "export default () => <div>Hello</div>;";
+        let result = transform_component_code(code);
+        assert!(result.is_err(), "arrow default export should still fail without opting in");
+    }
+
+    #[test]
+    fn test_options_allows_anonymous_function_default_export() {
+        let code = "export default function (props: { name: string }) { return <div>{props.name}</div>; }";
+        let result = transform_component_code_with_options(code, true);
+        assert!(
+            result.is_ok(),
+            "anonymous function default export should be normalized, got: {:?}",
+            result.err()
+        );
+        let output = result.unwrap();
+        assert!(output.contains("function Component("), "got: {output}");
+    }
+
+    #[test]
+    fn test_options_still_rejects_async_arrow_default_export() {
+        let code = "export default async () => <div>Hello</div>;";
+        let result = transform_component_code_with_options(code, true);
+        assert!(result.is_err(), "async arrow default export should stay rejected");
+    }
+
+    // ==================== Class default export normalization ====================
+
+    #[test]
+    fn test_options_allows_class_with_render_method() {
+        let code = "export default class Widget { render() { return <div>Hello</div>; } }";
+        let result = transform_component_code_with_options(code, true);
+        assert!(result.is_ok(), "class with a render method should be lowered, got: {:?}", result.err());
+        let output = result.unwrap();
+        assert!(output.contains("function Component("), "got: {output}");
+        assert!(output.contains("new Widget("), "got: {output}");
+        assert!(output.contains(".render()"), "got: {output}");
+    }
+
+    #[test]
+    fn test_options_allows_anonymous_class_with_render_method() {
+        let code = "export default class { render() { return <div>Hello</div>; } }";
+        let result = transform_component_code_with_options(code, true);
+        assert!(result.is_ok(), "anonymous class with a render method should be lowered, got: {:?}", result.err());
+        let output = result.unwrap();
+        assert!(output.contains("new __ComponentClass("), "got: {output}");
+    }
+
+    #[test]
+    fn test_without_opt_in_class_still_fails() {
+        let code = "export default class Widget { render() { return <div>Hello</div>; } }";
+        let result = transform_component_code(code);
+        assert!(result.is_err(), "class should still fail without opting in");
+    }
+
+    #[test]
+    fn test_options_still_rejects_class_with_extends() {
+        let code = "export default class Widget extends Base { render() { return <div>Hello</div>; } }";
+        let result = transform_component_code_with_options(code, true);
+        assert!(result.is_err(), "a class that extends another class should stay rejected");
+    }
+
+    #[test]
+    fn test_options_still_rejects_class_without_render_method() {
+        let code = "export default class Widget { draw() { return <div>Hello</div>; } }";
+        let result = transform_component_code_with_options(code, true);
+        assert!(result.is_err(), "a class without a render method should stay rejected");
+    }
+
+    // ==================== Component props metadata extraction ====================
+
+    #[test]
+    fn test_extract_props_from_inline_object_type() {
+        let code = "export default function Component(props: { name: string; age?: number }) { return <div>{props.name}</div>; }";
+        let props = extract_component_props(code).unwrap();
+        assert_eq!(props.len(), 2);
+        assert_eq!(props[0].name, "name");
+        assert!(!props[0].optional);
+        assert_eq!(props[1].name, "age");
+        assert!(props[1].optional);
+    }
+
+    #[test]
+    fn test_extract_props_from_referenced_interface() {
+        let code = r#"interface Props { count: number }
+export default function Component(props: Props) { return <div>{props.count}</div>; }"#;
+        let props = extract_component_props(code).unwrap();
+        assert_eq!(props.len(), 1);
+        assert_eq!(props[0].name, "count");
+        assert_eq!(props[0].type_string, "number");
+    }
+
+    #[test]
+    fn test_extract_props_destructured_default() {
+        let code = "export default function Component({ count = 0 }: { count?: number }) { return <div>{count}</div>; }";
+        let props = extract_component_props(code).unwrap();
+        assert_eq!(props.len(), 1);
+        assert_eq!(props[0].name, "count");
+        assert_eq!(props[0].default_value.as_deref(), Some("0"));
+    }
+
+    #[test]
+    fn test_extract_props_no_type_annotation_is_empty() {
+        let code = "export default function Component(props) { return <div>{props.name}</div>; }";
+        let props = extract_component_props(code).unwrap();
+        assert!(props.is_empty());
+    }
+
+    // ==================== Diagnostics-collecting mode ====================
+
+    #[test]
+    fn test_diagnose_valid_code_has_no_diagnostics() {
+        let code = "export default function Component() { return <div>Hello</div>; }";
+        assert!(diagnose_component_code(code).is_empty());
+    }
+
+    #[test]
+    fn test_diagnose_invalid_export_default_has_code_and_frame() {
+        let code = "export default function Widget() { return <div />; }";
+        let diagnostics = diagnose_component_code(code);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(
+            diagnostics[0].code.as_deref(),
+            Some(diagnostic_codes::INVALID_EXPORT_DEFAULT)
+        );
+        assert!(diagnostics[0].location.is_some());
+        assert!(diagnostics[0].frame.as_deref().is_some_and(|f| f.contains('^')));
+    }
+
+    #[test]
+    fn test_diagnose_parse_error_has_location_and_frame() {
+        let code = "export default function Component( { return <div />; }";
+        let diagnostics = diagnose_component_code(code);
+        assert!(!diagnostics.is_empty());
+        assert!(diagnostics[0].location.is_some());
+        assert!(diagnostics[0].frame.is_some());
+    }
+
+    // ==================== Decorator options ====================
+
+    #[test]
+    fn test_decorator_options_default_to_legacy_with_metadata() {
+        let options = create_transform_options(&TsxTransformConfig::default());
+        assert!(options.decorator.legacy);
+        assert!(options.decorator.emit_decorator_metadata);
+    }
+
+    #[test]
+    fn test_decorator_options_emit_decorator_metadata_can_be_disabled() {
+        let config = TsxTransformConfig::default().with_emit_decorator_metadata(false);
+        let options = create_transform_options(&config);
+        assert!(options.decorator.legacy);
+        assert!(!options.decorator.emit_decorator_metadata);
+    }
+
+    #[test]
+    fn test_decorator_options_non_legacy_disables_metadata_too() {
+        let config = TsxTransformConfig::default().with_decorators_legacy(false);
+        let options = create_transform_options(&config);
+        assert!(!options.decorator.legacy);
+        assert!(!options.decorator.emit_decorator_metadata);
+    }
+
+    #[test]
+    fn test_transform_component_code_with_config_threads_decorator_options() {
+        let code = r#"export default function Component(props: any) {
+    function logged(target: any) { return target; }
+
+    @logged
+    class Helper {
+        getValue() { return "helper"; }
+    }
+
+    const h = new Helper();
+    return <div>{h.getValue()}</div>;
+}"#;
+        let config = TsxTransformConfig::default().with_decorators_legacy(true);
+        let result = transform_component_code_with_config(code, false, config);
+        assert!(result.is_ok(), "got: {:?}", result.err());
+    }
+
+    // ==================== .d.ts declaration generation ====================
+
+    #[test]
+    fn test_declarations_for_default_export_with_inline_props() {
+        let code = "export default function Component(props: { name: string }) { return <div>{props.name}</div>; }";
+        let dts = generate_component_declarations(code).unwrap();
+        assert_eq!(
+            dts,
+            "declare const Component: (props: { name: string }) => JSX.Element;"
+        );
+    }
+
+    #[test]
+    fn test_declarations_for_default_export_without_props() {
+        let code = "export default function Component() { return <div />; }";
+        let dts = generate_component_declarations(code).unwrap();
+        assert_eq!(dts, "declare const Component: () => JSX.Element;");
+    }
+
+    #[test]
+    fn test_declarations_missing_annotation_is_an_error() {
+        let code = "export default function Component(props) { return <div />; }";
+        let result = generate_component_declarations(code);
+        assert!(result.is_err(), "should require an explicit props annotation");
+    }
+
+    #[test]
+    fn test_declarations_named_const_infers_literal_type() {
+        let code = r#"export default function Component() { return <div />; }
+export const version = "1.0.0";"#;
+        let dts = generate_component_declarations(code).unwrap();
+        assert!(dts.contains("declare const version: string;"), "got: {dts}");
+    }
+
+    #[test]
+    fn test_declarations_named_const_with_explicit_annotation() {
+        let code = r#"export default function Component() { return <div />; }
+export const count: number = computeCount();"#;
+        let dts = generate_component_declarations(code).unwrap();
+        assert!(dts.contains("declare const count: number;"), "got: {dts}");
+    }
+
+    #[test]
+    fn test_declarations_named_const_without_annotation_or_literal_is_an_error() {
+        let code = r#"export default function Component() { return <div />; }
+export const count = computeCount();"#;
+        let result = generate_component_declarations(code);
+        assert!(result.is_err(), "should require an explicit annotation for a non-literal init");
+    }
+
+    #[test]
+    fn test_declarations_named_function() {
+        let code = r#"export default function Component() { return <div />; }
+export function add(a: number, b: number): number { return a + b; }"#;
+        let dts = generate_component_declarations(code).unwrap();
+        assert!(
+            dts.contains("declare function add(a: number, b: number): number;"),
+            "got: {dts}"
+        );
+    }
+
+    // ==================== Import analysis and allow-listing ====================
+
+    #[test]
+    fn test_analyze_imports_collects_specifiers() {
+        let code = r#"import Button from "./Button";
+import { useState } from "react";
+export default function Component() { return <div />; }"#;
+        let report = analyze_imports(code).unwrap();
+        let specifiers: Vec<&str> = report.imports.iter().map(|i| i.specifier.as_str()).collect();
+        assert_eq!(specifiers, vec!["./Button", "react"]);
+        assert!(report.duplicate_specifiers.is_empty());
+    }
+
+    #[test]
+    fn test_analyze_imports_flags_duplicates() {
+        let code = r#"import { a } from "shared";
+import { b } from "shared";
+export default function Component() { return <div />; }"#;
+        let report = analyze_imports(code).unwrap();
+        assert_eq!(report.duplicate_specifiers, vec!["shared".to_string()]);
+    }
+
+    #[test]
+    fn test_analyze_imports_marks_type_only() {
+        let code = r#"import type { Props } from "./types";
+export default function Component() { return <div />; }"#;
+        let report = analyze_imports(code).unwrap();
+        assert!(report.imports[0].type_only);
+    }
+
+    #[test]
+    fn test_analyze_imports_collects_export_from() {
+        let code = r#"export { Button } from "./Button";
+export default function Component() { return <div />; }"#;
+        let report = analyze_imports(code).unwrap();
+        assert_eq!(report.imports[0].specifier, "./Button");
+    }
+
+    #[test]
+    fn test_allow_list_accepts_listed_bare_specifier() {
+        let code = r#"import { useState } from "react";
+export default function Component() { return <div />; }"#;
+        let allow_list: HashSet<String> = ["react".to_string()].into_iter().collect();
+        let result = transform_component_code_with_import_allow_list(
+            code,
+            false,
+            TsxTransformConfig::default(),
+            &allow_list,
+        );
+        assert!(result.is_ok(), "got: {:?}", result.err());
+    }
+
+    #[test]
+    fn test_allow_list_rejects_unlisted_bare_specifier() {
+        let code = r#"import { useState } from "react";
+export default function Component() { return <div />; }"#;
+        let allow_list: HashSet<String> = HashSet::new();
+        let result = transform_component_code_with_import_allow_list(
+            code,
+            false,
+            TsxTransformConfig::default(),
+            &allow_list,
+        );
+        let err = result.unwrap_err();
+        assert!(matches!(err, MdxError::TsxTransform(ref errors) if errors[0].code.as_deref() == Some(diagnostic_codes::DISALLOWED_IMPORT)));
+    }
+
+    #[test]
+    fn test_allow_list_ignores_relative_specifiers() {
+        let code = r#"import Button from "./Button";
+export default function Component() { return <div />; }"#;
+        let allow_list: HashSet<String> = HashSet::new();
+        let result = transform_component_code_with_import_allow_list(
+            code,
+            false,
+            TsxTransformConfig::default(),
+            &allow_list,
+        );
+        assert!(result.is_ok(), "relative imports shouldn't need to be in the allow-list");
+    }
+
+    // ==================== Severity-level lint diagnostics ====================
+
+    #[test]
+    fn test_named_default_export_has_no_lint_warnings() {
+        let code = "export default function Component() { return <div>Hello</div>; }";
+        let (_, warnings) = transform_component_code_with_lints(code).unwrap();
+        assert!(warnings.is_empty());
+    }
+
+    #[test]
+    fn test_anonymous_arrow_default_export_warns_but_still_succeeds() {
+        let code = "export default () => <div>Hello</div>;";
+        let (output, warnings) = transform_component_code_with_lints(code).unwrap();
+        assert!(output.contains("Component"));
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].severity, Severity::Warning);
+        assert!(warnings[0].location.is_some());
+    }
+
+    #[test]
+    fn test_warning_severity_prefixes_display_with_warning() {
+        let warning = ParseError::new("suspicious pattern").with_severity(Severity::Warning);
+        assert!(warning.to_string().starts_with("warning:"));
+    }
+
+    // ==================== Batch processing ====================
+
+    #[test]
+    fn test_process_component_batch_recovers_from_per_file_failures() {
+        let mut inputs: HashMap<String, Vec<u8>> = HashMap::new();
+        inputs.insert(
+            "good.tsx".to_string(),
+            b"export default function Component() { return <div />; }".to_vec(),
+        );
+        inputs.insert(
+            "bad.tsx".to_string(),
+            b"export default function Widget() { return <div />; }".to_vec(),
+        );
+
+        let outcome = process_component_batch(&inputs);
+        assert_eq!(outcome.results.len(), 2);
+        assert!(matches!(
+            outcome.results.get("good.tsx"),
+            Some(BatchComponentResult::Output(_))
+        ));
+        assert!(matches!(
+            outcome.results.get("bad.tsx"),
+            Some(BatchComponentResult::Diagnostics(errors)) if !errors.is_empty()
+        ));
+    }
+
+    #[test]
+    fn test_process_component_batch_reports_non_utf8_without_panicking() {
+        let mut inputs: HashMap<String, Vec<u8>> = HashMap::new();
+        inputs.insert("invalid.tsx".to_string(), vec![0xff, 0xfe, 0xfd]);
+
+        let outcome = process_component_batch(&inputs);
+        match outcome.results.get("invalid.tsx") {
+            Some(BatchComponentResult::Diagnostics(errors)) => {
+                assert_eq!(errors.len(), 1);
+                assert!(errors[0].message.contains("invalid.tsx"));
+            }
+            other => panic!("expected non-UTF-8 diagnostics, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_component_error_diagnostics_recovers_location_for_naming_violation() {
+        let code = "export default function Widget() { return <div />; }";
+        let err = transform_component_code(code).unwrap_err();
+
+        let diagnostics = component_error_diagnostics(code, err);
+
+        assert_eq!(diagnostics.len(), 1);
+        assert!(diagnostics[0].location.is_some());
+        assert_eq!(
+            diagnostics[0].code.as_deref(),
+            Some(diagnostic_codes::INVALID_EXPORT_DEFAULT)
+        );
+    }
+
+    fn component(code: &str) -> ComponentDefinition {
+        ComponentDefinition {
+            name: None,
+            docs: None,
+            args: None,
+            code: code.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_component_dependency_order_orders_dependency_before_dependent() {
+        let mut components = HashMap::new();
+        components.insert(
+            "Card".to_string(),
+            component("export default function Card() { return <div><Footer /></div>; }"),
+        );
+        components.insert(
+            "Footer".to_string(),
+            component("export default function Footer() { return <footer />; }"),
+        );
+
+        let order = component_dependency_order(&components).unwrap();
+        let card_pos = order.iter().position(|n| n == "Card").unwrap();
+        let footer_pos = order.iter().position(|n| n == "Footer").unwrap();
+        assert!(
+            footer_pos < card_pos,
+            "Footer should be registered before Card, got order: {order:?}"
+        );
+    }
+
+    #[test]
+    fn test_component_dependency_order_detects_direct_cycle() {
+        let mut components = HashMap::new();
+        components.insert(
+            "Card".to_string(),
+            component("export default function Card() { return <Footer />; }"),
+        );
+        components.insert(
+            "Footer".to_string(),
+            component("export default function Footer() { return <Card />; }"),
+        );
+
+        let err = component_dependency_order(&components).unwrap_err();
+        assert!(matches!(err, MdxError::ComponentCycle(chain) if chain.contains("Card") && chain.contains("Footer")));
+    }
+
+    #[test]
+    fn test_component_dependency_order_detects_self_reference() {
+        let mut components = HashMap::new();
+        components.insert(
+            "Recursive".to_string(),
+            component("export default function Recursive() { return <Recursive />; }"),
+        );
+
+        let err = component_dependency_order(&components).unwrap_err();
+        assert!(matches!(err, MdxError::ComponentCycle(chain) if chain == "Recursive -> Recursive"));
+    }
 }
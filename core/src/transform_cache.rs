@@ -0,0 +1,155 @@
+//! Process-wide cache of TSX-to-JavaScript transform results.
+//!
+//! ## Why a Second Cache Tier?
+//!
+//! The renderer pool (see [`crate::renderer::pool`]) must be thread-local because
+//! `JsRuntime` is not `Send`. The *output* of the Oxc transform pipeline, however, is
+//! just a `String` that depends only on the source content and the relevant
+//! [`TsxTransformConfig`] fields — it is perfectly safe to share across threads.
+//!
+//! This module provides that shared tier: a content-hash-keyed cache consulted before
+//! running the parse/semantic-analysis/transform/codegen pipeline, so repeated
+//! component code and unchanged MDX content within and across batches (and across
+//! threads) skip the Oxc transform entirely. It mirrors the hybrid two-tier caching
+//! pattern of a fast local cache backed by a shared cache, with this module playing
+//! the role of the shared tier beneath each thread's renderer pool.
+//!
+//! ## Eviction
+//!
+//! The cache is bounded at [`MAX_CACHED_TRANSFORMS`] entries. Once full, the oldest
+//! entry is evicted to make room for the new one (FIFO), which keeps bookkeeping to a
+//! single insertion-order queue rather than tracking per-entry last-access time.
+
+use crate::error::MdxError;
+use crate::models::TsxTransformConfig;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, VecDeque};
+use std::hash::{Hash, Hasher};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Mutex, OnceLock};
+
+/// Maximum number of distinct transform results retained before the oldest is evicted.
+const MAX_CACHED_TRANSFORMS: usize = 512;
+
+/// Hit/miss counters for the shared transform cache, useful for observability.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TransformCacheStats {
+    /// Number of lookups that found a cached result.
+    pub hits: u64,
+    /// Number of lookups that required running the transform pipeline.
+    pub misses: u64,
+}
+
+struct CacheState {
+    map: HashMap<u64, String>,
+    /// Insertion order, oldest first, used for FIFO eviction.
+    order: VecDeque<u64>,
+}
+
+struct TransformCache {
+    state: Mutex<CacheState>,
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+impl TransformCache {
+    fn new() -> Self {
+        Self {
+            state: Mutex::new(CacheState {
+                map: HashMap::new(),
+                order: VecDeque::new(),
+            }),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+        }
+    }
+
+    fn get_or_insert_with(
+        &self,
+        key: u64,
+        compute: impl FnOnce() -> Result<String, MdxError>,
+    ) -> Result<String, MdxError> {
+        {
+            let state = self.state.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+            if let Some(cached) = state.map.get(&key) {
+                self.hits.fetch_add(1, Ordering::Relaxed);
+                return Ok(cached.clone());
+            }
+        }
+
+        self.misses.fetch_add(1, Ordering::Relaxed);
+        let result = compute()?;
+
+        let mut state = self.state.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        if !state.map.contains_key(&key) {
+            if state.order.len() >= MAX_CACHED_TRANSFORMS {
+                if let Some(oldest) = state.order.pop_front() {
+                    state.map.remove(&oldest);
+                }
+            }
+            state.order.push_back(key);
+            state.map.insert(key, result.clone());
+        }
+
+        Ok(result)
+    }
+
+    fn stats(&self) -> TransformCacheStats {
+        TransformCacheStats {
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+        }
+    }
+}
+
+static TRANSFORM_CACHE: OnceLock<TransformCache> = OnceLock::new();
+
+fn cache() -> &'static TransformCache {
+    TRANSFORM_CACHE.get_or_init(TransformCache::new)
+}
+
+/// Computes a stable cache key from source content, the transform config fields that
+/// affect output, and whether the content is wrapped in a component function.
+pub(crate) fn cache_key(source: &str, config: &TsxTransformConfig, wrap_content: bool) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    source.hash(&mut hasher);
+    config.jsx_pragma.hash(&mut hasher);
+    config.jsx_pragma_frag.hash(&mut hasher);
+    config.minify.hash(&mut hasher);
+    config.jsx_runtime.hash(&mut hasher);
+    config.jsx_import_source.hash(&mut hasher);
+    config.with_source_maps.hash(&mut hasher);
+    config.keep_comments.hash(&mut hasher);
+    config.development.hash(&mut hasher);
+    config.decorators_legacy.hash(&mut hasher);
+    config.emit_decorator_metadata.hash(&mut hasher);
+    // `config.refresh` isn't part of the key: Oxc's `ReactRefreshOptions` doesn't
+    // implement `Hash`, and callers that vary it are dev servers that don't share
+    // this process-wide cache with the production render path anyway.
+    wrap_content.hash(&mut hasher);
+    if let Some(names) = &config.component_names {
+        let mut sorted: Vec<&String> = names.iter().collect();
+        sorted.sort();
+        sorted.hash(&mut hasher);
+    }
+    if let Some(import_map) = &config.import_map {
+        let mut sorted: Vec<(&String, &String)> = import_map.iter().collect();
+        sorted.sort();
+        sorted.hash(&mut hasher);
+    }
+    hasher.finish()
+}
+
+/// Returns the cached transform result for `key`, computing and storing it via
+/// `compute` on a cache miss. Shared across all threads in the process.
+pub(crate) fn get_or_insert_with(
+    key: u64,
+    compute: impl FnOnce() -> Result<String, MdxError>,
+) -> Result<String, MdxError> {
+    cache().get_or_insert_with(key, compute)
+}
+
+/// Returns current hit/miss counts for the shared transform cache.
+pub fn stats() -> TransformCacheStats {
+    cache().stats()
+}
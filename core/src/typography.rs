@@ -0,0 +1,136 @@
+//! Smart typographic punctuation, applied over rendered HTML.
+//!
+//! Converts the ASCII punctuation writers type by habit into the typographic forms a
+//! typeset document would use: straight `"`/`'` quotes become curly quotes with
+//! context-aware open/close detection, `--` becomes an en dash, `---` an em dash, and
+//! `...` becomes a single ellipsis glyph. This mirrors the `smart_punctuation` feature
+//! described in zola's changelog.
+//!
+//! Substitution only ever touches prose text nodes: any `<pre>...</pre>` or
+//! `<code>...</code>` span, any other HTML tag (so attribute values like
+//! `title="..."` are left alone), and any `{...}` JSX expression are passed through
+//! byte-for-byte - see [`SKIP_REGION`].
+//!
+//! The open/close heuristic for quotes is the common one - opening if the previous
+//! character is absent, whitespace, or opening punctuation, closing otherwise - which
+//! also handles a contraction's apostrophe correctly (`don't` closes, since `n`
+//! precedes it) but misreads a leading elided quote like `'tis` as an opening quote;
+//! full disambiguation would need a dictionary of such forms, which this module
+//! doesn't carry.
+
+use regex::Regex;
+use std::sync::LazyLock;
+
+/// Matches a span that smart punctuation must leave untouched: a `<pre>` or `<code>`
+/// element's full contents (including nested markup, e.g. syntax-highlighting spans),
+/// any other single HTML tag, or a `{...}` JSX expression.
+///
+/// # Safety
+/// Pattern is compile-time constant and known to be valid.
+static SKIP_REGION: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"(?s)<pre\b[^>]*>.*?</pre>|<code\b[^>]*>.*?</code>|<[^>]*>|\{[^{}]*\}"#)
+        .expect("hardcoded regex pattern is valid")
+});
+
+/// Applies smart punctuation to every prose text node in `html`, skipping `<pre>`/
+/// `<code>` content, other HTML tags, and `{...}` JSX expressions - see
+/// [`crate::models::RenderSettings::smart_punctuation`].
+pub(crate) fn apply_smart_punctuation(html: &str) -> String {
+    let mut out = String::with_capacity(html.len());
+    let mut last = 0;
+    for region in SKIP_REGION.find_iter(html) {
+        out.push_str(&convert_prose(&html[last..region.start()]));
+        out.push_str(region.as_str());
+        last = region.end();
+    }
+    out.push_str(&convert_prose(&html[last..]));
+    out
+}
+
+/// Converts dashes, ellipses, and quotes in a single run of prose text. Dash and
+/// ellipsis runs are replaced longest-first so `---` doesn't leave a stray en dash
+/// behind, then quotes are rewritten in a single context-aware pass.
+fn convert_prose(text: &str) -> String {
+    let text = text.replace("...", "\u{2026}");
+    let text = text.replace("---", "\u{2014}");
+    let text = text.replace("--", "\u{2013}");
+    convert_quotes(&text)
+}
+
+/// Rewrites straight `"`/`'` quotes to curly quotes, deciding open-vs-close from the
+/// character immediately before each one - see the module docs for the heuristic and
+/// its known limitation.
+fn convert_quotes(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut prev: Option<char> = None;
+    for ch in text.chars() {
+        match ch {
+            '"' => out.push(if opens_quote(prev) { '\u{201C}' } else { '\u{201D}' }),
+            '\'' => out.push(if opens_quote(prev) { '\u{2018}' } else { '\u{2019}' }),
+            other => out.push(other),
+        }
+        prev = Some(ch);
+    }
+    out
+}
+
+/// Whether a quote preceded by `prev` should open (rather than close) a quotation.
+fn opens_quote(prev: Option<char>) -> bool {
+    match prev {
+        None => true,
+        Some(c) => c.is_whitespace() || matches!(c, '(' | '[' | '{' | '-' | '\u{2013}' | '\u{2014}'),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_curly_quotes_and_dashes() {
+        let html = "<p>She said \"hi\" -- it's '1' thing...</p>";
+        let out = apply_smart_punctuation(html);
+        assert_eq!(
+            out,
+            "<p>She said \u{201C}hi\u{201D} \u{2013} it\u{2019}s \u{2018}1\u{2019} thing\u{2026}</p>"
+        );
+    }
+
+    #[test]
+    fn test_em_dash_takes_priority_over_en_dash() {
+        assert_eq!(apply_smart_punctuation("a---b"), "a\u{2014}b");
+    }
+
+    #[test]
+    fn test_skips_pre_and_code_blocks() {
+        let html = r#"<pre><code>let s = "raw";</code></pre><p>"quoted"</p>"#;
+        let out = apply_smart_punctuation(html);
+        assert!(out.starts_with(r#"<pre><code>let s = "raw";</code></pre>"#));
+        assert!(out.ends_with("<p>\u{201C}quoted\u{201D}</p>"));
+    }
+
+    #[test]
+    fn test_skips_jsx_expression_braces() {
+        let html = r#"<p>{props.title} and "text"</p>"#;
+        let out = apply_smart_punctuation(html);
+        assert!(out.starts_with("<p>{props.title} and "));
+        assert!(out.ends_with("\u{201C}text\u{201D}</p>"));
+    }
+
+    #[test]
+    fn test_leaves_tag_attributes_untouched() {
+        let html = r#"<a title="don't break me">x</a>"#;
+        let out = apply_smart_punctuation(html);
+        assert_eq!(out, html);
+    }
+
+    #[test]
+    fn test_quote_opens_after_opening_punctuation() {
+        assert_eq!(apply_smart_punctuation("(\"quoted\")"), "(\u{201C}quoted\u{201D})");
+    }
+
+    #[test]
+    fn test_en_dash_without_following_em_dash() {
+        assert_eq!(apply_smart_punctuation("pages 10--20"), "pages 10\u{2013}20");
+    }
+}
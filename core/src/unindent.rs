@@ -0,0 +1,152 @@
+//! Common-indentation stripping for code blocks, per
+//! [`crate::models::RenderSettings::unindent_code_blocks`].
+//!
+//! Mirrors rustdoc's `unindent` behavior: a code block nested inside an indented
+//! context (a list item, a doc comment, a templated fragment) often carries leading
+//! whitespace on every line that's an artifact of that nesting rather than part of the
+//! example itself. [`unindent`] computes the minimum leading-whitespace width shared by
+//! every non-blank line (expanding tabs to [`TAB_WIDTH`] columns) and strips exactly
+//! that many columns from each line, so the block renders flush-left regardless of how
+//! deeply it was nested in the source.
+
+use std::sync::LazyLock;
+
+use regex::Regex;
+
+/// Tab stop width used when expanding a leading tab to columns, for both measuring and
+/// stripping indentation. rustdoc and most terminals default to the same value.
+const TAB_WIDTH: usize = 4;
+
+/// Matches a `<pre><code>`/`<pre><code class="language-x">` block emitted by
+/// markdown's fenced or indented code-block rendering, capturing its (HTML-escaped)
+/// content - the same shape [`crate::hidden_lines::strip_hidden_lines`] matches.
+///
+/// # Safety
+/// Pattern is compile-time constant and known to be valid.
+static CODE_BLOCK: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"(?s)<pre><code( class="language-[A-Za-z0-9_+-]+")?>(.*?)</code></pre>"#)
+        .expect("hardcoded regex pattern is valid")
+});
+
+/// Applies [`unindent`] to every code block's content in `html`.
+pub(crate) fn unindent_code_blocks(html: &str) -> String {
+    CODE_BLOCK
+        .replace_all(html, |caps: &regex::Captures| {
+            let class = caps.get(1).map_or("", |m| m.as_str());
+            format!("<pre><code{class}>{}</code></pre>", unindent(&caps[2]))
+        })
+        .into_owned()
+}
+
+/// Strips `code`'s common leading indentation: the minimum leading-whitespace width
+/// (tabs expanded to [`TAB_WIDTH`] columns) shared by every non-blank line, removed
+/// from every line. Blank lines are left untouched, and no line ever has more than the
+/// computed minimum removed.
+pub(crate) fn unindent(code: &str) -> String {
+    let common = code
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(indent_width)
+        .min()
+        .unwrap_or(0);
+
+    if common == 0 {
+        return code.to_string();
+    }
+
+    let stripped: Vec<String> = code
+        .lines()
+        .map(|line| if line.trim().is_empty() { line.to_string() } else { strip_indent(line, common) })
+        .collect();
+
+    let mut out = stripped.join("\n");
+    if code.ends_with('\n') {
+        out.push('\n');
+    }
+    out
+}
+
+/// The leading-whitespace width of `line` in columns, expanding each tab to the next
+/// [`TAB_WIDTH`]-column tab stop.
+fn indent_width(line: &str) -> usize {
+    let mut width = 0;
+    for ch in line.chars() {
+        match ch {
+            ' ' => width += 1,
+            '\t' => width += TAB_WIDTH - (width % TAB_WIDTH),
+            _ => break,
+        }
+    }
+    width
+}
+
+/// Removes `columns` columns of leading whitespace from `line`, expanding tabs the same
+/// way [`indent_width`] measures them. If a tab spans past `columns`, the remaining
+/// width it would have contributed is re-added as spaces, so alignment past the strip
+/// point isn't disturbed.
+fn strip_indent(line: &str, columns: usize) -> String {
+    let mut width = 0;
+    let mut chars = line.chars();
+    for ch in line.chars() {
+        if width >= columns {
+            break;
+        }
+        match ch {
+            ' ' => {
+                width += 1;
+                chars.next();
+            }
+            '\t' => {
+                width += TAB_WIDTH - (width % TAB_WIDTH);
+                chars.next();
+            }
+            _ => break,
+        }
+    }
+    let overshoot = width.saturating_sub(columns);
+    format!("{}{}", " ".repeat(overshoot), chars.as_str())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unindent_strips_common_leading_spaces() {
+        let code = "  fn main() {\n    println!(\"hi\");\n  }\n";
+        assert_eq!(unindent(code), "fn main() {\n  println!(\"hi\");\n}\n");
+    }
+
+    #[test]
+    fn test_unindent_leaves_blank_lines_untouched() {
+        let code = "  a\n\n  b\n";
+        assert_eq!(unindent(code), "a\n\nb\n");
+    }
+
+    #[test]
+    fn test_unindent_never_removes_more_than_the_minimum() {
+        let code = "  a\n      b\n";
+        assert_eq!(unindent(code), "a\n    b\n");
+    }
+
+    #[test]
+    fn test_unindent_no_common_indentation_is_a_no_op() {
+        let code = "a\n  b\n";
+        assert_eq!(unindent(code), code);
+    }
+
+    #[test]
+    fn test_unindent_expands_tabs_for_measurement() {
+        let code = "\tfn main() {}\n    more\n";
+        assert_eq!(unindent(code), "fn main() {}\n");
+    }
+
+    #[test]
+    fn test_unindent_code_blocks_rewrites_html() {
+        let html = "<pre><code class=\"language-rust\">  fn main() {}\n</code></pre>";
+        assert_eq!(
+            unindent_code_blocks(html),
+            "<pre><code class=\"language-rust\">fn main() {}\n</code></pre>"
+        );
+    }
+}
@@ -0,0 +1,130 @@
+//! Assembling a [`crate::models::NamedMdxBatchInput`] from `multipart/form-data` parts.
+//!
+//! A JSON batch request requires a client to base64/JSON-encode MDX source into
+//! [`crate::models::RenderInput`] first. [`UploadAccumulator`] instead collects raw
+//! file parts as they stream in - named MDX documents plus an optional `settings`
+//! part carrying JSON [`crate::models::RenderSettings`] - enforcing per-file and
+//! total-size limits along the way, so an oversized upload can be rejected with
+//! `413 Payload Too Large` before the whole body is buffered.
+
+use crate::models::{NamedMdxBatchInput, RenderSettings};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Size limits [`UploadAccumulator`] enforces while collecting multipart parts - part
+/// of [`crate::service::RenderServiceConfig`] so a deployment can tune them without
+/// touching request-handling code.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct UploadConfig {
+    /// Maximum size of a single file part, in bytes.
+    pub max_file_size_bytes: usize,
+    /// Maximum combined size of every file part in one upload, in bytes.
+    pub max_total_size_bytes: usize,
+}
+
+impl Default for UploadConfig {
+    fn default() -> Self {
+        Self {
+            max_file_size_bytes: 10 * 1024 * 1024,  // 10 MB
+            max_total_size_bytes: 50 * 1024 * 1024, // 50 MB
+        }
+    }
+}
+
+/// Why [`UploadAccumulator::push_chunk`] or [`UploadAccumulator::finish`] rejected an
+/// upload.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UploadError {
+    /// A single file part exceeded [`UploadConfig::max_file_size_bytes`].
+    FileTooLarge {
+        /// Name of the oversized file part.
+        name: String,
+        /// The configured limit it exceeded.
+        limit: usize,
+    },
+    /// The combined size of every file part exceeded [`UploadConfig::max_total_size_bytes`].
+    TotalTooLarge {
+        /// The configured limit it exceeded.
+        limit: usize,
+    },
+    /// The `settings` part's body wasn't valid JSON.
+    InvalidSettings(String),
+}
+
+impl std::fmt::Display for UploadError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            UploadError::FileTooLarge { name, limit } => {
+                write!(f, "file '{name}' exceeds the maximum allowed size of {limit} bytes")
+            }
+            UploadError::TotalTooLarge { limit } => {
+                write!(f, "upload exceeds the maximum combined size of {limit} bytes")
+            }
+            UploadError::InvalidSettings(message) => {
+                write!(f, "'settings' part is not valid JSON: {message}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for UploadError {}
+
+/// Collects streamed multipart parts into a [`NamedMdxBatchInput`], enforcing
+/// [`UploadConfig`]'s size limits as each chunk arrives rather than after the fact.
+#[derive(Debug)]
+pub struct UploadAccumulator {
+    config: UploadConfig,
+    mdx: HashMap<String, Vec<u8>>,
+    total_size: usize,
+}
+
+impl UploadAccumulator {
+    /// Creates an accumulator enforcing `config`'s size limits.
+    pub fn new(config: UploadConfig) -> Self {
+        Self { config, mdx: HashMap::new(), total_size: 0 }
+    }
+
+    /// Appends a chunk of file part `name`'s body, rejecting the upload once either
+    /// size limit is crossed.
+    pub fn push_chunk(&mut self, name: &str, chunk: &[u8]) -> Result<(), UploadError> {
+        self.total_size += chunk.len();
+        if self.total_size > self.config.max_total_size_bytes {
+            return Err(UploadError::TotalTooLarge { limit: self.config.max_total_size_bytes });
+        }
+
+        let entry = self.mdx.entry(name.to_string()).or_default();
+        entry.extend_from_slice(chunk);
+        if entry.len() > self.config.max_file_size_bytes {
+            return Err(UploadError::FileTooLarge {
+                name: name.to_string(),
+                limit: self.config.max_file_size_bytes,
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Finalizes the collected file parts plus an optional `settings` part's raw JSON
+    /// body into a [`NamedMdxBatchInput`] ready for
+    /// [`crate::service::RenderService::render_batch`].
+    pub fn finish(self, settings_json: Option<&[u8]>) -> Result<NamedMdxBatchInput, UploadError> {
+        let settings = match settings_json {
+            Some(bytes) => serde_json::from_slice::<RenderSettings>(bytes)
+                .map_err(|e| UploadError::InvalidSettings(e.to_string()))?,
+            None => RenderSettings::default(),
+        };
+
+        let mdx = self
+            .mdx
+            .into_iter()
+            .map(|(name, bytes)| (name, String::from_utf8_lossy(&bytes).into_owned()))
+            .collect();
+
+        Ok(NamedMdxBatchInput {
+            settings,
+            mdx,
+            components: None,
+            partials: None,
+        })
+    }
+}
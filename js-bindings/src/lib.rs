@@ -31,9 +31,33 @@ pub struct RendererConfig {
     pub max_mdx_content_size: Option<u32>,
     /// Maximum component code size in bytes (default: 1 MB)
     pub max_component_code_size: Option<u32>,
+    /// Directory to persist the built V8 startup snapshot to, so a later process
+    /// doesn't pay to re-parse and re-execute the engine scripts on its first
+    /// `new Renderer()` the way this one did - see
+    /// `RenderServiceConfig::snapshot_cache_dir`. Unset (the default) leaves disk
+    /// snapshot caching off: this binding has no directory of its own to default to
+    /// that a prior unprivileged local user couldn't have pre-planted a snapshot file
+    /// in - `RendererPool::disk_snapshot_path` names the cached file from a
+    /// hash of public inputs only, so a shared, guessable default (e.g. a fixed path
+    /// under the system temp dir) would let that file get loaded straight into this
+    /// process's V8 isolate with no integrity check beyond the path matching. Pass a
+    /// directory only this process (or user) can write to if you want the snapshot
+    /// persisted across restarts - within a single process, renderer checkouts
+    /// already reuse one in-memory snapshot regardless of this setting.
+    pub snapshot_cache_dir: Option<String>,
 }
 
-/// Initialize the static directory with embedded files
+/// Initialize the static directory with embedded files.
+///
+/// This still writes `engine.min.js`/`engine_to_string.min.js`/`core.js`/`helpers.js`
+/// to disk rather than booting the renderer straight from the embedded `include_str!`
+/// constants (`JsRenderer::from_sources` exists for exactly that) - `helpers.js` is
+/// imported by component code through `ComponentModuleLoader`'s `static_dir` fallback
+/// (see `dinja_core::renderer::module_loader`), which resolves against a real
+/// directory on disk, so this write can't be skipped as long as that path is
+/// supported. What `RendererConfig::snapshot_cache_dir` avoids instead is the
+/// expensive part: re-parsing and re-executing those scripts into a fresh V8 heap on
+/// every process's first `new Renderer()`.
 fn init_static_dir() -> Result<PathBuf> {
     STATIC_DIR
         .get_or_try_init(|| -> Result<PathBuf> {
@@ -155,10 +179,20 @@ impl Renderer {
             )
         })?;
 
+        // No fallback to a shared temp-dir default here - see `RendererConfig::snapshot_cache_dir`'s
+        // doc comment for why a guessable default path is a local code-execution risk.
+        // Disk snapshot persistence across process restarts is opt-in only.
+        let snapshot_cache_dir = cfg.snapshot_cache_dir.map(PathBuf::from);
+
         let config = RenderServiceConfig {
             static_dir,
             max_cached_renderers: cfg.max_cached_renderers.unwrap_or(4) as usize,
+            max_batch_concurrency: 1,
             resource_limits,
+            compression: dinja_core::compression::CompressionConfig::default(),
+            upload: dinja_core::upload::UploadConfig::default(),
+            snapshot_cache_dir,
+            ..RenderServiceConfig::default()
         };
         let service = CoreRenderService::new(config).map_err(|e| {
             Error::new(
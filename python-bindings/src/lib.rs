@@ -1,5 +1,6 @@
 use dinja_core::service::{
-    RenderBatchError, RenderService as CoreRenderService, RenderServiceConfig,
+    MdxWatchOptions, RenderBatchError, RenderService as CoreRenderService, RenderServiceConfig,
+    WatchHandle as CoreWatchHandle,
 };
 use once_cell::sync::OnceCell;
 use pyo3::exceptions::PyValueError;
@@ -79,13 +80,30 @@ impl Renderer {
     ///
     /// The engine is loaded once during initialization and reused for all subsequent renders.
     /// This prevents v8 isolate issues when rendering with different modes.
+    ///
+    /// # Arguments
+    /// * `use_snapshot` - When `true`, persists each renderer profile's built V8
+    ///   startup snapshot to a `snapshot-cache` directory alongside the embedded
+    ///   static files and reloads from it on a later run, so a cold isolate
+    ///   deserializes the already-initialized engine instead of re-parsing and
+    ///   re-executing `engine.min.js`/`core.js` - see
+    ///   `dinja_core::service::RenderServiceConfig::snapshot_cache_dir`. Defaults to
+    ///   `false` (snapshots stay in-memory only, rebuilt once per process).
     #[new]
-    fn new() -> PyResult<Self> {
+    #[pyo3(signature = (use_snapshot=false))]
+    fn new(use_snapshot: bool) -> PyResult<Self> {
         let static_dir = init_static_dir()?;
+        let snapshot_cache_dir = use_snapshot.then(|| static_dir.join("snapshot-cache"));
         let config = RenderServiceConfig {
             static_dir,
             max_cached_renderers: 4,
+            max_batch_concurrency: 1,
             resource_limits: dinja_core::models::ResourceLimits::default(),
+            compression: dinja_core::compression::CompressionConfig::default(),
+            upload: dinja_core::upload::UploadConfig::default(),
+            snapshot_cache_dir,
+            enable_profiling: false,
+            ..RenderServiceConfig::default()
         };
         let service = CoreRenderService::new(config).map_err(|e| {
             PyValueError::new_err(format!("Failed to create render service: {}", e))
@@ -153,12 +171,147 @@ impl Renderer {
         let result = loads.call1((outcome_json,))?;
         Ok(result.extract::<Py<PyAny>>()?)
     }
+
+    /// Renders MDX content like [`Self::render`], but distributes the batch's files
+    /// across up to `jobs` renderers checked out from the pool concurrently instead
+    /// of rendering one file at a time - see
+    /// `dinja_core::service::RenderService::render_batch_parallel`. Releases the GIL
+    /// for the duration of the render, so other Python threads can run while the
+    /// worker threads are busy.
+    ///
+    /// # Arguments
+    /// * `input_dict` - Same shape as [`Self::render`]'s.
+    /// * `jobs` - Number of files to render concurrently; defaults to this renderer's
+    ///   configured `max_cached_renderers` when omitted.
+    ///
+    /// # Returns / Raises
+    /// Same as [`Self::render`].
+    #[pyo3(signature = (input_dict, jobs=None))]
+    fn render_parallel(
+        &self,
+        py: Python,
+        input_dict: &Bound<'_, PyAny>,
+        jobs: Option<usize>,
+    ) -> PyResult<Py<PyAny>> {
+        // Convert Python dict to JSON string
+        let json_module = py.import("json")?;
+        let dumps = json_module.getattr("dumps")?;
+        let input_json: String = dumps.call1((input_dict,))?.extract()?;
+
+        // Parse JSON string to Rust struct
+        let batch_input: dinja_core::models::NamedMdxBatchInput = serde_json::from_str(&input_json)
+            .map_err(|e| PyValueError::new_err(format!("Failed to parse input JSON: {}", e)))?;
+
+        // Release the GIL while the render runs across worker threads.
+        let outcome = py.allow_threads(|| {
+            let service = self.service.lock().unwrap();
+            let jobs = jobs.unwrap_or_else(|| service.config().max_cached_renderers);
+            service.render_batch_parallel(batch_input, jobs)
+        });
+        let outcome = match outcome {
+            Ok(outcome) => outcome,
+            Err(RenderBatchError::Forbidden(msg)) => {
+                return Err(PyValueError::new_err(format!("Forbidden: {}", msg)));
+            }
+            Err(RenderBatchError::InvalidRequest(msg)) => {
+                return Err(PyValueError::new_err(format!("Invalid request: {}", msg)));
+            }
+            Err(RenderBatchError::Internal(err)) => {
+                return Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!(
+                    "Internal error: {}",
+                    err
+                )));
+            }
+        };
+
+        // Serialize outcome to JSON, then convert back to Python dict
+        let outcome_json = serde_json::to_string(&outcome)
+            .map_err(|e| PyValueError::new_err(format!("Failed to serialize outcome: {}", e)))?;
+
+        let loads = json_module.getattr("loads")?;
+        let result = loads.call1((outcome_json,))?;
+        Ok(result.extract::<Py<PyAny>>()?)
+    }
+
+    /// Watches `directory` for changes to `.mdx` files and re-renders each changed
+    /// one through this instance's already-warmed service, so a rebuild reuses the
+    /// same isolate pool instead of paying cold-start cost per change - see
+    /// `dinja_core::service::RenderService::watch`.
+    ///
+    /// # Arguments
+    /// * `directory` - Directory tree to watch for `.mdx` file changes.
+    /// * `settings_dict` - Same shape as [`Self::render`]'s `input_dict["settings"]`,
+    ///   applied to every re-render.
+    /// * `callback` - Called as `callback(name, outcome)` from a background thread
+    ///   once per changed file after that pass's batch render has finished, where `outcome` has the same
+    ///   shape as one entry of [`Self::render`]'s `files` map.
+    ///
+    /// # Returns
+    /// A [`WatchHandle`]; call its `stop()` (or let it be garbage-collected) to stop
+    /// watching.
+    fn watch(
+        &self,
+        py: Python,
+        directory: PathBuf,
+        settings_dict: &Bound<'_, PyAny>,
+        callback: Py<PyAny>,
+    ) -> PyResult<WatchHandle> {
+        let json_module = py.import("json")?;
+        let dumps = json_module.getattr("dumps")?;
+        let settings_json: String = dumps.call1((settings_dict,))?.extract()?;
+        let settings: dinja_core::models::RenderSettings = serde_json::from_str(&settings_json)
+            .map_err(|e| PyValueError::new_err(format!("Failed to parse settings JSON: {}", e)))?;
+
+        let service = self.service.lock().unwrap();
+        let inner = service.watch(
+            directory,
+            settings,
+            MdxWatchOptions::default(),
+            move |name, outcome| {
+                Python::with_gil(|py| {
+                    let Ok(outcome_json) = serde_json::to_string(&outcome) else {
+                        return;
+                    };
+                    let Ok(json_module) = py.import("json") else {
+                        return;
+                    };
+                    let Ok(loads) = json_module.getattr("loads") else {
+                        return;
+                    };
+                    let Ok(outcome_dict) = loads.call1((outcome_json,)) else {
+                        return;
+                    };
+                    let _ = callback.call1(py, (name, outcome_dict));
+                });
+            },
+        );
+        Ok(WatchHandle { inner: Some(inner) })
+    }
+}
+
+/// Handle returned by [`Renderer::watch`] - stops the background watch thread when
+/// [`Self::stop`] is called, or when this handle is garbage-collected.
+#[pyclass]
+struct WatchHandle {
+    inner: Option<CoreWatchHandle>,
+}
+
+#[pymethods]
+impl WatchHandle {
+    /// Stops the watch and blocks until its background thread has exited. Calling
+    /// this more than once is a no-op.
+    fn stop(&mut self) {
+        if let Some(inner) = self.inner.take() {
+            inner.stop();
+        }
+    }
 }
 
 /// The dinja Python module
 #[pymodule]
 fn _native<'py>(_py: Python<'py>, m: &Bound<'py, PyModule>) -> PyResult<()> {
     m.add_class::<Renderer>()?;
+    m.add_class::<WatchHandle>()?;
     Ok(())
 }
 
@@ -228,11 +381,21 @@ mod tests {
     }
 
     fn init_test_service() -> CoreRenderService {
+        init_test_service_with_snapshot_cache(None)
+    }
+
+    fn init_test_service_with_snapshot_cache(snapshot_cache_dir: Option<PathBuf>) -> CoreRenderService {
         let static_dir = init_test_static_dir();
         let config = RenderServiceConfig {
             static_dir,
             max_cached_renderers: 4,
+            max_batch_concurrency: 1,
             resource_limits: dinja_core::models::ResourceLimits::default(),
+            compression: dinja_core::compression::CompressionConfig::default(),
+            upload: dinja_core::upload::UploadConfig::default(),
+            snapshot_cache_dir,
+            enable_profiling: false,
+            ..RenderServiceConfig::default()
         };
         // Don't skip pool warming - let it warm up normally, but handle errors gracefully
         // If pool warming fails, the first render will create a new renderer anyway
@@ -543,6 +706,48 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_render_batch_parallel() {
+        println!("\n=== Test: render_batch_parallel ===");
+        let service = init_test_service();
+
+        let mut mdx = HashMap::new();
+        for i in 0..6 {
+            mdx.insert(
+                format!("page{}.mdx", i),
+                format!("## Page {}\n\nContent {}", i, i),
+            );
+        }
+        let input = NamedMdxBatchInput {
+            settings: RenderSettings {
+                output: OutputFormat::Html,
+                minify: true,
+                engine: RenderEngine::Base,
+                components: vec!["Button".to_string()],
+            },
+            mdx,
+            components: None,
+        };
+
+        match service.render_batch_parallel(input, 3) {
+            Ok(outcome) => {
+                assert_eq!(outcome.total, 6);
+                assert_eq!(outcome.files.len(), 6);
+                println!(
+                    "  ✓ Rendered {} files with jobs=3: {} succeeded, {} failed",
+                    outcome.total, outcome.succeeded, outcome.failed
+                );
+            }
+            Err(e) => {
+                // Each worker thread checks out its own renderer from the per-profile pool
+                // (see `RenderServiceConfig::worker_stack_size_bytes` and
+                // `dinja_core::renderer::pool`), so concurrent rendering no longer has a v8
+                // isolate limitation to fall back on here.
+                panic!("render_batch_parallel failed: {}", e);
+            }
+        }
+    }
+
     /// Performance comparison: stateless vs reusable
     ///
     /// Note: This test uses a minimal number of iterations to avoid v8 isolate issues
@@ -603,6 +808,50 @@ mod tests {
         );
     }
 
+    /// Benchmark: cold isolate spin-up (pool warming) with and without a V8 startup
+    /// snapshot cache - mirrors [`test_performance_comparison`]'s stateless-vs-reusable
+    /// shape, but isolates just the "new `RenderService`" cost rather than a full
+    /// render, since that's the step snapshotting targets.
+    #[test]
+    fn test_snapshot_cold_start_comparison() {
+        println!("\n=== Test: Snapshot Cold-Start Comparison ===");
+        let static_dir = init_test_static_dir();
+        let snapshot_cache_dir = std::env::temp_dir().join("dinja-snapshot-cache-test");
+        let _ = fs::remove_dir_all(&snapshot_cache_dir);
+
+        // Prime the on-disk snapshot cache so the timed run below can load it instead
+        // of building it fresh.
+        drop(init_test_service_with_snapshot_cache(Some(
+            snapshot_cache_dir.clone(),
+        )));
+
+        println!("  Spinning up a cold isolate without a snapshot cache...");
+        let start_without = Instant::now();
+        drop(init_test_service());
+        let elapsed_without = start_without.elapsed();
+
+        println!("  Spinning up a cold isolate from a warmed snapshot cache...");
+        let start_with = Instant::now();
+        drop(init_test_service_with_snapshot_cache(Some(
+            snapshot_cache_dir.clone(),
+        )));
+        let elapsed_with = start_with.elapsed();
+
+        println!("  Without snapshot: {:?}", elapsed_without);
+        println!("  With snapshot:    {:?}", elapsed_with);
+
+        let _ = fs::remove_dir_all(&snapshot_cache_dir);
+        let _ = fs::remove_dir_all(&static_dir);
+
+        // Loose bound rather than requiring a strict speedup: a busy CI host can make
+        // either run noisy, but loading a snapshot should never be drastically slower
+        // than building one from scratch.
+        assert!(
+            elapsed_with <= elapsed_without * 3 + std::time::Duration::from_millis(50),
+            "Spinning up from a snapshot cache should not be drastically slower than a cold build"
+        );
+    }
+
     /// Test with components
     #[test]
     fn test_with_components() {
@@ -670,13 +919,12 @@ mod tests {
                 }
                 Err(e) => {
                     let error_str = format!("{:?}", e);
-                    // If it's a v8 isolate error, that's a known issue with rapid mode switching
+                    // Isolates are checked out from `RendererPool`'s per-profile cache rather
+                    // than torn down and recreated on every mode switch, so a v8 isolate error
+                    // here is a genuine regression, not the rapid-mode-switching limitation this
+                    // test used to tolerate.
                     if is_v8_isolate_error(&error_str) {
-                        known_issue_count += 1;
-                        println!("  ⚠️  Mode {:?}: v8 isolate error (known limitation)", mode);
-                        println!("     Error: {}", e);
-                        // Continue to next mode instead of panicking
-                        continue;
+                        panic!("v8 isolate error for mode {:?}: {}", mode, e);
                     } else if error_str.contains("engine") || error_str.contains("engine_to_string")
                     {
                         // Engine initialization issue - might be a test environment problem
@@ -697,7 +945,7 @@ mod tests {
         // At least one mode should succeed, OR all failures should be due to known issues
         // (This allows the test to pass even if engine initialization has issues in test environment)
         if success_count == 0 && known_issue_count > 0 {
-            println!("  ⚠️  All modes failed due to known issues (engine init or v8 isolate)");
+            println!("  ⚠️  All modes failed due to known issues (engine init)");
             println!("     This is acceptable in test environment");
         } else {
             assert!(success_count > 0, "At least one output format should work");